@@ -0,0 +1,58 @@
+//! Generates a large synthetic type graph to benchmark the compile time of the
+//! type set computation.
+//!
+//! The graph consists of `CTL_BENCH_DEPTH` layers of `CTL_BENCH_WIDTH` structs.
+//! Every struct refers to two structs of the layer below and, through a raw
+//! pointer, to its neighbour in its own layer, which makes every layer cyclic.
+
+use std::{env, fmt::Write, fs, path::PathBuf};
+
+fn env_usize(key: &str, default: usize) -> usize {
+    println!("cargo:rerun-if-env-changed={key}");
+
+    env::var(key)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+fn main() {
+    let depth = env_usize("CTL_BENCH_DEPTH", 12).max(1);
+    let width = env_usize("CTL_BENCH_WIDTH", 16).max(1);
+
+    let mut source = String::new();
+
+    for layer in 0..depth {
+        for i in 0..width {
+            let next = (i + 1) % width;
+
+            source.push_str("#[repr(C)]\n#[derive(TypeLayout)]\n");
+            write!(source, "pub struct T{layer}x{i} {{").unwrap();
+            write!(source, " sibling: *const T{layer}x{next},").unwrap();
+
+            if layer == 0 {
+                source.push_str(" a: u8, b: u16, c: u32, d: u64,");
+            } else {
+                let below = layer - 1;
+                write!(source, " left: T{below}x{i}, right: T{below}x{next},").unwrap();
+            }
+
+            source.push_str(" }\n\n");
+        }
+    }
+
+    source.push_str("#[repr(C)]\n#[derive(TypeLayout)]\npub struct Root {");
+    for i in 0..width {
+        write!(source, " t{i}: T{}x{i},", depth - 1).unwrap();
+    }
+    source.push_str(" }\n\n");
+
+    write!(
+        source,
+        "pub const DEPTH: usize = {depth};\npub const WIDTH: usize = {width};\n"
+    )
+    .unwrap();
+
+    let out = PathBuf::from(env::var_os("OUT_DIR").unwrap()).join("graph.rs");
+    fs::write(out, source).unwrap();
+}
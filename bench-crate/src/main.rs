@@ -0,0 +1,35 @@
+//! Compile-time benchmark for the type set computation of a large synthetic
+//! type graph, which is generated by the build script.
+//!
+//! Time the compilation of this crate with, e.g.,
+//!
+//! ```shell
+//! CTL_BENCH_DEPTH=16 CTL_BENCH_WIDTH=24 cargo build -p bench-crate --timings
+//! ```
+
+#![deny(clippy::complexity)]
+#![deny(clippy::correctness)]
+#![warn(clippy::nursery)]
+#![warn(clippy::pedantic)]
+#![deny(clippy::perf)]
+#![deny(clippy::style)]
+#![deny(clippy::suspicious)]
+#![feature(const_type_name)]
+#![allow(dead_code)]
+
+use const_type_layout::{TypeGraphLayout, TypeLayout};
+
+include!(concat!(env!("OUT_DIR"), "/graph.rs"));
+
+// every struct, its sibling pointer, the root, and the four integers
+const NUM_TYPES: usize = DEPTH * WIDTH * 2 + 1 + 4;
+
+const _: () = assert!(Root::TYPE_GRAPH.tys.len() == NUM_TYPES);
+
+fn main() {
+    println!(
+        "{} has a type graph with {} types",
+        Root::TYPE_GRAPH.ty,
+        Root::TYPE_GRAPH.tys.len()
+    );
+}
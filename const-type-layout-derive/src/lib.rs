@@ -55,6 +55,29 @@ use syn::{parse_macro_input, spanned::Spanned};
 ///   the type parameter `<type>` from the trait implementation, e.g. when
 ///   implementing a wrapper around [`PhantomData<T>`](std::marker::PhantomData)
 ///   which should implement the trait for any `T`.
+/// - `#[layout(name = "<name>")]` sets the stable name of the type, which is
+///   used instead of its [`core::any::type_name`] in its layout. The stable
+///   names of the type parameters of a generic type are appended to its name,
+///   e.g. `<name><u8, T>`, where freed type parameters use their
+///   [`core::any::type_name`]. This attribute is not supported for types with
+///   const generic parameters.
+/// - `#[layout(ffi_safe)]` checks at compile time that the layout of the type,
+///   and of all types it contains or links to, is guaranteed, i.e. not chosen
+///   by the compiler as for `#[repr(Rust)]` types, using
+///   [`const_type_layout::analysis::ffi::assert_ffi_safe`](https://docs.rs/const-type-layout/0.3/const_type_layout/analysis/ffi/fn.assert_ffi_safe.html).
+///   This check is only supported for non-generic types.
+/// - `#[layout(warn_padding)]` or `#[layout(warn_padding = "<bytes>")]` emits a
+///   warning at compile time if reordering the fields of a `#[repr(C)]` struct by
+///   decreasing alignment would save more than `<bytes>` bytes, by default zero,
+///   using [`const_type_layout::analysis::reorder::avoidable_padding`](https://docs.rs/const-type-layout/0.3/const_type_layout/analysis/reorder/fn.avoidable_padding.html).
+///   This check is only supported for non-generic types.
+///
+/// The fields of a struct also accept a `#[layout(cache_group = "<group>")]`
+/// attribute, which assigns the field to a group of fields that are written
+/// together, e.g. by the same thread. If any field has this attribute, the
+/// derive also implements the
+/// [`const_type_layout::analysis::cache::FieldGroups`](https://docs.rs/const-type-layout/0.3/const_type_layout/analysis/cache/trait.FieldGroups.html)
+/// trait, whose groups are used to find false sharing between the groups.
 pub fn derive_type_layout(input: TokenStream) -> TokenStream {
     // Parse the input tokens into a syntax tree
     let input = parse_macro_input!(input as syn::DeriveInput);
@@ -73,12 +96,86 @@ pub fn derive_type_layout(input: TokenStream) -> TokenStream {
         reprs,
         extra_bounds,
         crate_path,
+        name,
+        ffi_safe,
+        warn_padding,
     } = parse_attributes(&input.attrs, &mut type_params);
 
+    let ffi_safe_check = ffi_safe.then(|| {
+        if !input.generics.params.is_empty() {
+            emit_error!(
+                input.generics.span(),
+                "[const-type-layout]: #[layout(ffi_safe)] can only check non-generic types."
+            );
+        }
+
+        quote! {
+            const _: () = #crate_path::analysis::ffi::assert_ffi_safe::<#ty_name>();
+        }
+    });
+
+    let warn_padding_check = warn_padding.map(|(span, bytes)| {
+        if !input.generics.params.is_empty() {
+            emit_error!(
+                input.generics.span(),
+                "[const-type-layout]: #[layout(warn_padding)] can only check non-generic types."
+            );
+        }
+
+        if !matches!(input.data, syn::Data::Struct(_)) || !reprs.split(',').any(|repr| repr == "C")
+        {
+            emit_error!(
+                span,
+                "[const-type-layout]: #[layout(warn_padding)] can only check #[repr(C)] structs."
+            );
+        }
+
+        // deprecation warnings are not reported inside derive expansions, so
+        //  the deprecated path must only carry the span of the attribute
+        let warn = quote_spanned! { span => WarnPadding::<WARN>::warn() };
+
+        quote! {
+            const _: () = {
+                use #crate_path::analysis::reorder::WarnPadding;
+
+                const WARN: bool =
+                    #crate_path::analysis::reorder::avoidable_padding::<#ty_name>() > #bytes;
+
+                #warn
+            };
+        }
+    });
+
+    let field_groups = field_groups_of_type(&input.data);
+    proc_macro_error2::abort_if_dirty();
+
+    let field_groups_impl = (!field_groups.is_empty()).then(|| {
+        let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+        let (fields, groups): (Vec<_>, Vec<_>) = field_groups.into_iter().unzip();
+
+        quote! {
+            impl #impl_generics #crate_path::analysis::cache::FieldGroups for
+                #ty_name #ty_generics #where_clause
+            {
+                const FIELD_GROUPS: &'static [(&'static str, &'static str)] =
+                    &[#((#fields, #groups)),*];
+            }
+        }
+    });
+
+    let stable_name = name.map(|name| {
+        let stable_name = stable_name_of_type(&crate_path, &name, &input.generics, &type_params);
+
+        quote! {
+            const STABLE_NAME: &'static str = #stable_name;
+        }
+    });
+
     let inhabited = inhabited_for_type(&crate_path, &input.data);
     let layout = layout_of_type(&crate_path, &ty_name, &ty_generics, &input.data, &reprs);
 
     let inner_types = extract_inner_types(&input.data);
+    let key_name = ty_name.to_string();
 
     let discriminant_ty = if let syn::Data::Enum(_) = input.data {
         Some(quote! { ::core::mem::Discriminant<Self>, })
@@ -87,13 +184,16 @@ pub fn derive_type_layout(input: TokenStream) -> TokenStream {
     };
 
     let Generics {
-        type_layout_input_generics,
-        type_set_input_generics,
+        layout: type_layout_input_generics,
+        set: type_set_input_generics,
+        key: type_key_input_generics,
     } = generate_generics(&crate_path, &input.generics, &extra_bounds, &type_params);
     let (type_layout_impl_generics, type_layout_ty_generics, type_layout_where_clause) =
         type_layout_input_generics.split_for_impl();
     let (type_set_impl_generics, type_set_ty_generics, type_set_where_clause) =
         type_set_input_generics.split_for_impl();
+    let (type_key_impl_generics, type_key_ty_generics, type_key_where_clause) =
+        type_key_input_generics.split_for_impl();
 
     quote! {
         unsafe impl #type_layout_impl_generics #crate_path::TypeLayout for
@@ -101,9 +201,11 @@ pub fn derive_type_layout(input: TokenStream) -> TokenStream {
         {
             const INHABITED: #crate_path::MaybeUninhabited = #inhabited;
 
+            #stable_name
+
             const TYPE_LAYOUT: #crate_path::TypeLayoutInfo<'static> = {
                 #crate_path::TypeLayoutInfo {
-                    name: ::core::any::type_name::<Self>(),
+                    name: <Self as #crate_path::TypeLayout>::STABLE_NAME,
                     size: ::core::mem::size_of::<Self>(),
                     alignment: ::core::mem::align_of::<Self>(),
                     structure: #layout,
@@ -119,6 +221,18 @@ pub fn derive_type_layout(input: TokenStream) -> TokenStream {
                     #(#inner_types,)* #discriminant_ty .. @ __TypeSetRest
                 ];
         }
+
+        impl #type_key_impl_generics #crate_path::typeset::ComputeTypeKey for
+            #ty_name #type_key_ty_generics #type_key_where_clause
+        {
+            type Key = #crate_path::typeset::tkey![
+                ::core::concat!(::core::module_path!(), "::", #key_name); #(#type_params),*
+            ];
+        }
+
+        #ffi_safe_check
+        #warn_padding_check
+        #field_groups_impl
     }
     .into()
 }
@@ -127,6 +241,9 @@ struct Attributes {
     reprs: String,
     extra_bounds: Vec<syn::WherePredicate>,
     crate_path: syn::Path,
+    name: Option<syn::LitStr>,
+    ffi_safe: bool,
+    warn_padding: Option<(proc_macro2::Span, usize)>,
 }
 
 #[allow(clippy::too_many_lines)]
@@ -138,6 +255,12 @@ fn parse_attributes(attrs: &[syn::Attribute], type_params: &mut Vec<&syn::Ident>
 
     let mut crate_path = None;
 
+    let mut name = None;
+
+    let mut ffi_safe = false;
+
+    let mut warn_padding = None;
+
     for attr in attrs {
         #[allow(clippy::collapsible_if)]
         if attr.path().is_ident("repr") {
@@ -157,6 +280,35 @@ fn parse_attributes(attrs: &[syn::Attribute], type_params: &mut Vec<&syn::Ident>
             #[allow(clippy::blocks_in_conditions)]
             if attr
                 .parse_nested_meta(|meta| {
+                    if meta.path.is_ident("ffi_safe") {
+                        if ffi_safe {
+                            emit_error!(
+                                meta.path.span(),
+                                "[const-type-layout]: Duplicate #[layout(ffi_safe)] attribute."
+                            );
+                        }
+                        ffi_safe = true;
+                        return Ok(());
+                    }
+
+                    if meta.path.is_ident("warn_padding") {
+                        if warn_padding.is_some() {
+                            emit_error!(
+                                meta.path.span(),
+                                "[const-type-layout]: Duplicate #[layout(warn_padding)] attribute."
+                            );
+                        }
+
+                        let bytes = if meta.input.peek(syn::Token![=]) {
+                            parse_warn_padding_bytes(&meta)
+                        } else {
+                            0
+                        };
+
+                        warn_padding = Some((meta.path.span(), bytes));
+                        return Ok(());
+                    }
+
                     let Ok(value) = meta.value() else {
                         emit_error!(
                             meta.path.span(),
@@ -230,11 +382,21 @@ fn parse_attributes(attrs: &[syn::Attribute], type_params: &mut Vec<&syn::Ident>
                                 err
                             ),
                         }
+                    } else if meta.path.is_ident("name") {
+                        if name.is_none() {
+                            name = Some(s);
+                        } else {
+                            emit_error!(
+                                meta.path.span(),
+                                "[const-type-layout]: Duplicate #[layout(name)] attribute: the \
+                                 stable name of a type can only be set once per `derive`.",
+                            );
+                        }
                     } else {
                         emit_error!(
                             meta.path.span(),
-                            "[const-type-layout]: Unknown attribute, use `bound`, `crate`, or \
-                             `free`."
+                            "[const-type-layout]: Unknown attribute, use `bound`, `crate`, \
+                             `ffi_safe`, `free`, `name`, or `warn_padding`."
                         );
                     }
                     Ok(())
@@ -260,9 +422,108 @@ fn parse_attributes(attrs: &[syn::Attribute], type_params: &mut Vec<&syn::Ident>
         reprs,
         extra_bounds,
         crate_path: crate_path.unwrap_or_else(|| syn::parse_quote!(::const_type_layout)),
+        name,
+        ffi_safe,
+        warn_padding,
     }
 }
 
+fn parse_warn_padding_bytes(meta: &syn::meta::ParseNestedMeta) -> usize {
+    let Ok(s) = meta
+        .value()
+        .and_then(<syn::LitStr as syn::parse::Parse>::parse)
+    else {
+        emit_error!(
+            meta.path.span(),
+            "[const-type-layout]: Expected #[layout(warn_padding = \"<bytes>\")] syntax."
+        );
+        return 0;
+    };
+
+    s.value().parse().unwrap_or_else(|err| {
+        emit_error!(
+            s.span(),
+            "[const-type-layout]: Invalid #[layout(warn_padding = \"<bytes>\")] attribute: {}.",
+            err
+        );
+        0
+    })
+}
+
+fn field_groups_of_type(data: &syn::Data) -> Vec<(String, String)> {
+    let mut field_groups = Vec::new();
+
+    let (fields, is_struct) = match data {
+        syn::Data::Struct(data) => (data.fields.iter().collect::<Vec<_>>(), true),
+        syn::Data::Enum(data) => (
+            data.variants
+                .iter()
+                .flat_map(|variant| &variant.fields)
+                .collect(),
+            false,
+        ),
+        syn::Data::Union(data) => (data.fields.named.iter().collect(), false),
+    };
+
+    for (field_index, field) in fields.into_iter().enumerate() {
+        let field_name = field
+            .ident
+            .as_ref()
+            .map_or_else(|| field_index.to_string(), ToString::to_string);
+
+        for attr in &field.attrs {
+            if !attr.path().is_ident("layout") {
+                continue;
+            }
+
+            if !is_struct {
+                emit_error!(
+                    attr.span(),
+                    "[const-type-layout]: #[layout(cache_group)] can only be used on struct \
+                     fields."
+                );
+                continue;
+            }
+
+            #[allow(clippy::blocks_in_conditions)]
+            if attr
+                .parse_nested_meta(|meta| {
+                    if !meta.path.is_ident("cache_group") {
+                        emit_error!(
+                            meta.path.span(),
+                            "[const-type-layout]: Unknown field attribute, use `cache_group`."
+                        );
+                        return Ok(());
+                    }
+
+                    if let Ok(group) = meta
+                        .value()
+                        .and_then(<syn::LitStr as syn::parse::Parse>::parse)
+                    {
+                        field_groups.push((field_name.clone(), group.value()));
+                    } else {
+                        emit_error!(
+                            meta.path.span(),
+                            "[const-type-layout]: Expected #[layout(cache_group = \"<group>\")] \
+                             syntax."
+                        );
+                    }
+
+                    Ok(())
+                })
+                .is_err()
+            {
+                emit_error!(
+                    attr.span(),
+                    "[const-type-layout]: Expected #[layout(cache_group = \"<group>\")] syntax."
+                );
+            }
+        }
+    }
+
+    field_groups
+}
+
 fn intersperse_commas(items: Vec<String>) -> String {
     let mut acc = String::with_capacity(
         items.iter().map(String::len).sum::<usize>() + items.len().saturating_sub(1),
@@ -308,9 +569,48 @@ fn extract_inner_types(data: &syn::Data) -> Vec<&syn::Type> {
     inner_types
 }
 
+fn stable_name_of_type(
+    crate_path: &syn::Path,
+    name: &syn::LitStr,
+    generics: &syn::Generics,
+    type_params: &[&syn::Ident],
+) -> proc_macro2::TokenStream {
+    let params = generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            syn::GenericParam::Lifetime(_) => None,
+            syn::GenericParam::Type(syn::TypeParam { ident, .. }) => {
+                if type_params.contains(&ident) {
+                    Some(quote! { <#ident as #crate_path::TypeLayout>::STABLE_NAME })
+                } else {
+                    Some(quote! { ::core::any::type_name::<#ident>() })
+                }
+            },
+            syn::GenericParam::Const(param) => {
+                emit_error!(
+                    param.span(),
+                    "[const-type-layout]: #[layout(name)] does not support const generic \
+                     parameters."
+                );
+                None
+            },
+        })
+        .collect::<Vec<_>>();
+
+    if params.is_empty() {
+        quote! { #name }
+    } else {
+        quote! {
+            #crate_path::name::compose!(#name; .generic(&[#(#params),*]))
+        }
+    }
+}
+
 struct Generics {
-    type_layout_input_generics: syn::Generics,
-    type_set_input_generics: syn::Generics,
+    layout: syn::Generics,
+    set: syn::Generics,
+    key: syn::Generics,
 }
 
 fn generate_generics(
@@ -321,6 +621,7 @@ fn generate_generics(
 ) -> Generics {
     let mut type_layout_input_generics = generics.clone();
     let mut type_set_input_generics = generics.clone();
+    let mut type_key_input_generics = generics.clone();
 
     for ty in type_params {
         type_layout_input_generics
@@ -336,6 +637,13 @@ fn generate_generics(
             .push(syn::parse_quote! {
                 #ty: #crate_path::typeset::ComputeTypeSet
             });
+
+        type_key_input_generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote! {
+                #ty: #crate_path::typeset::ComputeTypeKey
+            });
     }
 
     for bound in extra_bounds {
@@ -348,11 +656,17 @@ fn generate_generics(
             .make_where_clause()
             .predicates
             .push(bound.clone());
+
+        type_key_input_generics
+            .make_where_clause()
+            .predicates
+            .push(bound.clone());
     }
 
     Generics {
-        type_layout_input_generics,
-        type_set_input_generics,
+        layout: type_layout_input_generics,
+        set: type_set_input_generics,
+        key: type_key_input_generics,
     }
 }
 
@@ -419,7 +733,7 @@ fn quote_structlike_fields(
                     #crate_path::Field {
                         name: #field_name_str,
                         offset: { #field_offset },
-                        ty: ::core::any::type_name::<#field_ty>(),
+                        ty: <#field_ty as #crate_path::TypeLayout>::STABLE_NAME,
                     }
                 }
             })
@@ -444,7 +758,7 @@ fn quote_structlike_fields(
                     #crate_path::Field {
                         name: #field_name_str,
                         offset: { #field_offset },
-                        ty: ::core::any::type_name::<#field_ty>(),
+                        ty: <#field_ty as #crate_path::TypeLayout>::STABLE_NAME,
                     }
                 }
             })
@@ -575,7 +889,7 @@ fn quote_variant_fields(
                     #crate_path::Field {
                         name: #field_name_str,
                         offset: #offset,
-                        ty: ::core::any::type_name::<#field_ty>(),
+                        ty: <#field_ty as #crate_path::TypeLayout>::STABLE_NAME,
                     }
                 }
             })
@@ -603,7 +917,7 @@ fn quote_variant_fields(
                     #crate_path::Field {
                         name: #field_name_str,
                         offset: #offset,
-                        ty: ::core::any::type_name::<#field_ty>(),
+                        ty: <#field_ty as #crate_path::TypeLayout>::STABLE_NAME,
                     }
                 }
             })
@@ -0,0 +1,658 @@
+//! Helper module to deserialise [`TypeLayoutGraph`]s from the binary format
+//! that is produced by [`TypeLayoutGraph::serialise`].
+//!
+//! The deserialised graphs borrow their names from the serialised bytes and
+//! allocate their fields, variants, and type lists inside an [`Arena`], such
+//! that they have the same shape as the `'static` graphs that are computed at
+//! compile time, e.g. [`const_type_layout::TypeGraphLayout::TYPE_GRAPH`].
+
+use std::{fmt, marker::PhantomData, str::Utf8Error};
+
+use bumpalo::Bump;
+use const_type_layout::{
+    wire, Discriminant, Field, MaybeUninhabited, TypeLayoutGraph, TypeLayoutInfo, TypeStructure,
+    Variant,
+};
+
+#[derive(Default)]
+/// Arena that owns the names, fields, variants, and type lists of deserialised
+/// [`TypeLayoutGraph`]s.
+pub struct Arena {
+    bump: Bump,
+}
+
+impl Arena {
+    #[must_use]
+    /// Create a new empty arena.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn alloc<T: Copy>(&self, value: T) -> &T {
+        self.bump.alloc(value)
+    }
+
+    pub(crate) fn alloc_slice<T: Copy>(&self, values: &[T]) -> &[T] {
+        self.bump.alloc_slice_copy(values)
+    }
+
+    pub(crate) fn alloc_str(&self, value: &str) -> &str {
+        self.bump.alloc_str(value)
+    }
+}
+
+impl fmt::Debug for Arena {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Arena")
+            .field("allocated", &self.bump.allocated_bytes())
+            .finish()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Error that occurs when bytes cannot be deserialised into a
+/// [`TypeLayoutGraph`].
+pub enum Error {
+    /// The bytes ended before the graph was complete.
+    UnexpectedEnd,
+    /// A length or number does not fit into a [`usize`].
+    Overflow,
+    /// A name is not valid UTF-8.
+    Utf8(Utf8Error),
+    /// A type structure has an unknown tag.
+    InvalidStructure(u8),
+    /// A field offset or variant discriminant has an unknown inhabitedness
+    /// tag.
+    InvalidInhabited(u8),
+    /// A type or string reference points outside of the type table or to a
+    /// string that has not been deserialised yet.
+    InvalidReference(usize),
+    /// The graph was serialised in a newer version of the binary format, see
+    /// [`const_type_layout::wire`].
+    UnsupportedVersion(usize),
+    /// The graph's length prefix does not match the number of bytes that
+    /// the graph occupies.
+    LengthMismatch {
+        /// The length that is stored in the length prefix.
+        expected: usize,
+        /// The number of bytes that the graph occupies.
+        found: usize,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnexpectedEnd => fmt.write_str("unexpected end of the serialised graph"),
+            Self::Overflow => fmt.write_str("serialised number overflows usize"),
+            Self::Utf8(err) => fmt.write_fmt(format_args!("invalid serialised name: {err}")),
+            Self::InvalidStructure(tag) => {
+                fmt.write_fmt(format_args!("invalid type structure tag {tag:#04x}"))
+            },
+            Self::InvalidInhabited(tag) => {
+                fmt.write_fmt(format_args!("invalid inhabitedness tag {tag:#04x}"))
+            },
+            Self::InvalidReference(reference) => {
+                fmt.write_fmt(format_args!("invalid type or string reference {reference}"))
+            },
+            Self::UnsupportedVersion(version) => fmt.write_fmt(format_args!(
+                "unsupported serialised format version {version}, at most version {} is supported",
+                wire::VERSION
+            )),
+            Self::LengthMismatch { expected, found } => fmt.write_fmt(format_args!(
+                "serialised graph has length {found} but claims length {expected}"
+            )),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Utf8(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// [`TypeLayoutGraph`] that was deserialised by [`deserialise`].
+pub struct Deserialised<'a> {
+    /// The version of the binary format in which the graph was serialised.
+    pub format: usize,
+    /// The version of the `const-type-layout` crate that serialised the graph,
+    /// which is only stored in the unversioned format 0.
+    pub version: Option<&'a str>,
+    /// The deserialised graph.
+    pub graph: TypeLayoutGraph<'a>,
+}
+
+/// Deserialise one [`TypeLayoutGraph`] from the start of the `bytes`, which
+/// were produced by [`TypeLayoutGraph::serialise`] or
+/// [`TypeLayoutGraph::serialise_compact`], and return it together with the
+/// number of bytes that it occupied.
+///
+/// All versions of the binary format up to [`wire::VERSION`] can be
+/// deserialised, including the unversioned format 0.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(const_type_name)]
+/// use const_type_layout::{serialised_type_graph_len, TypeGraphLayout};
+/// use const_type_layout_tools::deserialise::{deserialise, Arena};
+///
+/// type Ty = Option<&'static u32>;
+///
+/// let mut bytes = [0_u8; serialised_type_graph_len::<Ty>()];
+/// Ty::TYPE_GRAPH.serialise(&mut bytes);
+///
+/// let arena = Arena::new();
+/// let (deserialised, len) = deserialise(&bytes, &arena).unwrap();
+///
+/// assert_eq!(deserialised.graph, Ty::TYPE_GRAPH);
+/// assert_eq!(len, bytes.len());
+/// ```
+///
+/// # Errors
+///
+/// Returns an [`Error`] if the `bytes` do not start with a valid serialised
+/// [`TypeLayoutGraph`].
+pub fn deserialise<'a>(
+    bytes: &'a [u8],
+    arena: &'a Arena,
+) -> Result<(Deserialised<'a>, usize), Error> {
+    let mut deserialiser = Deserialiser {
+        bytes,
+        cursor: 0,
+        arena,
+        format: 0,
+        tys: &[],
+        strings: Vec::new(),
+    };
+
+    let (format, len) = if bytes.starts_with(&wire::MAGIC) {
+        deserialiser.cursor = wire::MAGIC.len();
+
+        let format = deserialiser.usize()?;
+
+        if format > wire::VERSION {
+            return Err(Error::UnsupportedVersion(format));
+        }
+
+        (format, deserialiser.usize()?)
+    } else {
+        (0, deserialiser.usize()?)
+    };
+
+    if len > bytes.len() {
+        return Err(Error::UnexpectedEnd);
+    }
+
+    deserialiser.bytes = &bytes[..len];
+    deserialiser.format = format;
+
+    let version = match format {
+        0 => Some(deserialiser.str()?),
+        _ => None,
+    };
+    let graph = deserialiser.graph()?;
+
+    if deserialiser.cursor != len {
+        return Err(Error::LengthMismatch {
+            expected: len,
+            found: deserialiser.cursor,
+        });
+    }
+
+    Ok((
+        Deserialised {
+            format,
+            version,
+            graph,
+        },
+        len,
+    ))
+}
+
+struct Deserialiser<'a> {
+    bytes: &'a [u8],
+    cursor: usize,
+    arena: &'a Arena,
+    format: usize,
+    /// The type table of the compact format 2.
+    tys: &'a [&'a str],
+    /// The string slots of the compact format 2.
+    strings: Vec<&'a str>,
+}
+
+impl<'a> Deserialiser<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let end = self.cursor.checked_add(len).ok_or(Error::Overflow)?;
+        let bytes = self
+            .bytes
+            .get(self.cursor..end)
+            .ok_or(Error::UnexpectedEnd)?;
+        self.cursor = end;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> Result<u8, Error> {
+        let byte = *self.bytes.get(self.cursor).ok_or(Error::UnexpectedEnd)?;
+        self.cursor += 1;
+        Ok(byte)
+    }
+
+    fn usize(&mut self) -> Result<usize, Error> {
+        let mut value = 0_usize;
+        let mut shift = 0_u32;
+
+        loop {
+            let byte = self.byte()?;
+
+            let bits = usize::from(byte & 0b0111_1111_u8);
+
+            if shift >= usize::BITS || ((bits << shift) >> shift) != bits {
+                return Err(Error::Overflow);
+            }
+
+            value |= bits << shift;
+            shift += 7;
+
+            if (byte & 0b1000_0000_u8) == 0 {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn str(&mut self) -> Result<&'a str, Error> {
+        let len = self.usize()?;
+        std::str::from_utf8(self.bytes(len)?).map_err(Error::Utf8)
+    }
+
+    fn string_ref(&mut self) -> Result<&'a str, Error> {
+        if self.format < 2 {
+            return self.str();
+        }
+
+        let string = match self.usize()? {
+            0 => self.str()?,
+            reference => *self
+                .strings
+                .get(reference - 1)
+                .ok_or(Error::InvalidReference(reference))?,
+        };
+
+        self.strings.push(string);
+
+        Ok(string)
+    }
+
+    fn type_ref(&mut self) -> Result<&'a str, Error> {
+        if self.format < 2 {
+            return self.str();
+        }
+
+        match self.usize()? {
+            reference if reference < self.tys.len() => Ok(self.tys[reference]),
+            reference if reference == self.tys.len() => self.str(),
+            reference => Err(Error::InvalidReference(reference)),
+        }
+    }
+
+    fn inhabited(&mut self) -> Result<bool, Error> {
+        match self.byte()? {
+            b'h' => Ok(true),
+            b'n' => Ok(false),
+            tag => Err(Error::InvalidInhabited(tag)),
+        }
+    }
+
+    fn field(&mut self) -> Result<Field<'a>, Error> {
+        let name = self.string_ref()?;
+        let offset = if self.inhabited()? {
+            MaybeUninhabited::Inhabited(self.usize()?)
+        } else {
+            MaybeUninhabited::Uninhabited
+        };
+        let ty = self.type_ref()?;
+
+        Ok(Field { name, offset, ty })
+    }
+
+    fn fields(&mut self) -> Result<&'a [Field<'a>], Error> {
+        let len = self.usize()?;
+
+        let fields = (0..len)
+            .map(|_| self.field())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(self.arena.alloc_slice(&fields))
+    }
+
+    fn variant(&mut self) -> Result<Variant<'a>, Error> {
+        let name = self.string_ref()?;
+        let discriminant = if self.inhabited()? {
+            let len = self.usize()?;
+
+            MaybeUninhabited::Inhabited(Discriminant {
+                value: self.bytes(len)?,
+                _marker: PhantomData,
+            })
+        } else {
+            MaybeUninhabited::Uninhabited
+        };
+        let fields = self.fields()?;
+
+        Ok(Variant {
+            name,
+            discriminant,
+            fields,
+        })
+    }
+
+    fn variants(&mut self) -> Result<&'a [Variant<'a>], Error> {
+        let len = self.usize()?;
+
+        let variants = (0..len)
+            .map(|_| self.variant())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(self.arena.alloc_slice(&variants))
+    }
+
+    fn structure(&mut self) -> Result<TypeStructure<'a>, Error> {
+        match self.byte()? {
+            b'p' => Ok(TypeStructure::Primitive),
+            b's' => Ok(TypeStructure::Struct {
+                repr: self.string_ref()?,
+                fields: self.fields()?,
+            }),
+            b'u' => Ok(TypeStructure::Union {
+                repr: self.string_ref()?,
+                fields: self.fields()?,
+            }),
+            b'e' => Ok(TypeStructure::Enum {
+                repr: self.string_ref()?,
+                variants: self.variants()?,
+            }),
+            tag => Err(Error::InvalidStructure(tag)),
+        }
+    }
+
+    fn layout(&mut self, name: &'a str) -> Result<&'a TypeLayoutInfo<'a>, Error> {
+        let layout = TypeLayoutInfo {
+            name,
+            size: self.usize()?,
+            alignment: self.usize()?,
+            structure: self.structure()?,
+        };
+
+        Ok(self.arena.alloc(layout))
+    }
+
+    fn graph(&mut self) -> Result<TypeLayoutGraph<'a>, Error> {
+        if self.format >= 2 {
+            return self.compact_graph();
+        }
+
+        let ty = self.str()?;
+
+        let len = self.usize()?;
+
+        let tys = (0..len)
+            .map(|_| {
+                let name = self.str()?;
+                self.layout(name)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(TypeLayoutGraph {
+            ty,
+            tys: self.arena.alloc_slice(&tys),
+        })
+    }
+
+    fn compact_graph(&mut self) -> Result<TypeLayoutGraph<'a>, Error> {
+        let len = self.usize()?;
+
+        let names = (0..len)
+            .map(|_| self.str())
+            .collect::<Result<Vec<_>, _>>()?;
+        self.tys = self.arena.alloc_slice(&names);
+
+        let ty = self.type_ref()?;
+
+        let tys = self
+            .tys
+            .iter()
+            .map(|name| self.layout(name))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(TypeLayoutGraph {
+            ty,
+            tys: self.arena.alloc_slice(&tys),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use const_type_layout::{
+        wire, Field, MaybeUninhabited, TypeGraphLayout, TypeLayoutGraph, TypeStructure,
+    };
+
+    use super::{deserialise, Arena, Error};
+
+    fn leb128(mut value: usize, bytes: &mut Vec<u8>) {
+        while value > 0x7F {
+            #[allow(clippy::cast_possible_truncation)]
+            bytes.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+
+        #[allow(clippy::cast_possible_truncation)]
+        bytes.push(value as u8);
+    }
+
+    fn string(value: &str, bytes: &mut Vec<u8>) {
+        leb128(value.len(), bytes);
+        bytes.extend_from_slice(value.as_bytes());
+    }
+
+    fn fields(fields: &[Field], bytes: &mut Vec<u8>) {
+        leb128(fields.len(), bytes);
+
+        for field in fields {
+            string(field.name, bytes);
+            match field.offset {
+                MaybeUninhabited::Inhabited(offset) => {
+                    bytes.push(b'h');
+                    leb128(offset, bytes);
+                },
+                MaybeUninhabited::Uninhabited => bytes.push(b'n'),
+            }
+            string(field.ty, bytes);
+        }
+    }
+
+    /// Serialise the `graph` in its canonical form without deduplicated
+    /// strings, which is the body of the format 1.
+    fn canonical(graph: &TypeLayoutGraph) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        string(graph.ty, &mut bytes);
+        leb128(graph.tys.len(), &mut bytes);
+
+        for ty in graph.tys {
+            string(ty.name, &mut bytes);
+            leb128(ty.size, &mut bytes);
+            leb128(ty.alignment, &mut bytes);
+
+            match ty.structure {
+                TypeStructure::Primitive => bytes.push(b'p'),
+                TypeStructure::Struct { repr, fields: fs } => {
+                    bytes.push(b's');
+                    string(repr, &mut bytes);
+                    fields(fs, &mut bytes);
+                },
+                TypeStructure::Union { repr, fields: fs } => {
+                    bytes.push(b'u');
+                    string(repr, &mut bytes);
+                    fields(fs, &mut bytes);
+                },
+                TypeStructure::Enum { repr, variants } => {
+                    bytes.push(b'e');
+                    string(repr, &mut bytes);
+                    leb128(variants.len(), &mut bytes);
+
+                    for variant in variants {
+                        string(variant.name, &mut bytes);
+                        match variant.discriminant {
+                            MaybeUninhabited::Inhabited(discriminant) => {
+                                bytes.push(b'h');
+                                let len = discriminant
+                                    .value
+                                    .iter()
+                                    .rposition(|byte| *byte != 0)
+                                    .map_or(0, |i| i + 1);
+                                leb128(len, &mut bytes);
+                                bytes.extend_from_slice(&discriminant.value[..len]);
+                            },
+                            MaybeUninhabited::Uninhabited => bytes.push(b'n'),
+                        }
+                        fields(variant.fields, &mut bytes);
+                    }
+                },
+            }
+        }
+
+        bytes
+    }
+
+    /// Prefix the `body` with its length, which includes the length of the
+    /// prefix itself.
+    fn length_prefixed(mut prefix: Vec<u8>, body: &[u8]) -> Vec<u8> {
+        let header_len = prefix.len();
+        let mut prefix_len = 1;
+
+        loop {
+            prefix.truncate(header_len);
+            leb128(header_len + prefix_len + body.len(), &mut prefix);
+
+            if prefix.len() == header_len + prefix_len {
+                break;
+            }
+
+            prefix_len = prefix.len() - header_len;
+        }
+
+        prefix.extend_from_slice(body);
+        prefix
+    }
+
+    fn serialise<T: TypeGraphLayout>() -> Vec<u8> {
+        let mut bytes = vec![0; T::TYPE_GRAPH.serialised_len()];
+        T::TYPE_GRAPH.serialise(&mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn current_format() {
+        let bytes = serialise::<Option<core::ops::Range<u16>>>();
+
+        let arena = Arena::new();
+        let (deserialised, len) = deserialise(&bytes, &arena).unwrap();
+
+        assert_eq!(deserialised.format, wire::CANONICAL_VERSION);
+        assert_eq!(deserialised.version, None);
+        assert_eq!(
+            deserialised.graph,
+            <Option<core::ops::Range<u16>>>::TYPE_GRAPH
+        );
+        assert_eq!(len, bytes.len());
+    }
+
+    #[test]
+    fn compact_format() {
+        type Ty = Result<Option<core::ops::Range<u16>>, core::ops::Range<u16>>;
+
+        let mut bytes = vec![0; Ty::TYPE_GRAPH.serialised_compact_len()];
+        Ty::TYPE_GRAPH.serialise_compact(&mut bytes);
+
+        // the compact format deduplicates the repeated names
+        assert!(bytes.len() < serialise::<Ty>().len());
+
+        let arena = Arena::new();
+        let (deserialised, len) = deserialise(&bytes, &arena).unwrap();
+
+        assert_eq!(deserialised.format, wire::COMPACT_VERSION);
+        assert_eq!(deserialised.graph, Ty::TYPE_GRAPH);
+        assert_eq!(len, bytes.len());
+    }
+
+    #[test]
+    fn canonical_format() {
+        let mut header = wire::MAGIC.to_vec();
+        leb128(1, &mut header);
+        let bytes = length_prefixed(header, &canonical(&<Result<u8, i64>>::TYPE_GRAPH));
+
+        let arena = Arena::new();
+        let (deserialised, len) = deserialise(&bytes, &arena).unwrap();
+
+        assert_eq!(bytes, serialise::<Result<u8, i64>>());
+        assert_eq!(deserialised.format, 1);
+        assert_eq!(deserialised.version, None);
+        assert_eq!(deserialised.graph, <Result<u8, i64>>::TYPE_GRAPH);
+        assert_eq!(len, bytes.len());
+    }
+
+    #[test]
+    fn unversioned_format() {
+        // format 0 starts with its length and the crate version
+        let version = "0.3.2";
+        let mut body = Vec::new();
+        string(version, &mut body);
+        body.extend_from_slice(&canonical(&<Result<u8, i64>>::TYPE_GRAPH));
+        let legacy = length_prefixed(Vec::new(), &body);
+
+        let arena = Arena::new();
+        let (deserialised, len) = deserialise(&legacy, &arena).unwrap();
+
+        assert_eq!(deserialised.format, 0);
+        assert_eq!(deserialised.version, Some(version));
+        assert_eq!(deserialised.graph, <Result<u8, i64>>::TYPE_GRAPH);
+        assert_eq!(len, legacy.len());
+    }
+
+    #[test]
+    fn invalid_reference() {
+        let mut body = Vec::new();
+        // a type table with one type
+        leb128(1, &mut body);
+        string("u8", &mut body);
+        // a root type reference past the inline reference
+        leb128(2, &mut body);
+
+        let mut header = wire::MAGIC.to_vec();
+        leb128(2, &mut header);
+        let bytes = length_prefixed(header, &body);
+
+        assert_eq!(
+            deserialise(&bytes, &Arena::new()),
+            Err(Error::InvalidReference(2))
+        );
+    }
+
+    #[test]
+    fn future_format() {
+        let mut bytes = wire::MAGIC.to_vec();
+        leb128(wire::VERSION + 1, &mut bytes);
+        leb128(bytes.len() + 1, &mut bytes);
+
+        assert_eq!(
+            deserialise(&bytes, &Arena::new()),
+            Err(Error::UnsupportedVersion(wire::VERSION + 1))
+        );
+    }
+}
@@ -0,0 +1,713 @@
+//! Helper module to cross-check the layouts inside a [`TypeLayoutGraph`]
+//! against the DWARF debug information that a compiler emitted.
+//!
+//! Hand-written and remote [`const_type_layout::TypeLayout`] implementations
+//! can thus be validated against the compiler's ground truth, and the layouts
+//! of Rust types can be compared with the layouts of the C types that a
+//! library compiled with `-g` uses at its FFI boundary.
+
+use std::{borrow::Cow, collections::HashMap, fmt, fs, io, path::Path};
+
+use const_type_layout::{
+    diff::Difference, Field, MaybeUninhabited, TypeLayoutGraph, TypeLayoutInfo, TypeStructure,
+};
+use gimli::{AttributeValue, EndianSlice, RunTimeEndian, UnitOffset};
+use object::{Object, ObjectSection};
+
+#[derive(Debug)]
+/// Error that occurs when DWARF debug information cannot be read.
+pub enum Error {
+    /// The file could not be read.
+    Io(io::Error),
+    /// The file is not a supported object file.
+    Object(object::read::Error),
+    /// The DWARF debug information is malformed.
+    Dwarf(gimli::Error),
+    /// The location of the data member `member` is neither a constant offset
+    /// nor a `DW_OP_plus_uconst` expression.
+    UnsupportedLocation {
+        /// The name of the data member.
+        member: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(err) => fmt.write_fmt(format_args!("failed to read the file: {err}")),
+            Self::Object(err) => fmt.write_fmt(format_args!("failed to parse the object: {err}")),
+            Self::Dwarf(err) => fmt.write_fmt(format_args!("failed to parse the DWARF: {err}")),
+            Self::UnsupportedLocation { member } => fmt.write_fmt(format_args!(
+                "unsupported location of the data member `{member}`"
+            )),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Object(err) => Some(err),
+            Self::Dwarf(err) => Some(err),
+            Self::UnsupportedLocation { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<object::read::Error> for Error {
+    fn from(err: object::read::Error) -> Self {
+        Self::Object(err)
+    }
+}
+
+impl From<gimli::Error> for Error {
+    fn from(err: gimli::Error) -> Self {
+        Self::Dwarf(err)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Layout of a named type, as described by DWARF debug information.
+pub struct Type {
+    /// The type's qualified name, e.g. `core::option::Option<u8>` for a Rust
+    /// type or `foo` for a C type.
+    pub name: String,
+    /// The type's size, if known.
+    pub size: Option<u64>,
+    /// The type's alignment, if known.
+    pub alignment: Option<u64>,
+    /// The type's data members, for structs and unions.
+    pub members: Vec<Member>,
+    /// The type's variants, for Rust enums with fields.
+    pub variants: Vec<Variant>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Data member of a struct, union, or enum variant.
+pub struct Member {
+    /// The member's name, where the `__0` names of Rust tuple fields are
+    /// normalised to `0`.
+    pub name: String,
+    /// The member's byte offset from the start of the type that contains it,
+    /// i.e. of its struct, union, or enum variant.
+    pub offset: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Variant of a Rust enum.
+pub struct Variant {
+    /// The variant's name.
+    pub name: String,
+    /// The variant's data members.
+    pub members: Vec<Member>,
+}
+
+#[derive(Clone, Debug, Default)]
+/// Named types that are described by the DWARF debug information of a binary
+/// or object file.
+pub struct DebugInfo {
+    types: HashMap<String, Type>,
+    /// Qualified names of the types, indexed by their unqualified names
+    unqualified: HashMap<String, String>,
+}
+
+impl DebugInfo {
+    /// Read the DWARF debug information from the binary or object file at
+    /// `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the file cannot be read, is not an object file,
+    /// or contains malformed DWARF debug information.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::parse(&fs::read(path)?)
+    }
+
+    /// Read the DWARF debug information from the bytes of a binary or object
+    /// file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the bytes are not an object file or contain
+    /// malformed DWARF debug information.
+    pub fn parse(data: &[u8]) -> Result<Self, Error> {
+        let object = object::File::parse(data)?;
+
+        let endian = if object.is_little_endian() {
+            RunTimeEndian::Little
+        } else {
+            RunTimeEndian::Big
+        };
+
+        let sections = gimli::DwarfSections::load(|id| -> Result<Cow<[u8]>, Error> {
+            match object.section_by_name(id.name()) {
+                Some(section) => Ok(section.uncompressed_data()?),
+                None => Ok(Cow::Borrowed(&[])),
+            }
+        })?;
+        let dwarf = sections.borrow(|section| EndianSlice::new(section, endian));
+
+        let mut this = Self::default();
+
+        let mut units = dwarf.units();
+
+        while let Some(header) = units.next()? {
+            let unit = dwarf.unit(header)?;
+
+            let mut entries = HashMap::new();
+            let mut tree = unit.entries_tree(None)?;
+            collect_entries(&dwarf, &unit, tree.root()?, &mut Vec::new(), &mut entries)?;
+
+            this.insert_unit_types(&entries);
+        }
+
+        Ok(this)
+    }
+
+    #[must_use]
+    /// Returns the layout of the type with the `name`.
+    ///
+    /// The `name` is first looked up as a qualified name, e.g.
+    /// `core::option::Option<u8>`. If no such type exists, the unqualified
+    /// name, e.g. `Option<u8>`, is looked up among the types that are not
+    /// inside any namespace, e.g. the types of a C library.
+    pub fn get(&self, name: &str) -> Option<&Type> {
+        self.types.get(name).or_else(|| {
+            self.unqualified
+                .get(unqualified_name(name))
+                .and_then(|name| self.types.get(name))
+        })
+    }
+
+    #[must_use]
+    /// Compare the layouts of all types in the `graph` (before) with their
+    /// layouts in the DWARF debug information (after).
+    ///
+    /// The following differences are reported:
+    /// - [`Difference::Removed`] if a struct, union, or enum is not described
+    ///   by the debug information, while missing primitive types are ignored
+    /// - [`Difference::Size`] and [`Difference::Alignment`] if the debug
+    ///   information describes a different size or alignment
+    /// - [`Difference::FieldRemoved`], [`Difference::FieldAdded`], and
+    ///   [`Difference::FieldOffset`] if the fields of a struct, union, or enum
+    ///   variant differ, where fields are matched by their names
+    /// - [`Difference::VariantRemoved`] if an enum variant with fields is not
+    ///   described by the debug information
+    pub fn compare<'a>(&'a self, graph: &TypeLayoutGraph<'a>) -> Vec<Difference<'a>> {
+        let mut differences = Vec::new();
+
+        for layout in graph.tys {
+            self.compare_type(layout, &mut differences);
+        }
+
+        differences
+    }
+
+    fn compare_type<'a>(
+        &'a self,
+        layout: &TypeLayoutInfo<'a>,
+        differences: &mut Vec<Difference<'a>>,
+    ) {
+        let ty = layout.name;
+
+        let Some(dwarf) = self.get(ty) else {
+            if !matches!(layout.structure, TypeStructure::Primitive) {
+                differences.push(Difference::Removed { ty });
+            }
+            return;
+        };
+
+        if let Some(size) = dwarf.size.and_then(|size| usize::try_from(size).ok()) {
+            if size != layout.size {
+                differences.push(Difference::Size {
+                    ty,
+                    before: layout.size,
+                    after: size,
+                });
+            }
+        }
+
+        if let Some(alignment) = dwarf
+            .alignment
+            .and_then(|align| usize::try_from(align).ok())
+        {
+            if alignment != layout.alignment {
+                differences.push(Difference::Alignment {
+                    ty,
+                    before: layout.alignment,
+                    after: alignment,
+                });
+            }
+        }
+
+        match &layout.structure {
+            TypeStructure::Primitive => (),
+            TypeStructure::Struct { fields, .. } | TypeStructure::Union { fields, .. } => {
+                compare_fields(ty, None, fields, &dwarf.members, differences);
+            },
+            TypeStructure::Enum { variants, .. } => {
+                for variant in *variants {
+                    // field-less variants may not be described as separate structs
+                    if variant.fields.is_empty() {
+                        continue;
+                    }
+
+                    match dwarf
+                        .variants
+                        .iter()
+                        .find(|other| other.name == variant.name)
+                    {
+                        Some(other) => compare_fields(
+                            ty,
+                            Some(variant.name),
+                            variant.fields,
+                            &other.members,
+                            differences,
+                        ),
+                        None => differences.push(Difference::VariantRemoved {
+                            ty,
+                            variant: variant.name,
+                        }),
+                    }
+                }
+            },
+        }
+    }
+
+    fn insert_unit_types(&mut self, entries: &HashMap<UnitOffset, Entry>) {
+        for entry in entries.values() {
+            let Some(name) = &entry.name else {
+                continue;
+            };
+
+            let Some(resolved) = resolve_typedefs(entries, entry) else {
+                continue;
+            };
+
+            if resolved.declaration {
+                continue;
+            }
+
+            // prefer complete definitions over earlier forward declarations
+            if self
+                .types
+                .get(name)
+                .map_or(false, |existing| existing.size.is_some())
+            {
+                continue;
+            }
+
+            let variants = resolved
+                .variants
+                .iter()
+                .map(|(variant, offset)| Variant {
+                    name: variant.clone(),
+                    members: entries
+                        .get(offset)
+                        .map(|entry| entry.members.clone())
+                        .unwrap_or_default(),
+                })
+                .collect();
+
+            if !entry.namespaced {
+                self.unqualified.insert(name.clone(), name.clone());
+            }
+
+            self.types.insert(
+                name.clone(),
+                Type {
+                    name: name.clone(),
+                    size: resolved.size,
+                    alignment: resolved.alignment,
+                    members: resolved.members.clone(),
+                    variants,
+                },
+            );
+        }
+    }
+}
+
+fn compare_fields<'a>(
+    ty: &'a str,
+    variant: Option<&'a str>,
+    fields: &[Field<'a>],
+    members: &'a [Member],
+    differences: &mut Vec<Difference<'a>>,
+) {
+    for field in fields {
+        let Some(member) = members.iter().find(|member| member.name == field.name) else {
+            differences.push(Difference::FieldRemoved {
+                ty,
+                variant,
+                field: field.name,
+            });
+            continue;
+        };
+
+        let offset = usize::try_from(member.offset).unwrap_or(usize::MAX);
+
+        if let MaybeUninhabited::Inhabited(field_offset) = field.offset {
+            if field_offset != offset {
+                differences.push(Difference::FieldOffset {
+                    ty,
+                    variant,
+                    field: field.name,
+                    before: field.offset,
+                    after: MaybeUninhabited::Inhabited(offset),
+                });
+            }
+        }
+    }
+
+    for member in members {
+        if !fields.iter().any(|field| field.name == member.name) {
+            differences.push(Difference::FieldAdded {
+                ty,
+                variant,
+                field: &member.name,
+            });
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+/// Type-related debug information entry of a single compilation unit.
+struct Entry {
+    name: Option<String>,
+    namespaced: bool,
+    declaration: bool,
+    size: Option<u64>,
+    alignment: Option<u64>,
+    members: Vec<Member>,
+    variants: Vec<(String, UnitOffset)>,
+    typedef: Option<UnitOffset>,
+}
+
+type Reader<'a> = EndianSlice<'a, RunTimeEndian>;
+
+fn collect_entries(
+    dwarf: &gimli::Dwarf<Reader>,
+    unit: &gimli::Unit<Reader>,
+    node: gimli::EntriesTreeNode<Reader>,
+    namespace: &mut Vec<String>,
+    entries: &mut HashMap<UnitOffset, Entry>,
+) -> Result<(), Error> {
+    let die = node.entry();
+    let tag = die.tag();
+
+    let name = match die.attr_value(gimli::DW_AT_name)? {
+        Some(name) => Some(
+            dwarf
+                .attr_string(unit, name)?
+                .to_string_lossy()
+                .into_owned(),
+        ),
+        None => None,
+    };
+
+    let is_type = matches!(
+        tag,
+        gimli::DW_TAG_structure_type
+            | gimli::DW_TAG_union_type
+            | gimli::DW_TAG_enumeration_type
+            | gimli::DW_TAG_class_type
+            | gimli::DW_TAG_base_type
+            | gimli::DW_TAG_pointer_type
+            | gimli::DW_TAG_typedef
+    );
+
+    if is_type {
+        let mut entry = Entry {
+            name: name.as_ref().map(|name| qualified_name(namespace, name)),
+            namespaced: !namespace.is_empty(),
+            declaration: matches!(
+                die.attr_value(gimli::DW_AT_declaration)?,
+                Some(AttributeValue::Flag(true))
+            ),
+            size: udata(die.attr_value(gimli::DW_AT_byte_size)?),
+            alignment: udata(die.attr_value(gimli::DW_AT_alignment)?),
+            ..Entry::default()
+        };
+
+        if tag == gimli::DW_TAG_typedef {
+            entry.typedef = unit_ref(die.attr_value(gimli::DW_AT_type)?);
+        }
+
+        entries.insert(die.offset(), entry);
+    }
+
+    let offset = die.offset();
+
+    let scope = match (tag, &name) {
+        (gimli::DW_TAG_namespace, Some(name)) => Some(name.clone()),
+        (_, Some(name)) if is_type => Some(name.clone()),
+        _ => None,
+    };
+
+    if let Some(scope) = &scope {
+        namespace.push(scope.clone());
+    }
+
+    let mut children = node.children();
+
+    while let Some(child) = children.next()? {
+        match child.entry().tag() {
+            gimli::DW_TAG_member if is_type => {
+                let member = member(dwarf, unit, child.entry())?;
+
+                if let (Some(entry), Some(member)) = (entries.get_mut(&offset), member) {
+                    entry.members.push(member);
+                }
+            },
+            // Rust enums with fields describe their variants in a variant part
+            gimli::DW_TAG_variant_part if is_type => {
+                let mut variants = child.children();
+
+                while let Some(variant) = variants.next()? {
+                    let mut members = variant.children();
+
+                    while let Some(member) = members.next()? {
+                        let die = member.entry();
+
+                        if die.tag() != gimli::DW_TAG_member {
+                            continue;
+                        }
+
+                        let (Some(name), Some(ty)) = (
+                            die.attr_value(gimli::DW_AT_name)?,
+                            unit_ref(die.attr_value(gimli::DW_AT_type)?),
+                        ) else {
+                            continue;
+                        };
+
+                        let name = dwarf
+                            .attr_string(unit, name)?
+                            .to_string_lossy()
+                            .into_owned();
+
+                        if let Some(entry) = entries.get_mut(&offset) {
+                            entry.variants.push((name, ty));
+                        }
+                    }
+                }
+            },
+            _ => collect_entries(dwarf, unit, child, namespace, entries)?,
+        }
+    }
+
+    if scope.is_some() {
+        namespace.pop();
+    }
+
+    Ok(())
+}
+
+fn member(
+    dwarf: &gimli::Dwarf<Reader>,
+    unit: &gimli::Unit<Reader>,
+    die: &gimli::DebuggingInformationEntry<Reader>,
+) -> Result<Option<Member>, Error> {
+    let Some(name) = die.attr_value(gimli::DW_AT_name)? else {
+        return Ok(None);
+    };
+    let name = dwarf
+        .attr_string(unit, name)?
+        .to_string_lossy()
+        .into_owned();
+
+    let offset = match die.attr_value(gimli::DW_AT_data_member_location)? {
+        // union members have no location and start at offset zero
+        None => 0,
+        Some(location) => match member_location(location, unit.encoding())? {
+            Some(offset) => offset,
+            None => return Err(Error::UnsupportedLocation { member: name }),
+        },
+    };
+
+    Ok(Some(Member {
+        name: normalise_member_name(name),
+        offset,
+    }))
+}
+
+/// Evaluates a `DW_AT_data_member_location`, which is either a constant
+/// offset or, e.g. in DWARF 2, a `DW_OP_plus_uconst` expression that adds the
+/// offset to the address of the containing type.
+fn member_location(
+    location: AttributeValue<Reader>,
+    encoding: gimli::Encoding,
+) -> Result<Option<u64>, gimli::Error> {
+    let expression = match location {
+        AttributeValue::Exprloc(expression) => expression,
+        AttributeValue::Block(block) => gimli::Expression(block),
+        location => return Ok(location.udata_value()),
+    };
+
+    let mut operations = expression.operations(encoding);
+
+    match (operations.next()?, operations.next()?) {
+        (Some(gimli::Operation::PlusConstant { value }), None) => Ok(Some(value)),
+        _ => Ok(None),
+    }
+}
+
+fn udata(value: Option<AttributeValue<Reader>>) -> Option<u64> {
+    value.and_then(|value| value.udata_value())
+}
+
+const fn unit_ref(value: Option<AttributeValue<Reader>>) -> Option<UnitOffset> {
+    match value {
+        Some(AttributeValue::UnitRef(offset)) => Some(offset),
+        _ => None,
+    }
+}
+
+/// Follows a chain of typedefs, e.g. `typedef struct foo { ... } foo_t;`, to
+/// the type that is being named.
+fn resolve_typedefs<'a>(
+    entries: &'a HashMap<UnitOffset, Entry>,
+    mut entry: &'a Entry,
+) -> Option<&'a Entry> {
+    // bound the chain length to guard against malformed cyclic typedefs
+    for _ in 0..entries.len() {
+        match entry.typedef {
+            Some(offset) => entry = entries.get(&offset)?,
+            None => return Some(entry),
+        }
+    }
+
+    None
+}
+
+fn qualified_name(namespace: &[String], name: &str) -> String {
+    let mut qualified = namespace.join("::");
+
+    if !qualified.is_empty() {
+        qualified.push_str("::");
+    }
+
+    qualified.push_str(name);
+    qualified
+}
+
+/// Strips the path from the outermost type's name, e.g. `mycrate::ffi::Foo<T>`
+/// becomes `Foo<T>`.
+fn unqualified_name(name: &str) -> &str {
+    let generics = name.find('<').unwrap_or(name.len());
+
+    name[..generics]
+        .rfind("::")
+        .map_or(name, |separator| &name[(separator + 2)..])
+}
+
+/// Rust names tuple fields `__0`, `__1`, ... in DWARF.
+fn normalise_member_name(name: String) -> String {
+    match name.strip_prefix("__") {
+        Some(index) if !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()) => {
+            String::from(index)
+        },
+        _ => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use const_type_layout::{diff::Difference, TypeGraphLayout};
+
+    use super::DebugInfo;
+
+    type Checked = (
+        core::ops::Range<u32>,
+        Option<&'static u64>,
+        Result<u16, i32>,
+        core::cmp::Ordering,
+        core::num::Wrapping<i128>,
+    );
+
+    static CHECKED: Checked = (
+        3..4,
+        None,
+        Ok(42),
+        core::cmp::Ordering::Less,
+        core::num::Wrapping(24),
+    );
+
+    #[test]
+    fn member_locations() {
+        use gimli::{AttributeValue, EndianSlice, Format, RunTimeEndian};
+
+        let encoding = gimli::Encoding {
+            address_size: 8,
+            format: Format::Dwarf32,
+            version: 2,
+        };
+        let expression = |bytes| EndianSlice::new(bytes, RunTimeEndian::Little);
+
+        assert_eq!(
+            super::member_location(AttributeValue::Udata(24), encoding),
+            Ok(Some(24))
+        );
+        assert_eq!(
+            super::member_location(
+                AttributeValue::Block(expression(&[gimli::DW_OP_plus_uconst.0, 0x90, 0x01])),
+                encoding
+            ),
+            Ok(Some(144))
+        );
+        assert_eq!(
+            super::member_location(
+                AttributeValue::Exprloc(gimli::Expression(expression(&[
+                    gimli::DW_OP_plus_uconst.0,
+                    8
+                ]))),
+                encoding
+            ),
+            Ok(Some(8))
+        );
+        assert_eq!(
+            super::member_location(
+                AttributeValue::Exprloc(gimli::Expression(expression(&[
+                    gimli::DW_OP_plus_uconst.0,
+                    8,
+                    gimli::DW_OP_deref.0
+                ]))),
+                encoding
+            ),
+            Ok(None)
+        );
+        assert_eq!(
+            super::member_location(
+                AttributeValue::Exprloc(gimli::Expression(expression(&[gimli::DW_OP_lit4.0]))),
+                encoding
+            ),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn current_exe() {
+        std::hint::black_box(&CHECKED);
+
+        let debug_info = DebugInfo::open(std::env::current_exe().unwrap()).unwrap();
+
+        assert_eq!(debug_info.compare(&<Checked>::TYPE_GRAPH), Vec::new());
+        assert_eq!(
+            debug_info.compare(&<core::ops::Bound<core::cmp::Reverse<u8>>>::TYPE_GRAPH),
+            vec![
+                Difference::Removed {
+                    ty: "core::ops::range::Bound<core::cmp::Reverse<u8>>"
+                },
+                Difference::Removed {
+                    ty: "core::cmp::Reverse<u8>"
+                },
+            ]
+        );
+    }
+}
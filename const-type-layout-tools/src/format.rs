@@ -0,0 +1,321 @@
+//! Helper module to read and write lists of [`TypeLayoutGraph`]s in the
+//! binary format of [`TypeLayoutGraph::serialise`], as JSON, or as RON, and to
+//! read the graphs that were embedded into binaries, static libraries, and
+//! object files.
+
+use std::{fmt, marker::PhantomData, path::Path};
+
+use const_type_layout::{
+    Discriminant, Field, MaybeUninhabited, OwnedField, OwnedTypeLayoutGraph, OwnedTypeStructure,
+    OwnedVariant, TypeLayoutGraph, TypeLayoutInfo, TypeStructure, Variant,
+};
+use object::FileKind;
+
+use crate::{
+    deserialise::{self, deserialise, Arena},
+    section,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+/// Format in which [`TypeLayoutGraph`]s are stored.
+pub enum Format {
+    /// The binary format of [`TypeLayoutGraph::serialise`], where several
+    /// graphs are concatenated.
+    Binary,
+    /// JSON, where several graphs are stored in an array.
+    Json,
+    /// RON, where several graphs are stored in a list.
+    Ron,
+    /// A binary, static library, or object file, into which the graphs were
+    /// embedded with [`const_type_layout::section::embed_type_graphs!`]. This
+    /// format can only be read.
+    Object,
+}
+
+impl Format {
+    #[must_use]
+    /// Detect the format of the `data` that was read from the file at `path`.
+    ///
+    /// Binaries, static libraries, and object files are detected by their
+    /// contents. Otherwise, the `.json` and `.ron` file extensions are
+    /// detected, and any other file is assumed to use the binary format.
+    pub fn detect(path: &Path, data: &[u8]) -> Self {
+        if FileKind::parse(data).is_ok() {
+            return Self::Object;
+        }
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => Self::Json,
+            Some("ron") => Self::Ron,
+            _ => Self::Binary,
+        }
+    }
+
+    #[must_use]
+    /// Returns the name of the format.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Binary => "binary",
+            Self::Json => "json",
+            Self::Ron => "ron",
+            Self::Object => "object",
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(self.name())
+    }
+}
+
+#[derive(Debug)]
+/// Error that occurs when [`TypeLayoutGraph`]s cannot be read or written.
+pub enum Error {
+    /// The binary format cannot be deserialised.
+    Binary(deserialise::Error),
+    /// The JSON cannot be parsed or written.
+    Json(serde_json::Error),
+    /// The RON cannot be parsed.
+    RonRead(ron::error::SpannedError),
+    /// The RON cannot be written.
+    RonWrite(ron::Error),
+    /// The embedded graphs cannot be extracted.
+    Object(section::Error),
+    /// Graphs cannot be written into a binary, static library, or object file.
+    WriteObject,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Binary(err) => fmt.write_fmt(format_args!("invalid binary graph: {err}")),
+            Self::Json(err) => fmt.write_fmt(format_args!("invalid JSON graph: {err}")),
+            Self::RonRead(err) => fmt.write_fmt(format_args!("invalid RON graph: {err}")),
+            Self::RonWrite(err) => fmt.write_fmt(format_args!("failed to write RON: {err}")),
+            Self::Object(err) => err.fmt(fmt),
+            Self::WriteObject => fmt.write_str("graphs cannot be written into an object file"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Binary(err) => Some(err),
+            Self::Json(err) => Some(err),
+            Self::RonRead(err) => Some(err),
+            Self::RonWrite(err) => Some(err),
+            Self::Object(err) => Some(err),
+            Self::WriteObject => None,
+        }
+    }
+}
+
+/// Read all [`TypeLayoutGraph`]s that are stored in the `format` in `data`.
+///
+/// # Errors
+///
+/// Returns an [`Error`] if the `data` is not valid in the `format`.
+pub fn read<'a>(
+    data: &'a [u8],
+    format: Format,
+    arena: &'a Arena,
+) -> Result<Vec<TypeLayoutGraph<'a>>, Error> {
+    match format {
+        Format::Binary => {
+            let mut graphs = Vec::new();
+            let mut offset = 0;
+
+            while offset < data.len() {
+                let (deserialised, len) =
+                    deserialise(&data[offset..], arena).map_err(Error::Binary)?;
+                graphs.push(deserialised.graph);
+                offset += len;
+            }
+
+            Ok(graphs)
+        },
+        Format::Json if is_list(data) => {
+            let graphs: Vec<OwnedTypeLayoutGraph> =
+                serde_json::from_slice(data).map_err(Error::Json)?;
+            Ok(graphs
+                .iter()
+                .map(|graph| alloc_graph(graph, arena))
+                .collect())
+        },
+        Format::Json => {
+            let graph: OwnedTypeLayoutGraph = serde_json::from_slice(data).map_err(Error::Json)?;
+            Ok(vec![alloc_graph(&graph, arena)])
+        },
+        Format::Ron if is_list(data) => {
+            let graphs: Vec<OwnedTypeLayoutGraph> =
+                ron::de::from_bytes(data).map_err(Error::RonRead)?;
+            Ok(graphs
+                .iter()
+                .map(|graph| alloc_graph(graph, arena))
+                .collect())
+        },
+        Format::Ron => {
+            let graph: OwnedTypeLayoutGraph = ron::de::from_bytes(data).map_err(Error::RonRead)?;
+            Ok(vec![alloc_graph(&graph, arena)])
+        },
+        Format::Object => Ok(section::extract(data, arena)
+            .map_err(Error::Object)?
+            .into_iter()
+            .map(|embedded| embedded.graph.graph)
+            .collect()),
+    }
+}
+
+/// Write the [`TypeLayoutGraph`]s in the `format`.
+///
+/// A single graph is written as a JSON object or RON struct, several graphs
+/// are written as an array or list.
+///
+/// # Errors
+///
+/// Returns an [`Error`] if the graphs cannot be written in the `format`.
+pub fn write(graphs: &[TypeLayoutGraph], format: Format) -> Result<Vec<u8>, Error> {
+    match (format, graphs) {
+        (Format::Binary, graphs) => {
+            let mut bytes = Vec::new();
+
+            for graph in graphs {
+                let offset = bytes.len();
+                bytes.resize(offset + graph.serialised_len(), 0);
+                graph.serialise(&mut bytes[offset..]);
+            }
+
+            Ok(bytes)
+        },
+        (Format::Json, [graph]) => serde_json::to_vec_pretty(graph).map_err(Error::Json),
+        (Format::Json, graphs) => serde_json::to_vec_pretty(graphs).map_err(Error::Json),
+        (Format::Ron, [graph]) => ron::ser::to_string_pretty(graph, ron::ser::PrettyConfig::new())
+            .map(String::into_bytes)
+            .map_err(Error::RonWrite),
+        (Format::Ron, graphs) => ron::ser::to_string_pretty(graphs, ron::ser::PrettyConfig::new())
+            .map(String::into_bytes)
+            .map_err(Error::RonWrite),
+        (Format::Object, _) => Err(Error::WriteObject),
+    }
+}
+
+/// Checks whether the JSON or RON `data` contains a list of graphs.
+fn is_list(data: &[u8]) -> bool {
+    data.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'[')
+}
+
+fn alloc_graph<'a>(graph: &OwnedTypeLayoutGraph, arena: &'a Arena) -> TypeLayoutGraph<'a> {
+    let tys = graph
+        .tys
+        .iter()
+        .map(|layout| {
+            arena.alloc(TypeLayoutInfo {
+                name: arena.alloc_str(&layout.name),
+                size: layout.size,
+                alignment: layout.alignment,
+                structure: match &layout.structure {
+                    OwnedTypeStructure::Primitive => TypeStructure::Primitive,
+                    OwnedTypeStructure::Struct { repr, fields } => TypeStructure::Struct {
+                        repr: arena.alloc_str(repr),
+                        fields: alloc_fields(fields, arena),
+                    },
+                    OwnedTypeStructure::Union { repr, fields } => TypeStructure::Union {
+                        repr: arena.alloc_str(repr),
+                        fields: alloc_fields(fields, arena),
+                    },
+                    OwnedTypeStructure::Enum { repr, variants } => TypeStructure::Enum {
+                        repr: arena.alloc_str(repr),
+                        variants: arena.alloc_slice(
+                            &variants
+                                .iter()
+                                .map(|variant| alloc_variant(variant, arena))
+                                .collect::<Vec<_>>(),
+                        ),
+                    },
+                },
+            })
+        })
+        .collect::<Vec<_>>();
+
+    TypeLayoutGraph {
+        ty: arena.alloc_str(&graph.ty),
+        tys: arena.alloc_slice(&tys),
+    }
+}
+
+fn alloc_variant<'a>(variant: &OwnedVariant, arena: &'a Arena) -> Variant<'a> {
+    Variant {
+        name: arena.alloc_str(&variant.name),
+        discriminant: match &variant.discriminant {
+            MaybeUninhabited::Inhabited(discriminant) => {
+                MaybeUninhabited::Inhabited(Discriminant {
+                    value: arena.alloc_slice(&discriminant.value),
+                    _marker: PhantomData,
+                })
+            },
+            MaybeUninhabited::Uninhabited => MaybeUninhabited::Uninhabited,
+        },
+        fields: alloc_fields(&variant.fields, arena),
+    }
+}
+
+fn alloc_fields<'a>(fields: &[OwnedField], arena: &'a Arena) -> &'a [Field<'a>] {
+    arena.alloc_slice(
+        &fields
+            .iter()
+            .map(|field| Field {
+                name: arena.alloc_str(&field.name),
+                offset: field.offset,
+                ty: arena.alloc_str(&field.ty),
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use const_type_layout::{OwnedTypeLayoutGraph, TypeGraphLayout};
+
+    use super::{read, write, Format};
+    use crate::deserialise::Arena;
+
+    #[test]
+    fn roundtrip() {
+        let graphs = [
+            <Option<&'static core::num::NonZeroU16>>::TYPE_GRAPH,
+            <Result<core::ops::Range<i8>, core::cmp::Ordering>>::TYPE_GRAPH,
+        ];
+
+        for format in [Format::Binary, Format::Json, Format::Ron] {
+            for graphs in [&graphs[..1], &graphs[..]] {
+                let bytes = write(graphs, format).unwrap();
+
+                let arena = Arena::new();
+                assert_eq!(read(&bytes, format, &arena).unwrap(), graphs, "{format}");
+            }
+        }
+    }
+
+    #[test]
+    fn escaped_json() {
+        let graph = <&'static u8>::TYPE_GRAPH;
+
+        let json = String::from_utf8(write(&[graph.clone()], Format::Json).unwrap())
+            .unwrap()
+            .replace('&', "\\u0026");
+        assert!(json.contains("\\u0026"));
+
+        let arena = Arena::new();
+        assert_eq!(
+            read(json.as_bytes(), Format::Json, &arena).unwrap(),
+            [graph.clone()]
+        );
+
+        let owned: OwnedTypeLayoutGraph<'static> =
+            serde_json::from_reader(json.as_bytes()).unwrap();
+        assert_eq!(owned, graph);
+    }
+}
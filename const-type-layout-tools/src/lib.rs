@@ -0,0 +1,27 @@
+//! `const-type-layout-tools` provides std-only tools to cross-check the
+//! [`TypeLayoutGraph`](const_type_layout::TypeLayoutGraph)s produced by the
+//! [`const-type-layout`](const_type_layout) crate against the layouts that a
+//! compiler actually emitted into a binary.
+//!
+//! - The [`dwarf`] module reads the DWARF debug information of an ELF, Mach-O,
+//!   or PE binary or object file, e.g. a Rust binary or a C library that was
+//!   compiled with `-g`, and compares it with a
+//!   [`TypeLayoutGraph`](const_type_layout::TypeLayoutGraph).
+//! - The [`section`] module extracts the graphs that were embedded into a
+//!   binary, static library, or object file with
+//!   [`const_type_layout::section::embed_type_graphs!`], which are decoded by
+//!   the [`deserialise`] module.
+//! - The [`format`](mod@format) module reads and writes graphs in the binary
+//!   format, as JSON, or as RON.
+//!
+//! With the default `cli` feature, the crate also provides the
+//! `const-type-layout` command-line tool, which offers the `show`, `diff`,
+//! `hash`, `convert`, and `check` subcommands to inspect and compare graphs
+//! without writing any Rust.
+
+#![cfg_attr(test, feature(used_with_arg))]
+
+pub mod deserialise;
+pub mod dwarf;
+pub mod format;
+pub mod section;
@@ -0,0 +1,335 @@
+//! `const-type-layout` command-line tool to inspect, visualise, diff, hash,
+//! convert, and check serialised [`TypeLayoutGraph`]s.
+//!
+//! Graphs are read from files in the binary format of
+//! [`TypeLayoutGraph::serialise`], from JSON or RON files, or from binaries,
+//! static libraries, and object files into which they were embedded with
+//! [`const_type_layout::section::embed_type_graphs!`].
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use const_type_layout::{
+    diff,
+    render::{dot::Digraph, pahole::Report},
+    TypeLayoutGraph,
+};
+use const_type_layout_tools::{
+    deserialise::Arena,
+    format::{self, Format},
+};
+
+#[derive(Parser)]
+#[command(version, about)]
+/// Inspect, diff, hash, convert, and check serialised type layout graphs.
+///
+/// Graphs are read from the binary `serialise` format, from JSON or RON, or
+/// from binaries, static libraries, and object files into which they were
+/// embedded.
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the layouts of all types in the graphs as pahole-style tables.
+    Show {
+        #[command(flatten)]
+        input: Input,
+    },
+    /// Print the type dependency graphs in the Graphviz DOT language.
+    Dot {
+        #[command(flatten)]
+        input: Input,
+        /// Connect fields directly to the types behind primitive pointers,
+        /// references, and arrays instead of rendering primitive types.
+        #[arg(long)]
+        collapse_primitives: bool,
+        /// Only render types up to this many edges away from the root type.
+        #[arg(long)]
+        max_depth: Option<usize>,
+    },
+    /// Print the differences between the graphs in two files, and exit with
+    /// a non-zero code if graphs were removed or the layouts of existing types
+    /// changed. Added graphs and types are reported without failing.
+    Diff {
+        #[command(flatten)]
+        before: Input,
+        /// The file with the graphs after the change.
+        after: PathBuf,
+        /// The format of the file after the change, detected by default.
+        #[arg(long, value_enum)]
+        after_format: Option<Format>,
+    },
+    /// Print the hash of every graph.
+    Hash {
+        #[command(flatten)]
+        input: Input,
+        /// The seed of the hash, which is the first half of the key for
+        /// SipHash-128.
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// The hasher to use.
+        #[arg(long, value_enum, default_value_t = Hasher::Fnv1a64)]
+        hasher: Hasher,
+    },
+    /// Convert the graphs into another format.
+    Convert {
+        #[command(flatten)]
+        input: Input,
+        /// The format to convert into.
+        #[arg(long, value_enum)]
+        to: Format,
+        /// The output file, standard output by default.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Check that every graph in a baseline file is unchanged in the input,
+    /// which may contain additional graphs, and that all input graphs are
+    /// internally consistent. Exits with a non-zero code if the check fails.
+    Check {
+        #[command(flatten)]
+        input: Input,
+        /// The file with the baseline graphs.
+        #[arg(long)]
+        baseline: PathBuf,
+        /// Overwrite the baseline with the input graphs instead, keeping the
+        /// baseline's format.
+        #[arg(long)]
+        update: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+/// Hasher for the `hash` subcommand.
+enum Hasher {
+    /// The 64-bit FNV-1a hash, see [`TypeLayoutGraph::hash`].
+    Fnv1a64,
+    /// The 128-bit SipHash-2-4, see [`TypeLayoutGraph::hash128`].
+    Siphash128,
+}
+
+#[derive(Args)]
+struct Input {
+    /// The file with the graphs.
+    input: PathBuf,
+    /// The format of the file, detected by default.
+    #[arg(long, value_enum)]
+    format: Option<Format>,
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse().command) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::from(2)
+        },
+    }
+}
+
+/// Runs the `command` and returns whether it succeeded.
+fn run(command: Command) -> Result<bool, Box<dyn std::error::Error>> {
+    let arena = Arena::new();
+
+    match command {
+        Command::Show { input } => {
+            let data = read_file(&input.input)?;
+            let graphs = read(&input.input, input.format, &data, &arena)?;
+
+            let mut stdout = io::stdout().lock();
+
+            for graph in &graphs {
+                writeln!(
+                    stdout,
+                    "// graph of `{}`\n{}",
+                    graph.ty,
+                    Report::graph(graph)
+                )?;
+            }
+
+            Ok(true)
+        },
+        Command::Dot {
+            input,
+            collapse_primitives,
+            max_depth,
+        } => {
+            let data = read_file(&input.input)?;
+            let graphs = read(&input.input, input.format, &data, &arena)?;
+
+            let mut stdout = io::stdout().lock();
+
+            for graph in &graphs {
+                let digraph = Digraph::new(graph).collapse_primitives(collapse_primitives);
+
+                match max_depth {
+                    Some(max_depth) => write!(stdout, "{}", digraph.with_max_depth(max_depth))?,
+                    None => write!(stdout, "{digraph}")?,
+                }
+            }
+
+            Ok(true)
+        },
+        Command::Diff {
+            before,
+            after,
+            after_format,
+        } => {
+            let before_data = read_file(&before.input)?;
+            let before = read(&before.input, before.format, &before_data, &arena)?;
+            let after_data = read_file(&after)?;
+            let after = read(&after, after_format, &after_data, &arena)?;
+
+            Ok(compare(&before, &after))
+        },
+        Command::Hash {
+            input,
+            seed,
+            hasher,
+        } => {
+            let data = read_file(&input.input)?;
+
+            for graph in read(&input.input, input.format, &data, &arena)? {
+                match hasher {
+                    Hasher::Fnv1a64 => println!("{:#018x}  {}", graph.hash(seed), graph.ty),
+                    Hasher::Siphash128 => {
+                        println!("{:#034x}  {}", graph.hash128(seed, 0), graph.ty);
+                    },
+                }
+            }
+
+            Ok(true)
+        },
+        Command::Convert { input, to, output } => {
+            let data = read_file(&input.input)?;
+            let graphs = read(&input.input, input.format, &data, &arena)?;
+
+            let bytes = format::write(&graphs, to)?;
+
+            match output {
+                Some(output) => fs::write(output, bytes)?,
+                None => io::stdout().lock().write_all(&bytes)?,
+            }
+
+            Ok(true)
+        },
+        Command::Check {
+            input,
+            baseline,
+            update,
+        } => {
+            let data = read_file(&input.input)?;
+            let graphs = read(&input.input, input.format, &data, &arena)?;
+
+            if update {
+                // a missing baseline is created, but other read errors are reported
+                let existing = match fs::read(&baseline) {
+                    Ok(existing) => existing,
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+                    Err(err) => {
+                        return Err(format!("failed to read `{}`: {err}", baseline.display()).into())
+                    },
+                };
+                let format = Format::detect(&baseline, &existing);
+
+                fs::write(&baseline, format::write(&graphs, format)?)?;
+
+                return Ok(true);
+            }
+
+            let baseline_data = read_file(&baseline)?;
+            let baseline = read(&baseline, None, &baseline_data, &arena)?;
+
+            Ok(check(&graphs, &baseline))
+        },
+    }
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, String> {
+    fs::read(path).map_err(|err| format!("failed to read `{}`: {err}", path.display()))
+}
+
+fn read<'a>(
+    path: &Path,
+    format: Option<Format>,
+    data: &'a [u8],
+    arena: &'a Arena,
+) -> Result<Vec<TypeLayoutGraph<'a>>, format::Error> {
+    format::read(
+        data,
+        format.unwrap_or_else(|| Format::detect(path, data)),
+        arena,
+    )
+}
+
+/// Prints the differences between the graphs `before` and `after` a change
+/// and returns whether no graph was removed and no layout of an existing type
+/// changed, i.e. whether all differences are additions.
+fn compare(before: &[TypeLayoutGraph], after: &[TypeLayoutGraph]) -> bool {
+    let mut unchanged = true;
+
+    for graph in before {
+        if let Some(other) = after.iter().find(|other| other.ty == graph.ty) {
+            let differences = diff::graphs(graph, other);
+
+            report(graph.ty, &differences);
+
+            unchanged &= differences
+                .iter()
+                .all(|difference| matches!(difference, diff::Difference::Added { .. }));
+        } else {
+            println!("`{}`: graph removed", graph.ty);
+            unchanged = false;
+        }
+    }
+
+    for graph in after {
+        if !before.iter().any(|other| other.ty == graph.ty) {
+            println!("`{}`: graph added", graph.ty);
+        }
+    }
+
+    unchanged
+}
+
+/// Prints the inconsistencies in the `graphs` and their differences from the
+/// `baseline`, and returns whether there are none.
+fn check(graphs: &[TypeLayoutGraph], baseline: &[TypeLayoutGraph]) -> bool {
+    let mut success = true;
+
+    for graph in graphs {
+        if let Err(err) = graph.verify() {
+            println!("`{}`: inconsistent graph: {err}", graph.ty);
+            success = false;
+        }
+    }
+
+    for expected in baseline {
+        if let Some(graph) = graphs.iter().find(|graph| graph.ty == expected.ty) {
+            success &= report(graph.ty, &diff::graphs(expected, graph));
+        } else {
+            println!("`{}`: graph missing", expected.ty);
+            success = false;
+        }
+    }
+
+    success
+}
+
+/// Prints the `differences` of the graph of `ty` and returns whether there
+/// are none.
+fn report(ty: &str, differences: &[diff::Difference]) -> bool {
+    for difference in differences {
+        println!("`{ty}`: {difference}");
+    }
+
+    differences.is_empty()
+}
@@ -0,0 +1,186 @@
+//! Helper module to extract the [`TypeLayoutGraph`]s that were embedded into
+//! a binary, static library, or object file with
+//! [`const_type_layout::section::embed_type_graphs!`], without running it.
+//!
+//! The extracted graphs allow artifact-level audits, e.g. comparing the
+//! layouts that were shipped in the last release's binary with the layouts in
+//! today's build, or the layouts in a host binary with those in a
+//! cross-compiled firmware image, using [`const_type_layout::diff::graphs`].
+//!
+//! [`TypeLayoutGraph`]: const_type_layout::TypeLayoutGraph
+
+use std::{borrow::Cow, fmt};
+
+use const_type_layout::section::{MACHO_NAME, NAME};
+use object::{read::archive::ArchiveFile, FileKind, Object, ObjectSection};
+
+use crate::deserialise::{self, deserialise, Arena, Deserialised};
+
+#[derive(Debug)]
+/// Error that occurs when the embedded graphs cannot be extracted.
+pub enum Error {
+    /// The file is not a supported object file or archive.
+    Object(object::read::Error),
+    /// An embedded graph cannot be deserialised.
+    Deserialise {
+        /// The name of the object, or of the archive member, that contains the
+        /// graph.
+        object: String,
+        /// The byte offset of the graph inside the object's section.
+        offset: usize,
+        /// The reason why the graph cannot be deserialised.
+        source: deserialise::Error,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Object(err) => fmt.write_fmt(format_args!("failed to parse the object: {err}")),
+            Self::Deserialise {
+                object,
+                offset,
+                source,
+            } => fmt.write_fmt(format_args!(
+                "failed to deserialise the graph at offset {offset} in `{object}`: {source}"
+            )),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Object(err) => Some(err),
+            Self::Deserialise { source, .. } => Some(source),
+        }
+    }
+}
+
+impl From<object::read::Error> for Error {
+    fn from(err: object::read::Error) -> Self {
+        Self::Object(err)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// [`TypeLayoutGraph`](const_type_layout::TypeLayoutGraph) that was extracted
+/// by [`extract`].
+pub struct Embedded<'a> {
+    /// The name of the archive member that contained the graph, or [`None`]
+    /// if the graph was embedded in a binary or object file.
+    pub member: Option<&'a str>,
+    /// The deserialised graph.
+    pub graph: Deserialised<'a>,
+}
+
+/// Extract and deserialise every
+/// [`TypeLayoutGraph`](const_type_layout::TypeLayoutGraph) that was embedded
+/// into the ELF, Mach-O, or PE binary or object file, or the static library
+/// archive of object files, in `data`.
+///
+/// Graphs are returned in the order in which they appear in the file. If a
+/// type's graph was embedded by several object files, it is returned several
+/// times.
+///
+/// # Errors
+///
+/// Returns an [`Error`] if the `data` is not a supported object file or
+/// archive, or if an embedded graph cannot be deserialised.
+pub fn extract<'a>(data: &'a [u8], arena: &'a Arena) -> Result<Vec<Embedded<'a>>, Error> {
+    let mut embedded = Vec::new();
+
+    if FileKind::parse(data)? == FileKind::Archive {
+        let archive = ArchiveFile::parse(data)?;
+
+        for member in archive.members() {
+            let member = member?;
+
+            // archives may contain non-object members, e.g. rustc metadata
+            let Ok(object) = object::File::parse(member.data(data)?) else {
+                continue;
+            };
+
+            let name = std::str::from_utf8(member.name()).unwrap_or("<non-utf8>");
+
+            extract_object(&object, Some(name), arena, &mut embedded)?;
+        }
+    } else {
+        extract_object(&object::File::parse(data)?, None, arena, &mut embedded)?;
+    }
+
+    Ok(embedded)
+}
+
+fn extract_object<'a>(
+    object: &object::File<'a>,
+    member: Option<&'a str>,
+    arena: &'a Arena,
+    embedded: &mut Vec<Embedded<'a>>,
+) -> Result<(), Error> {
+    for section in object.sections() {
+        if !matches!(section.name(), Ok(name) if name == NAME || name == MACHO_NAME) {
+            continue;
+        }
+
+        let data = match section.uncompressed_data()? {
+            Cow::Borrowed(data) => data,
+            Cow::Owned(data) => arena.alloc_slice(&data),
+        };
+
+        let mut offset = 0;
+
+        while offset < data.len() {
+            // skip any zero padding that a linker inserted between the graphs
+            if data[offset] == 0 {
+                offset += 1;
+                continue;
+            }
+
+            let (graph, len) =
+                deserialise(&data[offset..], arena).map_err(|source| Error::Deserialise {
+                    object: String::from(member.unwrap_or("<object>")),
+                    offset,
+                    source,
+                })?;
+
+            embedded.push(Embedded { member, graph });
+
+            offset += len;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use const_type_layout::{section::embed_type_graphs, TypeGraphLayout};
+
+    use super::{extract, Arena};
+
+    embed_type_graphs![core::ops::Range<u32>, Option<&'static u64>];
+
+    #[test]
+    fn current_exe() {
+        let data = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+
+        let arena = Arena::new();
+        let embedded = extract(&data, &arena).unwrap();
+
+        assert_eq!(embedded.len(), 2);
+
+        for graph in [
+            <core::ops::Range<u32>>::TYPE_GRAPH,
+            <Option<&'static u64>>::TYPE_GRAPH,
+        ] {
+            assert!(
+                embedded
+                    .iter()
+                    .any(|embedded| embedded.member.is_none() && embedded.graph.graph == graph),
+                "{} is not embedded",
+                graph.ty,
+            );
+        }
+    }
+}
@@ -0,0 +1,241 @@
+//! Tests the exit codes and file handling of the `const-type-layout`
+//! command-line tool on small fixture graphs.
+
+#![feature(const_type_name)]
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use const_type_layout::{TypeGraphLayout, TypeLayoutGraph, TypeLayoutInfo};
+use const_type_layout_tools::{
+    deserialise::Arena,
+    format::{self, Format},
+};
+
+/// Runs the tool with the `args` and returns its exit code and output.
+fn run(args: &[&dyn AsRef<std::ffi::OsStr>]) -> (i32, String, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_const-type-layout"))
+        .args(args.iter().map(AsRef::as_ref))
+        .output()
+        .unwrap();
+
+    (
+        output.status.code().unwrap(),
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+/// Writes the `graphs` in the `format` into a fresh fixture file.
+fn fixture(test: &str, name: &str, graphs: &[TypeLayoutGraph], format: Format) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"))
+        .join("cli")
+        .join(test);
+    fs::create_dir_all(&dir).unwrap();
+
+    let path = dir.join(name);
+    fs::write(&path, format::write(graphs, format).unwrap()).unwrap();
+    path
+}
+
+const fn graphs() -> [TypeLayoutGraph<'static>; 2] {
+    [
+        <Option<&'static core::num::NonZeroU16>>::TYPE_GRAPH,
+        <(u8, u16)>::TYPE_GRAPH,
+    ]
+}
+
+#[test]
+fn diff() {
+    let [option, tuple] = graphs();
+
+    let before = fixture(
+        "diff",
+        "before.bin",
+        &[option.clone(), tuple.clone()],
+        Format::Binary,
+    );
+    let same = fixture(
+        "diff",
+        "same.json",
+        &[option.clone(), tuple.clone()],
+        Format::Json,
+    );
+    let fewer = fixture("diff", "fewer.ron", &[option.clone()], Format::Ron);
+
+    assert_eq!(
+        run(&[&"diff", &before, &same]),
+        (0, String::new(), String::new())
+    );
+
+    let (code, stdout, _) = run(&[&"diff", &before, &fewer]);
+    assert_eq!(code, 1);
+    assert_eq!(stdout, "`(u8, u16)`: graph removed\n");
+
+    // additions are reported but do not fail
+    let (code, stdout, _) = run(&[&"diff", &fewer, &before]);
+    assert_eq!(code, 0);
+    assert_eq!(stdout, "`(u8, u16)`: graph added\n");
+
+    let mut tys = tuple.tys.to_vec();
+    tys.push(<u32>::TYPE_GRAPH.tys[0]);
+    let extended = fixture(
+        "diff",
+        "extended.bin",
+        &[
+            option.clone(),
+            TypeLayoutGraph {
+                ty: tuple.ty,
+                tys: &tys,
+            },
+        ],
+        Format::Binary,
+    );
+
+    let (code, stdout, _) = run(&[&"diff", &before, &extended]);
+    assert_eq!(code, 0);
+    assert_eq!(stdout, "`(u8, u16)`: `u32` was added\n");
+
+    let root = TypeLayoutInfo {
+        size: 8,
+        ..*tuple.tys[0]
+    };
+    let mut tys = tuple.tys.to_vec();
+    tys[0] = &root;
+    let resized = fixture(
+        "diff",
+        "resized.bin",
+        &[TypeLayoutGraph {
+            ty: tuple.ty,
+            tys: &tys,
+        }],
+        Format::Binary,
+    );
+    let tuple = fixture("diff", "tuple.bin", &[tuple], Format::Binary);
+
+    let (code, stdout, _) = run(&[&"diff", &tuple, &resized]);
+    assert_eq!(code, 1);
+    assert_eq!(
+        stdout,
+        "`(u8, u16)`: the size of `(u8, u16)` changed from 4 to 8\n"
+    );
+}
+
+#[test]
+fn check() {
+    let [option, tuple] = graphs();
+
+    let input = fixture(
+        "check",
+        "input.bin",
+        &[option.clone(), tuple.clone()],
+        Format::Binary,
+    );
+    let subset = fixture("check", "subset.json", &[tuple], Format::Json);
+    let missing = fixture("check", "missing.bin", &[option], Format::Binary);
+
+    assert_eq!(
+        run(&[&"check", &input, &"--baseline", &subset]),
+        (0, String::new(), String::new())
+    );
+
+    let (code, stdout, _) = run(&[&"check", &missing, &"--baseline", &subset]);
+    assert_eq!(code, 1);
+    assert_eq!(stdout, "`(u8, u16)`: graph missing\n");
+}
+
+#[test]
+fn check_update() {
+    let [option, tuple] = graphs();
+
+    let input = fixture(
+        "update",
+        "input.bin",
+        &[option, tuple.clone()],
+        Format::Binary,
+    );
+
+    for (baseline, format) in [
+        ("baseline.json", Format::Json),
+        ("baseline.ron", Format::Ron),
+    ] {
+        let baseline = fixture("update", baseline, &[tuple.clone()], format);
+
+        assert_eq!(
+            run(&[&"check", &input, &"--baseline", &baseline, &"--update"]),
+            (0, String::new(), String::new())
+        );
+
+        let data = fs::read(&baseline).unwrap();
+        let arena = Arena::new();
+        assert_eq!(Format::detect(&baseline, &data), format);
+        assert_eq!(
+            format::read(&data, format, &arena).unwrap(),
+            graphs(),
+            "{format}"
+        );
+
+        assert_eq!(
+            run(&[&"check", &input, &"--baseline", &baseline]),
+            (0, String::new(), String::new())
+        );
+    }
+
+    // an extension-less baseline is written in the binary format
+    let baseline = fixture("update", "baseline", &[tuple], Format::Binary);
+
+    assert_eq!(
+        run(&[&"check", &input, &"--baseline", &baseline, &"--update"]).0,
+        0
+    );
+
+    let data = fs::read(&baseline).unwrap();
+    let arena = Arena::new();
+    assert_eq!(
+        format::read(&data, Format::Binary, &arena).unwrap(),
+        graphs()
+    );
+}
+
+#[test]
+fn errors() {
+    let [option, _] = graphs();
+
+    let input = fixture("errors", "input.bin", &[option], Format::Binary);
+    let invalid = fixture("errors", "invalid.bin", &[], Format::Binary);
+    fs::write(&invalid, b"not a graph").unwrap();
+    let missing = input.with_file_name("missing.bin");
+
+    let (code, stdout, stderr) = run(&[&"show", &missing]);
+    assert_eq!(code, 2);
+    assert_eq!(stdout, "");
+    assert!(stderr.starts_with("error: failed to read `"), "{stderr}");
+
+    let (code, _, stderr) = run(&[&"diff", &input, &invalid]);
+    assert_eq!(code, 2);
+    assert!(
+        stderr.starts_with("error: invalid binary graph: "),
+        "{stderr}"
+    );
+
+    let (code, _, stderr) = run(&[&"check", &input, &"--baseline", &missing]);
+    assert_eq!(code, 2);
+    assert!(stderr.starts_with("error: failed to read `"), "{stderr}");
+
+    // only a missing baseline is created, other read errors are reported
+    let directory = input.with_file_name("directory");
+    fs::create_dir_all(&directory).unwrap();
+    let (code, _, stderr) = run(&[&"check", &input, &"--baseline", &directory, &"--update"]);
+    assert_eq!(code, 2);
+    assert!(stderr.starts_with("error: failed to read `"), "{stderr}");
+
+    let (code, _, stderr) = run(&[&"convert", &input, &"--to", &"object"]);
+    assert_eq!(code, 2);
+    assert_eq!(
+        stderr,
+        "error: graphs cannot be written into an object file\n"
+    );
+}
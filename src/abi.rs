@@ -0,0 +1,1118 @@
+//! Helper module to recompute the layouts of `#[repr(C)]` types for a target
+//! other than the host, e.g. to check on a 64-bit CI machine whether a type's
+//! layout differs on a 32-bit embedded target.
+//!
+//! The layout of a `#[repr(C)]` type is fully defined by the sizes and
+//! alignments of its fields, which are recomputed bottom-up from a
+//! [`TargetModel`] of the target's primitive types.
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::fmt;
+
+use crate::{
+    diff::Difference,
+    primitive::Primitive,
+    render::raw::Endian,
+    repr::{EnumEncoding, Integer, Repr},
+    Field, MaybeUninhabited, TypeLayoutGraph, TypeLayoutInfo, TypeStructure, Variant,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Size and alignment of a primitive type.
+pub struct Scalar {
+    /// The size of the type.
+    pub size: usize,
+    /// The minimum alignment of the type.
+    pub alignment: usize,
+}
+
+impl Scalar {
+    #[must_use]
+    /// Construct a [`Scalar`] with the given `size` and `alignment`.
+    pub const fn new(size: usize, alignment: usize) -> Self {
+        Self { size, alignment }
+    }
+
+    #[must_use]
+    /// The [`Scalar`] of the type `T` on the host.
+    pub const fn of<T>() -> Self {
+        Self::new(core::mem::size_of::<T>(), core::mem::align_of::<T>())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Model of the sizes and alignments of a target's primitive types.
+///
+/// The single-byte `u8`, `i8`, and `bool` types, and the four-byte `char` and
+/// `f32` types, have the same size and alignment on all supported targets.
+pub struct TargetModel<'a> {
+    /// The name of the target, e.g. `"x86_64"`.
+    pub name: &'a str,
+    /// The target's byte order.
+    pub endian: Endian,
+    /// Thin pointers, references, function pointers, `usize`, and `isize`.
+    pub pointer: Scalar,
+    /// `u16` and `i16`.
+    pub int16: Scalar,
+    /// `u32` and `i32`.
+    pub int32: Scalar,
+    /// `u64` and `i64`.
+    pub int64: Scalar,
+    /// `u128` and `i128`.
+    pub int128: Scalar,
+    /// `f64`.
+    pub float64: Scalar,
+    /// The discriminant of a `#[repr(C)]` enum, i.e. a C `int`.
+    pub c_enum: Scalar,
+}
+
+impl TargetModel<'static> {
+    /// 64-bit ARM, e.g. `aarch64-unknown-linux-gnu`.
+    pub const AARCH64: Self = Self {
+        name: "aarch64",
+        ..Self::X86_64
+    };
+    /// 32-bit Arm v7 with 32-bit C enums, e.g. `armv7-unknown-linux-gnueabihf`.
+    ///
+    /// Bare-metal EABI targets such as `thumbv7em-none-eabihf` use short C
+    /// enums and are not covered by this model.
+    pub const ARMV7: Self = Self {
+        name: "armv7",
+        endian: Endian::Little,
+        pointer: Scalar::new(4, 4),
+        int16: Scalar::new(2, 2),
+        int32: Scalar::new(4, 4),
+        int64: Scalar::new(8, 8),
+        int128: Scalar::new(16, 8),
+        float64: Scalar::new(8, 8),
+        c_enum: Scalar::new(4, 4),
+    };
+    /// The model of the host target.
+    pub const HOST: Self = Self {
+        name: "host",
+        endian: Endian::NATIVE,
+        pointer: Scalar::of::<usize>(),
+        int16: Scalar::of::<u16>(),
+        int32: Scalar::of::<u32>(),
+        int64: Scalar::of::<u64>(),
+        int128: Scalar::of::<u128>(),
+        float64: Scalar::of::<f64>(),
+        c_enum: Scalar::of::<core::ffi::c_int>(),
+    };
+    /// 32-bit x86, e.g. `i686-unknown-linux-gnu`, where 64-bit integers and
+    /// floats are only 4-byte aligned.
+    pub const I686: Self = Self {
+        name: "i686",
+        endian: Endian::Little,
+        pointer: Scalar::new(4, 4),
+        int16: Scalar::new(2, 2),
+        int32: Scalar::new(4, 4),
+        int64: Scalar::new(8, 4),
+        int128: Scalar::new(16, 16),
+        float64: Scalar::new(8, 4),
+        c_enum: Scalar::new(4, 4),
+    };
+    /// Big-endian 64-bit `PowerPC`, e.g. `powerpc64-unknown-linux-gnu`.
+    pub const PPC64: Self = Self {
+        name: "ppc64",
+        endian: Endian::Big,
+        ..Self::X86_64
+    };
+    /// 32-bit WebAssembly, e.g. `wasm32-unknown-unknown`.
+    pub const WASM32: Self = Self {
+        name: "wasm32",
+        endian: Endian::Little,
+        pointer: Scalar::new(4, 4),
+        int16: Scalar::new(2, 2),
+        int32: Scalar::new(4, 4),
+        int64: Scalar::new(8, 8),
+        int128: Scalar::new(16, 16),
+        float64: Scalar::new(8, 8),
+        c_enum: Scalar::new(4, 4),
+    };
+    /// 64-bit x86, e.g. `x86_64-unknown-linux-gnu`.
+    pub const X86_64: Self = Self {
+        name: "x86_64",
+        endian: Endian::Little,
+        pointer: Scalar::new(8, 8),
+        int16: Scalar::new(2, 2),
+        int32: Scalar::new(4, 4),
+        int64: Scalar::new(8, 8),
+        int128: Scalar::new(16, 16),
+        float64: Scalar::new(8, 8),
+        c_enum: Scalar::new(4, 4),
+    };
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Layout of a type, as recomputed for a [`TargetModel`].
+pub struct Layout<'a> {
+    /// The fully-qualified name of the type.
+    pub ty: &'a str,
+    /// The type's size on the target.
+    pub size: usize,
+    /// The type's minimum alignment on the target.
+    pub alignment: usize,
+    /// The layouts of the type's fields, including the fields of all enum
+    /// variants, in their declaration order.
+    pub fields: Vec<FieldLayout<'a>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Layout of a field, as recomputed for a [`TargetModel`].
+pub struct FieldLayout<'a> {
+    /// The name of the enum variant that contains the field, if any.
+    pub variant: Option<&'a str>,
+    /// The field's name.
+    pub name: &'a str,
+    /// The field's byte offset on the target, iff the field is inhabited.
+    pub offset: MaybeUninhabited<usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Error that occurs when a layout cannot be recomputed for a
+/// [`TargetModel`].
+pub enum Error<'a> {
+    /// The layout of the type `ty` is not included in the graph.
+    UnknownType {
+        /// The fully-qualified name of the type.
+        ty: &'a str,
+    },
+    /// The layout of the type `ty` is chosen by the compiler, e.g. since it is
+    /// `#[repr(Rust)]`, and can thus not be recomputed.
+    UnspecifiedLayout {
+        /// The fully-qualified name of the type.
+        ty: &'a str,
+    },
+    /// The size and alignment of the opaque primitive type `ty` are unknown.
+    UnsupportedPrimitive {
+        /// The fully-qualified name of the type.
+        ty: &'a str,
+    },
+}
+
+impl fmt::Display for Error<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnknownType { ty } => {
+                fmt.write_fmt(format_args!("the layout of `{ty}` is not in the graph"))
+            },
+            Self::UnspecifiedLayout { ty } => fmt.write_fmt(format_args!(
+                "the layout of `{ty}` is chosen by the compiler and cannot be recomputed"
+            )),
+            Self::UnsupportedPrimitive { ty } => fmt.write_fmt(format_args!(
+                "the size and alignment of the primitive `{ty}` are unknown"
+            )),
+        }
+    }
+}
+
+impl<'m> TargetModel<'m> {
+    /// Recompute the layout of the root type of the `graph`, and of every type
+    /// that it contains by value, for this target.
+    ///
+    /// The layouts are returned in breadth-first order, starting with the root
+    /// type. Pointees are not included since they do not affect the layout.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the layout of any of the types cannot be
+    /// recomputed, e.g. since it is `#[repr(Rust)]`.
+    pub fn layouts<'a>(&self, graph: &TypeLayoutGraph<'a>) -> Result<Vec<Layout<'a>>, Error<'a>> {
+        let mut computer = Computer {
+            model: self,
+            graph,
+            layouts: BTreeMap::new(),
+        };
+
+        computer.layout_of(graph.ty)?;
+
+        let mut layouts = Vec::with_capacity(computer.layouts.len());
+        let mut i = 0;
+
+        layouts.extend(computer.layouts.remove(graph.ty));
+
+        // breadth-first traversal through the fields, which have all been computed
+        while let Some(layout) = layouts.get(i) {
+            let tys = graph
+                .find_type(layout.ty)
+                .map(|info| contained_types(info))
+                .unwrap_or_default();
+
+            for ty in tys {
+                layouts.extend(computer.layouts.remove(ty));
+            }
+
+            i += 1;
+        }
+
+        Ok(layouts)
+    }
+
+    /// Compare the layouts of the root type of the `graph`, and of every type
+    /// that it contains by value, on the host (before) with their recomputed
+    /// layouts on this target (after).
+    ///
+    /// Only [`Difference::Size`], [`Difference::Alignment`], and
+    /// [`Difference::FieldOffset`] are reported.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the layout of any of the types cannot be
+    /// recomputed, e.g. since it is `#[repr(Rust)]`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #![feature(const_type_name)]
+    /// use const_type_layout::{abi::TargetModel, diff::Difference, TypeGraphLayout};
+    ///
+    /// let graph = <Option<&'static u64>>::TYPE_GRAPH;
+    ///
+    /// assert_eq!(TargetModel::HOST.diff(&graph), Ok(Vec::new()));
+    ///
+    /// # if cfg!(target_arch = "x86_64") {
+    /// assert_eq!(
+    ///     TargetModel::ARMV7.diff(&graph),
+    ///     Ok(vec![
+    ///         Difference::Size { ty: graph.ty, before: 8, after: 4 },
+    ///         Difference::Alignment { ty: graph.ty, before: 8, after: 4 },
+    ///         Difference::Size { ty: "&u64", before: 8, after: 4 },
+    ///         Difference::Alignment { ty: "&u64", before: 8, after: 4 },
+    ///     ])
+    /// );
+    /// # }
+    /// ```
+    pub fn diff<'a>(&self, graph: &TypeLayoutGraph<'a>) -> Result<Vec<Difference<'a>>, Error<'a>> {
+        let mut differences = Vec::new();
+
+        for layout in self.layouts(graph)? {
+            let Some(host) = graph.find_type(layout.ty) else {
+                return Err(Error::UnknownType { ty: layout.ty });
+            };
+
+            if host.size != layout.size {
+                differences.push(Difference::Size {
+                    ty: layout.ty,
+                    before: host.size,
+                    after: layout.size,
+                });
+            }
+
+            if host.alignment != layout.alignment {
+                differences.push(Difference::Alignment {
+                    ty: layout.ty,
+                    before: host.alignment,
+                    after: layout.alignment,
+                });
+            }
+
+            for (host_field, field) in host_fields(host).zip(layout.fields) {
+                if host_field.offset != field.offset {
+                    differences.push(Difference::FieldOffset {
+                        ty: layout.ty,
+                        variant: field.variant,
+                        field: field.name,
+                        before: host_field.offset,
+                        after: field.offset,
+                    });
+                }
+            }
+        }
+
+        Ok(differences)
+    }
+
+    const fn integer(&self, int: Integer) -> Scalar {
+        match int {
+            Integer::U8 | Integer::I8 => Scalar::new(1, 1),
+            Integer::U16 | Integer::I16 => self.int16,
+            Integer::U32 | Integer::I32 => self.int32,
+            Integer::U64 | Integer::I64 => self.int64,
+            Integer::U128 | Integer::I128 => self.int128,
+            Integer::Usize | Integer::Isize => self.pointer,
+        }
+    }
+
+    const fn primitive(&self, name: &str) -> Option<Scalar> {
+        const INTS: [(&str, Integer); 12] = [
+            ("u8", Integer::U8),
+            ("u16", Integer::U16),
+            ("u32", Integer::U32),
+            ("u64", Integer::U64),
+            ("u128", Integer::U128),
+            ("usize", Integer::Usize),
+            ("i8", Integer::I8),
+            ("i16", Integer::I16),
+            ("i32", Integer::I32),
+            ("i64", Integer::I64),
+            ("i128", Integer::I128),
+            ("isize", Integer::Isize),
+        ];
+
+        match Primitive::parse(name) {
+            Primitive::Unit | Primitive::Never => Some(Scalar::new(0, 1)),
+            Primitive::Bool => Some(Scalar::new(1, 1)),
+            Primitive::Char => Some(Scalar::new(4, 4)),
+            Primitive::Int { .. } => {
+                let mut i = 0;
+
+                while i < INTS.len() {
+                    if crate::repr::bytes_eq(name.as_bytes(), INTS[i].0.as_bytes()) {
+                        return Some(self.integer(INTS[i].1));
+                    }
+
+                    i += 1;
+                }
+
+                None
+            },
+            Primitive::Float if name.len() == 3 && name.as_bytes()[1] == b'3' => {
+                Some(Scalar::new(4, 4))
+            },
+            Primitive::Float => Some(self.float64),
+            Primitive::Pointer { .. }
+            | Primitive::Reference { .. }
+            | Primitive::FnPointer { .. } => Some(self.pointer),
+            Primitive::Array { .. } | Primitive::Opaque => None,
+        }
+    }
+}
+
+/// Returns the fields of a type, including the fields of all enum variants, in
+/// their declaration order.
+fn host_fields<'a>(layout: &'a TypeLayoutInfo<'a>) -> impl Iterator<Item = &'a Field<'a>> {
+    let (fields, variants): (&[Field], &[Variant]) = match &layout.structure {
+        TypeStructure::Primitive => (&[], &[]),
+        TypeStructure::Struct { fields, .. } | TypeStructure::Union { fields, .. } => (fields, &[]),
+        TypeStructure::Enum { variants, .. } => (&[], variants),
+    };
+
+    fields
+        .iter()
+        .chain(variants.iter().flat_map(|variant| variant.fields.iter()))
+}
+
+/// Returns the types that a type contains by value, i.e. its fields' types and
+/// its array element type.
+fn contained_types<'a>(layout: &'a TypeLayoutInfo<'a>) -> Vec<&'a str> {
+    match Primitive::of(layout) {
+        Some(Primitive::Array { element, .. }) => alloc::vec![element],
+        _ => host_fields(layout).map(|field| field.ty).collect(),
+    }
+}
+
+struct Computer<'m, 'a, 'g> {
+    model: &'g TargetModel<'m>,
+    graph: &'g TypeLayoutGraph<'a>,
+    layouts: BTreeMap<&'a str, Layout<'a>>,
+}
+
+impl<'m, 'a, 'g> Computer<'m, 'a, 'g> {
+    fn layout_of(&mut self, ty: &'a str) -> Result<Scalar, Error<'a>> {
+        if let Some(layout) = self.layouts.get(ty) {
+            return Ok(Scalar::new(layout.size, layout.alignment));
+        }
+
+        let Some(info) = self.graph.find_type(ty) else {
+            return Err(Error::UnknownType { ty });
+        };
+
+        let layout = match &info.structure {
+            TypeStructure::Primitive => self.primitive(info)?,
+            TypeStructure::Struct { repr, fields } => {
+                let repr = Repr::parse(repr);
+
+                // zero-sized `#[repr(Rust)]` types, e.g. `PhantomData<T>`, have no
+                //  layout to choose
+                if repr.is_rust() && !fields.is_empty() {
+                    return Err(Error::UnspecifiedLayout { ty });
+                }
+
+                let mut layout = self.fields(ty, repr, None, None, fields)?;
+                layout.alignment = align(repr, layout.alignment);
+                layout.size = round_up(layout.size, layout.alignment);
+                layout
+            },
+            TypeStructure::Union { repr, fields } => {
+                let repr = Repr::parse(repr);
+
+                if repr.is_rust() && !fields.is_empty() {
+                    return Err(Error::UnspecifiedLayout { ty });
+                }
+
+                self.union(ty, repr, fields)?
+            },
+            TypeStructure::Enum { repr, variants } => {
+                self.enumeration(info, Repr::parse(repr), variants)?
+            },
+        };
+
+        let scalar = Scalar::new(layout.size, layout.alignment);
+
+        self.layouts.insert(ty, layout);
+
+        Ok(scalar)
+    }
+
+    fn primitive(&mut self, info: &'a TypeLayoutInfo<'a>) -> Result<Layout<'a>, Error<'a>> {
+        let scalar = match Primitive::parse(info.name) {
+            Primitive::Array { element, len } => {
+                let element = self.layout_of(element)?;
+                Scalar::new(element.size * len, element.alignment)
+            },
+            _ => match self.model.primitive(info.name) {
+                Some(scalar) => scalar,
+                None => return Err(Error::UnsupportedPrimitive { ty: info.name }),
+            },
+        };
+
+        Ok(Layout {
+            ty: info.name,
+            size: scalar.size,
+            alignment: scalar.alignment,
+            fields: Vec::new(),
+        })
+    }
+
+    /// Lays out the `fields` in declaration order as in a `#[repr(C)]` struct,
+    /// optionally after a `tag`, without the final trailing padding.
+    fn fields(
+        &mut self,
+        ty: &'a str,
+        repr: Repr,
+        variant: Option<&'a str>,
+        tag: Option<Scalar>,
+        fields: &[Field<'a>],
+    ) -> Result<Layout<'a>, Error<'a>> {
+        let mut layout = Layout {
+            ty,
+            size: 0,
+            alignment: 1,
+            fields: Vec::with_capacity(fields.len()),
+        };
+
+        if let Some(tag) = tag {
+            layout.size = tag.size;
+            layout.alignment = tag.alignment;
+        }
+
+        for field in fields {
+            let field_layout = self.layout_of(field.ty)?;
+            let alignment = packed(repr, field_layout.alignment);
+
+            let offset = round_up(layout.size, alignment);
+
+            layout.size = offset + field_layout.size;
+            layout.alignment = layout.alignment.max(alignment);
+
+            layout.fields.push(FieldLayout {
+                variant,
+                name: field.name,
+                offset: shift(at_start(field.offset), offset),
+            });
+        }
+
+        Ok(layout)
+    }
+
+    fn union(
+        &mut self,
+        ty: &'a str,
+        repr: Repr,
+        fields: &[Field<'a>],
+    ) -> Result<Layout<'a>, Error<'a>> {
+        let mut layout = Layout {
+            ty,
+            size: 0,
+            alignment: 1,
+            fields: Vec::with_capacity(fields.len()),
+        };
+
+        for field in fields {
+            let field_layout = self.layout_of(field.ty)?;
+
+            layout.size = layout.size.max(field_layout.size);
+            layout.alignment = layout.alignment.max(packed(repr, field_layout.alignment));
+
+            layout.fields.push(FieldLayout {
+                variant: None,
+                name: field.name,
+                offset: at_start(field.offset),
+            });
+        }
+
+        layout.alignment = align(repr, layout.alignment);
+        layout.size = round_up(layout.size, layout.alignment);
+
+        Ok(layout)
+    }
+
+    /// Lays out an enum following the `#[repr(C)]` and primitive
+    /// representations, as specified in RFC 2195.
+    fn enumeration(
+        &mut self,
+        info: &'a TypeLayoutInfo<'a>,
+        repr: Repr,
+        variants: &[Variant<'a>],
+    ) -> Result<Layout<'a>, Error<'a>> {
+        let ty = info.name;
+
+        if repr.is_rust() {
+            return match EnumEncoding::of(self.graph, info, repr, variants) {
+                // the null-pointer optimisation guarantees the layout of the only field
+                EnumEncoding::NullPointer { some, .. } => {
+                    let field = &variants[some].fields[0];
+                    let scalar = self.layout_of(field.ty)?;
+
+                    Ok(Layout {
+                        ty,
+                        size: scalar.size,
+                        alignment: scalar.alignment,
+                        fields: variants
+                            .iter()
+                            .flat_map(|variant| {
+                                variant.fields.iter().map(|field| FieldLayout {
+                                    variant: Some(variant.name),
+                                    name: field.name,
+                                    offset: at_start(field.offset),
+                                })
+                            })
+                            .collect(),
+                    })
+                },
+                _ if variants.is_empty() => Ok(Layout {
+                    ty,
+                    size: 0,
+                    alignment: 1,
+                    fields: Vec::new(),
+                }),
+                _ => Err(Error::UnspecifiedLayout { ty }),
+            };
+        }
+
+        let tag = match repr.int {
+            Some(int) => self.model.integer(int),
+            None => self.model.c_enum,
+        };
+
+        let mut layout = Layout {
+            ty,
+            size: tag.size,
+            alignment: tag.alignment,
+            fields: Vec::new(),
+        };
+
+        if repr.c {
+            // `#[repr(C)]` and `#[repr(C, Int)]` enums are a struct of the tag,
+            //  followed by a union of the variants' fields
+            let mut payloads = Vec::with_capacity(variants.len());
+            let mut payload = Scalar::new(0, 1);
+
+            for variant in variants {
+                let fields = self.fields(ty, repr, Some(variant.name), None, variant.fields)?;
+
+                payload.size = payload.size.max(fields.size);
+                payload.alignment = payload.alignment.max(fields.alignment);
+
+                payloads.push(fields);
+            }
+
+            let payload_offset = round_up(tag.size, payload.alignment);
+
+            for fields in payloads {
+                layout
+                    .fields
+                    .extend(fields.fields.into_iter().map(|mut field| {
+                        field.offset = shift(field.offset, payload_offset);
+                        field
+                    }));
+            }
+
+            layout.size = payload_offset + payload.size;
+            layout.alignment = layout.alignment.max(payload.alignment);
+        } else {
+            // `#[repr(Int)]` enums are a union of `#[repr(C)]` structs that each
+            //  start with the tag
+            for variant in variants {
+                let fields =
+                    self.fields(ty, repr, Some(variant.name), Some(tag), variant.fields)?;
+
+                layout.size = layout.size.max(fields.size);
+                layout.alignment = layout.alignment.max(fields.alignment);
+                layout.fields.extend(fields.fields);
+            }
+        }
+
+        layout.alignment = align(repr, layout.alignment);
+        layout.size = round_up(layout.size, layout.alignment);
+
+        Ok(layout)
+    }
+}
+
+/// Moves an inhabited field `offset` by `delta` bytes.
+const fn shift(offset: MaybeUninhabited<usize>, delta: usize) -> MaybeUninhabited<usize> {
+    match offset {
+        MaybeUninhabited::Inhabited(offset) => MaybeUninhabited::Inhabited(offset + delta),
+        MaybeUninhabited::Uninhabited => MaybeUninhabited::Uninhabited,
+    }
+}
+
+/// Places an inhabited field at the start of the type.
+const fn at_start(offset: MaybeUninhabited<usize>) -> MaybeUninhabited<usize> {
+    match offset {
+        MaybeUninhabited::Inhabited(_) => MaybeUninhabited::Inhabited(0),
+        MaybeUninhabited::Uninhabited => MaybeUninhabited::Uninhabited,
+    }
+}
+
+/// Reduce the `alignment` of a field inside a `#[repr(packed)]` type.
+const fn packed(repr: Repr, alignment: usize) -> usize {
+    match repr.packed {
+        Some(packed) if packed < alignment => packed,
+        _ => alignment,
+    }
+}
+
+/// Raise the `alignment` of a `#[repr(align)]` type.
+const fn align(repr: Repr, alignment: usize) -> usize {
+    match repr.align {
+        Some(align) if align > alignment => align,
+        _ => alignment,
+    }
+}
+
+const fn round_up(size: usize, alignment: usize) -> usize {
+    match size % alignment {
+        0 => size,
+        rem => size + (alignment - rem),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{
+        Field, MaybeUninhabited, TypeGraphLayout, TypeLayoutGraph, TypeLayoutInfo, TypeStructure,
+        Variant,
+    };
+
+    use super::{Error, TargetModel};
+
+    /// `#[repr(C)]` and `#[repr(u8)]` types with their `x86_64` layouts.
+    const TYS: &[&TypeLayoutInfo<'static>] = &[
+        &TypeLayoutInfo {
+            name: "Header",
+            size: 48,
+            alignment: 16,
+            structure: TypeStructure::Struct {
+                repr: "C",
+                fields: &[
+                    Field {
+                        name: "a",
+                        offset: MaybeUninhabited::Inhabited(0),
+                        ty: "u8",
+                    },
+                    Field {
+                        name: "b",
+                        offset: MaybeUninhabited::Inhabited(8),
+                        ty: "u64",
+                    },
+                    Field {
+                        name: "c",
+                        offset: MaybeUninhabited::Inhabited(16),
+                        ty: "u16",
+                    },
+                    Field {
+                        name: "d",
+                        offset: MaybeUninhabited::Inhabited(24),
+                        ty: "*const u8",
+                    },
+                    Field {
+                        name: "e",
+                        offset: MaybeUninhabited::Inhabited(32),
+                        ty: "u128",
+                    },
+                ],
+            },
+        },
+        &TypeLayoutInfo {
+            name: "Packed",
+            size: 16,
+            alignment: 4,
+            structure: TypeStructure::Struct {
+                repr: "C,packed(4)",
+                fields: &[
+                    Field {
+                        name: "a",
+                        offset: MaybeUninhabited::Inhabited(0),
+                        ty: "u8",
+                    },
+                    Field {
+                        name: "b",
+                        offset: MaybeUninhabited::Inhabited(4),
+                        ty: "u64",
+                    },
+                    Field {
+                        name: "c",
+                        offset: MaybeUninhabited::Inhabited(12),
+                        ty: "u16",
+                    },
+                ],
+            },
+        },
+        &TypeLayoutInfo {
+            name: "Aligned",
+            size: 32,
+            alignment: 16,
+            structure: TypeStructure::Struct {
+                repr: "C,align(16)",
+                fields: &[
+                    Field {
+                        name: "a",
+                        offset: MaybeUninhabited::Inhabited(0),
+                        ty: "u32",
+                    },
+                    Field {
+                        name: "b",
+                        offset: MaybeUninhabited::Inhabited(8),
+                        ty: "u64",
+                    },
+                    Field {
+                        name: "c",
+                        offset: MaybeUninhabited::Inhabited(16),
+                        ty: "u32",
+                    },
+                ],
+            },
+        },
+        &TypeLayoutInfo {
+            name: "CEnum",
+            size: 16,
+            alignment: 8,
+            structure: TypeStructure::Enum {
+                repr: "C",
+                variants: &[
+                    Variant {
+                        name: "Ping",
+                        discriminant: MaybeUninhabited::Inhabited(crate::discriminant!(0)),
+                        fields: &[],
+                    },
+                    Variant {
+                        name: "Data",
+                        discriminant: MaybeUninhabited::Inhabited(crate::discriminant!(1)),
+                        fields: &[Field {
+                            name: "0",
+                            offset: MaybeUninhabited::Inhabited(8),
+                            ty: "u64",
+                        }],
+                    },
+                    Variant {
+                        name: "Pair",
+                        discriminant: MaybeUninhabited::Inhabited(crate::discriminant!(2)),
+                        fields: &[
+                            Field {
+                                name: "0",
+                                offset: MaybeUninhabited::Inhabited(8),
+                                ty: "u8",
+                            },
+                            Field {
+                                name: "1",
+                                offset: MaybeUninhabited::Inhabited(12),
+                                ty: "u32",
+                            },
+                        ],
+                    },
+                ],
+            },
+        },
+        &TypeLayoutInfo {
+            name: "IntEnum",
+            size: 16,
+            alignment: 8,
+            structure: TypeStructure::Enum {
+                repr: "u8",
+                variants: &[
+                    Variant {
+                        name: "Ping",
+                        discriminant: MaybeUninhabited::Inhabited(crate::discriminant!(0)),
+                        fields: &[],
+                    },
+                    Variant {
+                        name: "Data",
+                        discriminant: MaybeUninhabited::Inhabited(crate::discriminant!(1)),
+                        fields: &[Field {
+                            name: "0",
+                            offset: MaybeUninhabited::Inhabited(8),
+                            ty: "u64",
+                        }],
+                    },
+                    Variant {
+                        name: "Pair",
+                        discriminant: MaybeUninhabited::Inhabited(crate::discriminant!(2)),
+                        fields: &[
+                            Field {
+                                name: "0",
+                                offset: MaybeUninhabited::Inhabited(1),
+                                ty: "u8",
+                            },
+                            Field {
+                                name: "1",
+                                offset: MaybeUninhabited::Inhabited(4),
+                                ty: "u32",
+                            },
+                        ],
+                    },
+                ],
+            },
+        },
+        &TypeLayoutInfo {
+            name: "Union",
+            size: 16,
+            alignment: 8,
+            structure: TypeStructure::Union {
+                repr: "C",
+                fields: &[
+                    Field {
+                        name: "a",
+                        offset: MaybeUninhabited::Inhabited(0),
+                        ty: "u8",
+                    },
+                    Field {
+                        name: "b",
+                        offset: MaybeUninhabited::Inhabited(0),
+                        ty: "u64",
+                    },
+                    Field {
+                        name: "c",
+                        offset: MaybeUninhabited::Inhabited(0),
+                        ty: "[u16; 5]",
+                    },
+                ],
+            },
+        },
+        &TypeLayoutInfo {
+            name: "*const u8",
+            size: 8,
+            alignment: 8,
+            structure: TypeStructure::Primitive,
+        },
+        &TypeLayoutInfo {
+            name: "[u16; 5]",
+            size: 10,
+            alignment: 2,
+            structure: TypeStructure::Primitive,
+        },
+        &TypeLayoutInfo {
+            name: "u128",
+            size: 16,
+            alignment: 16,
+            structure: TypeStructure::Primitive,
+        },
+        &TypeLayoutInfo {
+            name: "u16",
+            size: 2,
+            alignment: 2,
+            structure: TypeStructure::Primitive,
+        },
+        &TypeLayoutInfo {
+            name: "u32",
+            size: 4,
+            alignment: 4,
+            structure: TypeStructure::Primitive,
+        },
+        &TypeLayoutInfo {
+            name: "u64",
+            size: 8,
+            alignment: 8,
+            structure: TypeStructure::Primitive,
+        },
+        &TypeLayoutInfo {
+            name: "u8",
+            size: 1,
+            alignment: 1,
+            structure: TypeStructure::Primitive,
+        },
+    ];
+
+    /// The size, alignment, and field offsets of the type `ty` on the target.
+    fn layout(model: &TargetModel, ty: &'static str) -> (usize, usize, Vec<usize>) {
+        let layout = model
+            .layouts(&TypeLayoutGraph { ty, tys: TYS })
+            .unwrap()
+            .swap_remove(0);
+
+        let offsets = layout
+            .fields
+            .iter()
+            .map(|field| match field.offset {
+                MaybeUninhabited::Inhabited(offset) => offset,
+                MaybeUninhabited::Uninhabited => unreachable!(),
+            })
+            .collect();
+
+        (layout.size, layout.alignment, offsets)
+    }
+
+    /// The size, alignment, and field offsets of a type on a target.
+    type Expected = (usize, usize, &'static [usize]);
+
+    #[test]
+    fn target_layouts() {
+        // the layouts on I686, ARMV7, and WASM32
+        let expected: [(&str, [Expected; 3]); 6] = [
+            (
+                "Header",
+                [
+                    (48, 16, &[0, 4, 12, 16, 32]),
+                    (40, 8, &[0, 8, 16, 20, 24]),
+                    (48, 16, &[0, 8, 16, 20, 32]),
+                ],
+            ),
+            (
+                "Packed",
+                [
+                    (16, 4, &[0, 4, 12]),
+                    (16, 4, &[0, 4, 12]),
+                    (16, 4, &[0, 4, 12]),
+                ],
+            ),
+            (
+                "Aligned",
+                [
+                    (16, 16, &[0, 4, 12]),
+                    (32, 16, &[0, 8, 16]),
+                    (32, 16, &[0, 8, 16]),
+                ],
+            ),
+            (
+                "CEnum",
+                [
+                    (12, 4, &[4, 4, 8]),
+                    (16, 8, &[8, 8, 12]),
+                    (16, 8, &[8, 8, 12]),
+                ],
+            ),
+            (
+                "IntEnum",
+                [
+                    (12, 4, &[4, 1, 4]),
+                    (16, 8, &[8, 1, 4]),
+                    (16, 8, &[8, 1, 4]),
+                ],
+            ),
+            (
+                "Union",
+                [
+                    (12, 4, &[0, 0, 0]),
+                    (16, 8, &[0, 0, 0]),
+                    (16, 8, &[0, 0, 0]),
+                ],
+            ),
+        ];
+
+        for (ty, layouts) in expected {
+            let models = [TargetModel::I686, TargetModel::ARMV7, TargetModel::WASM32];
+
+            for (model, (size, alignment, offsets)) in models.iter().zip(layouts) {
+                assert_eq!(
+                    layout(model, ty),
+                    (size, alignment, offsets.to_vec()),
+                    "{ty} on {}",
+                    model.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn armv7_linux_layouts() {
+        // `armv7-unknown-linux-gnueabihf` has `c-enum-min-bits = 32`, unlike the
+        // `thumbv*-none-eabi*` targets whose short enums need 1-byte tags
+        assert_eq!(TargetModel::ARMV7.c_enum, super::Scalar::new(4, 4));
+        assert_eq!(
+            layout(&TargetModel::ARMV7, "CEnum"),
+            (16, 8, alloc::vec![8, 8, 12])
+        );
+    }
+
+    #[test]
+    fn x86_64_layouts() {
+        for ty in TYS {
+            let graph = TypeLayoutGraph {
+                ty: ty.name,
+                tys: TYS,
+            };
+
+            assert_eq!(
+                TargetModel::X86_64.diff(&graph),
+                Ok(Vec::new()),
+                "{}",
+                ty.name
+            );
+        }
+    }
+
+    macro_rules! assert_host_layout {
+        ($($ty:ty),* $(,)?) => {
+            for (graph, result) in [$((
+                <$ty>::TYPE_GRAPH,
+                TargetModel::HOST.diff(&<$ty>::TYPE_GRAPH),
+            )),*] {
+                assert_eq!(result, Ok(alloc::vec::Vec::new()), "{}", graph.ty);
+            }
+        };
+    }
+
+    #[test]
+    fn host_layouts() {
+        assert_host_layout![
+            u8,
+            i16,
+            u32,
+            i64,
+            u128,
+            usize,
+            f32,
+            f64,
+            char,
+            bool,
+            (),
+            [u64; 3],
+            [[u16; 2]; 3],
+            core::cell::Cell<u16>,
+            core::cell::UnsafeCell<u128>,
+            core::cmp::Ordering,
+            core::cmp::Reverse<i8>,
+            core::ffi::c_void,
+            core::marker::PhantomData<u64>,
+            core::mem::ManuallyDrop<u64>,
+            core::num::NonZeroU32,
+            core::num::Wrapping<i128>,
+            core::ptr::NonNull<u8>,
+            *const u8,
+            &'static u64,
+            fn(u8) -> u16,
+            Option<&'static u64>,
+            Option<core::num::NonZeroU16>,
+            Option<fn()>,
+            core::convert::Infallible,
+        ];
+    }
+
+    #[test]
+    fn unspecified_layouts() {
+        assert_eq!(
+            TargetModel::HOST.layouts(&<(u8, u16)>::TYPE_GRAPH),
+            Err(Error::UnspecifiedLayout { ty: "(u8, u16)" })
+        );
+        assert_eq!(
+            TargetModel::HOST.layouts(&<Option<u8>>::TYPE_GRAPH),
+            Err(Error::UnspecifiedLayout {
+                ty: "core::option::Option<u8>"
+            })
+        );
+    }
+}
@@ -0,0 +1,528 @@
+//! Helper module to find the fields of concurrently accessed structs that
+//! straddle cache-line boundaries, or that share a cache line with other
+//! frequently written fields and may thus suffer from false sharing.
+
+use alloc::{format, string::String, vec::Vec};
+use core::fmt;
+
+use crate::{
+    primitive::Primitive, MaybeUninhabited, TypeGraphLayout, TypeLayoutGraph, TypeLayoutInfo,
+    TypeStructure,
+};
+
+/// Utility trait that assigns the fields of a struct to groups of fields that
+/// are written together, e.g. by the same thread.
+///
+/// This trait is implemented by
+/// [`#[derive(TypeLayout)]`](const_type_layout_derive::TypeLayout) if any
+/// field of a struct has a `#[layout(cache_group = "<group>")]` attribute.
+pub trait FieldGroups {
+    /// Pairs of the names of the struct's fields and of their groups.
+    const FIELD_GROUPS: &'static [(&'static str, &'static str)];
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Cache-line issue of a struct, as found by [`false_sharing`].
+///
+/// Fields of nested structs are named by their path, e.g. `stats.hits`.
+pub enum Issue<'a> {
+    /// The struct is aligned to fewer bytes than a cache line, so which of
+    /// its frequently written fields share a cache line depends on its
+    /// address. The other issues assume that the struct starts at a cache-line
+    /// boundary.
+    Unaligned {
+        /// The alignment of the struct.
+        alignment: usize,
+    },
+    /// The field straddles a cache-line boundary, even though it would fit
+    /// into a single cache line.
+    Straddle {
+        /// The path of the field.
+        field: String,
+        /// The fully-qualified name of the field's type.
+        ty: &'a str,
+        /// The offset of the field inside the struct.
+        offset: usize,
+        /// The size of the field.
+        size: usize,
+    },
+    /// The elements of the array field contain atomics or an
+    /// [`UnsafeCell`](core::cell::UnsafeCell), but are not padded to a cache
+    /// line, so neighbouring elements share a cache line.
+    UnpaddedArray {
+        /// The path of the field.
+        field: String,
+        /// The fully-qualified name of the field's type.
+        ty: &'a str,
+        /// The size of the array's elements.
+        element_size: usize,
+    },
+    /// The cache line is shared by frequently written fields from different
+    /// groups, at least one of which contains atomics or an
+    /// [`UnsafeCell`](core::cell::UnsafeCell).
+    FalseSharing {
+        /// The index of the cache line, starting at the struct's start.
+        line: usize,
+        /// The paths of the fields that share the cache line.
+        fields: Vec<String>,
+    },
+}
+
+impl fmt::Display for Issue<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Unaligned { alignment } => fmt.write_fmt(format_args!(
+                "the struct is only aligned to {alignment} bytes, so the fields that share a \
+                 cache line depend on its address"
+            )),
+            Self::Straddle {
+                field,
+                ty,
+                offset,
+                size,
+            } => fmt.write_fmt(format_args!(
+                "`{field}: {ty}` at offset {offset} with size {size} straddles a cache-line \
+                 boundary"
+            )),
+            Self::UnpaddedArray {
+                field,
+                ty,
+                element_size,
+            } => fmt.write_fmt(format_args!(
+                "the {element_size}-byte elements of `{field}: {ty}` share cache lines"
+            )),
+            Self::FalseSharing { line, fields } => {
+                fmt.write_fmt(format_args!(
+                    "cache line {line} is shared by the independently written fields "
+                ))?;
+
+                for (i, field) in fields.iter().enumerate() {
+                    if i > 0 {
+                        fmt.write_str(", ")?;
+                    }
+
+                    fmt.write_fmt(format_args!("`{field}`"))?;
+                }
+
+                Ok(())
+            },
+        }
+    }
+}
+
+#[must_use]
+/// Find the cache-line issues of the struct, which is described by `layout`
+/// inside the `graph`, for cache lines of `line_size` bytes, e.g. 64 or 128.
+///
+/// A field is frequently written if it contains atomics or an
+/// [`UnsafeCell`](core::cell::UnsafeCell), or if it is assigned to a group by
+/// the `groups`, which contain pairs of field names and group names, e.g.
+/// from [`FieldGroups::FIELD_GROUPS`]. Fields inside the same group may share
+/// a cache line, while every ungrouped field forms its own group. The fields
+/// of nested structs are analysed individually, but inherit the group of their
+/// outermost field.
+///
+/// Returns no issues if the `layout` is not a struct.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(const_type_name)]
+/// use core::cell::UnsafeCell;
+///
+/// use const_type_layout::{analysis::cache::false_sharing, TypeGraphLayout, TypeLayout};
+///
+/// type Counters = (UnsafeCell<u32>, UnsafeCell<u32>);
+///
+/// let graph = <Counters>::TYPE_GRAPH;
+/// let issues = false_sharing(&graph, &<Counters>::TYPE_LAYOUT, 64, &[]);
+///
+/// assert_eq!(issues.len(), 2);
+/// assert_eq!(
+///     issues[1].to_string(),
+///     "cache line 0 is shared by the independently written fields `0`, `1`",
+/// );
+///
+/// // both fields are written together
+/// let groups = [("0", "writer"), ("1", "writer")];
+/// let issues = false_sharing(&graph, &<Counters>::TYPE_LAYOUT, 64, &groups);
+///
+/// assert_eq!(issues.len(), 1);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `line_size` is zero.
+pub fn false_sharing<'a>(
+    graph: &TypeLayoutGraph<'a>,
+    layout: &TypeLayoutInfo<'a>,
+    line_size: usize,
+    groups: &[(&str, &str)],
+) -> Vec<Issue<'a>> {
+    assert!(line_size > 0, "a cache line must not be empty");
+
+    let TypeStructure::Struct { fields, .. } = layout.structure else {
+        return Vec::new();
+    };
+
+    let mut leaves = Vec::new();
+
+    for field in fields {
+        let MaybeUninhabited::Inhabited(offset) = field.offset else {
+            continue;
+        };
+
+        let group = groups
+            .iter()
+            .find(|(name, _)| *name == field.name)
+            .map(|(_, group)| *group);
+
+        flatten(
+            graph,
+            String::from(field.name),
+            field.ty,
+            offset,
+            group,
+            graph.tys.len(),
+            &mut leaves,
+        );
+    }
+
+    let mut issues = Vec::new();
+
+    if layout.alignment < line_size
+        && leaves
+            .iter()
+            .any(|leaf| leaf.mutable || matches!(leaf.group, Group::Named(_)))
+    {
+        issues.push(Issue::Unaligned {
+            alignment: layout.alignment,
+        });
+    }
+
+    for leaf in &leaves {
+        if leaf.size > 0
+            && leaf.size <= line_size
+            && leaf.offset / line_size != (leaf.offset + leaf.size - 1) / line_size
+        {
+            issues.push(Issue::Straddle {
+                field: leaf.name.clone(),
+                ty: leaf.ty,
+                offset: leaf.offset,
+                size: leaf.size,
+            });
+        }
+    }
+
+    for leaf in &leaves {
+        if let Primitive::Array { element, len } = Primitive::parse(leaf.ty) {
+            let element_size = graph.find_type(element).map_or(0, |element| element.size);
+
+            if len > 1
+                && element_size > 0
+                && element_size < line_size
+                && is_interior_mutable(graph, element, graph.tys.len())
+            {
+                issues.push(Issue::UnpaddedArray {
+                    field: leaf.name.clone(),
+                    ty: leaf.ty,
+                    element_size,
+                });
+            }
+        }
+    }
+
+    for line in 0..layout.size.div_ceil(line_size) {
+        let (start, end) = (line * line_size, (line + 1) * line_size);
+
+        let sharing = leaves
+            .iter()
+            .filter(|leaf| {
+                leaf.size > 0
+                    && leaf.offset < end
+                    && leaf.offset + leaf.size > start
+                    && (leaf.mutable || matches!(leaf.group, Group::Named(_)))
+            })
+            .collect::<Vec<_>>();
+
+        let independent = sharing
+            .iter()
+            .any(|leaf| sharing.iter().any(|other| other.group != leaf.group));
+
+        if independent && sharing.iter().any(|leaf| leaf.mutable) {
+            issues.push(Issue::FalseSharing {
+                line,
+                fields: sharing.iter().map(|leaf| leaf.name.clone()).collect(),
+            });
+        }
+    }
+
+    issues
+}
+
+#[must_use]
+/// Find the cache-line issues of the struct `T`, whose fields are grouped by
+/// its [`FieldGroups`], for cache lines of `line_size` bytes, as described in
+/// [`false_sharing`].
+#[cfg_attr(
+    all(feature = "derive", feature = "impl-atomics"),
+    doc = r##"
+# Example
+
+```rust
+# #![feature(const_type_name)]
+use core::sync::atomic::{AtomicU32, AtomicU64};
+
+use const_type_layout::{
+    analysis::cache::{false_sharing_of, FieldGroups, Issue},
+    TypeLayout,
+};
+
+#[repr(C, align(64))]
+#[derive(TypeLayout)]
+struct Queue {
+    #[layout(cache_group = "producer")]
+    head: AtomicU64,
+    #[layout(cache_group = "producer")]
+    pushed: AtomicU32,
+    #[layout(cache_group = "consumer")]
+    tail: AtomicU64,
+    // ungrouped fields are written independently of all other fields
+    dropped: AtomicU32,
+}
+
+assert_eq!(
+    Queue::FIELD_GROUPS,
+    [("head", "producer"), ("pushed", "producer"), ("tail", "consumer")],
+);
+assert_eq!(
+    false_sharing_of::<Queue>(64),
+    [Issue::FalseSharing {
+        line: 0,
+        fields: vec![
+            String::from("head"),
+            String::from("pushed"),
+            String::from("tail"),
+            String::from("dropped"),
+        ],
+    }],
+);
+
+#[repr(C, align(64))]
+#[derive(TypeLayout)]
+struct PaddedQueue {
+    #[layout(cache_group = "producer")]
+    head: AtomicU64,
+    #[layout(cache_group = "producer")]
+    pushed: AtomicU32,
+    _padding: [u8; 52],
+    #[layout(cache_group = "consumer")]
+    tail: AtomicU64,
+}
+
+// the fields of the same group may share a cache line
+assert_eq!(false_sharing_of::<PaddedQueue>(64), []);
+```
+"##
+)]
+/// # Panics
+///
+/// Panics if `line_size` is zero.
+pub fn false_sharing_of<T: TypeGraphLayout + FieldGroups>(line_size: usize) -> Vec<Issue<'static>> {
+    false_sharing(&T::TYPE_GRAPH, &T::TYPE_LAYOUT, line_size, T::FIELD_GROUPS)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Group<'g> {
+    Named(&'g str),
+    /// The ungrouped leaf field with the index.
+    Leaf(usize),
+}
+
+/// Field that is analysed as a whole.
+struct Leaf<'a, 'g> {
+    name: String,
+    ty: &'a str,
+    offset: usize,
+    size: usize,
+    mutable: bool,
+    group: Group<'g>,
+}
+
+/// Collects the fields inside the field `name` of type `ty` that are analysed
+/// as a whole, i.e. which are not nested structs, or are atomics or cells.
+fn flatten<'a, 'g>(
+    graph: &TypeLayoutGraph<'a>,
+    name: String,
+    ty: &'a str,
+    offset: usize,
+    group: Option<&'g str>,
+    depth: usize,
+    leaves: &mut Vec<Leaf<'a, 'g>>,
+) {
+    let Some(layout) = graph.find_type(ty) else {
+        return;
+    };
+
+    if let TypeStructure::Struct { fields, .. } = layout.structure {
+        if depth > 0 && !is_atomic(ty) && !is_cell(ty) {
+            for field in fields {
+                if let MaybeUninhabited::Inhabited(field_offset) = field.offset {
+                    flatten(
+                        graph,
+                        format!("{name}.{}", field.name),
+                        field.ty,
+                        offset + field_offset,
+                        group,
+                        depth - 1,
+                        leaves,
+                    );
+                }
+            }
+
+            return;
+        }
+    }
+
+    leaves.push(Leaf {
+        name,
+        ty,
+        offset,
+        size: layout.size,
+        mutable: is_interior_mutable(graph, ty, graph.tys.len()),
+        group: group.map_or(Group::Leaf(leaves.len()), Group::Named),
+    });
+}
+
+fn is_atomic(ty: &str) -> bool {
+    ty.starts_with("core::sync::atomic::Atomic")
+}
+
+fn is_cell(ty: &str) -> bool {
+    ty.starts_with("core::cell::")
+}
+
+/// Checks whether the type `ty` contains an
+/// [`UnsafeCell`](core::cell::UnsafeCell) by value, which includes all atomics.
+fn is_interior_mutable(graph: &TypeLayoutGraph, ty: &str, depth: usize) -> bool {
+    if ty.starts_with("core::cell::UnsafeCell<") {
+        return true;
+    }
+
+    let Some(layout) = graph.find_type(ty) else {
+        return false;
+    };
+
+    if depth == 0 {
+        return false;
+    }
+
+    match layout.structure {
+        TypeStructure::Primitive => match Primitive::parse(ty) {
+            Primitive::Array { element, .. } => is_interior_mutable(graph, element, depth - 1),
+            _ => false,
+        },
+        TypeStructure::Struct { fields, .. } | TypeStructure::Union { fields, .. } => fields
+            .iter()
+            .any(|field| is_interior_mutable(graph, field.ty, depth - 1)),
+        TypeStructure::Enum { variants, .. } => variants.iter().any(|variant| {
+            variant
+                .fields
+                .iter()
+                .any(|field| is_interior_mutable(graph, field.ty, depth - 1))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::String, vec};
+    use core::{any::type_name, cell::UnsafeCell};
+
+    use crate::{
+        Field, MaybeUninhabited, TypeLayout, TypeLayoutGraph, TypeLayoutInfo, TypeStructure,
+    };
+
+    use super::{false_sharing, Issue};
+
+    #[test]
+    fn issues() {
+        const GRAPH: TypeLayoutGraph<'static> = TypeLayoutGraph {
+            ty: "Queue",
+            tys: &[
+                &TypeLayoutInfo {
+                    name: "Queue",
+                    size: 32,
+                    alignment: 8,
+                    structure: TypeStructure::Struct {
+                        repr: "C",
+                        fields: &[
+                            Field {
+                                name: "flags",
+                                offset: MaybeUninhabited::Inhabited(0),
+                                ty: type_name::<[UnsafeCell<u8>; 4]>(),
+                            },
+                            Field {
+                                name: "len",
+                                offset: MaybeUninhabited::Inhabited(4),
+                                ty: type_name::<u32>(),
+                            },
+                            Field {
+                                name: "hits",
+                                offset: MaybeUninhabited::Inhabited(8),
+                                ty: type_name::<UnsafeCell<u32>>(),
+                            },
+                            Field {
+                                name: "name",
+                                offset: MaybeUninhabited::Inhabited(12),
+                                ty: type_name::<[u8; 6]>(),
+                            },
+                            Field {
+                                name: "count",
+                                offset: MaybeUninhabited::Inhabited(24),
+                                ty: type_name::<UnsafeCell<u64>>(),
+                            },
+                        ],
+                    },
+                },
+                &<[UnsafeCell<u8>; 4] as TypeLayout>::TYPE_LAYOUT,
+                &<UnsafeCell<u8> as TypeLayout>::TYPE_LAYOUT,
+                &<u8 as TypeLayout>::TYPE_LAYOUT,
+                &<u32 as TypeLayout>::TYPE_LAYOUT,
+                &<UnsafeCell<u32> as TypeLayout>::TYPE_LAYOUT,
+                &<[u8; 6] as TypeLayout>::TYPE_LAYOUT,
+                &<UnsafeCell<u64> as TypeLayout>::TYPE_LAYOUT,
+                &<u64 as TypeLayout>::TYPE_LAYOUT,
+            ],
+        };
+
+        let layout = GRAPH.find_type(GRAPH.ty).unwrap();
+
+        assert_eq!(
+            false_sharing(&GRAPH, layout, 16, &[]),
+            [
+                Issue::Unaligned { alignment: 8 },
+                Issue::Straddle {
+                    field: String::from("name"),
+                    ty: "[u8; 6]",
+                    offset: 12,
+                    size: 6,
+                },
+                Issue::UnpaddedArray {
+                    field: String::from("flags"),
+                    ty: "[core::cell::UnsafeCell<u8>; 4]",
+                    element_size: 1,
+                },
+                Issue::FalseSharing {
+                    line: 0,
+                    fields: vec![String::from("flags"), String::from("hits")],
+                },
+            ]
+        );
+
+        assert_eq!(
+            false_sharing(&GRAPH, layout, 16, &[("flags", "a"), ("hits", "a")]).len(),
+            3
+        );
+    }
+}
@@ -0,0 +1,581 @@
+//! Helper module to find types whose layout is chosen by the compiler, i.e.
+//! which are not guaranteed to have the same layout across compiler versions
+//! or targets and must thus not cross an FFI boundary.
+
+use alloc::{
+    collections::{BTreeSet, VecDeque},
+    vec::Vec,
+};
+use core::fmt;
+
+use crate::{
+    primitive::Primitive,
+    repr::{EnumEncoding, Repr},
+    TypeGraphLayout, TypeLayoutGraph, TypeLayoutInfo, TypeStructure,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Reason why the layout of a type is not guaranteed.
+pub enum Reason {
+    /// The type is a `#[repr(Rust)]` struct, whose fields may be reordered.
+    Struct,
+    /// The type is a `#[repr(Rust)]` struct with a single field that is not
+    /// `#[repr(transparent)]`, and may thus add padding or change its
+    /// alignment.
+    Wrapper,
+    /// The type is a `#[repr(Rust)]` union.
+    Union,
+    /// The type is an enum without a `C` or primitive integer representation,
+    /// whose discriminant encoding is chosen by the compiler.
+    Enum,
+}
+
+impl Reason {
+    #[must_use]
+    /// Checks why the layout of the type, which is described by `layout` inside
+    /// the `graph`, is not guaranteed, or returns [`None`] if it is.
+    ///
+    /// The layout of a type is guaranteed if
+    /// - it is a primitive type
+    /// - it is zero-sized
+    /// - it is a struct or union with a `#[repr(C)]` or `#[repr(transparent)]`
+    ///   attribute
+    /// - it is an enum with a `#[repr(C)]` or primitive integer representation
+    /// - it is an `Option`-like enum that uses the guaranteed null-pointer
+    ///   optimisation, e.g. `Option<&T>` or `Option<NonZeroU32>`
+    ///
+    /// Note that the layouts of a type's fields are not checked.
+    pub const fn of<'a>(graph: &TypeLayoutGraph<'a>, layout: &TypeLayoutInfo<'a>) -> Option<Self> {
+        if layout.size == 0 {
+            return None;
+        }
+
+        match layout.structure {
+            TypeStructure::Primitive => None,
+            TypeStructure::Struct { repr, .. } | TypeStructure::Union { repr, .. }
+                if !Repr::parse(repr).is_rust() =>
+            {
+                None
+            },
+            TypeStructure::Struct { fields, .. } if fields.len() == 1 => Some(Self::Wrapper),
+            TypeStructure::Struct { .. } => Some(Self::Struct),
+            TypeStructure::Union { .. } => Some(Self::Union),
+            TypeStructure::Enum { repr, variants } => {
+                let repr = Repr::parse(repr);
+
+                if !repr.is_rust() {
+                    return None;
+                }
+
+                match EnumEncoding::of(graph, layout, repr, variants) {
+                    EnumEncoding::NullPointer { .. } => None,
+                    _ => Some(Self::Enum),
+                }
+            },
+        }
+    }
+
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Struct => "is a `#[repr(Rust)]` struct",
+            Self::Wrapper => "is a `#[repr(Rust)]` wrapper that is not `#[repr(transparent)]`",
+            Self::Union => "is a `#[repr(Rust)]` union",
+            Self::Enum => "is an enum without a `C` or primitive integer representation",
+        }
+    }
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Step along a [`Path`] from one type to another type that it contains or
+/// links to.
+pub enum Step<'a> {
+    /// The field `field` of a struct or union, or of the enum variant
+    /// `variant`.
+    Field {
+        /// The name of the enum variant that contains the field, if any.
+        variant: Option<&'a str>,
+        /// The name of the field.
+        field: &'a str,
+    },
+    /// The type that a pointer or reference points to.
+    Pointee,
+    /// The element type of an array.
+    Element,
+    /// A parameter or return type in the signature of a function pointer.
+    Signature,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Path from the root of a [`TypeLayoutGraph`] to a type inside it.
+pub struct Path<'a> {
+    /// The fully-qualified name of the root type.
+    pub root: &'a str,
+    /// The steps that lead from the root type to the type.
+    pub steps: Vec<Step<'a>>,
+}
+
+impl fmt::Display for Path<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(self.root)?;
+
+        for step in &self.steps {
+            match step {
+                Step::Field {
+                    variant: Some(variant),
+                    field,
+                } => fmt.write_fmt(format_args!("::{variant}.{field}")),
+                Step::Field {
+                    variant: None,
+                    field,
+                } => fmt.write_fmt(format_args!(".{field}")),
+                Step::Pointee => fmt.write_str(".*"),
+                Step::Element => fmt.write_str("[_]"),
+                Step::Signature => fmt.write_str("(fn)"),
+            }?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Type whose layout is not guaranteed, as found by [`unspecified_layouts`].
+pub struct UnspecifiedLayout<'a> {
+    /// The fully-qualified name of the type.
+    pub ty: &'a str,
+    /// The reason why the type's layout is not guaranteed.
+    pub reason: Reason,
+    /// A shortest path from the graph's root type to the type.
+    pub path: Path<'a>,
+}
+
+impl fmt::Display for UnspecifiedLayout<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_fmt(format_args!(
+            "`{}` has the type `{}`, which {}",
+            self.path, self.ty, self.reason
+        ))
+    }
+}
+
+#[must_use]
+/// Find all types that are reachable from the root of the `graph` and whose
+/// layout is not guaranteed, as defined by [`Reason::of`].
+///
+/// Types are reachable through struct, union, and enum variant fields, the
+/// pointees of pointers and references, array elements, and the parameter and
+/// return types of function pointers, as a C library may access any of them.
+///
+/// The results are ordered by the length of their [`Path`] from the root.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(const_type_name)]
+/// use const_type_layout::{analysis::ffi::unspecified_layouts, TypeGraphLayout};
+///
+/// type Ffi = (u8, Option<&'static core::ops::Range<u32>>);
+///
+/// let graph = <Ffi>::TYPE_GRAPH;
+/// let unspecified = unspecified_layouts(&graph);
+///
+/// assert_eq!(unspecified.len(), 2);
+/// assert_eq!(
+///     unspecified[0].to_string(),
+///     "`(u8, core::option::Option<&core::ops::range::Range<u32>>)` has the type \
+///      `(u8, core::option::Option<&core::ops::range::Range<u32>>)`, which is a \
+///      `#[repr(Rust)]` struct",
+/// );
+/// assert_eq!(
+///     unspecified[1].path.to_string(),
+///     "(u8, core::option::Option<&core::ops::range::Range<u32>>).1::Some.0.*",
+/// );
+/// ```
+pub fn unspecified_layouts<'a>(graph: &TypeLayoutGraph<'a>) -> Vec<UnspecifiedLayout<'a>> {
+    let mut unspecified = Vec::new();
+
+    let mut visited = BTreeSet::new();
+    let mut queue = VecDeque::from([(graph.ty, Vec::new())]);
+
+    while let Some((ty, steps)) = queue.pop_front() {
+        if !visited.insert(ty) {
+            continue;
+        }
+
+        let Some(layout) = graph.find_type(ty) else {
+            continue;
+        };
+
+        let mut visit = |ty: &'a str, step: Step<'a>| {
+            if !visited.contains(ty) {
+                let mut steps = steps.clone();
+                steps.push(step);
+                queue.push_back((ty, steps));
+            }
+        };
+
+        match &layout.structure {
+            TypeStructure::Primitive => match Primitive::parse(layout.name) {
+                Primitive::Pointer { pointee: inner, .. }
+                | Primitive::Reference {
+                    referent: inner, ..
+                } => visit(inner, Step::Pointee),
+                Primitive::Array { element, .. } => visit(element, Step::Element),
+                // function pointer signatures are not parsed, so approximate
+                //  by looking for type names inside the signature
+                Primitive::FnPointer { signature } => {
+                    for ty in graph.tys {
+                        if ty.name != signature && mentions_type(signature, ty.name) {
+                            visit(ty.name, Step::Signature);
+                        }
+                    }
+                },
+                _ => (),
+            },
+            TypeStructure::Struct { fields, .. } | TypeStructure::Union { fields, .. } => {
+                for field in *fields {
+                    visit(
+                        field.ty,
+                        Step::Field {
+                            variant: None,
+                            field: field.name,
+                        },
+                    );
+                }
+            },
+            TypeStructure::Enum { variants, .. } => {
+                for variant in *variants {
+                    for field in variant.fields {
+                        visit(
+                            field.ty,
+                            Step::Field {
+                                variant: Some(variant.name),
+                                field: field.name,
+                            },
+                        );
+                    }
+                }
+            },
+        }
+
+        if let Some(reason) = Reason::of(graph, layout) {
+            unspecified.push(UnspecifiedLayout {
+                ty,
+                reason,
+                path: Path {
+                    root: graph.ty,
+                    steps,
+                },
+            });
+        }
+    }
+
+    unspecified
+}
+
+#[must_use]
+/// Find any type inside the `graph` whose layout is not guaranteed, as defined
+/// by [`Reason::of`].
+///
+/// Unlike [`unspecified_layouts`], this function can be used in const
+/// contexts. It checks every type in [`TypeLayoutGraph::tys`], which includes
+/// all types that are reachable from the root.
+pub const fn find_unspecified_layout<'a>(
+    graph: &TypeLayoutGraph<'a>,
+) -> Option<(&'a TypeLayoutInfo<'a>, Reason)> {
+    let mut i = 0;
+
+    while i < graph.tys.len() {
+        if let Some(reason) = Reason::of(graph, graph.tys[i]) {
+            return Some((graph.tys[i], reason));
+        }
+
+        i += 1;
+    }
+
+    None
+}
+
+/// Assert that the layout of the type `T`, and of all types that it contains
+/// or links to, is guaranteed, as defined by [`Reason::of`].
+///
+/// This function is used by the `#[layout(ffi_safe)]` attribute of
+/// [`#[derive(TypeLayout)]`](const_type_layout_derive::TypeLayout) to check a
+/// type at compile time.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(const_type_name)]
+/// # use const_type_layout::analysis::ffi::assert_ffi_safe;
+/// const _: () = assert_ffi_safe::<Option<&core::num::NonZeroU32>>();
+/// ```
+///
+/// fails to compile since tuples have a `#[repr(Rust)]` layout:
+///
+/// ```rust,compile_fail
+/// # #![feature(const_type_name)]
+/// # use const_type_layout::analysis::ffi::assert_ffi_safe;
+/// const _: () = assert_ffi_safe::<[(u8, u16); 2]>();
+/// ```
+#[cfg_attr(
+    feature = "derive",
+    doc = r##"
+The `#[layout(ffi_safe)]` derive attribute performs the same check:
+
+```rust
+# #![feature(const_type_name)]
+use const_type_layout::TypeLayout;
+
+#[repr(C)]
+#[derive(TypeLayout)]
+#[layout(ffi_safe)]
+struct Header {
+    len: u32,
+    next: Option<&'static Header>,
+}
+```
+
+fails to compile since the field's tuple has a `#[repr(Rust)]` layout:
+
+```rust,compile_fail
+# #![feature(const_type_name)]
+# use const_type_layout::TypeLayout;
+#[repr(C)]
+#[derive(TypeLayout)]
+#[layout(ffi_safe)]
+struct Header {
+    len: u32,
+    range: (u8, u16),
+}
+```
+"##
+)]
+/// # Panics
+///
+/// This function panics iff the layout of any type in the
+/// [`TypeLayoutGraph`] of `T` is not guaranteed.
+pub const fn assert_ffi_safe<T: TypeGraphLayout>() {
+    const PREFIX: &str = "[const-type-layout]: the layout of `";
+    const INFIX: &str = "` is not guaranteed, it ";
+
+    let Some((layout, reason)) = find_unspecified_layout(&T::TYPE_GRAPH) else {
+        return;
+    };
+
+    let mut message = [0_u8; 512];
+    let mut len = 0;
+
+    len = write_str(&mut message, len, PREFIX);
+    len = write_str(&mut message, len, layout.name);
+    len = write_str(&mut message, len, INFIX);
+    len = write_str(&mut message, len, reason.as_str());
+
+    let (message, _) = message.split_at(len);
+
+    if let Ok(message) = core::str::from_utf8(message) {
+        panic!("{}", message);
+    }
+
+    // the message was truncated inside a multi-byte character
+    panic!("{}", layout.name);
+}
+
+/// Checks if the function pointer `signature` mentions the whole type `ty`,
+/// e.g. `fn(FooBar<u8>)` mentions `FooBar<u8>` and `u8`, but neither `Foo`,
+/// `Bar`, nor `FooBar`.
+fn mentions_type(signature: &str, ty: &str) -> bool {
+    const fn is_path_byte(byte: u8) -> bool {
+        byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b':') || !byte.is_ascii()
+    }
+
+    let bytes = signature.as_bytes();
+
+    signature.match_indices(ty).any(|(start, _)| {
+        let end = start + ty.len();
+
+        let before = start.checked_sub(1).map(|i| bytes[i]);
+        let after = bytes.get(end).copied();
+
+        !before.map_or(false, is_path_byte)
+            && !after.map_or(false, |b| is_path_byte(b) || b == b'<')
+    })
+}
+
+/// Writes as much of `s` into the `buffer`, starting at `len`, as fits, and
+/// returns the new length.
+pub(crate) const fn write_str(buffer: &mut [u8], mut len: usize, s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() && len < buffer.len() {
+        buffer[len] = bytes[i];
+        len += 1;
+        i += 1;
+    }
+
+    len
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec::Vec};
+
+    use crate::{
+        Field, MaybeUninhabited, TypeGraphLayout, TypeLayoutGraph, TypeLayoutInfo, TypeStructure,
+    };
+
+    use super::{mentions_type, unspecified_layouts, Reason, Step};
+
+    macro_rules! rust_struct {
+        ($name:literal) => {
+            &TypeLayoutInfo {
+                name: $name,
+                size: 2,
+                alignment: 1,
+                structure: TypeStructure::Struct {
+                    repr: "",
+                    fields: &[
+                        Field {
+                            name: "a",
+                            offset: MaybeUninhabited::Inhabited(0),
+                            ty: "u8",
+                        },
+                        Field {
+                            name: "b",
+                            offset: MaybeUninhabited::Inhabited(1),
+                            ty: "u8",
+                        },
+                    ],
+                },
+            }
+        };
+    }
+
+    const SIGNATURE: TypeLayoutGraph<'static> = TypeLayoutGraph {
+        ty: "fn(FooBar<u8>) -> a::Foo",
+        tys: &[
+            &TypeLayoutInfo {
+                name: "fn(FooBar<u8>) -> a::Foo",
+                size: 8,
+                alignment: 8,
+                structure: TypeStructure::Primitive,
+            },
+            rust_struct!("Bar"),
+            rust_struct!("Foo"),
+            rust_struct!("FooBar<u8>"),
+            rust_struct!("a::Foo"),
+            &TypeLayoutInfo {
+                name: "u8",
+                size: 1,
+                alignment: 1,
+                structure: TypeStructure::Primitive,
+            },
+        ],
+    };
+
+    fn unspecified(
+        graph: &TypeLayoutGraph<'static>,
+    ) -> Vec<(&'static str, Reason, Vec<Step<'static>>)> {
+        unspecified_layouts(graph)
+            .into_iter()
+            .map(|unspecified| (unspecified.ty, unspecified.reason, unspecified.path.steps))
+            .collect()
+    }
+
+    #[test]
+    fn paths() {
+        type Ffi = (
+            u8,
+            [(u16, u8); 2],
+            &'static Option<core::num::Wrapping<u8>>,
+            fn(core::ops::Range<u8>),
+        );
+
+        let graph = <Ffi>::TYPE_GRAPH;
+
+        assert_eq!(
+            unspecified(&graph),
+            [
+                (graph.ty, Reason::Struct, Vec::new()),
+                (
+                    "(u16, u8)",
+                    Reason::Struct,
+                    alloc::vec![
+                        Step::Field {
+                            variant: None,
+                            field: "1"
+                        },
+                        Step::Element
+                    ]
+                ),
+                (
+                    "core::option::Option<core::num::wrapping::Wrapping<u8>>",
+                    Reason::Enum,
+                    alloc::vec![
+                        Step::Field {
+                            variant: None,
+                            field: "2"
+                        },
+                        Step::Pointee
+                    ]
+                ),
+                (
+                    "core::ops::range::Range<u8>",
+                    Reason::Struct,
+                    alloc::vec![
+                        Step::Field {
+                            variant: None,
+                            field: "3"
+                        },
+                        Step::Signature
+                    ]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn variant_paths() {
+        let graph = <Option<(u8, u16)>>::TYPE_GRAPH;
+        let unspecified = unspecified_layouts(&graph);
+
+        assert_eq!(unspecified.len(), 2);
+        assert_eq!(unspecified[0].reason, Reason::Enum);
+        assert_eq!(unspecified[1].ty, "(u8, u16)");
+        assert_eq!(
+            unspecified[1].path.to_string(),
+            "core::option::Option<(u8, u16)>::Some.0"
+        );
+
+        assert_eq!(unspecified_layouts(&<Option<&'static u8>>::TYPE_GRAPH), []);
+    }
+
+    #[test]
+    fn signature_tokens() {
+        assert_eq!(
+            unspecified(&SIGNATURE),
+            [
+                ("FooBar<u8>", Reason::Struct, alloc::vec![Step::Signature]),
+                ("a::Foo", Reason::Struct, alloc::vec![Step::Signature]),
+            ]
+        );
+
+        assert!(mentions_type("fn(Foo)", "Foo"));
+        assert!(mentions_type("fn(&Foo, u8) -> Foo", "Foo"));
+        assert!(mentions_type("fn([Foo; 2])", "Foo"));
+        assert!(mentions_type("fn(Bar<Foo>)", "Foo"));
+        assert!(!mentions_type("fn(FooBar)", "Foo"));
+        assert!(!mentions_type("fn(BarFoo)", "Foo"));
+        assert!(!mentions_type("fn(Foo<u8>)", "Foo"));
+        assert!(!mentions_type("fn(a::Foo)", "Foo"));
+        assert!(!mentions_type("fn(Foo::Bar)", "Foo"));
+        assert!(!mentions_type("fn(Foo_)", "Foo"));
+    }
+}
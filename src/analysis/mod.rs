@@ -0,0 +1,9 @@
+//! Helper module to analyse [`crate::TypeLayoutGraph`]s, e.g. to find layout
+//! properties that matter when types cross an FFI boundary, to find field
+//! orders that waste less padding, to find fields that may suffer from false
+//! sharing, or to find pointers and interior mutability inside a type.
+
+pub mod cache;
+pub mod ffi;
+pub mod properties;
+pub mod reorder;
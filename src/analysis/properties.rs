@@ -0,0 +1,616 @@
+//! Helper module to find out whether a type transitively contains pointers,
+//! references, or interior mutability, e.g. to check that a type can be
+//! placed in shared memory, persisted to disk, or sent to another process.
+
+use alloc::{
+    collections::{BTreeSet, VecDeque},
+    vec::Vec,
+};
+use core::fmt;
+
+use crate::{
+    primitive::{is_non_null_pointer, strip_prefix, Primitive},
+    Field, TypeGraphLayout, TypeLayoutGraph, TypeLayoutInfo, TypeStructure,
+};
+
+use super::ffi::{write_str, Path, Step};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Property of a type that a [`TypeLayoutGraph`] can be searched for.
+pub enum Property {
+    /// The type is a raw pointer, e.g. `*const T` or `*mut T`.
+    RawPointer,
+    /// The type is a reference, e.g. `&T` or `&mut T`.
+    Reference,
+    /// The type is a [`NonNull`](core::ptr::NonNull) pointer.
+    NonNull,
+    /// The type is a function pointer, e.g. `fn(u8) -> u16`.
+    FnPointer,
+    /// The type provides interior mutability, i.e. it is an
+    /// [`UnsafeCell`](core::cell::UnsafeCell), one of the cells in
+    /// [`core::cell`] that are built on top of it, or an atomic.
+    InteriorMutability,
+}
+
+impl Property {
+    /// All properties that make a value depend on the address space that it
+    /// was created in, i.e. all kinds of pointers.
+    pub const ADDRESS_DEPENDENT: [Self; 4] = [
+        Self::RawPointer,
+        Self::Reference,
+        Self::NonNull,
+        Self::FnPointer,
+    ];
+
+    #[must_use]
+    /// Checks if the type, which is described by `layout`, itself has this
+    /// property, without looking at the types that it contains.
+    pub const fn matches(self, layout: &TypeLayoutInfo) -> bool {
+        match self {
+            Self::RawPointer => matches!(Primitive::of(layout), Some(Primitive::Pointer { .. })),
+            Self::Reference => matches!(Primitive::of(layout), Some(Primitive::Reference { .. })),
+            Self::NonNull => is_non_null_pointer(layout.name),
+            Self::FnPointer => matches!(Primitive::of(layout), Some(Primitive::FnPointer { .. })),
+            Self::InteriorMutability => {
+                strip_prefix(layout.name, "core::cell::UnsafeCell<").is_some()
+                    || strip_prefix(layout.name, "core::cell::SyncUnsafeCell<").is_some()
+                    || strip_prefix(layout.name, "core::cell::Cell<").is_some()
+                    || strip_prefix(layout.name, "core::cell::RefCell<").is_some()
+                    || strip_prefix(layout.name, "core::cell::once::OnceCell<").is_some()
+                    || strip_prefix(layout.name, "core::sync::atomic::Atomic").is_some()
+            },
+        }
+    }
+
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::RawPointer => "a raw pointer",
+            Self::Reference => "a reference",
+            Self::NonNull => "a `NonNull` pointer",
+            Self::FnPointer => "a function pointer",
+            Self::InteriorMutability => "interior mutability",
+        }
+    }
+}
+
+impl fmt::Display for Property {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(self.as_str())
+    }
+}
+
+#[must_use]
+/// Checks if the root type of the `graph` contains, by value, any type that
+/// has the `property`.
+///
+/// Types are contained through struct, union, and enum variant fields, and
+/// array elements, but not through the pointees of pointers or references,
+/// since those are not stored inside the value itself. A type that does not
+/// itself have the `property` is searched further, e.g. a
+/// [`NonNull`](core::ptr::NonNull) pointer contains a raw pointer.
+pub const fn contains(graph: &TypeLayoutGraph, property: Property) -> bool {
+    find_in_graph(graph, property, &mut [], 0).is_some()
+}
+
+#[must_use]
+/// Find a shortest [`Path`] from the root of the `graph` to a type that it
+/// contains by value, as defined by [`contains`], and that has the
+/// `property`.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(const_type_name)]
+/// use const_type_layout::{
+///     analysis::properties::{find, Property},
+///     TypeGraphLayout,
+/// };
+///
+/// type Shared = (u32, [Option<core::cell::Cell<u8>>; 4]);
+///
+/// let graph = <Shared>::TYPE_GRAPH;
+///
+/// assert_eq!(
+///     find(&graph, Property::InteriorMutability)
+///         .unwrap()
+///         .to_string(),
+///     "(u32, [core::option::Option<core::cell::Cell<u8>>; 4]).1[_]::Some.0",
+/// );
+/// assert_eq!(find(&graph, Property::Reference), None);
+/// ```
+pub fn find<'a>(graph: &TypeLayoutGraph<'a>, property: Property) -> Option<Path<'a>> {
+    let mut visited = BTreeSet::new();
+    let mut queue = VecDeque::from([(graph.ty, Vec::new())]);
+
+    while let Some((ty, steps)) = queue.pop_front() {
+        if !visited.insert(ty) {
+            continue;
+        }
+
+        let Some(layout) = graph.find_type(ty) else {
+            continue;
+        };
+
+        if property.matches(layout) {
+            return Some(Path {
+                root: graph.ty,
+                steps,
+            });
+        }
+
+        let mut visit = |ty: &'a str, step: Step<'a>| {
+            if !visited.contains(ty) {
+                let mut steps = steps.clone();
+                steps.push(step);
+                queue.push_back((ty, steps));
+            }
+        };
+
+        match &layout.structure {
+            TypeStructure::Primitive => {
+                if let Primitive::Array { element, .. } = Primitive::parse(layout.name) {
+                    visit(element, Step::Element);
+                }
+            },
+            TypeStructure::Struct { fields, .. } | TypeStructure::Union { fields, .. } => {
+                for field in *fields {
+                    visit(
+                        field.ty,
+                        Step::Field {
+                            variant: None,
+                            field: field.name,
+                        },
+                    );
+                }
+            },
+            TypeStructure::Enum { variants, .. } => {
+                for variant in *variants {
+                    for field in variant.fields {
+                        visit(
+                            field.ty,
+                            Step::Field {
+                                variant: Some(variant.name),
+                                field: field.name,
+                            },
+                        );
+                    }
+                }
+            },
+        }
+    }
+
+    None
+}
+
+/// Assert that the type `T` does not contain, by value, any type that has one
+/// of the `properties`, as defined by [`contains`].
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(const_type_name)]
+/// # use const_type_layout::analysis::properties::{assert_without, Property};
+/// const _: () = assert_without::<(u8, [u32; 4])>(&Property::ADDRESS_DEPENDENT);
+/// ```
+///
+/// fails to compile since the tuple contains a reference:
+///
+/// ```rust,compile_fail
+/// # #![feature(const_type_name)]
+/// # use const_type_layout::analysis::properties::{assert_without, Property};
+/// const _: () = assert_without::<(u8, Option<&'static u32>)>(&[Property::Reference]);
+/// ```
+///
+/// # Panics
+///
+/// This function panics iff the [`TypeLayoutGraph`] of `T` contains a type
+/// that has any of the `properties`. The panic message includes the path to
+/// the offending field.
+pub const fn assert_without<T: TypeGraphLayout>(properties: &[Property]) {
+    const PREFIX: &str = "[const-type-layout]: `";
+    const INFIX: &str = "` contains ";
+    const AT: &str = " at `";
+    const SUFFIX: &str = "`";
+
+    let graph = T::TYPE_GRAPH;
+
+    let mut i = 0;
+
+    while i < properties.len() {
+        let mut message = [0_u8; 512];
+        let mut len = 0;
+
+        len = write_str(&mut message, len, PREFIX);
+        len = write_str(&mut message, len, graph.ty);
+        len = write_str(&mut message, len, INFIX);
+        len = write_str(&mut message, len, properties[i].as_str());
+        len = write_str(&mut message, len, AT);
+        len = write_str(&mut message, len, graph.ty);
+
+        if let Some(mut len) = find_in_graph(&graph, properties[i], &mut message, len) {
+            len = write_str(&mut message, len, SUFFIX);
+
+            let (message, _) = message.split_at(len);
+
+            if let Ok(message) = core::str::from_utf8(message) {
+                panic!("{}", message);
+            }
+
+            // the message was truncated inside a multi-byte character
+            panic!("{}", graph.ty);
+        }
+
+        i += 1;
+    }
+}
+
+/// Assert that the type `T` can be shared with another address space, e.g.
+/// through shared memory or a file, by copying its bytes, i.e. that it
+/// neither contains any [`Property::ADDRESS_DEPENDENT`] pointers nor
+/// [`Property::InteriorMutability`], and does not need to be dropped.
+///
+/// Since drop glue is not described by a [`TypeLayoutGraph`], the
+/// [`needs_drop`](core::mem::needs_drop) check only reports `T` itself, not
+/// the field that needs to be dropped.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(const_type_name)]
+/// # use const_type_layout::analysis::properties::assert_shareable;
+/// const _: () = assert_shareable::<([u8; 8], Option<core::num::NonZeroU32>)>();
+/// ```
+///
+/// fails to compile since atomics are interior mutable:
+///
+/// ```rust,compile_fail
+/// # #![feature(const_type_name)]
+/// # use const_type_layout::analysis::properties::assert_shareable;
+/// const _: () = assert_shareable::<(u8, core::sync::atomic::AtomicU32)>();
+/// ```
+///
+/// # Panics
+///
+/// This function panics iff `T` contains a type that has any of the
+/// properties above, or if `T` needs to be dropped.
+pub const fn assert_shareable<T: TypeGraphLayout>() {
+    assert_without::<T>(&Property::ADDRESS_DEPENDENT);
+    assert_without::<T>(&[Property::InteriorMutability]);
+
+    if core::mem::needs_drop::<T>() {
+        const PREFIX: &str = "[const-type-layout]: `";
+        const SUFFIX: &str = "` needs to be dropped";
+
+        let mut message = [0_u8; 512];
+        let mut len = 0;
+
+        len = write_str(&mut message, len, PREFIX);
+        len = write_str(&mut message, len, T::TYPE_GRAPH.ty);
+        len = write_str(&mut message, len, SUFFIX);
+
+        let (message, _) = message.split_at(len);
+
+        if let Ok(message) = core::str::from_utf8(message) {
+            panic!("{}", message);
+        }
+
+        // the message was truncated inside a multi-byte character
+        panic!("{}", T::TYPE_GRAPH.ty);
+    }
+}
+
+/// The number of types in a graph that [`find_in_graph`] remembers as visited,
+/// beyond which types may be searched more than once.
+const MAX_VISITED: usize = 4096;
+
+/// Searches the root of the `graph` and the types that it contains by value
+/// for a type with the `property`, and writes the path to it into the
+/// `buffer`, starting at `len`. Returns the new length iff a type with the
+/// `property` was found.
+const fn find_in_graph(
+    graph: &TypeLayoutGraph,
+    property: Property,
+    buffer: &mut [u8],
+    len: usize,
+) -> Option<usize> {
+    // only search for a path if any type in the graph has the property
+    let mut i = 0;
+
+    while i < graph.tys.len() {
+        if property.matches(graph.tys[i]) {
+            break;
+        }
+
+        i += 1;
+    }
+
+    if i == graph.tys.len() {
+        return None;
+    }
+
+    let mut visited = [0_u64; MAX_VISITED / 64];
+
+    find_in_type(
+        graph,
+        graph.ty,
+        property,
+        graph.tys.len(),
+        &mut visited,
+        buffer,
+        len,
+    )
+}
+
+/// Searches the type `ty` and the types that it contains by value, up to
+/// `depth` levels deep, for a type with the `property`, and writes the path
+/// to it into the `buffer`, starting at `len`. Returns the new length iff a
+/// type with the `property` was found.
+///
+/// Every type is only searched once, after which it is marked in the
+/// `visited` bitmap of type indices.
+const fn find_in_type(
+    graph: &TypeLayoutGraph,
+    ty: &str,
+    property: Property,
+    depth: usize,
+    visited: &mut [u64; MAX_VISITED / 64],
+    buffer: &mut [u8],
+    len: usize,
+) -> Option<usize> {
+    let Some(index) = graph.find_type_index(ty) else {
+        return None;
+    };
+
+    if index < MAX_VISITED {
+        if (visited[index / 64] & (1 << (index % 64))) != 0 {
+            return None;
+        }
+
+        visited[index / 64] |= 1 << (index % 64);
+    }
+
+    let layout = graph.tys[index];
+
+    if property.matches(layout) {
+        return Some(len);
+    }
+
+    if depth == 0 {
+        return None;
+    }
+
+    match layout.structure {
+        TypeStructure::Primitive => match Primitive::parse(layout.name) {
+            Primitive::Array { element, .. } => {
+                let len = write_str(buffer, len, "[_]");
+                find_in_type(graph, element, property, depth - 1, visited, buffer, len)
+            },
+            _ => None,
+        },
+        TypeStructure::Struct { fields, .. } | TypeStructure::Union { fields, .. } => {
+            find_in_fields(
+                graph,
+                None,
+                fields,
+                property,
+                depth - 1,
+                visited,
+                buffer,
+                len,
+            )
+        },
+        TypeStructure::Enum { variants, .. } => {
+            let mut i = 0;
+
+            while i < variants.len() {
+                if let Some(len) = find_in_fields(
+                    graph,
+                    Some(variants[i].name),
+                    variants[i].fields,
+                    property,
+                    depth - 1,
+                    visited,
+                    buffer,
+                    len,
+                ) {
+                    return Some(len);
+                }
+
+                i += 1;
+            }
+
+            None
+        },
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+const fn find_in_fields(
+    graph: &TypeLayoutGraph,
+    variant: Option<&str>,
+    fields: &[Field],
+    property: Property,
+    depth: usize,
+    visited: &mut [u64; MAX_VISITED / 64],
+    buffer: &mut [u8],
+    len: usize,
+) -> Option<usize> {
+    let mut i = 0;
+
+    while i < fields.len() {
+        let mut field_len = len;
+
+        if let Some(variant) = variant {
+            field_len = write_str(buffer, field_len, "::");
+            field_len = write_str(buffer, field_len, variant);
+        }
+
+        field_len = write_str(buffer, field_len, ".");
+        field_len = write_str(buffer, field_len, fields[i].name);
+
+        if let Some(len) = find_in_type(
+            graph,
+            fields[i].ty,
+            property,
+            depth,
+            visited,
+            buffer,
+            field_len,
+        ) {
+            return Some(len);
+        }
+
+        i += 1;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::{assert_shareable, contains, find, find_in_graph, Property};
+    use crate::{
+        Field, MaybeUninhabited, TypeGraphLayout, TypeLayoutGraph, TypeLayoutInfo, TypeStructure,
+    };
+
+    const _: () = assert_shareable::<(u8, [u16; 2], Option<core::num::NonZeroU64>)>();
+
+    macro_rules! field {
+        ($name:literal, $ty:literal) => {
+            Field {
+                name: $name,
+                offset: MaybeUninhabited::Inhabited(0),
+                ty: $ty,
+            }
+        };
+    }
+
+    macro_rules! level {
+        ($name:literal, $ty:literal) => {
+            TypeLayoutInfo {
+                name: $name,
+                size: 0,
+                alignment: 1,
+                structure: TypeStructure::Struct {
+                    repr: "",
+                    fields: &[
+                        field!("a", $ty),
+                        field!("b", $ty),
+                        field!("c", $ty),
+                        field!("d", $ty),
+                    ],
+                },
+            }
+        };
+    }
+
+    macro_rules! primitive {
+        ($name:literal) => {
+            TypeLayoutInfo {
+                name: $name,
+                size: 0,
+                alignment: 1,
+                structure: TypeStructure::Primitive,
+            }
+        };
+    }
+
+    /// Graph with ten levels of structs with four fields each, which has 4^9
+    /// paths from the root `L9` to the leaf `L0`. The leaf only points to an
+    /// interior mutable cell, which it thus does not contain by value.
+    const DAG: TypeLayoutGraph<'static> = TypeLayoutGraph {
+        ty: "L9",
+        tys: &[
+            &level!("L9", "L8"),
+            &primitive!("&core::cell::Cell<u8>"),
+            &primitive!("*const u8"),
+            &TypeLayoutInfo {
+                name: "L0",
+                size: 0,
+                alignment: 1,
+                structure: TypeStructure::Struct {
+                    repr: "",
+                    fields: &[
+                        field!("a", "u8"),
+                        field!("b", "&core::cell::Cell<u8>"),
+                        field!("c", "u8"),
+                        field!("p", "*const u8"),
+                    ],
+                },
+            },
+            &level!("L1", "L0"),
+            &level!("L2", "L1"),
+            &level!("L3", "L2"),
+            &level!("L4", "L3"),
+            &level!("L5", "L4"),
+            &level!("L6", "L5"),
+            &level!("L7", "L6"),
+            &level!("L8", "L7"),
+            &TypeLayoutInfo {
+                name: "core::cell::Cell<u8>",
+                size: 1,
+                alignment: 1,
+                structure: TypeStructure::Struct {
+                    repr: "transparent",
+                    fields: &[field!("value", "u8")],
+                },
+            },
+            &primitive!("u8"),
+        ],
+    };
+
+    // every type must only be searched once
+    const _: () = assert!(!contains(&DAG, Property::InteriorMutability));
+    const _: () = assert!(contains(&DAG, Property::RawPointer));
+
+    #[test]
+    fn dag() {
+        assert!(contains(&DAG, Property::Reference));
+        assert!(!contains(&DAG, Property::InteriorMutability));
+
+        let mut buffer = [0_u8; 64];
+        let len = find_in_graph(&DAG, Property::RawPointer, &mut buffer, 0).unwrap();
+
+        assert_eq!(&buffer[..len], b".a.a.a.a.a.a.a.a.a.p");
+        assert_eq!(
+            find(&DAG, Property::RawPointer).unwrap().to_string(),
+            "L9.a.a.a.a.a.a.a.a.a.p"
+        );
+    }
+
+    #[test]
+    fn pointers() {
+        type Node = (u32, Option<core::ptr::NonNull<u8>>, fn(u8));
+
+        let graph = <Node>::TYPE_GRAPH;
+
+        assert!(contains(&graph, Property::NonNull));
+        assert!(contains(&graph, Property::RawPointer));
+        assert!(contains(&graph, Property::FnPointer));
+        assert!(!contains(&graph, Property::Reference));
+        assert!(!contains(&graph, Property::InteriorMutability));
+
+        assert_eq!(
+            find(&graph, Property::RawPointer).unwrap().to_string(),
+            "(u32, core::option::Option<core::ptr::non_null::NonNull<u8>>, \
+             fn(u8)).1::Some.0.pointer",
+        );
+    }
+
+    #[test]
+    fn cells() {
+        fn is_cell<T: TypeGraphLayout>() -> bool {
+            let graph = T::TYPE_GRAPH;
+
+            Property::InteriorMutability.matches(graph.find_type(graph.ty).unwrap())
+                && contains(&graph, Property::InteriorMutability)
+        }
+
+        assert!(is_cell::<core::cell::UnsafeCell<u8>>());
+        assert!(is_cell::<core::cell::Cell<u8>>());
+        assert!(is_cell::<core::cell::OnceCell<u8>>());
+        assert!(!is_cell::<Option<u8>>());
+    }
+}
@@ -0,0 +1,525 @@
+//! Helper module to find the order of a struct's fields that minimises its
+//! size and padding under `#[repr(C)]` layout rules.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{repr::Repr, TypeGraphLayout, TypeLayoutGraph, TypeLayoutInfo, TypeStructure};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// Order of a struct's fields that minimises its size and padding, as found
+/// by [`reorder`].
+///
+/// The [`fmt::Display`] implementation proposes a reordered definition of the
+/// struct that keeps its representation and field names.
+pub struct Reordering<'a> {
+    /// The fully-qualified name of the struct.
+    pub ty: &'a str,
+    /// The struct's representation, e.g. `C`.
+    pub repr: &'a str,
+    /// The size of the struct with its current field order.
+    pub size: usize,
+    /// The number of padding bytes with the current field order.
+    pub padding: usize,
+    /// The size of the struct with the reordered fields.
+    pub reordered_size: usize,
+    /// The number of padding bytes with the reordered fields.
+    pub reordered_padding: usize,
+    /// The reordered fields.
+    pub fields: Vec<Placement<'a>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// Placement of a field inside a [`Reordering`].
+pub struct Placement<'a> {
+    /// The name of the field.
+    pub name: &'a str,
+    /// The fully-qualified name of the field's type.
+    pub ty: &'a str,
+    /// The offset of the field in the reordered struct.
+    pub offset: usize,
+    /// The size of the field.
+    pub size: usize,
+    /// The alignment of the field inside the struct, which is limited by a
+    /// `#[repr(packed)]` representation.
+    pub alignment: usize,
+}
+
+impl Reordering<'_> {
+    #[must_use]
+    /// Returns the number of bytes that reordering the fields saves.
+    pub const fn savings(&self) -> usize {
+        self.size.saturating_sub(self.reordered_size)
+    }
+}
+
+impl fmt::Display for Reordering<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_fmt(format_args!(
+            "// size: {} (was {}), padding: {} (was {})\n",
+            self.reordered_size, self.size, self.reordered_padding, self.padding
+        ))?;
+
+        if !self.repr.is_empty() {
+            fmt.write_fmt(format_args!("#[repr({})]\n", self.repr))?;
+        }
+
+        fmt.write_fmt(format_args!("struct {} {{\n", self.ty))?;
+
+        for field in &self.fields {
+            fmt.write_fmt(format_args!("    {}: {},\n", field.name, field.ty))?;
+        }
+
+        fmt.write_str("}\n")
+    }
+}
+
+#[must_use]
+/// Find the order of the fields of the struct, which is described by `layout`
+/// inside the `graph`, that minimises its size and padding under `#[repr(C)]`
+/// layout rules, or returns [`None`] if the `layout` is not a struct or the
+/// layout of any of its fields is not included in the `graph`.
+///
+/// Since the size of every type is a multiple of its alignment, ordering the
+/// fields by decreasing alignment leaves no holes between them, and only the
+/// unavoidable tail padding remains. Fields with the same alignment keep
+/// their relative order.
+#[cfg_attr(
+    feature = "derive",
+    doc = r##"
+# Example
+
+```rust
+# #![feature(const_type_name)]
+use const_type_layout::{analysis::reorder::reorder, TypeGraphLayout, TypeLayout};
+
+#[repr(C)]
+#[derive(TypeLayout)]
+struct Sparse {
+    a: u8,
+    b: u32,
+    c: u16,
+}
+
+let graph = Sparse::TYPE_GRAPH;
+let reordering = reorder(&graph, &Sparse::TYPE_LAYOUT).unwrap();
+
+assert_eq!(reordering.savings(), 4);
+assert_eq!((reordering.padding, reordering.reordered_padding), (5, 1));
+assert_eq!(
+    reordering
+        .fields
+        .iter()
+        .map(|field| (field.name, field.offset))
+        .collect::<Vec<_>>(),
+    [("b", 0), ("c", 4), ("a", 6)],
+);
+```
+"##
+)]
+pub fn reorder<'a>(
+    graph: &TypeLayoutGraph<'a>,
+    layout: &TypeLayoutInfo<'a>,
+) -> Option<Reordering<'a>> {
+    let TypeStructure::Struct { repr, fields } = layout.structure else {
+        return None;
+    };
+
+    let packed = Repr::parse(repr).packed;
+
+    let mut sum = 0;
+    let mut placements = fields
+        .iter()
+        .map(|field| {
+            let ty = graph.find_type(field.ty)?;
+            sum += ty.size;
+
+            Some(Placement {
+                name: field.name,
+                ty: field.ty,
+                offset: 0,
+                size: ty.size,
+                alignment: field_alignment(ty.alignment, packed),
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    placements.sort_by(|a, b| b.alignment.cmp(&a.alignment));
+
+    let mut end = 0_usize;
+
+    for placement in &mut placements {
+        placement.offset = end.next_multiple_of(placement.alignment);
+        end = placement.offset + placement.size;
+    }
+
+    let reordered_size = end.next_multiple_of(layout.alignment);
+
+    Some(Reordering {
+        ty: layout.name,
+        repr,
+        size: layout.size,
+        padding: layout.size.saturating_sub(sum),
+        reordered_size,
+        reordered_padding: reordered_size - sum,
+        fields: placements,
+    })
+}
+
+#[must_use]
+/// Computes the size of the struct, which is described by `layout` inside the
+/// `graph`, with the field order that is found by [`reorder`], or returns
+/// [`None`] if the `layout` is not a struct or the layout of any of its
+/// fields is not included in the `graph`.
+///
+/// Unlike [`reorder`], this function can be used in const contexts.
+pub const fn reordered_size<'a>(
+    graph: &TypeLayoutGraph<'a>,
+    layout: &TypeLayoutInfo<'a>,
+) -> Option<usize> {
+    let TypeStructure::Struct { repr, fields } = layout.structure else {
+        return None;
+    };
+
+    let packed = Repr::parse(repr).packed;
+
+    let mut end = 0_usize;
+
+    // place the fields by decreasing alignment, i.e. at each step place all
+    //  fields with the largest alignment that is below the previous one
+    let mut previous = usize::MAX;
+
+    loop {
+        let mut alignment = 0;
+
+        let mut i = 0;
+        while i < fields.len() {
+            let Some(ty) = graph.find_type(fields[i].ty) else {
+                return None;
+            };
+            let field_alignment = field_alignment(ty.alignment, packed);
+
+            if field_alignment < previous && field_alignment > alignment {
+                alignment = field_alignment;
+            }
+
+            i += 1;
+        }
+
+        if alignment == 0 {
+            break;
+        }
+
+        let mut i = 0;
+        while i < fields.len() {
+            if let Some(ty) = graph.find_type(fields[i].ty) {
+                if field_alignment(ty.alignment, packed) == alignment {
+                    end = end.next_multiple_of(alignment) + ty.size;
+                }
+            }
+
+            i += 1;
+        }
+
+        previous = alignment;
+    }
+
+    Some(end.next_multiple_of(layout.alignment))
+}
+
+const fn field_alignment(alignment: usize, packed: Option<usize>) -> usize {
+    match packed {
+        Some(packed) if packed < alignment => packed,
+        _ => alignment,
+    }
+}
+
+#[must_use]
+/// Computes the number of bytes that reordering the fields of the struct `T`
+/// with [`reorder`] saves, which is zero if `T` is not a struct.
+///
+/// This function is used by the `#[layout(warn_padding)]` attribute of
+/// [`#[derive(TypeLayout)]`](const_type_layout_derive::TypeLayout) to check a
+/// struct at compile time.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(const_type_name)]
+/// # use const_type_layout::analysis::reorder::avoidable_padding;
+/// const _: () = assert!(avoidable_padding::<core::ops::Range<u32>>() == 0);
+/// ```
+#[cfg_attr(
+    feature = "derive",
+    doc = r##"
+The derive attribute accepts structs whose fields are already ordered, or
+whose avoidable padding is within the allowed number of bytes:
+
+```rust
+# #![feature(const_type_name)]
+# #![deny(deprecated)]
+use const_type_layout::TypeLayout;
+
+#[repr(C)]
+#[derive(TypeLayout)]
+#[layout(warn_padding)]
+struct Tight {
+    a: u32,
+    b: u16,
+    c: u8,
+}
+
+#[repr(C)]
+#[derive(TypeLayout)]
+#[layout(warn_padding = "4")]
+struct Sparse {
+    a: u8,
+    b: u32,
+    c: u16,
+}
+```
+
+but warns if reordering would save more bytes, which fails to compile here
+since the warning is denied:
+
+```rust,compile_fail
+# #![feature(const_type_name)]
+#![deny(deprecated)]
+# use const_type_layout::TypeLayout;
+#[repr(C)]
+#[derive(TypeLayout)]
+#[layout(warn_padding = "3")]
+struct Sparse {
+    a: u8,
+    b: u32,
+    c: u16,
+}
+```
+
+The attribute only supports `#[repr(C)]` structs:
+
+```rust,compile_fail
+# #![feature(const_type_name)]
+# use const_type_layout::TypeLayout;
+#[derive(TypeLayout)]
+#[layout(warn_padding)]
+struct Sparse {
+    a: u8,
+    b: u32,
+    c: u16,
+}
+```
+
+and cannot check generic types:
+
+```rust,compile_fail
+# #![feature(const_type_name)]
+# use const_type_layout::TypeLayout;
+#[repr(C)]
+#[derive(TypeLayout)]
+#[layout(warn_padding)]
+struct Pair<T> {
+    a: u8,
+    b: T,
+}
+```
+"##
+)]
+pub const fn avoidable_padding<T: TypeGraphLayout>() -> usize {
+    match reordered_size(&T::TYPE_GRAPH, &T::TYPE_LAYOUT) {
+        Some(size) => T::TYPE_LAYOUT.size.saturating_sub(size),
+        None => 0,
+    }
+}
+
+#[doc(hidden)]
+/// Helper type whose `warn` function is deprecated iff `WARN` is true, which
+/// is used by the `#[layout(warn_padding)]` attribute of
+/// [`#[derive(TypeLayout)]`](const_type_layout_derive::TypeLayout) to emit a
+/// warning at compile time.
+pub struct WarnPadding<const WARN: bool>;
+
+impl WarnPadding<true> {
+    #[deprecated(
+        note = "[const-type-layout]: reordering the fields of this struct by decreasing alignment \
+                saves more bytes than allowed by #[layout(warn_padding)], see \
+                `const_type_layout::analysis::reorder::reorder`"
+    )]
+    #[doc(hidden)]
+    pub const fn warn() {}
+}
+
+impl WarnPadding<false> {
+    #[doc(hidden)]
+    pub const fn warn() {}
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{Field, MaybeUninhabited, TypeLayoutGraph, TypeLayoutInfo, TypeStructure};
+
+    use super::{reorder, reordered_size};
+
+    const fn field(name: &'static str, offset: usize, ty: &'static str) -> Field<'static> {
+        Field {
+            name,
+            offset: MaybeUninhabited::Inhabited(offset),
+            ty,
+        }
+    }
+
+    const fn primitive(name: &'static str, size: usize) -> TypeLayoutInfo<'static> {
+        TypeLayoutInfo {
+            name,
+            size,
+            alignment: size,
+            structure: TypeStructure::Primitive,
+        }
+    }
+
+    const GRAPH: TypeLayoutGraph<'static> = TypeLayoutGraph {
+        ty: "Sparse",
+        tys: &[
+            &TypeLayoutInfo {
+                name: "Sparse",
+                size: 12,
+                alignment: 4,
+                structure: TypeStructure::Struct {
+                    repr: "C",
+                    fields: &[
+                        field("a", 0, "u8"),
+                        field("b", 4, "u32"),
+                        field("c", 8, "u16"),
+                    ],
+                },
+            },
+            &TypeLayoutInfo {
+                name: "Ties",
+                size: 6,
+                alignment: 2,
+                structure: TypeStructure::Struct {
+                    repr: "C",
+                    fields: &[
+                        field("a", 0, "u8"),
+                        field("b", 2, "u16"),
+                        field("c", 4, "u8"),
+                        field("d", 5, "u8"),
+                    ],
+                },
+            },
+            &TypeLayoutInfo {
+                name: "Packed",
+                size: 12,
+                alignment: 2,
+                structure: TypeStructure::Struct {
+                    repr: "C,packed(2)",
+                    fields: &[
+                        field("a", 0, "u8"),
+                        field("b", 2, "u64"),
+                        field("c", 10, "u16"),
+                    ],
+                },
+            },
+            &TypeLayoutInfo {
+                name: "Tight",
+                size: 16,
+                alignment: 8,
+                structure: TypeStructure::Struct {
+                    repr: "C",
+                    fields: &[
+                        field("a", 0, "u64"),
+                        field("b", 8, "u32"),
+                        field("c", 12, "u8"),
+                    ],
+                },
+            },
+            &TypeLayoutInfo {
+                name: "Broken",
+                size: 1,
+                alignment: 1,
+                structure: TypeStructure::Struct {
+                    repr: "C",
+                    fields: &[field("a", 0, "Missing")],
+                },
+            },
+            &TypeLayoutInfo {
+                name: "Union",
+                size: 4,
+                alignment: 4,
+                structure: TypeStructure::Union {
+                    repr: "C",
+                    fields: &[field("a", 0, "u32")],
+                },
+            },
+            &primitive("u16", 2),
+            &primitive("u32", 4),
+            &primitive("u64", 8),
+            &primitive("u8", 1),
+        ],
+    };
+
+    /// The reordered fields' names and offsets, the savings, and the
+    /// reordered padding of the struct `ty`.
+    fn reordered(ty: &str) -> (Vec<(&'static str, usize)>, usize, usize) {
+        let layout = GRAPH.find_type(ty).unwrap();
+        let reordering = reorder(&GRAPH, layout).unwrap();
+
+        assert_eq!(
+            reordered_size(&GRAPH, layout),
+            Some(reordering.reordered_size),
+            "{ty}"
+        );
+
+        (
+            reordering
+                .fields
+                .iter()
+                .map(|field| (field.name, field.offset))
+                .collect(),
+            reordering.savings(),
+            reordering.reordered_padding,
+        )
+    }
+
+    #[test]
+    fn decreasing_alignment() {
+        assert_eq!(
+            reordered("Sparse"),
+            (alloc::vec![("b", 0), ("c", 4), ("a", 6)], 4, 1)
+        );
+        assert_eq!(
+            reordered("Tight"),
+            (alloc::vec![("a", 0), ("b", 8), ("c", 12)], 0, 3)
+        );
+    }
+
+    #[test]
+    fn equal_alignment_ties() {
+        // fields with the same alignment keep their relative order
+        assert_eq!(
+            reordered("Ties"),
+            (alloc::vec![("b", 0), ("a", 2), ("c", 3), ("d", 4)], 0, 1)
+        );
+    }
+
+    #[test]
+    fn packed() {
+        // `packed(2)` caps the alignment of `b`, which thus ties with `c`
+        assert_eq!(
+            reordered("Packed"),
+            (alloc::vec![("b", 0), ("c", 8), ("a", 10)], 0, 1)
+        );
+    }
+
+    #[test]
+    fn unsupported() {
+        for ty in ["Broken", "Union", "u8"] {
+            let layout = GRAPH.find_type(ty).unwrap();
+
+            assert_eq!(reorder(&GRAPH, layout), None, "{ty}");
+            assert_eq!(reordered_size(&GRAPH, layout), None, "{ty}");
+        }
+    }
+}
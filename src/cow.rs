@@ -0,0 +1,617 @@
+//! Helper module for the owned representation of a [`TypeLayoutGraph`], in
+//! which all names are [`Cow`]s and all lists are [`Box`]ed slices.
+//!
+//! A [`TypeLayoutGraph`] borrows all of its names, which makes it impossible
+//! to deserialise it from a reader or from a format with escaped strings. An
+//! [`OwnedTypeLayoutGraph<'static>`] can instead be deserialised from any
+//! source, and then be compared directly with the graph of a type:
+//!
+//! ```rust
+//! # #![feature(const_type_name)]
+//! use const_type_layout::{OwnedTypeLayoutGraph, TypeGraphLayout};
+//!
+//! let graph = <Option<u8>>::TYPE_GRAPH;
+//!
+//! let owned: OwnedTypeLayoutGraph<'static> = graph.to_owned().into_static();
+//!
+//! assert_eq!(owned, graph);
+//! assert_eq!(graph, owned);
+//! ```
+
+use alloc::{borrow::Cow, boxed::Box};
+use core::{marker::PhantomData, ops::Deref};
+
+use crate::{
+    Discriminant, Field, MaybeUninhabited, TypeLayoutGraph, TypeLayoutInfo, TypeStructure, Variant,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+/// Owned description of the deep layout of a type, see [`TypeLayoutGraph`].
+pub struct OwnedTypeLayoutGraph<'a> {
+    /// The type's fully-qualified name.
+    pub ty: Cow<'a, str>,
+    /// The list of types that make up the complete graph describing the deep
+    /// layout of this type.
+    pub tys: Box<[OwnedTypeLayoutInfo<'a>]>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+/// Owned description of the shallow layout of a type, see
+/// [`TypeLayoutInfo`].
+pub struct OwnedTypeLayoutInfo<'a> {
+    /// The type's fully-qualified name.
+    pub name: Cow<'a, str>,
+    /// The type's size.
+    pub size: usize,
+    /// The type's minimum alignment.
+    pub alignment: usize,
+    /// The type's shallow structure.
+    pub structure: OwnedTypeStructure<'a>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+/// Owned description of the shallow structure of a type, see
+/// [`TypeStructure`].
+pub enum OwnedTypeStructure<'a> {
+    /// A primitive type, e.g. `()`, `u8`, `*const i32`, `&mut bool`, `[char;
+    /// 4]`, or `fn(f32) -> !`.
+    Primitive,
+    /// A struct-like type, including unit structs, tuple structs, structs, and
+    /// tuples.
+    Struct {
+        /// The string representation of the type's `#[repr(...)]` attributes.
+        repr: Cow<'a, str>,
+        /// The fields of the struct.
+        fields: Box<[OwnedField<'a>]>,
+    },
+    /// A union type.
+    Union {
+        /// The string representation of the type's `#[repr(...)]` attributes.
+        repr: Cow<'a, str>,
+        /// The fields of the union.
+        fields: Box<[OwnedField<'a>]>,
+    },
+    /// An enum type.
+    Enum {
+        /// The string representation of the type's `#[repr(...)]` attributes.
+        repr: Cow<'a, str>,
+        /// The variants of the union.
+        variants: Box<[OwnedVariant<'a>]>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+/// Owned description of the shallow layout of a variant, see [`Variant`].
+pub struct OwnedVariant<'a> {
+    /// The variant's name.
+    pub name: Cow<'a, str>,
+    /// The variant's descriminant, iff the variant is
+    /// [inhabited](https://doc.rust-lang.org/reference/glossary.html#inhabited).
+    pub discriminant: MaybeUninhabited<Discriminant<'a, Box<[u8]>>>,
+    /// The variant's fields.
+    pub fields: Box<[OwnedField<'a>]>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+/// Owned descriptor of the shallow layout of a field, see [`Field`].
+pub struct OwnedField<'a> {
+    /// The field's name.
+    pub name: Cow<'a, str>,
+    /// The field's byte offset, iff the field is
+    /// [inhabited](https://doc.rust-lang.org/reference/glossary.html#inhabited).
+    pub offset: MaybeUninhabited<usize>,
+    /// The fully-qualified name of the field's type.
+    pub ty: Cow<'a, str>,
+}
+
+impl<
+        'a,
+        F: Deref<Target = [Field<'a>]>,
+        D: Deref<Target = [u8]>,
+        V: Deref<Target = [Variant<'a, F, D>]>,
+        I: Deref<Target = TypeLayoutInfo<'a, F, D, V>>,
+        G: Deref<Target = [I]>,
+    > TypeLayoutGraph<'a, F, D, V, I, G>
+{
+    #[must_use]
+    /// Convert this graph into an [`OwnedTypeLayoutGraph`], which borrows all
+    /// names from this graph but owns all of its lists.
+    pub fn to_owned(&self) -> OwnedTypeLayoutGraph<'a> {
+        OwnedTypeLayoutGraph {
+            ty: Cow::Borrowed(self.ty),
+            tys: self
+                .tys
+                .iter()
+                .map(|ty| OwnedTypeLayoutInfo::from(&**ty))
+                .collect(),
+        }
+    }
+}
+
+impl<'a> OwnedTypeLayoutGraph<'a> {
+    #[must_use]
+    /// Convert this graph into an [`OwnedTypeLayoutGraph<'static>`] that also
+    /// owns all of its names.
+    pub fn into_static(self) -> OwnedTypeLayoutGraph<'static> {
+        OwnedTypeLayoutGraph {
+            ty: into_static(self.ty),
+            tys: self
+                .tys
+                .into_vec()
+                .into_iter()
+                .map(OwnedTypeLayoutInfo::into_static)
+                .collect(),
+        }
+    }
+}
+
+impl<'a> OwnedTypeLayoutInfo<'a> {
+    #[must_use]
+    /// Convert this layout into an [`OwnedTypeLayoutInfo<'static>`] that also
+    /// owns all of its names.
+    pub fn into_static(self) -> OwnedTypeLayoutInfo<'static> {
+        OwnedTypeLayoutInfo {
+            name: into_static(self.name),
+            size: self.size,
+            alignment: self.alignment,
+            structure: match self.structure {
+                OwnedTypeStructure::Primitive => OwnedTypeStructure::Primitive,
+                OwnedTypeStructure::Struct { repr, fields } => OwnedTypeStructure::Struct {
+                    repr: into_static(repr),
+                    fields: fields_into_static(fields),
+                },
+                OwnedTypeStructure::Union { repr, fields } => OwnedTypeStructure::Union {
+                    repr: into_static(repr),
+                    fields: fields_into_static(fields),
+                },
+                OwnedTypeStructure::Enum { repr, variants } => OwnedTypeStructure::Enum {
+                    repr: into_static(repr),
+                    variants: variants
+                        .into_vec()
+                        .into_iter()
+                        .map(|variant| OwnedVariant {
+                            name: into_static(variant.name),
+                            discriminant: match variant.discriminant {
+                                MaybeUninhabited::Inhabited(discriminant) => {
+                                    MaybeUninhabited::Inhabited(Discriminant {
+                                        value: discriminant.value,
+                                        _marker: PhantomData,
+                                    })
+                                },
+                                MaybeUninhabited::Uninhabited => MaybeUninhabited::Uninhabited,
+                            },
+                            fields: fields_into_static(variant.fields),
+                        })
+                        .collect(),
+                },
+            },
+        }
+    }
+}
+
+impl<
+        'a,
+        F: Deref<Target = [Field<'a>]>,
+        D: Deref<Target = [u8]>,
+        V: Deref<Target = [Variant<'a, F, D>]>,
+    > From<&TypeLayoutInfo<'a, F, D, V>> for OwnedTypeLayoutInfo<'a>
+{
+    fn from(layout: &TypeLayoutInfo<'a, F, D, V>) -> Self {
+        Self {
+            name: Cow::Borrowed(layout.name),
+            size: layout.size,
+            alignment: layout.alignment,
+            structure: match &layout.structure {
+                TypeStructure::Primitive => OwnedTypeStructure::Primitive,
+                TypeStructure::Struct { repr, fields } => OwnedTypeStructure::Struct {
+                    repr: Cow::Borrowed(repr),
+                    fields: fields.iter().map(OwnedField::from).collect(),
+                },
+                TypeStructure::Union { repr, fields } => OwnedTypeStructure::Union {
+                    repr: Cow::Borrowed(repr),
+                    fields: fields.iter().map(OwnedField::from).collect(),
+                },
+                TypeStructure::Enum { repr, variants } => OwnedTypeStructure::Enum {
+                    repr: Cow::Borrowed(repr),
+                    variants: variants
+                        .iter()
+                        .map(|variant| OwnedVariant {
+                            name: Cow::Borrowed(variant.name),
+                            discriminant: match &variant.discriminant {
+                                MaybeUninhabited::Inhabited(discriminant) => {
+                                    MaybeUninhabited::Inhabited(Discriminant {
+                                        value: Box::from(&*discriminant.value),
+                                        _marker: PhantomData,
+                                    })
+                                },
+                                MaybeUninhabited::Uninhabited => MaybeUninhabited::Uninhabited,
+                            },
+                            fields: variant.fields.iter().map(OwnedField::from).collect(),
+                        })
+                        .collect(),
+                },
+            },
+        }
+    }
+}
+
+impl<'a> From<&Field<'a>> for OwnedField<'a> {
+    fn from(field: &Field<'a>) -> Self {
+        Self {
+            name: Cow::Borrowed(field.name),
+            offset: field.offset,
+            ty: Cow::Borrowed(field.ty),
+        }
+    }
+}
+
+impl<
+        'a,
+        'b,
+        F: Deref<Target = [Field<'a>]>,
+        D: Deref<Target = [u8]>,
+        V: Deref<Target = [Variant<'a, F, D>]>,
+        I: Deref<Target = TypeLayoutInfo<'a, F, D, V>>,
+        G: Deref<Target = [I]>,
+    > PartialEq<OwnedTypeLayoutGraph<'b>> for TypeLayoutGraph<'a, F, D, V, I, G>
+{
+    fn eq(&self, other: &OwnedTypeLayoutGraph<'b>) -> bool {
+        self.ty == other.ty
+            && self.tys.len() == other.tys.len()
+            && self
+                .tys
+                .iter()
+                .zip(other.tys.iter())
+                .all(|(ty, other)| **ty == *other)
+    }
+}
+
+impl<
+        'a,
+        'b,
+        F: Deref<Target = [Field<'b>]>,
+        D: Deref<Target = [u8]>,
+        V: Deref<Target = [Variant<'b, F, D>]>,
+        I: Deref<Target = TypeLayoutInfo<'b, F, D, V>>,
+        G: Deref<Target = [I]>,
+    > PartialEq<TypeLayoutGraph<'b, F, D, V, I, G>> for OwnedTypeLayoutGraph<'a>
+{
+    fn eq(&self, other: &TypeLayoutGraph<'b, F, D, V, I, G>) -> bool {
+        other == self
+    }
+}
+
+impl<
+        'a,
+        'b,
+        F: Deref<Target = [Field<'a>]>,
+        D: Deref<Target = [u8]>,
+        V: Deref<Target = [Variant<'a, F, D>]>,
+    > PartialEq<OwnedTypeLayoutInfo<'b>> for TypeLayoutInfo<'a, F, D, V>
+{
+    fn eq(&self, other: &OwnedTypeLayoutInfo<'b>) -> bool {
+        if self.name != other.name || self.size != other.size || self.alignment != other.alignment {
+            return false;
+        }
+
+        match (&self.structure, &other.structure) {
+            (TypeStructure::Primitive, OwnedTypeStructure::Primitive) => true,
+            (
+                TypeStructure::Struct { repr, fields },
+                OwnedTypeStructure::Struct {
+                    repr: other_repr,
+                    fields: other_fields,
+                },
+            )
+            | (
+                TypeStructure::Union { repr, fields },
+                OwnedTypeStructure::Union {
+                    repr: other_repr,
+                    fields: other_fields,
+                },
+            ) => *repr == other_repr && fields_eq(fields, other_fields),
+            (
+                TypeStructure::Enum { repr, variants },
+                OwnedTypeStructure::Enum {
+                    repr: other_repr,
+                    variants: other_variants,
+                },
+            ) => {
+                *repr == other_repr
+                    && variants.len() == other_variants.len()
+                    && variants
+                        .iter()
+                        .zip(other_variants.iter())
+                        .all(|(variant, other)| variant_eq(variant, other))
+            },
+            _ => false,
+        }
+    }
+}
+
+impl<
+        'a,
+        'b,
+        F: Deref<Target = [Field<'b>]>,
+        D: Deref<Target = [u8]>,
+        V: Deref<Target = [Variant<'b, F, D>]>,
+    > PartialEq<TypeLayoutInfo<'b, F, D, V>> for OwnedTypeLayoutInfo<'a>
+{
+    fn eq(&self, other: &TypeLayoutInfo<'b, F, D, V>) -> bool {
+        other == self
+    }
+}
+
+fn variant_eq<'a, F: Deref<Target = [Field<'a>]>, D: Deref<Target = [u8]>>(
+    variant: &Variant<'a, F, D>,
+    other: &OwnedVariant,
+) -> bool {
+    let discriminant_eq = match (&variant.discriminant, &other.discriminant) {
+        (MaybeUninhabited::Inhabited(discriminant), MaybeUninhabited::Inhabited(other)) => {
+            discriminant == other
+        },
+        (MaybeUninhabited::Uninhabited, MaybeUninhabited::Uninhabited) => true,
+        _ => false,
+    };
+
+    variant.name == other.name && discriminant_eq && fields_eq(&variant.fields, &other.fields)
+}
+
+fn fields_eq(fields: &[Field], other: &[OwnedField]) -> bool {
+    fields.len() == other.len()
+        && fields.iter().zip(other.iter()).all(|(field, other)| {
+            field.name == other.name && field.offset == other.offset && field.ty == other.ty
+        })
+}
+
+fn into_static(name: Cow<str>) -> Cow<'static, str> {
+    Cow::Owned(name.into_owned())
+}
+
+fn fields_into_static(fields: Box<[OwnedField]>) -> Box<[OwnedField<'static>]> {
+    fields
+        .into_vec()
+        .into_iter()
+        .map(|field| OwnedField {
+            name: into_static(field.name),
+            offset: field.offset,
+            ty: into_static(field.ty),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{borrow::Cow, boxed::Box, vec::Vec};
+    use core::marker::PhantomData;
+
+    use crate::{
+        discriminant, Discriminant, Field, MaybeUninhabited, OwnedTypeLayoutGraph, TypeLayoutGraph,
+        TypeLayoutInfo, TypeStructure, Variant,
+    };
+
+    use super::{OwnedField, OwnedTypeStructure};
+
+    const GRAPH: TypeLayoutGraph = TypeLayoutGraph {
+        ty: "Enum",
+        tys: &[
+            &TypeLayoutInfo {
+                name: "Enum",
+                size: 4,
+                alignment: 2,
+                structure: TypeStructure::Enum {
+                    repr: "u16",
+                    variants: &[
+                        Variant {
+                            name: "A",
+                            discriminant: MaybeUninhabited::Inhabited(discriminant!(0)),
+                            fields: &[],
+                        },
+                        Variant {
+                            name: "B",
+                            discriminant: MaybeUninhabited::Inhabited(discriminant!(-1)),
+                            fields: &[Field {
+                                name: "0",
+                                offset: MaybeUninhabited::Inhabited(2),
+                                ty: "Union",
+                            }],
+                        },
+                        Variant {
+                            name: "C",
+                            discriminant: MaybeUninhabited::Uninhabited,
+                            fields: &[Field {
+                                name: "0",
+                                offset: MaybeUninhabited::Uninhabited,
+                                ty: "!",
+                            }],
+                        },
+                    ],
+                },
+            },
+            &TypeLayoutInfo {
+                name: "Union",
+                size: 2,
+                alignment: 1,
+                structure: TypeStructure::Union {
+                    repr: "C",
+                    fields: &[Field {
+                        name: "pair",
+                        offset: MaybeUninhabited::Inhabited(0),
+                        ty: "Pair",
+                    }],
+                },
+            },
+            &TypeLayoutInfo {
+                name: "Pair",
+                size: 2,
+                alignment: 1,
+                structure: TypeStructure::Struct {
+                    repr: "",
+                    fields: &[
+                        Field {
+                            name: "0",
+                            offset: MaybeUninhabited::Inhabited(0),
+                            ty: "u8",
+                        },
+                        Field {
+                            name: "1",
+                            offset: MaybeUninhabited::Inhabited(1),
+                            ty: "u8",
+                        },
+                    ],
+                },
+            },
+            &TypeLayoutInfo {
+                name: "u8",
+                size: 1,
+                alignment: 1,
+                structure: TypeStructure::Primitive,
+            },
+            &TypeLayoutInfo {
+                name: "!",
+                size: 0,
+                alignment: 1,
+                structure: TypeStructure::Primitive,
+            },
+        ],
+    };
+
+    type VecVariant<'a> = Variant<'a, Vec<Field<'a>>, Vec<u8>>;
+    type VecTypeLayoutInfo<'a> = TypeLayoutInfo<'a, Vec<Field<'a>>, Vec<u8>, Vec<VecVariant<'a>>>;
+
+    fn to_vecs<'a>(layout: &TypeLayoutInfo<'a>) -> VecTypeLayoutInfo<'a> {
+        TypeLayoutInfo {
+            name: layout.name,
+            size: layout.size,
+            alignment: layout.alignment,
+            structure: match layout.structure {
+                TypeStructure::Primitive => TypeStructure::Primitive,
+                TypeStructure::Struct { repr, fields } => TypeStructure::Struct {
+                    repr,
+                    fields: fields.to_vec(),
+                },
+                TypeStructure::Union { repr, fields } => TypeStructure::Union {
+                    repr,
+                    fields: fields.to_vec(),
+                },
+                TypeStructure::Enum { repr, variants } => TypeStructure::Enum {
+                    repr,
+                    variants: variants
+                        .iter()
+                        .map(|variant| Variant {
+                            name: variant.name,
+                            discriminant: match variant.discriminant {
+                                MaybeUninhabited::Inhabited(discriminant) => {
+                                    MaybeUninhabited::Inhabited(Discriminant {
+                                        value: discriminant.value.to_vec(),
+                                        _marker: PhantomData,
+                                    })
+                                },
+                                MaybeUninhabited::Uninhabited => MaybeUninhabited::Uninhabited,
+                            },
+                            fields: variant.fields.to_vec(),
+                        })
+                        .collect(),
+                },
+            },
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let owned = GRAPH.to_owned();
+
+        assert!(matches!(owned.ty, Cow::Borrowed("Enum")));
+        assert_eq!(owned.tys.len(), GRAPH.tys.len());
+        assert_eq!(owned, GRAPH);
+        assert_eq!(GRAPH, owned);
+
+        let owned = owned.into_static();
+
+        assert!(matches!(owned.ty, Cow::Owned(_)));
+        assert!(owned.tys.iter().all(|ty| matches!(ty.name, Cow::Owned(_))));
+        assert_eq!(owned, GRAPH);
+        assert_eq!(GRAPH, owned);
+        assert_eq!(owned, GRAPH.to_owned());
+
+        for (ty, other) in GRAPH.tys.iter().zip(owned.tys.iter()) {
+            assert_eq!(**ty, *other);
+            assert_eq!(*other, **ty);
+        }
+    }
+
+    #[test]
+    fn cross_container() {
+        let tys = GRAPH
+            .tys
+            .iter()
+            .map(|ty| Box::new(to_vecs(ty)))
+            .collect::<Vec<_>>();
+        let graph = TypeLayoutGraph { ty: GRAPH.ty, tys };
+
+        let owned: OwnedTypeLayoutGraph<'static> = graph.to_owned().into_static();
+
+        assert_eq!(graph, owned);
+        assert_eq!(owned, graph);
+        assert_eq!(owned, GRAPH);
+        assert_eq!(graph.to_owned(), GRAPH.to_owned());
+    }
+
+    #[test]
+    fn inequality() {
+        let owned = GRAPH.to_owned().into_static();
+
+        let mut other = owned.clone();
+        other.ty = Cow::Borrowed("Union");
+        assert_ne!(GRAPH, other);
+
+        let mut other = owned.clone();
+        other.tys = other.tys[..4].into();
+        assert_ne!(GRAPH, other);
+        assert_ne!(other, GRAPH);
+
+        let mut other = owned.clone();
+        other.tys[3].size = 2;
+        assert_ne!(GRAPH, other);
+
+        let mut other = owned.clone();
+        other.tys[1].structure = OwnedTypeStructure::Struct {
+            repr: Cow::Borrowed("C"),
+            fields: Box::new([OwnedField {
+                name: Cow::Borrowed("pair"),
+                offset: MaybeUninhabited::Inhabited(0),
+                ty: Cow::Borrowed("Pair"),
+            }]),
+        };
+        assert_ne!(GRAPH, other);
+
+        let mut other = owned.clone();
+        if let OwnedTypeStructure::Struct { fields, .. } = &mut other.tys[2].structure {
+            fields[1].offset = MaybeUninhabited::Inhabited(0);
+        }
+        assert_ne!(GRAPH, other);
+
+        let mut other = owned.clone();
+        if let OwnedTypeStructure::Enum { variants, .. } = &mut other.tys[0].structure {
+            variants[1].discriminant = MaybeUninhabited::Inhabited(Discriminant {
+                value: Box::new([1]),
+                _marker: PhantomData,
+            });
+        }
+        assert_ne!(GRAPH, other);
+
+        let mut other = owned;
+        if let OwnedTypeStructure::Enum { variants, .. } = &mut other.tys[0].structure {
+            variants[2].discriminant = MaybeUninhabited::Inhabited(Discriminant {
+                value: Box::new([]),
+                _marker: PhantomData,
+            });
+        }
+        assert_ne!(GRAPH, other);
+    }
+}
@@ -0,0 +1,739 @@
+//! Helper module to compare the layouts of types, e.g. between two versions of
+//! a [`TypeLayoutGraph`] or between the host and another target.
+
+use alloc::{collections::BTreeSet, vec::Vec};
+use core::fmt;
+
+use crate::{Field, MaybeUninhabited, TypeLayoutGraph, TypeLayoutInfo, TypeStructure, Variant};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Difference between the `before` and `after` layouts of a type.
+pub enum Difference<'a> {
+    /// The type `ty` only exists before.
+    Removed {
+        /// The fully-qualified name of the type.
+        ty: &'a str,
+    },
+    /// The type `ty` only exists after.
+    Added {
+        /// The fully-qualified name of the type.
+        ty: &'a str,
+    },
+    /// The size of the type `ty` changed.
+    Size {
+        /// The fully-qualified name of the type.
+        ty: &'a str,
+        /// The size before.
+        before: usize,
+        /// The size after.
+        after: usize,
+    },
+    /// The alignment of the type `ty` changed.
+    Alignment {
+        /// The fully-qualified name of the type.
+        ty: &'a str,
+        /// The alignment before.
+        before: usize,
+        /// The alignment after.
+        after: usize,
+    },
+    /// The type `ty` changed between being a primitive, struct, union, or
+    /// enum.
+    Structure {
+        /// The fully-qualified name of the type.
+        ty: &'a str,
+        /// The kind of structure before, e.g. `"struct"`.
+        before: &'static str,
+        /// The kind of structure after, e.g. `"enum"`.
+        after: &'static str,
+    },
+    /// The `#[repr(...)]` attributes of the type `ty` changed.
+    Repr {
+        /// The fully-qualified name of the type.
+        ty: &'a str,
+        /// The `repr` string before.
+        before: &'a str,
+        /// The `repr` string after.
+        after: &'a str,
+    },
+    /// The `field` of the type `ty`, or of its enum `variant`, only exists
+    /// before.
+    FieldRemoved {
+        /// The fully-qualified name of the type.
+        ty: &'a str,
+        /// The name of the enum variant that contains the field, if any.
+        variant: Option<&'a str>,
+        /// The name of the field.
+        field: &'a str,
+    },
+    /// The `field` of the type `ty`, or of its enum `variant`, only exists
+    /// after.
+    FieldAdded {
+        /// The fully-qualified name of the type.
+        ty: &'a str,
+        /// The name of the enum variant that contains the field, if any.
+        variant: Option<&'a str>,
+        /// The name of the field.
+        field: &'a str,
+    },
+    /// The offset of the `field` of the type `ty`, or of its enum `variant`,
+    /// changed.
+    FieldOffset {
+        /// The fully-qualified name of the type.
+        ty: &'a str,
+        /// The name of the enum variant that contains the field, if any.
+        variant: Option<&'a str>,
+        /// The name of the field.
+        field: &'a str,
+        /// The field's offset before.
+        before: MaybeUninhabited<usize>,
+        /// The field's offset after.
+        after: MaybeUninhabited<usize>,
+    },
+    /// The type of the `field` of the type `ty`, or of its enum `variant`,
+    /// changed.
+    FieldType {
+        /// The fully-qualified name of the type.
+        ty: &'a str,
+        /// The name of the enum variant that contains the field, if any.
+        variant: Option<&'a str>,
+        /// The name of the field.
+        field: &'a str,
+        /// The fully-qualified name of the field's type before.
+        before: &'a str,
+        /// The fully-qualified name of the field's type after.
+        after: &'a str,
+    },
+    /// The `variant` of the enum `ty` only exists before.
+    VariantRemoved {
+        /// The fully-qualified name of the enum.
+        ty: &'a str,
+        /// The name of the variant.
+        variant: &'a str,
+    },
+    /// The `variant` of the enum `ty` only exists after.
+    VariantAdded {
+        /// The fully-qualified name of the enum.
+        ty: &'a str,
+        /// The name of the variant.
+        variant: &'a str,
+    },
+    /// The discriminant of the `variant` of the enum `ty` changed.
+    Discriminant {
+        /// The fully-qualified name of the enum.
+        ty: &'a str,
+        /// The name of the variant.
+        variant: &'a str,
+        /// The variant's discriminant before.
+        before: MaybeUninhabited<i128>,
+        /// The variant's discriminant after.
+        after: MaybeUninhabited<i128>,
+    },
+}
+
+impl fmt::Display for Difference<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Removed { ty } => fmt.write_fmt(format_args!("`{ty}` was removed")),
+            Self::Added { ty } => fmt.write_fmt(format_args!("`{ty}` was added")),
+            Self::Size { ty, before, after } => fmt.write_fmt(format_args!(
+                "the size of `{ty}` changed from {before} to {after}"
+            )),
+            Self::Alignment { ty, before, after } => fmt.write_fmt(format_args!(
+                "the alignment of `{ty}` changed from {before} to {after}"
+            )),
+            Self::Structure { ty, before, after } => {
+                fmt.write_fmt(format_args!("`{ty}` changed from a {before} to a {after}"))
+            },
+            Self::Repr { ty, before, after } => fmt.write_fmt(format_args!(
+                "the repr of `{ty}` changed from `{before}` to `{after}`"
+            )),
+            Self::FieldRemoved { ty, variant, field } => fmt.write_fmt(format_args!(
+                "the field `{}` was removed",
+                FieldPath(ty, *variant, field)
+            )),
+            Self::FieldAdded { ty, variant, field } => fmt.write_fmt(format_args!(
+                "the field `{}` was added",
+                FieldPath(ty, *variant, field)
+            )),
+            Self::FieldOffset {
+                ty,
+                variant,
+                field,
+                before,
+                after,
+            } => fmt.write_fmt(format_args!(
+                "the offset of `{}` changed from {} to {}",
+                FieldPath(ty, *variant, field),
+                Inhabited(*before),
+                Inhabited(*after),
+            )),
+            Self::FieldType {
+                ty,
+                variant,
+                field,
+                before,
+                after,
+            } => fmt.write_fmt(format_args!(
+                "the type of `{}` changed from `{before}` to `{after}`",
+                FieldPath(ty, *variant, field)
+            )),
+            Self::VariantRemoved { ty, variant } => {
+                fmt.write_fmt(format_args!("the variant `{ty}::{variant}` was removed"))
+            },
+            Self::VariantAdded { ty, variant } => {
+                fmt.write_fmt(format_args!("the variant `{ty}::{variant}` was added"))
+            },
+            Self::Discriminant {
+                ty,
+                variant,
+                before,
+                after,
+            } => fmt.write_fmt(format_args!(
+                "the discriminant of `{ty}::{variant}` changed from {} to {}",
+                Inhabited(*before),
+                Inhabited(*after),
+            )),
+        }
+    }
+}
+
+struct FieldPath<'a>(&'a str, Option<&'a str>, &'a str);
+
+impl fmt::Display for FieldPath<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self(ty, Some(variant), field) => {
+                fmt.write_fmt(format_args!("{ty}::{variant}.{field}"))
+            },
+            Self(ty, None, field) => fmt.write_fmt(format_args!("{ty}.{field}")),
+        }
+    }
+}
+
+struct Inhabited<T>(MaybeUninhabited<T>);
+
+impl<T: fmt::Display> fmt::Display for Inhabited<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match &self.0 {
+            MaybeUninhabited::Inhabited(value) => value.fmt(fmt),
+            MaybeUninhabited::Uninhabited => fmt.write_str("<uninhabited>"),
+        }
+    }
+}
+
+#[must_use]
+/// Compare the layouts of all types in the `before` and `after` graphs, which
+/// are matched by their fully-qualified names.
+///
+/// The differences are reported in the order of [`TypeLayoutGraph::tys`] of
+/// the `before` graph, followed by the types that were added in the `after`
+/// graph.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(const_type_name)]
+/// use const_type_layout::{diff, TypeGraphLayout};
+///
+/// let before = <(u8, u16)>::TYPE_GRAPH;
+/// let after = <(u8, u16)>::TYPE_GRAPH;
+///
+/// assert!(diff::graphs(&before, &after).is_empty());
+/// ```
+pub fn graphs<'a>(
+    before: &TypeLayoutGraph<'a>,
+    after: &TypeLayoutGraph<'a>,
+) -> Vec<Difference<'a>> {
+    let mut differences = Vec::new();
+
+    let mut seen = BTreeSet::new();
+
+    for ty in before.tys {
+        seen.insert(ty.name);
+
+        match after.find_type(ty.name) {
+            Some(after) => differences.extend(layouts(ty, after)),
+            None => differences.push(Difference::Removed { ty: ty.name }),
+        }
+    }
+
+    for ty in after.tys {
+        if !seen.contains(ty.name) {
+            differences.push(Difference::Added { ty: ty.name });
+        }
+    }
+
+    differences
+}
+
+#[must_use]
+/// Compare the `before` and `after` shallow layouts of the same type.
+///
+/// Fields and variants are matched by their names. The layouts of the fields'
+/// types are not compared.
+pub fn layouts<'a>(before: &TypeLayoutInfo<'a>, after: &TypeLayoutInfo<'a>) -> Vec<Difference<'a>> {
+    let mut differences = Vec::new();
+
+    let ty = before.name;
+
+    if before.size != after.size {
+        differences.push(Difference::Size {
+            ty,
+            before: before.size,
+            after: after.size,
+        });
+    }
+
+    if before.alignment != after.alignment {
+        differences.push(Difference::Alignment {
+            ty,
+            before: before.alignment,
+            after: after.alignment,
+        });
+    }
+
+    match (&before.structure, &after.structure) {
+        (TypeStructure::Primitive, TypeStructure::Primitive) => (),
+        (
+            TypeStructure::Struct {
+                repr: repr_before,
+                fields: fields_before,
+            },
+            TypeStructure::Struct {
+                repr: repr_after,
+                fields: fields_after,
+            },
+        )
+        | (
+            TypeStructure::Union {
+                repr: repr_before,
+                fields: fields_before,
+            },
+            TypeStructure::Union {
+                repr: repr_after,
+                fields: fields_after,
+            },
+        ) => {
+            diff_repr(&mut differences, ty, repr_before, repr_after);
+            diff_fields(&mut differences, ty, None, fields_before, fields_after);
+        },
+        (
+            TypeStructure::Enum {
+                repr: repr_before,
+                variants: variants_before,
+            },
+            TypeStructure::Enum {
+                repr: repr_after,
+                variants: variants_after,
+            },
+        ) => {
+            diff_repr(&mut differences, ty, repr_before, repr_after);
+            diff_variants(&mut differences, ty, variants_before, variants_after);
+        },
+        (before, after) => differences.push(Difference::Structure {
+            ty,
+            before: structure_kind(before),
+            after: structure_kind(after),
+        }),
+    }
+
+    differences
+}
+
+const fn structure_kind(structure: &TypeStructure) -> &'static str {
+    match structure {
+        TypeStructure::Primitive => "primitive",
+        TypeStructure::Struct { .. } => "struct",
+        TypeStructure::Union { .. } => "union",
+        TypeStructure::Enum { .. } => "enum",
+    }
+}
+
+fn diff_repr<'a>(
+    differences: &mut Vec<Difference<'a>>,
+    ty: &'a str,
+    before: &'a str,
+    after: &'a str,
+) {
+    if before != after {
+        differences.push(Difference::Repr { ty, before, after });
+    }
+}
+
+fn diff_fields<'a>(
+    differences: &mut Vec<Difference<'a>>,
+    ty: &'a str,
+    variant: Option<&'a str>,
+    before: &[Field<'a>],
+    after: &[Field<'a>],
+) {
+    for field in before {
+        let Some(other) = after.iter().find(|other| other.name == field.name) else {
+            differences.push(Difference::FieldRemoved {
+                ty,
+                variant,
+                field: field.name,
+            });
+            continue;
+        };
+
+        if field.offset != other.offset {
+            differences.push(Difference::FieldOffset {
+                ty,
+                variant,
+                field: field.name,
+                before: field.offset,
+                after: other.offset,
+            });
+        }
+
+        if field.ty != other.ty {
+            differences.push(Difference::FieldType {
+                ty,
+                variant,
+                field: field.name,
+                before: field.ty,
+                after: other.ty,
+            });
+        }
+    }
+
+    for field in after {
+        if !before.iter().any(|other| other.name == field.name) {
+            differences.push(Difference::FieldAdded {
+                ty,
+                variant,
+                field: field.name,
+            });
+        }
+    }
+}
+
+fn diff_variants<'a>(
+    differences: &mut Vec<Difference<'a>>,
+    ty: &'a str,
+    before: &[Variant<'a>],
+    after: &[Variant<'a>],
+) {
+    for variant in before {
+        let Some(other) = after.iter().find(|other| other.name == variant.name) else {
+            differences.push(Difference::VariantRemoved {
+                ty,
+                variant: variant.name,
+            });
+            continue;
+        };
+
+        if variant.discriminant != other.discriminant {
+            differences.push(Difference::Discriminant {
+                ty,
+                variant: variant.name,
+                before: discriminant_value(variant),
+                after: discriminant_value(other),
+            });
+        }
+
+        diff_fields(
+            differences,
+            ty,
+            Some(variant.name),
+            variant.fields,
+            other.fields,
+        );
+    }
+
+    for variant in after {
+        if !before.iter().any(|other| other.name == variant.name) {
+            differences.push(Difference::VariantAdded {
+                ty,
+                variant: variant.name,
+            });
+        }
+    }
+}
+
+const fn discriminant_value(variant: &Variant) -> MaybeUninhabited<i128> {
+    match &variant.discriminant {
+        MaybeUninhabited::Inhabited(discriminant) => {
+            MaybeUninhabited::Inhabited(discriminant.to_wrapping_i128())
+        },
+        MaybeUninhabited::Uninhabited => MaybeUninhabited::Uninhabited,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec::Vec};
+
+    use crate::{
+        Field, MaybeUninhabited, TypeGraphLayout, TypeLayoutGraph, TypeLayoutInfo, TypeStructure,
+        Variant,
+    };
+
+    use super::{graphs, Difference};
+
+    const BEFORE: TypeLayoutGraph<'static> = TypeLayoutGraph {
+        ty: "S",
+        tys: &[
+            &TypeLayoutInfo {
+                name: "S",
+                size: 8,
+                alignment: 4,
+                structure: TypeStructure::Struct {
+                    repr: "C",
+                    fields: &[
+                        Field {
+                            name: "a",
+                            offset: MaybeUninhabited::Inhabited(0),
+                            ty: "u8",
+                        },
+                        Field {
+                            name: "b",
+                            offset: MaybeUninhabited::Inhabited(4),
+                            ty: "u32",
+                        },
+                        Field {
+                            name: "gone",
+                            offset: MaybeUninhabited::Inhabited(1),
+                            ty: "u8",
+                        },
+                    ],
+                },
+            },
+            &TypeLayoutInfo {
+                name: "E",
+                size: 2,
+                alignment: 1,
+                structure: TypeStructure::Enum {
+                    repr: "u8",
+                    variants: &[
+                        Variant {
+                            name: "A",
+                            discriminant: MaybeUninhabited::Inhabited(crate::discriminant!(0)),
+                            fields: &[Field {
+                                name: "x",
+                                offset: MaybeUninhabited::Inhabited(1),
+                                ty: "u8",
+                            }],
+                        },
+                        Variant {
+                            name: "B",
+                            discriminant: MaybeUninhabited::Inhabited(crate::discriminant!(1)),
+                            fields: &[],
+                        },
+                        Variant {
+                            name: "Gone",
+                            discriminant: MaybeUninhabited::Inhabited(crate::discriminant!(2)),
+                            fields: &[],
+                        },
+                    ],
+                },
+            },
+            &TypeLayoutInfo {
+                name: "U",
+                size: 4,
+                alignment: 4,
+                structure: TypeStructure::Union {
+                    repr: "C",
+                    fields: &[],
+                },
+            },
+            &TypeLayoutInfo {
+                name: "Old",
+                size: 0,
+                alignment: 1,
+                structure: TypeStructure::Primitive,
+            },
+        ],
+    };
+
+    const AFTER: TypeLayoutGraph<'static> = TypeLayoutGraph {
+        ty: "S",
+        tys: &[
+            &TypeLayoutInfo {
+                name: "S",
+                size: 16,
+                alignment: 8,
+                structure: TypeStructure::Struct {
+                    repr: "C,align(8)",
+                    fields: &[
+                        Field {
+                            name: "a",
+                            offset: MaybeUninhabited::Inhabited(0),
+                            ty: "u8",
+                        },
+                        Field {
+                            name: "new",
+                            offset: MaybeUninhabited::Inhabited(1),
+                            ty: "u8",
+                        },
+                        Field {
+                            name: "b",
+                            offset: MaybeUninhabited::Inhabited(8),
+                            ty: "u64",
+                        },
+                    ],
+                },
+            },
+            &TypeLayoutInfo {
+                name: "E",
+                size: 2,
+                alignment: 1,
+                structure: TypeStructure::Enum {
+                    repr: "u8",
+                    variants: &[
+                        Variant {
+                            name: "New",
+                            discriminant: MaybeUninhabited::Inhabited(crate::discriminant!(3)),
+                            fields: &[],
+                        },
+                        Variant {
+                            name: "B",
+                            discriminant: MaybeUninhabited::Uninhabited,
+                            fields: &[],
+                        },
+                        Variant {
+                            name: "A",
+                            discriminant: MaybeUninhabited::Inhabited(crate::discriminant!(0)),
+                            fields: &[Field {
+                                name: "x",
+                                offset: MaybeUninhabited::Uninhabited,
+                                ty: "u8",
+                            }],
+                        },
+                    ],
+                },
+            },
+            &TypeLayoutInfo {
+                name: "U",
+                size: 4,
+                alignment: 4,
+                structure: TypeStructure::Struct {
+                    repr: "C",
+                    fields: &[],
+                },
+            },
+            &TypeLayoutInfo {
+                name: "New",
+                size: 0,
+                alignment: 1,
+                structure: TypeStructure::Primitive,
+            },
+        ],
+    };
+
+    #[test]
+    fn unchanged() {
+        assert_eq!(graphs(&BEFORE, &BEFORE), []);
+        assert_eq!(
+            graphs(
+                &<Result<u8, i64>>::TYPE_GRAPH,
+                &<Result<u8, i64>>::TYPE_GRAPH
+            ),
+            []
+        );
+    }
+
+    #[test]
+    fn differences() {
+        assert_eq!(
+            graphs(&BEFORE, &AFTER),
+            [
+                Difference::Size {
+                    ty: "S",
+                    before: 8,
+                    after: 16,
+                },
+                Difference::Alignment {
+                    ty: "S",
+                    before: 4,
+                    after: 8,
+                },
+                Difference::Repr {
+                    ty: "S",
+                    before: "C",
+                    after: "C,align(8)",
+                },
+                Difference::FieldOffset {
+                    ty: "S",
+                    variant: None,
+                    field: "b",
+                    before: MaybeUninhabited::Inhabited(4),
+                    after: MaybeUninhabited::Inhabited(8),
+                },
+                Difference::FieldType {
+                    ty: "S",
+                    variant: None,
+                    field: "b",
+                    before: "u32",
+                    after: "u64",
+                },
+                Difference::FieldRemoved {
+                    ty: "S",
+                    variant: None,
+                    field: "gone",
+                },
+                Difference::FieldAdded {
+                    ty: "S",
+                    variant: None,
+                    field: "new",
+                },
+                Difference::FieldOffset {
+                    ty: "E",
+                    variant: Some("A"),
+                    field: "x",
+                    before: MaybeUninhabited::Inhabited(1),
+                    after: MaybeUninhabited::Uninhabited,
+                },
+                Difference::Discriminant {
+                    ty: "E",
+                    variant: "B",
+                    before: MaybeUninhabited::Inhabited(1),
+                    after: MaybeUninhabited::Uninhabited,
+                },
+                Difference::VariantRemoved {
+                    ty: "E",
+                    variant: "Gone",
+                },
+                Difference::VariantAdded {
+                    ty: "E",
+                    variant: "New",
+                },
+                Difference::Structure {
+                    ty: "U",
+                    before: "union",
+                    after: "struct",
+                },
+                Difference::Removed { ty: "Old" },
+                Difference::Added { ty: "New" },
+            ]
+        );
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            graphs(&BEFORE, &AFTER)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                "the size of `S` changed from 8 to 16",
+                "the alignment of `S` changed from 4 to 8",
+                "the repr of `S` changed from `C` to `C,align(8)`",
+                "the offset of `S.b` changed from 4 to 8",
+                "the type of `S.b` changed from `u32` to `u64`",
+                "the field `S.gone` was removed",
+                "the field `S.new` was added",
+                "the offset of `E::A.x` changed from 1 to <uninhabited>",
+                "the discriminant of `E::B` changed from 1 to <uninhabited>",
+                "the variant `E::Gone` was removed",
+                "the variant `E::New` was added",
+                "`U` changed from a union to a struct",
+                "`Old` was removed",
+                "`New` was added",
+            ]
+        );
+    }
+}
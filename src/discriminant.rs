@@ -1,6 +1,6 @@
 use core::{marker::PhantomData, ops::Deref};
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Copy, Debug, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
 /// Discriminant of an enum variant.
 ///
@@ -33,6 +33,47 @@ pub struct Discriminant<'a, D: Deref<Target = [u8]> = &'a [u8]> {
     pub _marker: PhantomData<&'a [u8]>,
 }
 
+impl<'a, 'b, D: Deref<Target = [u8]>, D2: Deref<Target = [u8]>> PartialEq<Discriminant<'b, D2>>
+    for Discriminant<'a, D>
+{
+    fn eq(&self, other: &Discriminant<'b, D2>) -> bool {
+        *self.value == *other.value
+    }
+}
+
+impl Discriminant<'_> {
+    #[must_use]
+    /// Returns the numeric value of the discriminant, wrapped into the two's
+    /// complement representation of an [`i128`].
+    ///
+    /// Truncating the result to the discriminant's integer type, e.g. by
+    /// reading only its least significant byte for a `#[repr(u8)]` enum,
+    /// produces the in-memory value of the discriminant.
+    pub const fn to_wrapping_i128(&self) -> i128 {
+        let mut value = 0_i128;
+        let mut weight = 1_i128;
+
+        let mut i = 0;
+
+        while i < self.value.len() {
+            let mut bit = 0;
+
+            while bit < 8 {
+                if (self.value[i] & (1_u8 << bit)) != 0 {
+                    value = value.wrapping_add(weight);
+                }
+
+                weight = weight.wrapping_mul(-2);
+                bit += 1;
+            }
+
+            i += 1;
+        }
+
+        value
+    }
+}
+
 /// Helper macro to construct a [`Discriminant`] from its constant value.
 ///
 /// The [`discriminant!`] macro is invoked with the constant expression value
@@ -254,4 +295,22 @@ mod tests {
             // ...
         }
     }
+
+    #[test]
+    fn wrapping_value() {
+        macro_rules! check {
+            ($($n:expr),*) => {
+                $(
+                    assert_eq!(
+                        crate::discriminant!($n).to_wrapping_i128(), $n as i128,
+                        "wrong value for {}", $n,
+                    );
+                )*
+            };
+        }
+
+        check! {
+            0, 1, -1, 2, -2, 42, -42, 255, -256, 257, i64::MIN, i64::MAX, i128::MIN, i128::MAX
+        }
+    }
 }
@@ -0,0 +1,874 @@
+//! Helper module to generate random bytes that are valid instances of a type,
+//! using the layout information from a [`TypeLayoutGraph`], e.g. to fuzz the
+//! FFI boundary of a C library that consumes these types.
+
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+use crate::{
+    primitive::{self, Primitive},
+    repr::{EnumEncoding, Repr},
+    Field, MaybeUninhabited, TypeLayoutGraph, TypeLayoutInfo, TypeStructure, Variant,
+};
+
+/// Source of randomness for the [`Generator`].
+pub trait Rng {
+    /// Returns the next random [`u64`].
+    fn next_u64(&mut self) -> u64;
+
+    /// Fills `bytes` with random bytes.
+    fn fill_bytes(&mut self, bytes: &mut [u8]) {
+        for chunk in bytes.chunks_mut(core::mem::size_of::<u64>()) {
+            let random = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&random[..chunk.len()]);
+        }
+    }
+
+    /// Returns a random [`usize`] in the range `0..bound`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics iff `bound` is zero.
+    fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0, "empty range");
+
+        // Lemire's multiply-shift method, bound fits into a u64
+        #[allow(clippy::cast_possible_truncation)]
+        let value = (u128::from(self.next_u64()) * (bound as u128)) >> 64;
+
+        #[allow(clippy::cast_possible_truncation)]
+        {
+            value as usize
+        }
+    }
+}
+
+impl<R: Rng + ?Sized> Rng for &mut R {
+    fn next_u64(&mut self) -> u64 {
+        R::next_u64(self)
+    }
+
+    fn fill_bytes(&mut self, bytes: &mut [u8]) {
+        R::fill_bytes(self, bytes);
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        R::below(self, bound)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+/// Draws the randomness from the unstructured fuzzer input. Once the input
+/// is exhausted, all further values are zero.
+impl Rng for arbitrary::Unstructured<'_> {
+    fn next_u64(&mut self) -> u64 {
+        <u64 as arbitrary::Arbitrary>::arbitrary(self).unwrap_or(0)
+    }
+
+    fn fill_bytes(&mut self, bytes: &mut [u8]) {
+        if self.fill_buffer(bytes).is_err() {
+            bytes.fill(0);
+        }
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0, "empty range");
+
+        self.choose_index(bound).unwrap_or(0)
+    }
+}
+
+#[cfg(feature = "proptest")]
+/// Draws the randomness from the random number generator of a proptest
+/// [`TestRunner`](proptest::test_runner::TestRunner).
+impl Rng for proptest::test_runner::TestRng {
+    fn next_u64(&mut self) -> u64 {
+        proptest::prelude::RngCore::next_u64(self)
+    }
+
+    fn fill_bytes(&mut self, bytes: &mut [u8]) {
+        proptest::prelude::RngCore::fill_bytes(self, bytes);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// Small and fast, but not cryptographically secure, seeded [`Rng`].
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    #[must_use]
+    /// Construct a new [`SplitMix64`] random number generator from a `seed`.
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+}
+
+impl Rng for SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+/// Content of the padding bytes that are not covered by any field.
+pub enum Padding {
+    /// Padding bytes are zeroed.
+    #[default]
+    Zeroed,
+    /// Padding bytes are filled with random garbage, which can catch readers
+    /// that accidentally depend on the content of padding.
+    Garbage,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Error that occurs when a valid value cannot be generated.
+pub enum Error<'a> {
+    /// The layout of the type `ty` is not included in the graph.
+    UnknownType {
+        /// The fully-qualified name of the type.
+        ty: &'a str,
+    },
+    /// The type `ty` is uninhabited and has no valid values.
+    Uninhabited {
+        /// The fully-qualified name of the type.
+        ty: &'a str,
+    },
+    /// The type `ty` must be a non-null pointer, but no pointer value was
+    /// provided using [`Generator::with_pointer`].
+    MissingPointer {
+        /// The fully-qualified name of the type.
+        ty: &'a str,
+    },
+    /// The encoding of the variants of the enum `ty` cannot be inferred from
+    /// its layout, e.g. since it uses the default Rust representation.
+    UnsupportedEnum {
+        /// The fully-qualified name of the type.
+        ty: &'a str,
+    },
+    /// The valid values of the opaque primitive type `ty` are unknown.
+    UnsupportedPrimitive {
+        /// The fully-qualified name of the type.
+        ty: &'a str,
+    },
+    /// A field or element of the type `ty` lies outside of the type's size,
+    /// i.e. the graph is inconsistent.
+    OutOfBounds {
+        /// The fully-qualified name of the type.
+        ty: &'a str,
+    },
+    /// The buffer is smaller than the `required` size of the type.
+    BufferTooSmall {
+        /// The size of the type.
+        required: usize,
+        /// The size of the buffer.
+        provided: usize,
+    },
+}
+
+impl fmt::Display for Error<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnknownType { ty } => {
+                fmt.write_fmt(format_args!("the layout of `{ty}` is not in the graph"))
+            },
+            Self::Uninhabited { ty } => fmt.write_fmt(format_args!("`{ty}` is uninhabited")),
+            Self::MissingPointer { ty } => fmt.write_fmt(format_args!(
+                "`{ty}` must be non-null but no pointer value was provided"
+            )),
+            Self::UnsupportedEnum { ty } => fmt.write_fmt(format_args!(
+                "the variant encoding of the enum `{ty}` is unknown"
+            )),
+            Self::UnsupportedPrimitive { ty } => fmt.write_fmt(format_args!(
+                "the valid values of the primitive `{ty}` are unknown"
+            )),
+            Self::OutOfBounds { ty } => fmt.write_fmt(format_args!(
+                "a field or element of `{ty}` lies outside of its layout"
+            )),
+            Self::BufferTooSmall { required, provided } => fmt.write_fmt(format_args!(
+                "the buffer of {provided} bytes is smaller than the type's {required} bytes"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+/// Generator of random bytes that are valid instances of a type whose layout
+/// is described by a [`TypeLayoutGraph`].
+///
+/// The generated values respect the validity invariants that can be derived
+/// from the layout:
+/// - `bool`s are `0` or `1`, and `char`s are valid Unicode scalar values
+/// - `NonZero*` integers are non-zero
+/// - enum discriminants are chosen from the enum's inhabited [`Variant`]s
+/// - union values initialise one of the union's inhabited fields
+/// - raw pointers are either null or the value provided by
+///   [`Self::with_pointer`], while references, function pointers, and
+///   [`core::ptr::NonNull`] pointers always use the provided value
+///
+/// Enums can only be generated if the encoding of their variants is known,
+/// i.e. if they have a `#[repr(C)]` or primitive integer representation, are
+/// field-less, have only one inhabited variant, or use the guaranteed
+/// null-pointer optimisation (e.g. `Option<&T>`).
+///
+/// Integers, floats, and opaque bytes are always fully random. The content of
+/// padding bytes is configured by [`Self::with_padding`].
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(const_type_name)]
+/// use const_type_layout::{
+///     generate::{Generator, SplitMix64},
+///     TypeGraphLayout,
+/// };
+///
+/// type Value = (bool, char, core::num::NonZeroU32);
+///
+/// let graph = <Value>::TYPE_GRAPH;
+/// let mut rng = SplitMix64::new(42);
+///
+/// for _ in 0..100 {
+///     let bytes = Generator::new(&graph)
+///         .generate_vec(graph.ty, &mut rng)
+///         .unwrap();
+///
+///     // Safety: the bytes are a valid instance of `Value`
+///     let (b, c, n): Value = unsafe { core::ptr::read_unaligned(bytes.as_ptr().cast()) };
+///
+///     assert!(u32::from(n) != 0);
+/// }
+/// ```
+pub struct Generator<'a, 'b> {
+    graph: &'b TypeLayoutGraph<'a>,
+    pointer: Option<usize>,
+    padding: Padding,
+}
+
+impl<'a, 'b> Generator<'a, 'b> {
+    #[must_use]
+    /// Construct a generator for values of the types whose layout is included
+    /// in the `graph`.
+    ///
+    /// By default, no pointer value is provided and padding is zeroed.
+    pub const fn new(graph: &'b TypeLayoutGraph<'a>) -> Self {
+        Self {
+            graph,
+            pointer: None,
+            padding: Padding::Zeroed,
+        }
+    }
+
+    #[must_use]
+    /// Use the `address` as the value of non-null pointers, and randomly as
+    /// the value of raw pointers, which are otherwise null.
+    pub const fn with_pointer(mut self, address: usize) -> Self {
+        self.pointer = Some(address);
+        self
+    }
+
+    #[must_use]
+    /// Fill padding bytes according to the `padding` strategy.
+    pub const fn with_padding(mut self, padding: Padding) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Generate a random valid value of the type `ty` into the start of the
+    /// `bytes` buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if `bytes` is too small for a value of `ty`, or if
+    /// no valid value can be generated for `ty`.
+    pub fn generate<'c>(
+        &self,
+        ty: &'c str,
+        rng: &mut impl Rng,
+        bytes: &mut [u8],
+    ) -> Result<(), Error<'c>>
+    where
+        'a: 'c,
+    {
+        let layout = self.find_type(ty)?;
+
+        if bytes.len() < layout.size {
+            return Err(Error::BufferTooSmall {
+                required: layout.size,
+                provided: bytes.len(),
+            });
+        }
+
+        self.fill(layout, rng, &mut bytes[..layout.size])
+    }
+
+    /// Generate a random valid value of the type `ty` into a new buffer of
+    /// the type's size.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if no valid value can be generated for `ty`.
+    pub fn generate_vec<'c>(&self, ty: &'c str, rng: &mut impl Rng) -> Result<Vec<u8>, Error<'c>>
+    where
+        'a: 'c,
+    {
+        let layout = self.find_type(ty)?;
+
+        let mut bytes = vec![0_u8; layout.size];
+        self.fill(layout, rng, &mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    #[cfg(feature = "proptest")]
+    #[must_use]
+    /// Construct a proptest [`Strategy`](proptest::strategy::Strategy) that
+    /// generates random valid values of the type `ty`.
+    pub const fn strategy(self, ty: &'a str) -> ValueStrategy<'a, 'b> {
+        ValueStrategy {
+            generator: self,
+            ty,
+        }
+    }
+
+    fn find_type<'c>(&self, ty: &'c str) -> Result<&'a TypeLayoutInfo<'a>, Error<'c>> {
+        self.graph.find_type(ty).ok_or(Error::UnknownType { ty })
+    }
+
+    fn fill<'c>(
+        &self,
+        layout: &'a TypeLayoutInfo<'a>,
+        rng: &mut impl Rng,
+        bytes: &mut [u8],
+    ) -> Result<(), Error<'c>>
+    where
+        'a: 'c,
+    {
+        match &layout.structure {
+            TypeStructure::Primitive => self.fill_primitive(layout, rng, bytes),
+            TypeStructure::Struct { fields, .. } => {
+                if primitive::is_non_null_pointer(layout.name) {
+                    return self.fill_pointer(layout.name, bytes, true);
+                }
+
+                self.fill_padding(rng, bytes);
+                self.fill_fields(layout, fields, rng, bytes)?;
+
+                if primitive::is_non_zero_integer(layout.name) && bytes.iter().all(|b| *b == 0) {
+                    // any non-zero bit-pattern is valid, independent of the endianness
+                    *bytes
+                        .first_mut()
+                        .ok_or(Error::OutOfBounds { ty: layout.name })? = 1;
+                }
+
+                Ok(())
+            },
+            TypeStructure::Union { fields, .. } => {
+                let mut inhabited = fields
+                    .iter()
+                    .filter(|field| matches!(field.offset, MaybeUninhabited::Inhabited(_)));
+                let count = inhabited.clone().count();
+
+                let Some(field) = (count > 0)
+                    .then(|| inhabited.nth(rng.below(count)))
+                    .flatten()
+                else {
+                    return Err(Error::Uninhabited { ty: layout.name });
+                };
+
+                self.fill_padding(rng, bytes);
+                self.fill_fields(layout, core::slice::from_ref(field), rng, bytes)
+            },
+            TypeStructure::Enum { repr, variants } => {
+                self.fill_enum(layout, Repr::parse(repr), variants, rng, bytes)
+            },
+        }
+    }
+
+    fn fill_enum<'c>(
+        &self,
+        layout: &'a TypeLayoutInfo<'a>,
+        repr: Repr,
+        variants: &'a [Variant<'a>],
+        rng: &mut impl Rng,
+        bytes: &mut [u8],
+    ) -> Result<(), Error<'c>>
+    where
+        'a: 'c,
+    {
+        let inhabited = variants
+            .iter()
+            .enumerate()
+            .filter_map(|(i, variant)| match &variant.discriminant {
+                MaybeUninhabited::Inhabited(discriminant) => Some((i, discriminant)),
+                MaybeUninhabited::Uninhabited => None,
+            })
+            .collect::<Vec<_>>();
+
+        if inhabited.is_empty() {
+            return Err(Error::Uninhabited { ty: layout.name });
+        }
+
+        let encoding = EnumEncoding::of(self.graph, layout, repr, variants);
+
+        let (variant, discriminant) = inhabited[rng.below(inhabited.len())];
+
+        self.fill_padding(rng, bytes);
+
+        match encoding {
+            EnumEncoding::Tagged { size } => {
+                if size > bytes.len() || size > 16 {
+                    return Err(Error::OutOfBounds { ty: layout.name });
+                }
+
+                let tag = discriminant.to_wrapping_i128();
+                let tag = if cfg!(target_endian = "big") {
+                    let tag = tag.to_be_bytes();
+                    let (_, tag) = tag.split_at(tag.len() - size);
+                    let mut bytes = [0_u8; 16];
+                    bytes[..size].copy_from_slice(tag);
+                    bytes
+                } else {
+                    tag.to_le_bytes()
+                };
+
+                bytes[..size].copy_from_slice(&tag[..size]);
+            },
+            EnumEncoding::NullPointer { none, .. } if variant == none => {
+                bytes.fill(0);
+                return Ok(());
+            },
+            // without a pointer, a payload that must be non-null cannot be
+            // generated and only the null pointer, i.e. `none`, is valid
+            EnumEncoding::NullPointer { none, .. } if self.pointer.is_none() => {
+                return match self.fill_fields(layout, variants[variant].fields, rng, bytes) {
+                    Err(Error::MissingPointer { .. })
+                        if inhabited.iter().any(|(variant, _)| *variant == none) =>
+                    {
+                        bytes.fill(0);
+                        Ok(())
+                    },
+                    result => result,
+                };
+            },
+            EnumEncoding::Single { .. } | EnumEncoding::NullPointer { .. } => (),
+            EnumEncoding::Unknown => return Err(Error::UnsupportedEnum { ty: layout.name }),
+        }
+
+        self.fill_fields(layout, variants[variant].fields, rng, bytes)
+    }
+
+    fn fill_fields<'c>(
+        &self,
+        layout: &'a TypeLayoutInfo<'a>,
+        fields: &'a [Field<'a>],
+        rng: &mut impl Rng,
+        bytes: &mut [u8],
+    ) -> Result<(), Error<'c>>
+    where
+        'a: 'c,
+    {
+        for field in fields {
+            let MaybeUninhabited::Inhabited(offset) = field.offset else {
+                return Err(Error::Uninhabited { ty: layout.name });
+            };
+
+            let field_layout = self.find_type(field.ty)?;
+
+            self.fill(
+                field_layout,
+                rng,
+                subslice(layout, bytes, offset, field_layout.size)?,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn fill_primitive<'c>(
+        &self,
+        layout: &'a TypeLayoutInfo<'a>,
+        rng: &mut impl Rng,
+        bytes: &mut [u8],
+    ) -> Result<(), Error<'c>>
+    where
+        'a: 'c,
+    {
+        match Primitive::parse(layout.name) {
+            Primitive::Unit => Ok(()),
+            Primitive::Never => Err(Error::Uninhabited { ty: layout.name }),
+            Primitive::Bool => {
+                #[allow(clippy::cast_possible_truncation)]
+                let value = rng.below(2) as u8;
+                bytes.fill(0);
+                *bytes
+                    .first_mut()
+                    .ok_or(Error::OutOfBounds { ty: layout.name })? = value;
+                Ok(())
+            },
+            Primitive::Char if bytes.len() == core::mem::size_of::<char>() => {
+                // skip over the surrogate code points
+                #[allow(clippy::cast_possible_truncation)]
+                let mut value = rng.below(0x11_0000 - 0x800) as u32;
+                if value >= 0xD800 {
+                    value += 0x800;
+                }
+                bytes.copy_from_slice(&value.to_ne_bytes());
+                Ok(())
+            },
+            Primitive::Int { .. } | Primitive::Float => {
+                rng.fill_bytes(bytes);
+                Ok(())
+            },
+            Primitive::Pointer { .. } => {
+                let non_null = self.pointer.is_some() && rng.below(2) == 1;
+                self.fill_pointer(layout.name, bytes, non_null)
+            },
+            Primitive::Reference { .. } | Primitive::FnPointer { .. } => {
+                self.fill_pointer(layout.name, bytes, true)
+            },
+            Primitive::Array { element, len } => {
+                let element = self.find_type(element)?;
+
+                // zero-sized elements only need to be checked for validity once
+                let len = if element.size == 0 { len.min(1) } else { len };
+
+                for i in 0..len {
+                    let offset = i * element.size;
+                    self.fill(element, rng, subslice(layout, bytes, offset, element.size)?)?;
+                }
+
+                Ok(())
+            },
+            Primitive::Char | Primitive::Opaque => {
+                Err(Error::UnsupportedPrimitive { ty: layout.name })
+            },
+        }
+    }
+
+    fn fill_pointer<'c>(
+        &self,
+        ty: &'c str,
+        bytes: &mut [u8],
+        non_null: bool,
+    ) -> Result<(), Error<'c>> {
+        if bytes.len() != core::mem::size_of::<usize>() {
+            return Err(Error::UnsupportedPrimitive { ty });
+        }
+
+        match (non_null, self.pointer) {
+            (true, Some(address)) => bytes.copy_from_slice(&address.to_ne_bytes()),
+            (true, None) => return Err(Error::MissingPointer { ty }),
+            (false, _) => bytes.fill(0),
+        }
+
+        Ok(())
+    }
+
+    fn fill_padding(&self, rng: &mut impl Rng, bytes: &mut [u8]) {
+        match self.padding {
+            Padding::Zeroed => bytes.fill(0),
+            Padding::Garbage => rng.fill_bytes(bytes),
+        }
+    }
+}
+
+#[cfg(feature = "proptest")]
+#[derive(Clone, Copy, Debug)]
+/// proptest [`Strategy`](proptest::strategy::Strategy) that generates random
+/// valid values of a type, which is constructed by [`Generator::strategy`].
+///
+/// The generated values are not shrunk, since a smaller byte pattern is not
+/// necessarily a simpler value. If no value can be generated, the test run is
+/// aborted with the [`Error`]'s message.
+pub struct ValueStrategy<'a, 'b> {
+    generator: Generator<'a, 'b>,
+    ty: &'a str,
+}
+
+#[cfg(feature = "proptest")]
+impl proptest::strategy::Strategy for ValueStrategy<'_, '_> {
+    type Tree = proptest::strategy::Just<Vec<u8>>;
+    type Value = Vec<u8>;
+
+    fn new_tree(
+        &self,
+        runner: &mut proptest::test_runner::TestRunner,
+    ) -> proptest::strategy::NewTree<Self> {
+        self.generator
+            .generate_vec(self.ty, runner.rng())
+            .map(proptest::strategy::Just)
+            .map_err(|err| alloc::string::ToString::to_string(&err).into())
+    }
+}
+
+/// Returns the `size` bytes at `offset` inside the `bytes` of a value of the
+/// type `layout`, which must not lie outside of them.
+fn subslice<'a, 'b, 'c>(
+    layout: &'a TypeLayoutInfo<'a>,
+    bytes: &'b mut [u8],
+    offset: usize,
+    size: usize,
+) -> Result<&'b mut [u8], Error<'c>>
+where
+    'a: 'c,
+{
+    offset
+        .checked_add(size)
+        .and_then(|end| bytes.get_mut(offset..end))
+        .ok_or(Error::OutOfBounds { ty: layout.name })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{
+        Field, MaybeUninhabited, TypeGraphLayout, TypeLayoutGraph, TypeLayoutInfo, TypeStructure,
+    };
+
+    use super::{Error, Generator, Padding, SplitMix64};
+
+    const SAMPLES: usize = 64;
+
+    const VOID: TypeLayoutGraph<'static> = TypeLayoutGraph {
+        ty: "Void",
+        tys: &[
+            &TypeLayoutInfo {
+                name: "Void",
+                size: 0,
+                alignment: 1,
+                structure: TypeStructure::Union {
+                    repr: "C",
+                    fields: &[Field {
+                        name: "never",
+                        offset: MaybeUninhabited::Uninhabited,
+                        ty: "!",
+                    }],
+                },
+            },
+            &TypeLayoutInfo {
+                name: "!",
+                size: 0,
+                alignment: 1,
+                structure: TypeStructure::Primitive,
+            },
+        ],
+    };
+
+    const BROKEN: TypeLayoutGraph<'static> = TypeLayoutGraph {
+        ty: "Broken",
+        tys: &[
+            &TypeLayoutInfo {
+                name: "Broken",
+                size: 2,
+                alignment: 1,
+                structure: TypeStructure::Struct {
+                    repr: "C",
+                    fields: &[Field {
+                        name: "a",
+                        offset: MaybeUninhabited::Inhabited(usize::MAX),
+                        ty: "u16",
+                    }],
+                },
+            },
+            &TypeLayoutInfo {
+                name: "u16",
+                size: 2,
+                alignment: 2,
+                structure: TypeStructure::Primitive,
+            },
+        ],
+    };
+
+    fn samples<T: TypeGraphLayout>(pointer: Option<usize>, padding: Padding) -> Vec<Vec<u8>> {
+        let graph = T::TYPE_GRAPH;
+        let mut generator = Generator::new(&graph).with_padding(padding);
+        if let Some(address) = pointer {
+            generator = generator.with_pointer(address);
+        }
+        let mut rng = SplitMix64::new(42);
+
+        (0..SAMPLES)
+            .map(|_| generator.generate_vec(graph.ty, &mut rng).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn discriminants() {
+        let mut seen = samples::<core::cmp::Ordering>(None, Padding::Zeroed)
+            .into_iter()
+            .map(|bytes| i8::from_ne_bytes([bytes[0]]))
+            .collect::<Vec<_>>();
+        seen.sort_unstable();
+        seen.dedup();
+
+        assert_eq!(seen, [-1, 0, 1]);
+    }
+
+    #[test]
+    fn niches() {
+        let address = 0x1000_usize.to_ne_bytes();
+
+        let options = samples::<Option<&'static u8>>(Some(0x1000), Padding::Zeroed);
+        assert!(options
+            .iter()
+            .all(|bytes| bytes == &[0; core::mem::size_of::<usize>()] || bytes == &address));
+        assert!(options.iter().any(|bytes| bytes == &address));
+        assert!(options.iter().any(|bytes| bytes.iter().all(|b| *b == 0)));
+
+        let non_zero = samples::<Option<core::num::NonZeroU32>>(None, Padding::Zeroed);
+        assert!(non_zero.iter().any(|bytes| bytes.iter().all(|b| *b == 0)));
+        assert!(non_zero.iter().any(|bytes| bytes.iter().any(|b| *b != 0)));
+    }
+
+    #[test]
+    fn null_pointer_niches_without_pointer() {
+        let null = [0; core::mem::size_of::<usize>()];
+
+        assert!(samples::<Option<&'static u64>>(None, Padding::Zeroed)
+            .iter()
+            .all(|bytes| bytes == &null));
+        assert!(
+            samples::<Option<core::ptr::NonNull<u8>>>(None, Padding::Zeroed)
+                .iter()
+                .all(|bytes| bytes == &null)
+        );
+    }
+
+    #[test]
+    fn unions() {
+        let values = samples::<core::mem::MaybeUninit<bool>>(None, Padding::Zeroed);
+        assert!(values.iter().all(|bytes| bytes[0] <= 1));
+        assert!(values.iter().any(|bytes| bytes[0] == 1));
+
+        assert_eq!(
+            Generator::new(&VOID).generate_vec(VOID.ty, &mut SplitMix64::new(42)),
+            Err(Error::Uninhabited { ty: "Void" })
+        );
+    }
+
+    #[test]
+    fn zero_sized_arrays() {
+        assert!(samples::<[(); usize::MAX]>(None, Padding::Zeroed)
+            .iter()
+            .all(Vec::is_empty));
+
+        let graph = <[core::convert::Infallible; usize::MAX]>::TYPE_GRAPH;
+        assert_eq!(
+            Generator::new(&graph).generate_vec(graph.ty, &mut SplitMix64::new(42)),
+            Err(Error::Uninhabited {
+                ty: "core::convert::Infallible"
+            })
+        );
+    }
+
+    #[test]
+    fn padding() {
+        let zeroed = samples::<(u8, u16)>(None, Padding::Zeroed);
+        assert!(zeroed.iter().all(|bytes| bytes[1] == 0));
+
+        let garbage = samples::<(u8, u16)>(None, Padding::Garbage);
+        assert!(garbage.iter().any(|bytes| bytes[1] != 0));
+    }
+
+    #[test]
+    fn pointers() {
+        let null = [0; core::mem::size_of::<usize>()];
+        let address = 0x1000_usize.to_ne_bytes();
+
+        assert!(samples::<*const u8>(None, Padding::Zeroed)
+            .iter()
+            .all(|bytes| bytes == &null));
+
+        let raw = samples::<*mut u8>(Some(0x1000), Padding::Zeroed);
+        assert!(raw.iter().any(|bytes| bytes == &null));
+        assert!(raw.iter().any(|bytes| bytes == &address));
+
+        assert!(
+            samples::<core::ptr::NonNull<u8>>(Some(0x1000), Padding::Zeroed)
+                .iter()
+                .all(|bytes| bytes == &address)
+        );
+        assert!(samples::<fn()>(Some(0x1000), Padding::Zeroed)
+            .iter()
+            .all(|bytes| bytes == &address));
+
+        let graph = <&'static u8>::TYPE_GRAPH;
+        assert_eq!(
+            Generator::new(&graph).generate_vec(graph.ty, &mut SplitMix64::new(42)),
+            Err(Error::MissingPointer { ty: "&u8" })
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let graph = <Option<u8>>::TYPE_GRAPH;
+        let mut rng = SplitMix64::new(42);
+
+        assert_eq!(
+            Generator::new(&graph).generate_vec(graph.ty, &mut rng),
+            Err(Error::UnsupportedEnum {
+                ty: "core::option::Option<u8>"
+            })
+        );
+        assert_eq!(
+            Generator::new(&graph).generate_vec("u16", &mut rng),
+            Err(Error::UnknownType { ty: "u16" })
+        );
+        assert_eq!(
+            Generator::new(&graph).generate("u8", &mut rng, &mut []),
+            Err(Error::BufferTooSmall {
+                required: 1,
+                provided: 0
+            })
+        );
+
+        assert_eq!(
+            Generator::new(&BROKEN).generate_vec(BROKEN.ty, &mut rng),
+            Err(Error::OutOfBounds { ty: "Broken" })
+        );
+
+        let graph = <core::mem::Discriminant<u8>>::TYPE_GRAPH;
+        assert_eq!(
+            Generator::new(&graph).generate_vec(graph.ty, &mut rng),
+            Err(Error::UnsupportedPrimitive {
+                ty: "core::mem::Discriminant<u8>"
+            })
+        );
+    }
+
+    #[cfg(feature = "proptest")]
+    #[test]
+    fn strategy() {
+        use proptest::{strategy::ValueTree, test_runner::TestRunner};
+
+        let graph = <[bool; 2]>::TYPE_GRAPH;
+        let strategy = Generator::new(&graph).strategy(graph.ty);
+        let mut runner = TestRunner::deterministic();
+
+        for _ in 0..SAMPLES {
+            let bytes = proptest::strategy::Strategy::new_tree(&strategy, &mut runner)
+                .unwrap()
+                .current();
+            assert_eq!(bytes.len(), 2);
+            assert!(bytes.iter().all(|b| *b <= 1));
+        }
+
+        let strategy = Generator::new(&graph).strategy("u16");
+        assert_eq!(
+            proptest::strategy::Strategy::new_tree(&strategy, &mut runner)
+                .err()
+                .map(|reason| alloc::string::String::from(reason.message())),
+            Some(alloc::string::String::from(
+                "the layout of `u16` is not in the graph"
+            ))
+        );
+    }
+}
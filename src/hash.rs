@@ -0,0 +1,258 @@
+//! Helper module to hash [`TypeLayoutGraph`]s with configurable hashers.
+//!
+//! All hashers are computed over the canonical form of a
+//! [`TypeLayoutGraph`], which is described in the [`wire`](crate::wire)
+//! module, such that the hashes do not depend on the binary format that is
+//! produced by [`TypeLayoutGraph::serialise`].
+//!
+//! The [`HashedTypeGraph`] trait provides the hash of a type's
+//! [`TypeGraphLayout::TYPE_GRAPH`] as an associated const for every
+//! [`TypeGraphHasher`], which is only computed once per type and hasher:
+//!
+//! ```rust
+//! # #![feature(const_type_name)]
+//! use const_type_layout::{
+//!     hash::{Fnv1a64, HashedTypeGraph, SipHash128},
+//!     TypeGraphLayout,
+//! };
+//!
+//! type Ty = Option<&'static u32>;
+//!
+//! const FNV: u64 = <Ty as HashedTypeGraph<Fnv1a64>>::HASH;
+//! const SIP: u128 = <Ty as HashedTypeGraph<SipHash128<1, 2>>>::HASH;
+//!
+//! assert_eq!(FNV, Ty::TYPE_GRAPH.hash(0));
+//! assert_eq!(SIP, Ty::TYPE_GRAPH.hash128(1, 2));
+//! assert_eq!(Ty::TYPE_HASH, Ty::TYPE_GRAPH.hash128(0, 0));
+//! ```
+
+use alloc::{vec, vec::Vec};
+use core::{fmt, hash::Hash};
+
+use crate::{ser::Serialiser, TypeGraphLayout, TypeLayoutGraph};
+
+/// Hasher that produces a hash of a [`TypeLayoutGraph`].
+///
+/// This crate provides the [`Fnv1a64`] and [`SipHash128`] hashers, and other
+/// hashers can implement this trait by hashing the bytes of the graph's
+/// [`canonical_form`]:
+///
+/// ```rust
+/// # #![feature(const_type_name)]
+/// use const_type_layout::{
+///     hash::{canonical_form, TypeGraphHasher},
+///     TypeGraphLayout, TypeLayoutGraph,
+/// };
+///
+/// struct Sum;
+///
+/// impl TypeGraphHasher for Sum {
+///     type Hash = u64;
+///
+///     fn hash(graph: &TypeLayoutGraph) -> Self::Hash {
+///         canonical_form(graph).iter().map(|byte| u64::from(*byte)).sum()
+///     }
+/// }
+///
+/// assert_ne!(Sum::hash(&u8::TYPE_GRAPH), Sum::hash(&u16::TYPE_GRAPH));
+/// ```
+///
+/// Trait methods cannot be called in const contexts, so
+/// [`HashedTypeGraph::HASH`] is instead implemented separately for the hashers
+/// of this crate using their inherent `const fn hash_graph`. The orphan rules
+/// only allow other crates to implement [`HashedTypeGraph`] for their hashers
+/// and their own types.
+pub trait TypeGraphHasher {
+    /// The type of the produced hash.
+    type Hash: Copy + Eq + Ord + Hash + fmt::Debug;
+
+    /// Hash the `graph`.
+    fn hash(graph: &TypeLayoutGraph) -> Self::Hash;
+}
+
+#[must_use]
+/// The canonical form of the `graph`, i.e. the bytes that all
+/// [`TypeGraphHasher`]s hash, which are the bytes produced by
+/// [`TypeLayoutGraph::serialise`] without the header, see the
+/// [`wire`](crate::wire) module.
+pub fn canonical_form(graph: &TypeLayoutGraph) -> Vec<u8> {
+    let mut counter = Serialiser::counter(0);
+    counter.serialise_type_layout_graph(graph);
+
+    let mut bytes = vec![0; counter.cursor()];
+
+    let mut writer = Serialiser::writer(&mut bytes, 0);
+    writer.serialise_type_layout_graph(graph);
+
+    bytes
+}
+
+/// Utility trait that provides the hash of a type's
+/// [`TypeGraphLayout::TYPE_GRAPH`] using the hasher `H`.
+pub trait HashedTypeGraph<H: TypeGraphHasher>: TypeGraphLayout {
+    /// Hash of the type's [`TypeGraphLayout::TYPE_GRAPH`].
+    const HASH: H::Hash;
+}
+
+/// The hasher that is used for [`TypeGraphLayout::TYPE_HASH`].
+pub type DefaultHasher = SipHash128;
+
+/// The 64-bit [FNV-1a](https://en.wikipedia.org/wiki/Fowler%E2%80%93Noll%E2%80%93Vo_hash_function)
+/// hasher, which first hashes the `SEED`.
+///
+/// This hasher is fast but not resistant against collisions across large
+/// numbers of types, which [`SipHash128`] is better suited for.
+pub struct Fnv1a64<const SEED: u64 = 0>;
+
+impl<const SEED: u64> Fnv1a64<SEED> {
+    #[must_use]
+    /// Hash the `graph`, see [`TypeLayoutGraph::hash`].
+    pub const fn hash_graph(graph: &TypeLayoutGraph) -> u64 {
+        graph.hash(SEED)
+    }
+}
+
+impl<const SEED: u64> TypeGraphHasher for Fnv1a64<SEED> {
+    type Hash = u64;
+
+    fn hash(graph: &TypeLayoutGraph) -> Self::Hash {
+        Self::hash_graph(graph)
+    }
+}
+
+impl<T: TypeGraphLayout, const SEED: u64> HashedTypeGraph<Fnv1a64<SEED>> for T {
+    const HASH: u64 = Fnv1a64::<SEED>::hash_graph(&T::TYPE_GRAPH);
+}
+
+/// The 128-bit [SipHash-2-4](https://en.wikipedia.org/wiki/SipHash) hasher
+/// with the 128-bit key `(K0, K1)`.
+pub struct SipHash128<const K0: u64 = 0, const K1: u64 = 0>;
+
+impl<const K0: u64, const K1: u64> SipHash128<K0, K1> {
+    #[must_use]
+    /// Hash the `graph`, see [`TypeLayoutGraph::hash128`].
+    pub const fn hash_graph(graph: &TypeLayoutGraph) -> u128 {
+        graph.hash128(K0, K1)
+    }
+}
+
+impl<const K0: u64, const K1: u64> TypeGraphHasher for SipHash128<K0, K1> {
+    type Hash = u128;
+
+    fn hash(graph: &TypeLayoutGraph) -> Self::Hash {
+        Self::hash_graph(graph)
+    }
+}
+
+impl<T: TypeGraphLayout, const K0: u64, const K1: u64> HashedTypeGraph<SipHash128<K0, K1>> for T {
+    const HASH: u128 = SipHash128::<K0, K1>::hash_graph(&T::TYPE_GRAPH);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{canonical_form, Fnv1a64, SipHash128, TypeGraphHasher};
+    use crate::{ser::SipState, TypeGraphLayout, TypeLayoutGraph};
+
+    /// FNV-1a over the canonical form, implemented outside of the serialiser.
+    struct Fnv<const SEED: u64>;
+
+    impl<const SEED: u64> TypeGraphHasher for Fnv<SEED> {
+        type Hash = u64;
+
+        fn hash(graph: &TypeLayoutGraph) -> Self::Hash {
+            SEED.to_le_bytes()
+                .iter()
+                .chain(&canonical_form(graph))
+                .fold(0xcbf2_9ce4_8422_2325, |state, byte| {
+                    (state ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+                })
+        }
+    }
+
+    #[test]
+    fn custom_hasher() {
+        for graph in [
+            u8::TYPE_GRAPH,
+            <Option<&'static u32>>::TYPE_GRAPH,
+            <(u16, [char; 3])>::TYPE_GRAPH,
+        ] {
+            assert_eq!(Fnv::<0>::hash(&graph), Fnv1a64::<0>::hash(&graph));
+            assert_eq!(Fnv::<42>::hash(&graph), Fnv1a64::<42>::hash(&graph));
+        }
+    }
+
+    #[test]
+    fn hash_regression_vectors() {
+        // version 0.3.2 hashed the length-prefixed graph, which included the
+        // crate version and listed the types in a different order, and
+        // produced 0xcb41_178b_bb02_a5eb and 0x655b_1c25_721e_8a6b instead
+        assert_eq!(Fnv1a64::<0>::hash(&u8::TYPE_GRAPH), 0x6f6d_3deb_6dcf_ea64);
+        assert_eq!(
+            Fnv1a64::<0>::hash(&<Option<&'static u32>>::TYPE_GRAPH),
+            0xfeab_e1ed_ad81_71e2
+        );
+
+        assert_eq!(
+            SipHash128::<0, 0>::hash(&u8::TYPE_GRAPH),
+            0x3140_5f60_e84d_1b52_5256_c6de_23b2_c0bd
+        );
+        assert_eq!(
+            SipHash128::<0, 0>::hash(&<Option<&'static u32>>::TYPE_GRAPH),
+            0x1751_0ba1_8118_5e5d_8404_376a_be84_4536
+        );
+    }
+
+    const fn siphash128(bytes: &[u8]) -> u128 {
+        let mut state = SipState::new(0x0706_0504_0302_0100, 0x0f0e_0d0c_0b0a_0908);
+
+        let mut i = 0;
+
+        while i < bytes.len() {
+            state.write_byte(bytes[i]);
+            i += 1;
+        }
+
+        state.finish()
+    }
+
+    #[test]
+    fn siphash128_reference_vectors() {
+        const MESSAGE: [u8; 64] = {
+            let mut message = [0; 64];
+
+            let mut i = 0;
+
+            while i < message.len() {
+                #[allow(clippy::cast_possible_truncation)]
+                {
+                    message[i] = i as u8;
+                }
+                i += 1;
+            }
+
+            message
+        };
+
+        assert_eq!(
+            siphash128(&MESSAGE[..0]).to_le_bytes(),
+            [
+                0xa3, 0x81, 0x7f, 0x04, 0xba, 0x25, 0xa8, 0xe6, 0x6d, 0xf6, 0x72, 0x14, 0xc7, 0x55,
+                0x02, 0x93
+            ]
+        );
+        assert_eq!(
+            siphash128(&MESSAGE[..15]).to_le_bytes(),
+            [
+                0x54, 0x93, 0xe9, 0x99, 0x33, 0xb0, 0xa8, 0x11, 0x7e, 0x08, 0xec, 0x0f, 0x97, 0xcf,
+                0xc3, 0xd9
+            ]
+        );
+        assert_eq!(
+            siphash128(&MESSAGE[..63]).to_le_bytes(),
+            [
+                0x51, 0x50, 0xd1, 0x77, 0x2f, 0x50, 0x83, 0x4a, 0x50, 0x3e, 0x06, 0x9a, 0x97, 0x3f,
+                0xbd, 0x7c
+            ]
+        );
+    }
+}
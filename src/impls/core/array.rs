@@ -1,12 +1,14 @@
 use crate::{
-    typeset::{tset, ComputeTypeSet, ExpandTypeSet},
+    typeset::{tkey, tset, ComputeTypeKey, ComputeTypeSet, ExpandTypeSet},
     TypeLayout, TypeLayoutInfo, TypeStructure,
 };
 
 unsafe impl<T: TypeLayout, const N: usize> TypeLayout for [T; N] {
     const INHABITED: crate::MaybeUninhabited = T::INHABITED;
+    const STABLE_NAME: &'static str =
+        crate::name::compose!("["; .str(T::STABLE_NAME).str("; ").usize(N).str("]"));
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Primitive,
@@ -16,3 +18,7 @@ unsafe impl<T: TypeLayout, const N: usize> TypeLayout for [T; N] {
 unsafe impl<T: ComputeTypeSet, const N: usize> ComputeTypeSet for [T; N] {
     type Output<R: ExpandTypeSet> = tset![T, .. @ R];
 }
+
+impl<T: ComputeTypeKey, const N: usize> ComputeTypeKey for [T; N] {
+    type Key = tkey!["[]"; T];
+}
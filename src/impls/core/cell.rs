@@ -1,12 +1,14 @@
 use crate::{
-    typeset::{tset, ComputeTypeSet, ExpandTypeSet},
+    typeset::{tkey, tset, ComputeTypeKey, ComputeTypeSet, ExpandTypeSet},
     Field, MaybeUninhabited, TypeLayout, TypeLayoutInfo, TypeStructure,
 };
 
 unsafe impl<T: TypeLayout> TypeLayout for core::cell::UnsafeCell<T> {
     const INHABITED: crate::MaybeUninhabited = T::INHABITED;
+    const STABLE_NAME: &'static str =
+        crate::name::compose!("core::cell::UnsafeCell"; .generic(&[T::STABLE_NAME]));
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Struct {
@@ -14,7 +16,7 @@ unsafe impl<T: TypeLayout> TypeLayout for core::cell::UnsafeCell<T> {
             fields: &[Field {
                 name: "value",
                 offset: MaybeUninhabited::new::<T>(0),
-                ty: ::core::any::type_name::<T>(),
+                ty: <T as TypeLayout>::STABLE_NAME,
             }],
         },
     };
@@ -24,10 +26,16 @@ unsafe impl<T: ComputeTypeSet> ComputeTypeSet for core::cell::UnsafeCell<T> {
     type Output<R: ExpandTypeSet> = tset![T, .. @ R];
 }
 
+impl<T: ComputeTypeKey> ComputeTypeKey for core::cell::UnsafeCell<T> {
+    type Key = tkey!["core::cell::UnsafeCell"; T];
+}
+
 unsafe impl<T: TypeLayout> TypeLayout for core::cell::Cell<T> {
     const INHABITED: crate::MaybeUninhabited = T::INHABITED;
+    const STABLE_NAME: &'static str =
+        crate::name::compose!("core::cell::Cell"; .generic(&[T::STABLE_NAME]));
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Struct {
@@ -35,7 +43,7 @@ unsafe impl<T: TypeLayout> TypeLayout for core::cell::Cell<T> {
             fields: &[Field {
                 name: "value",
                 offset: MaybeUninhabited::new::<T>(0),
-                ty: ::core::any::type_name::<core::cell::UnsafeCell<T>>(),
+                ty: <core::cell::UnsafeCell<T> as TypeLayout>::STABLE_NAME,
             }],
         },
     };
@@ -45,11 +53,17 @@ unsafe impl<T: ComputeTypeSet> ComputeTypeSet for core::cell::Cell<T> {
     type Output<R: ExpandTypeSet> = tset![core::cell::UnsafeCell<T>, .. @ R];
 }
 
+impl<T: ComputeTypeKey> ComputeTypeKey for core::cell::Cell<T> {
+    type Key = tkey!["core::cell::Cell"; T];
+}
+
 #[cfg(feature = "impl-sync-unsafe-cell")]
 unsafe impl<T: TypeLayout> TypeLayout for core::cell::SyncUnsafeCell<T> {
     const INHABITED: crate::MaybeUninhabited = T::INHABITED;
+    const STABLE_NAME: &'static str =
+        crate::name::compose!("core::cell::SyncUnsafeCell"; .generic(&[T::STABLE_NAME]));
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Struct {
@@ -57,7 +71,7 @@ unsafe impl<T: TypeLayout> TypeLayout for core::cell::SyncUnsafeCell<T> {
             fields: &[Field {
                 name: "value",
                 offset: MaybeUninhabited::new::<T>(0),
-                ty: ::core::any::type_name::<core::cell::UnsafeCell<T>>(),
+                ty: <core::cell::UnsafeCell<T> as TypeLayout>::STABLE_NAME,
             }],
         },
     };
@@ -68,10 +82,17 @@ unsafe impl<T: ComputeTypeSet> ComputeTypeSet for core::cell::SyncUnsafeCell<T>
     type Output<R: ExpandTypeSet> = tset![core::cell::UnsafeCell<T>, .. @ R];
 }
 
+#[cfg(feature = "impl-sync-unsafe-cell")]
+impl<T: ComputeTypeKey> ComputeTypeKey for core::cell::SyncUnsafeCell<T> {
+    type Key = tkey!["core::cell::SyncUnsafeCell"; T];
+}
+
 unsafe impl<T: TypeLayout> TypeLayout for core::cell::OnceCell<T> {
     const INHABITED: crate::MaybeUninhabited = T::INHABITED;
+    const STABLE_NAME: &'static str =
+        crate::name::compose!("core::cell::once::OnceCell"; .generic(&[T::STABLE_NAME]));
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Struct {
@@ -79,7 +100,7 @@ unsafe impl<T: TypeLayout> TypeLayout for core::cell::OnceCell<T> {
             fields: &[Field {
                 name: "inner",
                 offset: MaybeUninhabited::new::<T>(0),
-                ty: ::core::any::type_name::<core::cell::UnsafeCell<core::option::Option<T>>>(),
+                ty: <core::cell::UnsafeCell<core::option::Option<T>> as TypeLayout>::STABLE_NAME,
             }],
         },
     };
@@ -88,3 +109,7 @@ unsafe impl<T: TypeLayout> TypeLayout for core::cell::OnceCell<T> {
 unsafe impl<T: ComputeTypeSet> ComputeTypeSet for core::cell::OnceCell<T> {
     type Output<R: ExpandTypeSet> = tset![core::cell::UnsafeCell<core::option::Option<T>>, .. @ R];
 }
+
+impl<T: ComputeTypeKey> ComputeTypeKey for core::cell::OnceCell<T> {
+    type Key = tkey!["core::cell::OnceCell"; T];
+}
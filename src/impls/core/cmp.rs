@@ -1,12 +1,14 @@
 use crate::{
-    typeset::{tset, ComputeTypeSet, ExpandTypeSet},
+    typeset::{tkey, tset, ComputeTypeKey, ComputeTypeSet, ExpandTypeSet},
     Field, MaybeUninhabited, TypeLayout, TypeLayoutInfo, TypeStructure, Variant,
 };
 
 unsafe impl<T: TypeLayout> TypeLayout for core::cmp::Reverse<T> {
     const INHABITED: crate::MaybeUninhabited = T::INHABITED;
+    const STABLE_NAME: &'static str =
+        crate::name::compose!("core::cmp::Reverse"; .generic(&[T::STABLE_NAME]));
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Struct {
@@ -14,7 +16,7 @@ unsafe impl<T: TypeLayout> TypeLayout for core::cmp::Reverse<T> {
             fields: &[Field {
                 name: "0",
                 offset: MaybeUninhabited::new::<T>(0),
-                ty: ::core::any::type_name::<T>(),
+                ty: <T as TypeLayout>::STABLE_NAME,
             }],
         },
     };
@@ -24,10 +26,15 @@ unsafe impl<T: ComputeTypeSet> ComputeTypeSet for core::cmp::Reverse<T> {
     type Output<R: ExpandTypeSet> = tset![T, .. @ R];
 }
 
+impl<T: ComputeTypeKey> ComputeTypeKey for core::cmp::Reverse<T> {
+    type Key = tkey!["core::cmp::Reverse"; T];
+}
+
 unsafe impl TypeLayout for core::cmp::Ordering {
     const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![];
+    const STABLE_NAME: &'static str = "core::cmp::Ordering";
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Enum {
@@ -58,3 +65,7 @@ unsafe impl ComputeTypeSet for core::cmp::Ordering {
         ::core::mem::Discriminant<Self>, .. @ R
     ];
 }
+
+impl ComputeTypeKey for core::cmp::Ordering {
+    type Key = tkey!["core::cmp::Ordering"];
+}
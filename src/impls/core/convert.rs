@@ -1,12 +1,13 @@
 use crate::{
-    typeset::{tset, ComputeTypeSet, ExpandTypeSet},
+    typeset::{tkey, tset, ComputeTypeKey, ComputeTypeSet, ExpandTypeSet},
     TypeLayout, TypeLayoutInfo, TypeStructure,
 };
 
 unsafe impl TypeLayout for core::convert::Infallible {
     const INHABITED: crate::MaybeUninhabited = crate::inhabited::any![];
+    const STABLE_NAME: &'static str = "core::convert::Infallible";
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Enum {
@@ -19,3 +20,7 @@ unsafe impl TypeLayout for core::convert::Infallible {
 unsafe impl ComputeTypeSet for core::convert::Infallible {
     type Output<T: ExpandTypeSet> = tset![.. @ T];
 }
+
+impl ComputeTypeKey for core::convert::Infallible {
+    type Key = tkey!["core::convert::Infallible"];
+}
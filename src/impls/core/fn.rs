@@ -1,29 +1,40 @@
 use crate::{
-    typeset::{tset, ComputeTypeSet, ExpandTypeSet},
+    typeset::{tkey, tset, ComputeTypeKey, ComputeTypeSet, ExpandTypeSet},
     TypeLayout, TypeLayoutInfo, TypeStructure,
 };
 
 macro_rules! impl_fn_pointer_type_layout {
     (impl extern $abi:literal fn($($T:ident),*) -> $R:ident) => {
         impl_fn_pointer_type_layout!{
-            impl extern $abi fn($($T),*) -> $R,
+            impl "" extern $abi fn($($T),*) -> $R,
             extern $abi fn($($T),*) -> $R,
             extern $abi fn demo<$R, $($T),*>($(_: $T),*) -> $R { loop {} }
         }
     };
     (impl unsafe extern $abi:literal fn($($T:ident),*) -> $R:ident) => {
         impl_fn_pointer_type_layout!{
-            impl extern $abi fn($($T),*) -> $R,
+            impl "unsafe " extern $abi fn($($T),*) -> $R,
             unsafe extern $abi fn($($T),*) -> $R,
             unsafe extern $abi fn demo<$R, $($T),*>($(_: $T),*) -> $R { loop {} }
         }
     };
-    (impl extern $abi:literal fn($($T:ident),*) -> $R:ident, $ty:ty, $demo:item) => {
+    (
+        impl $unsafety:literal extern $abi:literal fn($($T:ident),*) -> $R:ident,
+        $ty:ty, $demo:item
+    ) => {
         unsafe impl<$R: TypeLayout, $($T: TypeLayout),*> TypeLayout for $ty {
             const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![];
+            const STABLE_NAME: &'static str = crate::name::compose!(
+                $unsafety;
+                .abi($abi)
+                .str("fn(")
+                .join(&[$($T::STABLE_NAME),*])
+                .str(")")
+                .ret($R::STABLE_NAME)
+            );
 
             const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-                name: ::core::any::type_name::<Self>(),
+                name: Self::STABLE_NAME,
                 size: ::core::mem::size_of::<Self>(),
                 alignment: ::core::mem::align_of::<Self>(),
                 structure: TypeStructure::Primitive,
@@ -33,6 +44,10 @@ macro_rules! impl_fn_pointer_type_layout {
         unsafe impl<$R: ComputeTypeSet, $($T: ComputeTypeSet),*> ComputeTypeSet for $ty {
             type Output<Z: ExpandTypeSet> = tset![$R $(, $T)*, .. @ Z];
         }
+
+        impl<$R: ComputeTypeKey, $($T: ComputeTypeKey),*> ComputeTypeKey for $ty {
+            type Key = tkey!["fn"; $R $(, $T)*];
+        }
     };
     ($(fn($($T:ident),*) -> $R:ident),*) => {
         $(impl_fn_pointer_type_layout!{impl extern "Rust" fn($($T),*) -> $R})*
@@ -64,9 +79,17 @@ macro_rules! impl_variadic_extern_fn_pointer_type_layout {
             for unsafe extern $abi fn($($T),*, ...) -> $R
         {
             const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![];
+            const STABLE_NAME: &'static str = crate::name::compose!(
+                "unsafe ";
+                .abi($abi)
+                .str("fn(")
+                .join(&[$($T::STABLE_NAME),*])
+                .str(", ...)")
+                .ret($R::STABLE_NAME)
+            );
 
             const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-                name: ::core::any::type_name::<Self>(),
+                name: Self::STABLE_NAME,
                 size: ::core::mem::size_of::<Self>(),
                 alignment: ::core::mem::align_of::<Self>(),
                 structure: TypeStructure::Primitive,
@@ -78,6 +101,12 @@ macro_rules! impl_variadic_extern_fn_pointer_type_layout {
         {
             type Output<Z: ExpandTypeSet> = tset![$R $(, $T)*, .. @ Z];
         }
+
+        impl<$R: ComputeTypeKey, $($T: ComputeTypeKey),*> ComputeTypeKey
+            for unsafe extern $abi fn($($T),*, ...) -> $R
+        {
+            type Key = tkey!["fn"; $R $(, $T)*];
+        }
     };
     ($(unsafe extern "C" fn($($T:ident),+, ...) -> $R:ident),*) => {
         $(impl_variadic_extern_fn_pointer_type_layout!{
@@ -1,12 +1,12 @@
 use crate::{
-    typeset::{tset, ComputeTypeSet, ExpandTypeSet},
+    typeset::{tkey, tset, ComputeTypeKey, ComputeTypeSet, ExpandTypeSet},
     TypeLayout, TypeLayoutInfo, TypeStructure,
 };
 
 unsafe impl<T> TypeLayout for core::marker::PhantomData<T> {
     const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![];
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Struct {
@@ -20,10 +20,15 @@ unsafe impl<T> ComputeTypeSet for core::marker::PhantomData<T> {
     type Output<R: ExpandTypeSet> = tset![.. @ R];
 }
 
+impl<T> ComputeTypeKey for core::marker::PhantomData<T> {
+    type Key = tkey!["core::marker::PhantomData"];
+}
+
 unsafe impl TypeLayout for core::marker::PhantomPinned {
     const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![];
+    const STABLE_NAME: &'static str = "core::marker::PhantomPinned";
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Struct {
@@ -36,3 +41,7 @@ unsafe impl TypeLayout for core::marker::PhantomPinned {
 unsafe impl ComputeTypeSet for core::marker::PhantomPinned {
     type Output<T: ExpandTypeSet> = tset![.. @ T];
 }
+
+impl ComputeTypeKey for core::marker::PhantomPinned {
+    type Key = tkey!["core::marker::PhantomPinned"];
+}
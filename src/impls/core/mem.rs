@@ -1,12 +1,15 @@
 use crate::{
-    typeset::{tset, ComputeTypeSet, ExpandTypeSet},
+    typeset::{tkey, tset, ComputeTypeKey, ComputeTypeSet, ExpandTypeSet},
     Field, MaybeUninhabited, TypeLayout, TypeLayoutInfo, TypeStructure,
 };
 
 unsafe impl<T: TypeLayout> TypeLayout for core::mem::ManuallyDrop<T> {
     const INHABITED: crate::MaybeUninhabited = T::INHABITED;
+    const STABLE_NAME: &'static str = crate::name::compose!(
+        "core::mem::manually_drop::ManuallyDrop"; .generic(&[T::STABLE_NAME])
+    );
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Struct {
@@ -14,7 +17,7 @@ unsafe impl<T: TypeLayout> TypeLayout for core::mem::ManuallyDrop<T> {
             fields: &[Field {
                 name: "value",
                 offset: MaybeUninhabited::new::<T>(0),
-                ty: ::core::any::type_name::<T>(),
+                ty: <T as TypeLayout>::STABLE_NAME,
             }],
         },
     };
@@ -24,10 +27,17 @@ unsafe impl<T: ComputeTypeSet> ComputeTypeSet for core::mem::ManuallyDrop<T> {
     type Output<R: ExpandTypeSet> = tset![T, .. @ R];
 }
 
+impl<T: ComputeTypeKey> ComputeTypeKey for core::mem::ManuallyDrop<T> {
+    type Key = tkey!["core::mem::ManuallyDrop"; T];
+}
+
 unsafe impl<T: TypeLayout> TypeLayout for core::mem::MaybeUninit<T> {
     const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![];
+    const STABLE_NAME: &'static str = crate::name::compose!(
+        "core::mem::maybe_uninit::MaybeUninit"; .generic(&[T::STABLE_NAME])
+    );
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Union {
@@ -36,12 +46,12 @@ unsafe impl<T: TypeLayout> TypeLayout for core::mem::MaybeUninit<T> {
                 Field {
                     name: "uninit",
                     offset: MaybeUninhabited::Inhabited(0),
-                    ty: ::core::any::type_name::<()>(),
+                    ty: <() as TypeLayout>::STABLE_NAME,
                 },
                 Field {
                     name: "value",
                     offset: MaybeUninhabited::new::<T>(0),
-                    ty: ::core::any::type_name::<core::mem::ManuallyDrop<T>>(),
+                    ty: <core::mem::ManuallyDrop<T> as TypeLayout>::STABLE_NAME,
                 },
             ],
         },
@@ -52,16 +62,27 @@ unsafe impl<T: ComputeTypeSet> ComputeTypeSet for core::mem::MaybeUninit<T> {
     type Output<R: ExpandTypeSet> = tset![(), core::mem::ManuallyDrop<T>, .. @ R];
 }
 
-unsafe impl<T> TypeLayout for core::mem::Discriminant<T> {
+impl<T: ComputeTypeKey> ComputeTypeKey for core::mem::MaybeUninit<T> {
+    type Key = tkey!["core::mem::MaybeUninit"; T];
+}
+
+unsafe impl<T: TypeLayout> TypeLayout for core::mem::Discriminant<T> {
     const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![];
+    const STABLE_NAME: &'static str = crate::name::compose!(
+        "core::mem::Discriminant"; .generic(&[T::STABLE_NAME])
+    );
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Primitive,
     };
 }
 
-unsafe impl<T> ComputeTypeSet for core::mem::Discriminant<T> {
+unsafe impl<T: TypeLayout> ComputeTypeSet for core::mem::Discriminant<T> {
     type Output<R: ExpandTypeSet> = tset![.. @ R];
 }
+
+impl<T> ComputeTypeKey for core::mem::Discriminant<T> {
+    type Key = tkey!["core::mem::Discriminant"];
+}
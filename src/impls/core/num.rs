@@ -1,5 +1,5 @@
 use crate::{
-    typeset::{tset, ComputeTypeSet, ExpandTypeSet},
+    typeset::{tkey, tset, ComputeTypeKey, ComputeTypeSet, ExpandTypeSet},
     Field, MaybeUninhabited, TypeLayout, TypeLayoutInfo, TypeStructure,
 };
 
@@ -7,9 +7,10 @@ macro_rules! impl_nonzero_type_layout {
     (impl $nz:ident => $ty:ty) => {
         unsafe impl TypeLayout for core::num::$nz {
             const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![];
+            const STABLE_NAME: &'static str = concat!("core::num::nonzero::NonZero<", stringify!($ty), ">");
 
             const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-                name: ::core::any::type_name::<Self>(),
+                name: Self::STABLE_NAME,
                 size: ::core::mem::size_of::<Self>(),
                 alignment: ::core::mem::align_of::<Self>(),
                 structure: TypeStructure::Struct {
@@ -18,7 +19,7 @@ macro_rules! impl_nonzero_type_layout {
                         Field {
                             name: "0",
                             offset: MaybeUninhabited::Inhabited(0),
-                            ty: ::core::any::type_name::<$ty>(),
+                            ty: <$ty as TypeLayout>::STABLE_NAME,
                         },
                     ],
                 },
@@ -28,6 +29,10 @@ macro_rules! impl_nonzero_type_layout {
         unsafe impl ComputeTypeSet for core::num::$nz {
             type Output<T: ExpandTypeSet> = tset![$ty, .. @ T];
         }
+
+        impl ComputeTypeKey for core::num::$nz {
+            type Key = tkey!["core::num::NonZero"; $ty];
+        }
     };
     ($($nz:ident => $ty:ty),*) => {
         $(impl_nonzero_type_layout!{impl $nz => $ty})*
@@ -43,8 +48,11 @@ impl_nonzero_type_layout! {
 
 unsafe impl<T: TypeLayout> TypeLayout for core::num::Wrapping<T> {
     const INHABITED: crate::MaybeUninhabited = T::INHABITED;
+    const STABLE_NAME: &'static str = crate::name::compose!(
+        "core::num::wrapping::Wrapping"; .generic(&[T::STABLE_NAME])
+    );
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Struct {
@@ -52,7 +60,7 @@ unsafe impl<T: TypeLayout> TypeLayout for core::num::Wrapping<T> {
             fields: &[Field {
                 name: "0",
                 offset: MaybeUninhabited::new::<T>(0),
-                ty: ::core::any::type_name::<T>(),
+                ty: <T as TypeLayout>::STABLE_NAME,
             }],
         },
     };
@@ -62,10 +70,17 @@ unsafe impl<T: ComputeTypeSet> ComputeTypeSet for core::num::Wrapping<T> {
     type Output<R: ExpandTypeSet> = tset![T, .. @ R];
 }
 
+impl<T: ComputeTypeKey> ComputeTypeKey for core::num::Wrapping<T> {
+    type Key = tkey!["core::num::Wrapping"; T];
+}
+
 unsafe impl<T: TypeLayout> TypeLayout for core::num::Saturating<T> {
     const INHABITED: crate::MaybeUninhabited = T::INHABITED;
+    const STABLE_NAME: &'static str = crate::name::compose!(
+        "core::num::saturating::Saturating"; .generic(&[T::STABLE_NAME])
+    );
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Struct {
@@ -73,7 +88,7 @@ unsafe impl<T: TypeLayout> TypeLayout for core::num::Saturating<T> {
             fields: &[Field {
                 name: "0",
                 offset: MaybeUninhabited::new::<T>(0),
-                ty: ::core::any::type_name::<T>(),
+                ty: <T as TypeLayout>::STABLE_NAME,
             }],
         },
     };
@@ -82,3 +97,7 @@ unsafe impl<T: TypeLayout> TypeLayout for core::num::Saturating<T> {
 unsafe impl<T: ComputeTypeSet> ComputeTypeSet for core::num::Saturating<T> {
     type Output<R: ExpandTypeSet> = tset![T, .. @ R];
 }
+
+impl<T: ComputeTypeKey> ComputeTypeKey for core::num::Saturating<T> {
+    type Key = tkey!["core::num::Saturating"; T];
+}
@@ -1,12 +1,15 @@
 use crate::{
-    typeset::{tset, ComputeTypeSet, ExpandTypeSet},
+    typeset::{tkey, tset, ComputeTypeKey, ComputeTypeSet, ExpandTypeSet},
     Field, MaybeUninhabited, TypeLayout, TypeLayoutInfo, TypeStructure, Variant,
 };
 
 unsafe impl<Idx: TypeLayout> TypeLayout for core::ops::Range<Idx> {
     const INHABITED: crate::MaybeUninhabited = Idx::INHABITED;
+    const STABLE_NAME: &'static str = crate::name::compose!(
+        "core::ops::range::Range"; .generic(&[Idx::STABLE_NAME])
+    );
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Struct {
@@ -15,12 +18,12 @@ unsafe impl<Idx: TypeLayout> TypeLayout for core::ops::Range<Idx> {
                 Field {
                     name: "start",
                     offset: MaybeUninhabited::new::<Idx>(::core::mem::offset_of!(Self, start)),
-                    ty: ::core::any::type_name::<Idx>(),
+                    ty: <Idx as TypeLayout>::STABLE_NAME,
                 },
                 Field {
                     name: "end",
                     offset: MaybeUninhabited::new::<Idx>(::core::mem::offset_of!(Self, end)),
-                    ty: ::core::any::type_name::<Idx>(),
+                    ty: <Idx as TypeLayout>::STABLE_NAME,
                 },
             ],
         },
@@ -31,10 +34,17 @@ unsafe impl<Idx: ComputeTypeSet> ComputeTypeSet for core::ops::Range<Idx> {
     type Output<R: ExpandTypeSet> = tset![Idx, .. @ R];
 }
 
+impl<Idx: ComputeTypeKey> ComputeTypeKey for core::ops::Range<Idx> {
+    type Key = tkey!["core::ops::Range"; Idx];
+}
+
 unsafe impl<Idx: TypeLayout> TypeLayout for core::ops::RangeFrom<Idx> {
     const INHABITED: crate::MaybeUninhabited = Idx::INHABITED;
+    const STABLE_NAME: &'static str = crate::name::compose!(
+        "core::ops::range::RangeFrom"; .generic(&[Idx::STABLE_NAME])
+    );
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Struct {
@@ -42,7 +52,7 @@ unsafe impl<Idx: TypeLayout> TypeLayout for core::ops::RangeFrom<Idx> {
             fields: &[Field {
                 name: "start",
                 offset: MaybeUninhabited::new::<Idx>(::core::mem::offset_of!(Self, start)),
-                ty: ::core::any::type_name::<Idx>(),
+                ty: <Idx as TypeLayout>::STABLE_NAME,
             }],
         },
     };
@@ -52,10 +62,15 @@ unsafe impl<Idx: ComputeTypeSet> ComputeTypeSet for core::ops::RangeFrom<Idx> {
     type Output<R: ExpandTypeSet> = tset![Idx, .. @ R];
 }
 
+impl<Idx: ComputeTypeKey> ComputeTypeKey for core::ops::RangeFrom<Idx> {
+    type Key = tkey!["core::ops::RangeFrom"; Idx];
+}
+
 unsafe impl TypeLayout for core::ops::RangeFull {
     const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![];
+    const STABLE_NAME: &'static str = "core::ops::range::RangeFull";
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Struct {
@@ -69,10 +84,17 @@ unsafe impl ComputeTypeSet for core::ops::RangeFull {
     type Output<R: ExpandTypeSet> = tset![.. @ R];
 }
 
+impl ComputeTypeKey for core::ops::RangeFull {
+    type Key = tkey!["core::ops::RangeFull"];
+}
+
 unsafe impl<Idx: TypeLayout> TypeLayout for core::ops::RangeTo<Idx> {
     const INHABITED: crate::MaybeUninhabited = Idx::INHABITED;
+    const STABLE_NAME: &'static str = crate::name::compose!(
+        "core::ops::range::RangeTo"; .generic(&[Idx::STABLE_NAME])
+    );
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Struct {
@@ -80,7 +102,7 @@ unsafe impl<Idx: TypeLayout> TypeLayout for core::ops::RangeTo<Idx> {
             fields: &[Field {
                 name: "end",
                 offset: MaybeUninhabited::new::<Idx>(::core::mem::offset_of!(Self, end)),
-                ty: ::core::any::type_name::<Idx>(),
+                ty: <Idx as TypeLayout>::STABLE_NAME,
             }],
         },
     };
@@ -90,10 +112,17 @@ unsafe impl<Idx: ComputeTypeSet> ComputeTypeSet for core::ops::RangeTo<Idx> {
     type Output<R: ExpandTypeSet> = tset![Idx, .. @ R];
 }
 
+impl<Idx: ComputeTypeKey> ComputeTypeKey for core::ops::RangeTo<Idx> {
+    type Key = tkey!["core::ops::RangeTo"; Idx];
+}
+
 unsafe impl<Idx: TypeLayout> TypeLayout for core::ops::RangeToInclusive<Idx> {
     const INHABITED: crate::MaybeUninhabited = Idx::INHABITED;
+    const STABLE_NAME: &'static str = crate::name::compose!(
+        "core::ops::range::RangeToInclusive"; .generic(&[Idx::STABLE_NAME])
+    );
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Struct {
@@ -101,7 +130,7 @@ unsafe impl<Idx: TypeLayout> TypeLayout for core::ops::RangeToInclusive<Idx> {
             fields: &[Field {
                 name: "end",
                 offset: MaybeUninhabited::new::<Idx>(::core::mem::offset_of!(Self, end)),
-                ty: ::core::any::type_name::<Idx>(),
+                ty: <Idx as TypeLayout>::STABLE_NAME,
             }],
         },
     };
@@ -111,10 +140,17 @@ unsafe impl<Idx: ComputeTypeSet> ComputeTypeSet for core::ops::RangeToInclusive<
     type Output<R: ExpandTypeSet> = tset![Idx, .. @ R];
 }
 
+impl<Idx: ComputeTypeKey> ComputeTypeKey for core::ops::RangeToInclusive<Idx> {
+    type Key = tkey!["core::ops::RangeToInclusive"; Idx];
+}
+
 unsafe impl<T: TypeLayout> TypeLayout for core::ops::Bound<T> {
     const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![];
+    const STABLE_NAME: &'static str = crate::name::compose!(
+        "core::ops::range::Bound"; .generic(&[T::STABLE_NAME])
+    );
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Enum {
@@ -128,7 +164,7 @@ unsafe impl<T: TypeLayout> TypeLayout for core::ops::Bound<T> {
                         offset: MaybeUninhabited::new::<T>(::core::mem::offset_of!(
                             Self, Included.0
                         )),
-                        ty: ::core::any::type_name::<T>(),
+                        ty: <T as TypeLayout>::STABLE_NAME,
                     }],
                 },
                 Variant {
@@ -139,7 +175,7 @@ unsafe impl<T: TypeLayout> TypeLayout for core::ops::Bound<T> {
                         offset: MaybeUninhabited::new::<T>(::core::mem::offset_of!(
                             Self, Excluded.0
                         )),
-                        ty: ::core::any::type_name::<T>(),
+                        ty: <T as TypeLayout>::STABLE_NAME,
                     }],
                 },
                 Variant {
@@ -158,10 +194,17 @@ unsafe impl<T: ComputeTypeSet> ComputeTypeSet for core::ops::Bound<T> {
     ];
 }
 
+impl<T: ComputeTypeKey> ComputeTypeKey for core::ops::Bound<T> {
+    type Key = tkey!["core::ops::Bound"; T];
+}
+
 unsafe impl<B: TypeLayout, C: TypeLayout> TypeLayout for core::ops::ControlFlow<B, C> {
     const INHABITED: crate::MaybeUninhabited = crate::inhabited::any![B, C];
+    const STABLE_NAME: &'static str = crate::name::compose!(
+        "core::ops::control_flow::ControlFlow"; .generic(&[B::STABLE_NAME, C::STABLE_NAME])
+    );
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Enum {
@@ -175,7 +218,7 @@ unsafe impl<B: TypeLayout, C: TypeLayout> TypeLayout for core::ops::ControlFlow<
                         offset: MaybeUninhabited::new::<C>(::core::mem::offset_of!(
                             Self, Continue.0
                         )),
-                        ty: ::core::any::type_name::<C>(),
+                        ty: <C as TypeLayout>::STABLE_NAME,
                     }],
                 },
                 Variant {
@@ -184,7 +227,7 @@ unsafe impl<B: TypeLayout, C: TypeLayout> TypeLayout for core::ops::ControlFlow<
                     fields: &[Field {
                         name: "0",
                         offset: MaybeUninhabited::new::<B>(::core::mem::offset_of!(Self, Break.0)),
-                        ty: ::core::any::type_name::<B>(),
+                        ty: <B as TypeLayout>::STABLE_NAME,
                     }],
                 },
             ],
@@ -197,3 +240,7 @@ unsafe impl<B: ComputeTypeSet, C: ComputeTypeSet> ComputeTypeSet for core::ops::
         B, C, ::core::mem::Discriminant<Self>, .. @ R
     ];
 }
+
+impl<B: ComputeTypeKey, C: ComputeTypeKey> ComputeTypeKey for core::ops::ControlFlow<B, C> {
+    type Key = tkey!["core::ops::ControlFlow"; B, C];
+}
@@ -1,12 +1,14 @@
 use crate::{
-    typeset::{tset, ComputeTypeSet, ExpandTypeSet},
+    typeset::{tkey, tset, ComputeTypeKey, ComputeTypeSet, ExpandTypeSet},
     Field, MaybeUninhabited, TypeLayout, TypeLayoutInfo, TypeStructure, Variant,
 };
 
 unsafe impl<T: TypeLayout> TypeLayout for core::option::Option<T> {
     const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![];
+    const STABLE_NAME: &'static str =
+        crate::name::compose!("core::option::Option"; .generic(&[T::STABLE_NAME]));
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Enum {
@@ -23,7 +25,7 @@ unsafe impl<T: TypeLayout> TypeLayout for core::option::Option<T> {
                     fields: &[Field {
                         name: "0",
                         offset: MaybeUninhabited::new::<T>(::core::mem::offset_of!(Self, Some.0)),
-                        ty: ::core::any::type_name::<T>(),
+                        ty: <T as TypeLayout>::STABLE_NAME,
                     }],
                 },
             ],
@@ -36,3 +38,7 @@ unsafe impl<T: ComputeTypeSet> ComputeTypeSet for core::option::Option<T> {
         T, ::core::mem::Discriminant<Self>, .. @ R
     ];
 }
+
+impl<T: ComputeTypeKey> ComputeTypeKey for core::option::Option<T> {
+    type Key = tkey!["core::option::Option"; T];
+}
@@ -1,5 +1,5 @@
 use crate::{
-    typeset::{tset, ComputeTypeSet, ExpandTypeSet},
+    typeset::{tkey, tset, ComputeTypeKey, ComputeTypeSet, ExpandTypeSet},
     TypeLayout, TypeLayoutInfo, TypeStructure,
 };
 
@@ -7,9 +7,10 @@ macro_rules! impl_primitive_type_layout {
     (impl $ty:ty => $val:expr) => {
         unsafe impl TypeLayout for $ty {
             const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![];
+            const STABLE_NAME: &'static str = stringify!($ty);
 
             const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-                name: ::core::any::type_name::<Self>(),
+                name: Self::STABLE_NAME,
                 size: ::core::mem::size_of::<Self>(),
                 alignment: ::core::mem::align_of::<Self>(),
                 structure: TypeStructure::Primitive,
@@ -19,6 +20,10 @@ macro_rules! impl_primitive_type_layout {
         unsafe impl ComputeTypeSet for $ty {
             type Output<T: ExpandTypeSet> = tset![.. @ T];
         }
+
+        impl ComputeTypeKey for $ty {
+            type Key = tkey![stringify!($ty)];
+        }
     };
     ($($ty:ty => $val:expr),*) => {
         $(impl_primitive_type_layout!{impl $ty => $val})*
@@ -35,8 +40,9 @@ impl_primitive_type_layout! {
 #[cfg(feature = "impl-never")]
 unsafe impl TypeLayout for ! {
     const INHABITED: crate::MaybeUninhabited = crate::inhabited::any![];
+    const STABLE_NAME: &'static str = "!";
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Primitive,
@@ -47,3 +53,8 @@ unsafe impl TypeLayout for ! {
 unsafe impl ComputeTypeSet for ! {
     type Output<T: ExpandTypeSet> = tset![.. @ T];
 }
+
+#[cfg(feature = "impl-never")]
+impl ComputeTypeKey for ! {
+    type Key = tkey!["!"];
+}
@@ -1,12 +1,13 @@
 use crate::{
-    typeset::{tset, ComputeTypeSet, ExpandTypeSet},
+    typeset::{tkey, tset, ComputeTypeKey, ComputeTypeSet, ExpandTypeSet},
     Field, MaybeUninhabited, TypeLayout, TypeLayoutInfo, TypeStructure,
 };
 
 unsafe impl<T: TypeLayout> TypeLayout for *const T {
     const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![];
+    const STABLE_NAME: &'static str = crate::name::compose!("*const "; .str(T::STABLE_NAME));
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Primitive,
@@ -17,10 +18,15 @@ unsafe impl<T: ComputeTypeSet> ComputeTypeSet for *const T {
     type Output<R: ExpandTypeSet> = tset![T, .. @ R];
 }
 
+impl<T: ComputeTypeKey> ComputeTypeKey for *const T {
+    type Key = tkey!["*const"; T];
+}
+
 unsafe impl<T: TypeLayout> TypeLayout for *mut T {
     const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![];
+    const STABLE_NAME: &'static str = crate::name::compose!("*mut "; .str(T::STABLE_NAME));
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Primitive,
@@ -31,10 +37,16 @@ unsafe impl<T: ComputeTypeSet> ComputeTypeSet for *mut T {
     type Output<R: ExpandTypeSet> = tset![T, .. @ R];
 }
 
+impl<T: ComputeTypeKey> ComputeTypeKey for *mut T {
+    type Key = tkey!["*mut"; T];
+}
+
 unsafe impl<T: TypeLayout> TypeLayout for core::ptr::NonNull<T> {
     const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![];
+    const STABLE_NAME: &'static str =
+        crate::name::compose!("core::ptr::non_null::NonNull"; .generic(&[T::STABLE_NAME]));
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Struct {
@@ -42,7 +54,7 @@ unsafe impl<T: TypeLayout> TypeLayout for core::ptr::NonNull<T> {
             fields: &[Field {
                 name: "pointer",
                 offset: MaybeUninhabited::Inhabited(0),
-                ty: ::core::any::type_name::<*const T>(),
+                ty: <*const T as TypeLayout>::STABLE_NAME,
             }],
         },
     };
@@ -51,3 +63,7 @@ unsafe impl<T: TypeLayout> TypeLayout for core::ptr::NonNull<T> {
 unsafe impl<T: ComputeTypeSet> ComputeTypeSet for core::ptr::NonNull<T> {
     type Output<R: ExpandTypeSet> = tset![*const T, .. @ R];
 }
+
+impl<T: ComputeTypeKey> ComputeTypeKey for core::ptr::NonNull<T> {
+    type Key = tkey!["core::ptr::NonNull"; T];
+}
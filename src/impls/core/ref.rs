@@ -1,12 +1,13 @@
 use crate::{
-    typeset::{tset, ComputeTypeSet, ExpandTypeSet},
+    typeset::{tkey, tset, ComputeTypeKey, ComputeTypeSet, ExpandTypeSet},
     TypeLayout, TypeLayoutInfo, TypeStructure,
 };
 
 unsafe impl<'a, T: TypeLayout + 'a> TypeLayout for &'a T {
     const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![];
+    const STABLE_NAME: &'static str = crate::name::compose!("&"; .str(T::STABLE_NAME));
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Primitive,
@@ -17,10 +18,15 @@ unsafe impl<'a, T: ComputeTypeSet + 'a> ComputeTypeSet for &'a T {
     type Output<R: ExpandTypeSet> = tset![T, .. @ R];
 }
 
+impl<'a, T: ComputeTypeKey + 'a> ComputeTypeKey for &'a T {
+    type Key = tkey!["&"; T];
+}
+
 unsafe impl<'a, T: TypeLayout + 'a> TypeLayout for &'a mut T {
     const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![];
+    const STABLE_NAME: &'static str = crate::name::compose!("&mut "; .str(T::STABLE_NAME));
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Primitive,
@@ -30,3 +36,7 @@ unsafe impl<'a, T: TypeLayout + 'a> TypeLayout for &'a mut T {
 unsafe impl<'a, T: ComputeTypeSet + 'a> ComputeTypeSet for &'a mut T {
     type Output<R: ExpandTypeSet> = tset![T, .. @ R];
 }
+
+impl<'a, T: ComputeTypeKey + 'a> ComputeTypeKey for &'a mut T {
+    type Key = tkey!["&mut"; T];
+}
@@ -1,12 +1,14 @@
 use crate::{
-    typeset::{tset, ComputeTypeSet, ExpandTypeSet},
+    typeset::{tkey, tset, ComputeTypeKey, ComputeTypeSet, ExpandTypeSet},
     Field, MaybeUninhabited, TypeLayout, TypeLayoutInfo, TypeStructure, Variant,
 };
 
 unsafe impl<T: TypeLayout, E: TypeLayout> TypeLayout for core::result::Result<T, E> {
     const INHABITED: crate::MaybeUninhabited = crate::inhabited::any![T, E];
+    const STABLE_NAME: &'static str =
+        crate::name::compose!("core::result::Result"; .generic(&[T::STABLE_NAME, E::STABLE_NAME]));
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Enum {
@@ -18,7 +20,7 @@ unsafe impl<T: TypeLayout, E: TypeLayout> TypeLayout for core::result::Result<T,
                     fields: &[Field {
                         name: "0",
                         offset: MaybeUninhabited::new::<T>(::core::mem::offset_of!(Self, Ok.0)),
-                        ty: ::core::any::type_name::<T>(),
+                        ty: <T as TypeLayout>::STABLE_NAME,
                     }],
                 },
                 Variant {
@@ -27,7 +29,7 @@ unsafe impl<T: TypeLayout, E: TypeLayout> TypeLayout for core::result::Result<T,
                     fields: &[Field {
                         name: "0",
                         offset: MaybeUninhabited::new::<E>(::core::mem::offset_of!(Self, Err.0)),
-                        ty: ::core::any::type_name::<E>(),
+                        ty: <E as TypeLayout>::STABLE_NAME,
                     }],
                 },
             ],
@@ -40,3 +42,7 @@ unsafe impl<T: ComputeTypeSet, E: ComputeTypeSet> ComputeTypeSet for core::resul
         T, E, ::core::mem::Discriminant<Self>, .. @ R
     ];
 }
+
+impl<T: ComputeTypeKey, E: ComputeTypeKey> ComputeTypeKey for core::result::Result<T, E> {
+    type Key = tkey!["core::result::Result"; T, E];
+}
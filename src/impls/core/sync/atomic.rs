@@ -1,5 +1,5 @@
 use crate::{
-    typeset::{tset, ComputeTypeSet, ExpandTypeSet},
+    typeset::{tkey, tset, ComputeTypeKey, ComputeTypeSet, ExpandTypeSet},
     Field, MaybeUninhabited, TypeLayout, TypeLayoutInfo, TypeStructure,
 };
 
@@ -8,9 +8,10 @@ macro_rules! impl_atomic_int_layout {
         #[cfg(target_has_atomic_load_store = $cfg)]
         unsafe impl TypeLayout for core::sync::atomic::$at {
             const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![];
+            const STABLE_NAME: &'static str = concat!("core::sync::atomic::", stringify!($at));
 
             const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-                name: ::core::any::type_name::<Self>(),
+                name: Self::STABLE_NAME,
                 size: ::core::mem::size_of::<Self>(),
                 alignment: ::core::mem::align_of::<Self>(),
                 structure: TypeStructure::Struct {
@@ -19,7 +20,7 @@ macro_rules! impl_atomic_int_layout {
                         Field {
                             name: "v",
                             offset: MaybeUninhabited::Inhabited(0),
-                            ty: core::any::type_name::<core::cell::UnsafeCell<$ty>>(),
+                            ty: <core::cell::UnsafeCell<$ty> as TypeLayout>::STABLE_NAME,
                         },
                     ],
                 },
@@ -30,6 +31,11 @@ macro_rules! impl_atomic_int_layout {
         unsafe impl ComputeTypeSet for core::sync::atomic::$at {
             type Output<T: ExpandTypeSet> = tset![core::cell::UnsafeCell<$ty>, .. @ T];
         }
+
+        #[cfg(target_has_atomic_load_store = $cfg)]
+        impl ComputeTypeKey for core::sync::atomic::$at {
+            type Key = tkey!["core::sync::atomic::Atomic"; $ty];
+        }
     };
     ($($at:ident ( $align:literal : $cfg:literal ) => $ty:ty => $val:literal),*) => {
         $(impl_atomic_int_layout!{impl $at ($align : $cfg) => $ty => $val})*
@@ -55,9 +61,10 @@ macro_rules! impl_atomic_int_ptr_sized_layout {
         #[cfg(target_pointer_width = $cfg)]
         unsafe impl TypeLayout for core::sync::atomic::$at {
             const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![];
+            const STABLE_NAME: &'static str = concat!("core::sync::atomic::", stringify!($at));
 
             const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-                name: ::core::any::type_name::<Self>(),
+                name: Self::STABLE_NAME,
                 size: ::core::mem::size_of::<Self>(),
                 alignment: ::core::mem::align_of::<Self>(),
                 structure: TypeStructure::Struct {
@@ -66,7 +73,7 @@ macro_rules! impl_atomic_int_ptr_sized_layout {
                         Field {
                             name: "v",
                             offset: MaybeUninhabited::Inhabited(0),
-                            ty: core::any::type_name::<core::cell::UnsafeCell<$ty>>(),
+                            ty: <core::cell::UnsafeCell<$ty> as TypeLayout>::STABLE_NAME,
                         },
                     ],
                 },
@@ -78,6 +85,12 @@ macro_rules! impl_atomic_int_ptr_sized_layout {
         unsafe impl ComputeTypeSet for core::sync::atomic::$at {
             type Output<T: ExpandTypeSet> = tset![core::cell::UnsafeCell<$ty>, .. @ T];
         }
+
+        #[cfg(target_has_atomic_load_store = "ptr")]
+        #[cfg(target_pointer_width = $cfg)]
+        impl ComputeTypeKey for core::sync::atomic::$at {
+            type Key = tkey!["core::sync::atomic::Atomic"; $ty];
+        }
     };
     ($($at:ident ( $align:literal : $cfg:literal ) => $ty:ty => $val:literal),*) => {
         $(impl_atomic_int_ptr_sized_layout!{impl $at ($align : $cfg) => $ty => $val})*
@@ -97,9 +110,12 @@ macro_rules! impl_atomic_ptr_layout {
         #[cfg(target_pointer_width = $cfg)]
         unsafe impl<T: TypeLayout> TypeLayout for core::sync::atomic::AtomicPtr<T> {
             const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![];
+            const STABLE_NAME: &'static str = crate::name::compose!(
+                "core::sync::atomic::AtomicPtr"; .generic(&[T::STABLE_NAME])
+            );
 
             const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-                name: ::core::any::type_name::<Self>(),
+                name: Self::STABLE_NAME,
                 size: ::core::mem::size_of::<Self>(),
                 alignment: ::core::mem::align_of::<Self>(),
                 structure: TypeStructure::Struct {
@@ -108,7 +124,7 @@ macro_rules! impl_atomic_ptr_layout {
                         Field {
                             name: "v",
                             offset: MaybeUninhabited::Inhabited(0),
-                            ty: core::any::type_name::<core::cell::UnsafeCell<*mut T>>(),
+                            ty: <core::cell::UnsafeCell<*mut T> as TypeLayout>::STABLE_NAME,
                         },
                     ],
                 },
@@ -118,7 +134,13 @@ macro_rules! impl_atomic_ptr_layout {
         #[cfg(target_has_atomic_load_store = "ptr")]
         #[cfg(target_pointer_width = $cfg)]
         unsafe impl<T: ComputeTypeSet> ComputeTypeSet for core::sync::atomic::AtomicPtr<T> {
-            type Output<R: ExpandTypeSet> = tset![core::cell::UnsafeCell<T>, .. @ R];
+            type Output<R: ExpandTypeSet> = tset![core::cell::UnsafeCell<*mut T>, .. @ R];
+        }
+
+        #[cfg(target_has_atomic_load_store = "ptr")]
+        #[cfg(target_pointer_width = $cfg)]
+        impl<T: ComputeTypeKey> ComputeTypeKey for core::sync::atomic::AtomicPtr<T> {
+            type Key = tkey!["core::sync::atomic::AtomicPtr"; T];
         }
     };
     ($(( $align:literal : $cfg:literal )),*) => {
@@ -1,6 +1,6 @@
 #[cfg(feature = "impl-sync-exclusive")]
 use crate::{
-    typeset::{tset, ComputeTypeSet, ExpandTypeSet},
+    typeset::{tkey, tset, ComputeTypeKey, ComputeTypeSet, ExpandTypeSet},
     Field, MaybeUninhabited, TypeLayout, TypeLayoutInfo, TypeStructure,
 };
 
@@ -10,8 +10,10 @@ mod atomic;
 #[cfg(feature = "impl-sync-exclusive")]
 unsafe impl<T: TypeLayout> TypeLayout for core::sync::Exclusive<T> {
     const INHABITED: crate::MaybeUninhabited = T::INHABITED;
+    const STABLE_NAME: &'static str =
+        crate::name::compose!("core::sync::exclusive::Exclusive"; .generic(&[T::STABLE_NAME]));
     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-        name: ::core::any::type_name::<Self>(),
+        name: Self::STABLE_NAME,
         size: ::core::mem::size_of::<Self>(),
         alignment: ::core::mem::align_of::<Self>(),
         structure: TypeStructure::Struct {
@@ -19,7 +21,7 @@ unsafe impl<T: TypeLayout> TypeLayout for core::sync::Exclusive<T> {
             fields: &[Field {
                 name: "inner",
                 offset: MaybeUninhabited::new::<T>(0),
-                ty: ::core::any::type_name::<T>(),
+                ty: <T as TypeLayout>::STABLE_NAME,
             }],
         },
     };
@@ -29,3 +31,8 @@ unsafe impl<T: TypeLayout> TypeLayout for core::sync::Exclusive<T> {
 unsafe impl<T: ComputeTypeSet> ComputeTypeSet for core::sync::Exclusive<T> {
     type Output<R: ExpandTypeSet> = tset![T, .. @ R];
 }
+
+#[cfg(feature = "impl-sync-exclusive")]
+impl<T: ComputeTypeKey> ComputeTypeKey for core::sync::Exclusive<T> {
+    type Key = tkey!["core::sync::Exclusive"; T];
+}
@@ -1,5 +1,5 @@
 use crate::{
-    typeset::{tset, ComputeTypeSet, ExpandTypeSet},
+    typeset::{tkey, tset, ComputeTypeKey, ComputeTypeSet, ExpandTypeSet},
     Field, MaybeUninhabited, TypeLayout, TypeLayoutInfo, TypeStructure,
 };
 
@@ -7,9 +7,12 @@ macro_rules! impl_tuple_type_layout {
     (impl ($($a:tt => $T:ident),+)) => {
         unsafe impl<$($T: TypeLayout),*> TypeLayout for ($($T,)*) {
             const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![$($T),*];
+            const STABLE_NAME: &'static str = crate::name::compose!(
+                ""; .tuple(&[$($T::STABLE_NAME),*])
+            );
 
             const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-                name: ::core::any::type_name::<Self>(),
+                name: Self::STABLE_NAME,
                 size: ::core::mem::size_of::<Self>(),
                 alignment: ::core::mem::align_of::<Self>(),
                 // Even though tuples are primitives, their field layout is non-trivial
@@ -18,7 +21,7 @@ macro_rules! impl_tuple_type_layout {
                     fields: &[$(Field {
                         name: stringify!($a),
                         offset: MaybeUninhabited::new::<$T>(core::mem::offset_of!(Self, $a)),
-                        ty: ::core::any::type_name::<$T>(),
+                        ty: <$T as TypeLayout>::STABLE_NAME,
                     }),*],
                 },
             };
@@ -27,6 +30,10 @@ macro_rules! impl_tuple_type_layout {
         unsafe impl<$($T: ComputeTypeSet),*> ComputeTypeSet for ($($T,)*) {
             type Output<T: ExpandTypeSet> = tset![$($T),*, .. @ T];
         }
+
+        impl<$($T: ComputeTypeKey),*> ComputeTypeKey for ($($T,)*) {
+            type Key = tkey!["(..)"; $($T),*];
+        }
     };
     ($(($($a:tt => $T:ident),+)),*) => {
         $(impl_tuple_type_layout!{impl ($($a => $T),*)})*
@@ -0,0 +1,614 @@
+//! Helper module to refer to types inside a [`TypeLayoutGraph`] by their
+//! index inside [`TypeLayoutGraph::tys`] instead of by their name.
+//!
+//! Every [`Field::ty`] is a fully-qualified type name that has to be looked
+//! up inside [`TypeLayoutGraph::tys`] to find the layout of the field's type.
+//! An [`IndexedTypeLayoutGraph`] resolves all of these names exactly once,
+//! such that the layout of every field's type can then be found in constant
+//! time.
+//!
+//! The indices are resolved from the names inside the graph, so two distinct
+//! types with the same fully-qualified name, e.g. from different versions of
+//! the same crate, still cannot be told apart. Representing such a collision
+//! is out of scope, and [`canonicalise`] instead rejects it with
+//! [`Error::DuplicateType`].
+//!
+//! The [`indexed_type_graph!`] macro canonicalises the [`TypeLayoutGraph`] of
+//! a type at compile time:
+//!
+//! ```rust
+//! # #![feature(const_type_name)]
+//! use const_type_layout::index::{indexed_type_graph, IndexedTypeLayoutGraph};
+//!
+//! const GRAPH: IndexedTypeLayoutGraph = indexed_type_graph!((u8, u16));
+//!
+//! assert_eq!(GRAPH.root().name, "(u8, u16)");
+//! assert_eq!(GRAPH.layout_of(GRAPH.root_index(), 1).name, "u16");
+//! ```
+//!
+//! At runtime, [`canonicalise`] can also write the indices into buffers of
+//! length [`field_starts_len`] and [`field_tys_len`]:
+//!
+//! ```rust
+//! # #![feature(const_type_name)]
+//! use const_type_layout::{index, TypeGraphLayout};
+//!
+//! let graph = <Option<u32>>::TYPE_GRAPH;
+//!
+//! let mut field_starts = vec![0; index::field_starts_len(&graph)];
+//! let mut field_tys = vec![0; index::field_tys_len(&graph)];
+//!
+//! let indexed = index::canonicalise(&graph, &mut field_starts, &mut field_tys).unwrap();
+//!
+//! for &ty in indexed.field_tys_of(indexed.root_index()) {
+//!     assert!(ty < graph.tys.len());
+//! }
+//! ```
+
+use core::cmp::Ordering;
+
+use crate::{
+    repr::{bytes_cmp, bytes_eq},
+    verify::Error,
+    Field, TypeLayoutGraph, TypeLayoutInfo, TypeStructure,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Description of the deep layout of a type, in which every field refers to
+/// the layout of its type by its index inside [`TypeLayoutGraph::tys`].
+///
+/// The fields of every type are numbered in declaration order. The fields of
+/// an enum are numbered across all of its variants, i.e. the first field of
+/// the second variant follows the last field of the first variant.
+pub struct IndexedTypeLayoutGraph<'a> {
+    /// The name-based graph that is indexed.
+    pub graph: TypeLayoutGraph<'a>,
+    /// The index of the graph's root type [`TypeLayoutGraph::ty`].
+    pub root: usize,
+    /// The fields of the `i`th type have their type indices at
+    /// `field_tys[field_starts[i]..field_starts[i + 1]]`.
+    pub field_starts: &'a [usize],
+    /// The index of every field's type, grouped by the type that contains
+    /// the field.
+    pub field_tys: &'a [usize],
+}
+
+impl<'a> IndexedTypeLayoutGraph<'a> {
+    #[must_use]
+    /// The index of the graph's root type inside [`TypeLayoutGraph::tys`].
+    pub const fn root_index(&self) -> usize {
+        self.root
+    }
+
+    #[must_use]
+    /// The layout of the graph's root type.
+    pub const fn root(&self) -> &'a TypeLayoutInfo<'a> {
+        self.graph.tys[self.root]
+    }
+
+    #[must_use]
+    /// The layout of the `ty`th type inside [`TypeLayoutGraph::tys`].
+    ///
+    /// # Panics
+    ///
+    /// This method panics iff `ty` is out of bounds.
+    pub const fn layout(&self, ty: usize) -> &'a TypeLayoutInfo<'a> {
+        self.graph.tys[ty]
+    }
+
+    #[must_use]
+    /// The type indices of all fields of the `ty`th type.
+    ///
+    /// # Panics
+    ///
+    /// This method panics iff `ty` is out of bounds.
+    pub const fn field_tys_of(&self, ty: usize) -> &'a [usize] {
+        let (_, field_tys) = self.field_tys.split_at(self.field_starts[ty]);
+        let (field_tys, _) = field_tys.split_at(self.field_starts[ty + 1] - self.field_starts[ty]);
+
+        field_tys
+    }
+
+    #[must_use]
+    /// The layout of the type of the `field`th field of the `ty`th type.
+    ///
+    /// # Panics
+    ///
+    /// This method panics iff `ty` or `field` are out of bounds.
+    pub const fn layout_of(&self, ty: usize, field: usize) -> &'a TypeLayoutInfo<'a> {
+        self.graph.tys[self.field_tys_of(ty)[field]]
+    }
+
+    #[must_use]
+    /// Find the index of the type with the fully-qualified `name`.
+    pub const fn index_of(&self, name: &str) -> Option<usize> {
+        self.graph.find_type_index(name)
+    }
+
+    /// Check the consistency of every type layout inside this graph, like
+    /// [`TypeLayoutGraph::verify`], but find the layout of every field's type
+    /// by its index instead of by its name.
+    ///
+    /// The graph must have been produced by [`canonicalise`], which already
+    /// checks that the types of all fields can be found.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first inconsistency in any type layout, see
+    /// [`Error`].
+    pub const fn verify(&self) -> Result<(), Error<'a>> {
+        crate::verify::indexed(self)
+    }
+}
+
+#[must_use]
+/// The length of the `field_starts` buffer that [`canonicalise`] requires for
+/// the `graph`.
+pub const fn field_starts_len(graph: &TypeLayoutGraph) -> usize {
+    graph.tys.len() + 1
+}
+
+#[must_use]
+/// The length of the `field_tys` buffer that [`canonicalise`] requires for the
+/// `graph`.
+pub const fn field_tys_len(graph: &TypeLayoutGraph) -> usize {
+    let mut len = 0;
+
+    let mut i = 0;
+
+    while i < graph.tys.len() {
+        len += num_fields(graph.tys[i]);
+        i += 1;
+    }
+
+    len
+}
+
+/// Resolve the type of every field inside the `graph` to its index inside
+/// [`TypeLayoutGraph::tys`] and write these indices into `field_starts` and
+/// `field_tys`, which must have lengths of exactly [`field_starts_len`] and
+/// [`field_tys_len`], respectively.
+///
+/// Use [`indexed_type_graph!`] instead to canonicalise the
+/// [`TypeLayoutGraph`] of a type `T` at compile time.
+///
+/// Types are identified only by their fully-qualified names, i.e. a field
+/// refers to whichever type in the `graph` has the name of the field's type.
+/// Two types with the same name are rejected instead of being told apart, and
+/// a type that is misnamed inside a hand-written graph cannot be detected.
+///
+/// If the `graph` is in canonical order, see [`TypeLayoutGraph::get`], every
+/// type is found with a binary search, otherwise with a linear scan.
+///
+/// # Errors
+///
+/// Returns [`Error::MissingType`] or [`Error::MissingFieldType`] iff the layout
+/// of the root type or of a field's type is not in the `graph`, and
+/// [`Error::DuplicateType`] iff two types in the `graph` share a name that
+/// is looked up or, if the `graph` is in canonical order, any name.
+///
+/// # Panics
+///
+/// This function panics iff `field_starts` or `field_tys` do not have the
+/// required length.
+pub const fn canonicalise<'a, 'b>(
+    graph: &TypeLayoutGraph<'a>,
+    field_starts: &'b mut [usize],
+    field_tys: &'b mut [usize],
+) -> Result<IndexedTypeLayoutGraph<'b>, Error<'a>>
+where
+    'a: 'b,
+{
+    assert!(
+        field_starts.len() == field_starts_len(graph),
+        "field_starts buffer has the wrong length"
+    );
+    assert!(
+        field_tys.len() == field_tys_len(graph),
+        "field_tys buffer has the wrong length"
+    );
+
+    let canonical = match canonical_order(graph) {
+        Ok(canonical) => canonical,
+        Err(err) => return Err(err),
+    };
+
+    let root = match find_unique(graph, graph.ty, canonical) {
+        Ok(Some(root)) => root,
+        Ok(None) => return Err(Error::MissingType { ty: graph.ty }),
+        Err(err) => return Err(err),
+    };
+
+    let mut len = 0;
+
+    let mut i = 0;
+
+    while i < graph.tys.len() {
+        field_starts[i] = len;
+
+        let layout = graph.tys[i];
+
+        match layout.structure {
+            TypeStructure::Primitive => (),
+            TypeStructure::Struct { fields, .. } | TypeStructure::Union { fields, .. } => {
+                match index_fields(graph, canonical, layout, fields, field_tys, len) {
+                    Ok(new_len) => len = new_len,
+                    Err(err) => return Err(err),
+                }
+            },
+            TypeStructure::Enum { variants, .. } => {
+                let mut v = 0;
+
+                while v < variants.len() {
+                    match index_fields(graph, canonical, layout, variants[v].fields, field_tys, len)
+                    {
+                        Ok(new_len) => len = new_len,
+                        Err(err) => return Err(err),
+                    }
+
+                    v += 1;
+                }
+            },
+        }
+
+        i += 1;
+    }
+
+    field_starts[i] = len;
+
+    Ok(IndexedTypeLayoutGraph {
+        graph: TypeLayoutGraph {
+            ty: graph.ty,
+            tys: graph.tys,
+        },
+        root,
+        field_starts,
+        field_tys,
+    })
+}
+
+/// Canonicalise the [`TypeLayoutGraph`] of a type `T` into an
+/// [`IndexedTypeLayoutGraph<'static>`] at compile time.
+///
+/// The type `T` cannot be generic, since the lengths of the index arrays
+/// depend on it.
+///
+/// # Panics
+///
+/// Fails to compile iff [`canonicalise`] returns an error.
+pub macro indexed_type_graph($T:ty) {{
+    const FIELD_STARTS_LEN: usize =
+        $crate::index::field_starts_len(&<$T as $crate::TypeGraphLayout>::TYPE_GRAPH);
+    const FIELD_TYS_LEN: usize =
+        $crate::index::field_tys_len(&<$T as $crate::TypeGraphLayout>::TYPE_GRAPH);
+
+    const INDICES: (usize, [usize; FIELD_STARTS_LEN], [usize; FIELD_TYS_LEN]) =
+        $crate::index::canonicalise_into_arrays(&<$T as $crate::TypeGraphLayout>::TYPE_GRAPH);
+
+    $crate::index::IndexedTypeLayoutGraph {
+        graph: <$T as $crate::TypeGraphLayout>::TYPE_GRAPH,
+        root: INDICES.0,
+        field_starts: &INDICES.1,
+        field_tys: &INDICES.2,
+    }
+}}
+
+#[doc(hidden)]
+#[must_use]
+/// Canonicalise the `graph` into a root index and into `field_starts` and
+/// `field_tys` arrays, which must have the required lengths.
+///
+/// # Panics
+///
+/// This function panics iff [`canonicalise`] returns an error.
+pub const fn canonicalise_into_arrays<const FIELD_STARTS_LEN: usize, const FIELD_TYS_LEN: usize>(
+    graph: &TypeLayoutGraph,
+) -> (usize, [usize; FIELD_STARTS_LEN], [usize; FIELD_TYS_LEN]) {
+    let mut field_starts = [0; FIELD_STARTS_LEN];
+    let mut field_tys = [0; FIELD_TYS_LEN];
+
+    let root = match canonicalise(graph, &mut field_starts, &mut field_tys) {
+        Ok(indexed) => indexed.root,
+        Err(_) => panic!("the type graph cannot be canonicalised"),
+    };
+
+    (root, field_starts, field_tys)
+}
+
+/// Write the type indices of all `fields` of the type `layout` into
+/// `field_tys`, starting at `len`, and return the new length.
+const fn index_fields<'a>(
+    graph: &TypeLayoutGraph<'a>,
+    canonical: bool,
+    layout: &TypeLayoutInfo<'a>,
+    fields: &[Field<'a>],
+    field_tys: &mut [usize],
+    mut len: usize,
+) -> Result<usize, Error<'a>> {
+    let mut i = 0;
+
+    while i < fields.len() {
+        field_tys[len] = match find_unique(graph, fields[i].ty, canonical) {
+            Ok(Some(ty)) => ty,
+            Ok(None) => {
+                return Err(Error::MissingFieldType {
+                    ty: layout.name,
+                    field: fields[i].name,
+                    field_ty: fields[i].ty,
+                })
+            },
+            Err(err) => return Err(err),
+        };
+
+        len += 1;
+        i += 1;
+    }
+
+    Ok(len)
+}
+
+const fn num_fields(layout: &TypeLayoutInfo) -> usize {
+    match layout.structure {
+        TypeStructure::Primitive => 0,
+        TypeStructure::Struct { fields, .. } | TypeStructure::Union { fields, .. } => fields.len(),
+        TypeStructure::Enum { variants, .. } => {
+            let mut len = 0;
+
+            let mut i = 0;
+
+            while i < variants.len() {
+                len += variants[i].fields.len();
+                i += 1;
+            }
+
+            len
+        },
+    }
+}
+
+/// Check whether the `graph` is in canonical order, see
+/// [`TypeLayoutGraph::get`], such that its types can be found with a binary
+/// search.
+///
+/// Since the types after the root are sorted by their names, the graph
+/// contains a duplicate type name iff two neighbouring types or the root and
+/// another type share their name.
+const fn canonical_order<'a>(graph: &TypeLayoutGraph<'a>) -> Result<bool, Error<'a>> {
+    let tys = graph.tys;
+
+    if tys.is_empty() || !bytes_eq(tys[0].name.as_bytes(), graph.ty.as_bytes()) {
+        return Ok(false);
+    }
+
+    let mut i = 2;
+
+    while i < tys.len() {
+        match bytes_cmp(tys[i - 1].name.as_bytes(), tys[i].name.as_bytes()) {
+            Ordering::Less => (),
+            Ordering::Equal => return Err(Error::DuplicateType { ty: tys[i].name }),
+            Ordering::Greater => return Ok(false),
+        }
+
+        i += 1;
+    }
+
+    let mut i = 1;
+
+    while i < tys.len() {
+        if bytes_eq(tys[i].name.as_bytes(), graph.ty.as_bytes()) {
+            return Err(Error::DuplicateType { ty: tys[i].name });
+        }
+
+        i += 1;
+    }
+
+    Ok(true)
+}
+
+/// Find the index of the type with the fully-qualified `name`, which must not
+/// be ambiguous.
+///
+/// A `canonical` graph has already been checked for duplicate type names by
+/// [`canonical_order`].
+const fn find_unique<'a>(
+    graph: &TypeLayoutGraph<'a>,
+    name: &str,
+    canonical: bool,
+) -> Result<Option<usize>, Error<'a>> {
+    if canonical {
+        return Ok(graph.search_type_index(name));
+    }
+
+    let Some(i) = graph.find_type_index(name) else {
+        return Ok(None);
+    };
+
+    let mut j = i + 1;
+
+    while j < graph.tys.len() {
+        if bytes_eq(graph.tys[j].name.as_bytes(), name.as_bytes()) {
+            return Err(Error::DuplicateType {
+                ty: graph.tys[j].name,
+            });
+        }
+
+        j += 1;
+    }
+
+    Ok(Some(i))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        verify::Error, Field, MaybeUninhabited, TypeGraphLayout, TypeLayoutGraph, TypeLayoutInfo,
+        TypeStructure, Variant,
+    };
+
+    use super::{field_starts_len, field_tys_len, indexed_type_graph};
+
+    #[test]
+    fn const_indexed_graph() {
+        const GRAPH: super::IndexedTypeLayoutGraph = indexed_type_graph!(Result<(u8, u16), u32>);
+
+        let graph = <Result<(u8, u16), u32>>::TYPE_GRAPH;
+
+        assert_eq!(GRAPH.root().name, graph.ty);
+
+        for (ty, layout) in graph.tys.iter().enumerate() {
+            let fields: &[Field] = match layout.structure {
+                TypeStructure::Primitive => &[],
+                TypeStructure::Struct { fields, .. } | TypeStructure::Union { fields, .. } => {
+                    fields
+                },
+                TypeStructure::Enum { variants, .. } => {
+                    assert_eq!(
+                        GRAPH.field_tys_of(ty).len(),
+                        variants.iter().map(|v| v.fields.len()).sum::<usize>()
+                    );
+                    continue;
+                },
+            };
+
+            assert_eq!(GRAPH.field_tys_of(ty).len(), fields.len());
+
+            for (i, field) in fields.iter().enumerate() {
+                assert_eq!(GRAPH.layout_of(ty, i).name, field.ty);
+            }
+        }
+
+        let root_fields = GRAPH
+            .field_tys_of(GRAPH.root_index())
+            .iter()
+            .map(|ty| GRAPH.layout(*ty).name)
+            .collect::<alloc::vec::Vec<_>>();
+        assert!(root_fields.contains(&"(u8, u16)"));
+        assert!(root_fields.contains(&"u32"));
+    }
+
+    #[test]
+    fn verify() {
+        const U16: TypeLayoutInfo = TypeLayoutInfo {
+            name: "u16",
+            size: 2,
+            alignment: 2,
+            structure: TypeStructure::Primitive,
+        };
+
+        const ENUM: TypeLayoutInfo = TypeLayoutInfo {
+            name: "Enum",
+            size: 4,
+            alignment: 2,
+            structure: TypeStructure::Enum {
+                repr: "u8",
+                variants: &[
+                    Variant {
+                        name: "A",
+                        discriminant: MaybeUninhabited::Inhabited(crate::discriminant!(0)),
+                        fields: &[Field {
+                            name: "0",
+                            offset: MaybeUninhabited::Inhabited(2),
+                            ty: "u16",
+                        }],
+                    },
+                    Variant {
+                        name: "B",
+                        discriminant: MaybeUninhabited::Inhabited(crate::discriminant!(1)),
+                        fields: &[Field {
+                            name: "0",
+                            offset: MaybeUninhabited::Inhabited(1),
+                            ty: "u16",
+                        }],
+                    },
+                ],
+            },
+        };
+
+        const GRAPH: super::IndexedTypeLayoutGraph = indexed_type_graph!(Result<(u8, u16), u32>);
+
+        assert_eq!(GRAPH.verify(), Ok(()));
+
+        let graph: TypeLayoutGraph = TypeLayoutGraph {
+            ty: "Enum",
+            tys: &[&ENUM, &U16],
+        };
+
+        let mut field_starts = alloc::vec![0; field_starts_len(&graph)];
+        let mut field_tys = alloc::vec![0; field_tys_len(&graph)];
+
+        let indexed = super::canonicalise(&graph, &mut field_starts, &mut field_tys).unwrap();
+
+        assert_eq!(
+            indexed.verify(),
+            Err(Error::MisalignedField {
+                ty: "Enum",
+                field: "0",
+                offset: 1,
+                alignment: 2,
+            })
+        );
+        assert_eq!(indexed.verify(), graph.verify());
+    }
+
+    #[test]
+    fn ambiguous_and_missing_types() {
+        const U8: TypeLayoutInfo = TypeLayoutInfo {
+            name: "u8",
+            size: 1,
+            alignment: 1,
+            structure: TypeStructure::Primitive,
+        };
+
+        const FOO: TypeLayoutInfo = TypeLayoutInfo {
+            name: "Foo",
+            size: 1,
+            alignment: 1,
+            structure: TypeStructure::Struct {
+                repr: "C",
+                fields: &[Field {
+                    name: "a",
+                    offset: MaybeUninhabited::Inhabited(0),
+                    ty: "u8",
+                }],
+            },
+        };
+
+        fn canonicalise(
+            tys: &'static [&'static TypeLayoutInfo<'static>],
+        ) -> Result<usize, Error<'static>> {
+            let graph = TypeLayoutGraph { ty: "Foo", tys };
+
+            let mut field_starts = alloc::vec![0; field_starts_len(&graph)];
+            let mut field_tys = alloc::vec![0; field_tys_len(&graph)];
+
+            super::canonicalise(&graph, &mut field_starts, &mut field_tys)
+                .map(|indexed| indexed.root)
+        }
+
+        assert_eq!(canonicalise(&[&U8, &FOO]), Ok(1));
+        assert_eq!(
+            canonicalise(&[&FOO]),
+            Err(Error::MissingFieldType {
+                ty: "Foo",
+                field: "a",
+                field_ty: "u8",
+            })
+        );
+        assert_eq!(
+            canonicalise(&[&FOO, &U8, &U8]),
+            Err(Error::DuplicateType { ty: "u8" })
+        );
+        assert_eq!(canonicalise(&[&U8]), Err(Error::MissingType { ty: "Foo" }));
+
+        // canonical graphs are checked for any duplicate type names
+        assert_eq!(canonicalise(&[&FOO, &U8]), Ok(0));
+        assert_eq!(
+            canonicalise(&[&FOO, &FOO, &U8]),
+            Err(Error::DuplicateType { ty: "Foo" })
+        );
+        assert_eq!(
+            canonicalise(&[&FOO, &U8, &FOO]),
+            Err(Error::DuplicateType { ty: "Foo" })
+        );
+    }
+}
@@ -126,11 +126,11 @@ r#"TypeLayoutInfo {
 #![feature(cfg_version)]
 #![feature(const_type_name)]
 #![feature(decl_macro)]
-#![feature(freeze)]
 #![feature(offset_of_enum)]
 // required, soon-stabilized features
 #![cfg_attr(not(version("1.83")), feature(const_mut_refs))]
 #![cfg_attr(not(version("1.82")), feature(offset_of_nested))]
+#![cfg_attr(not(version("1.83")), feature(const_slice_from_raw_parts_mut))]
 // docs-specific features
 #![cfg_attr(doc, feature(doc_auto_cfg))]
 // optional feature-gated features
@@ -145,7 +145,10 @@ r#"TypeLayoutInfo {
 #![cfg_attr(feature = "impl-sync-unsafe-cell", feature(sync_unsafe_cell))]
 // required INCOMPLETE features
 #![allow(incomplete_features)]
-#![feature(specialization)]
+#![cfg_attr(
+    not(feature = "typeset-without-specialization"),
+    feature(specialization)
+)]
 // optional feature-gated INCOMPLETE features
 #![cfg_attr(
     feature = "serialize-to-generic-const-array",
@@ -166,12 +169,29 @@ use core::ops::Deref;
 #[cfg(feature = "derive")]
 pub use const_type_layout_derive::TypeLayout;
 
+pub mod abi;
+pub mod analysis;
+mod cow;
+pub mod diff;
 mod discriminant;
+pub mod generate;
+pub mod hash;
 mod impls;
+pub mod index;
 pub mod inhabited;
+pub mod name;
+pub mod primitive;
+pub mod render;
+pub mod repr;
+pub mod section;
 mod ser;
 pub mod typeset;
+pub mod verify;
+pub mod wire;
 
+pub use cow::{
+    OwnedField, OwnedTypeLayoutGraph, OwnedTypeLayoutInfo, OwnedTypeStructure, OwnedVariant,
+};
 pub use discriminant::Discriminant;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -273,7 +293,7 @@ impl<T: Default> Default for MaybeUninhabited<T> {
 ///     const INHABITED: MaybeUninhabited = inhabited::all![u8, u16];
 ///
 ///     const TYPE_LAYOUT: TypeLayoutInfo<'static> = TypeLayoutInfo {
-///         name: ::core::any::type_name::<Self>(),
+///         name: <Self as TypeLayout>::STABLE_NAME,
 ///         size: ::core::mem::size_of::<Self>(),
 ///         alignment: ::core::mem::align_of::<Self>(),
 ///         structure: TypeStructure::Struct {
@@ -282,12 +302,12 @@ impl<T: Default> Default for MaybeUninhabited<T> {
 ///                 Field {
 ///                     name: "a",
 ///                     offset: MaybeUninhabited::new::<u8>(::core::mem::offset_of!(Self, a)),
-///                     ty: ::core::any::type_name::<u8>(),
+///                     ty: <u8 as TypeLayout>::STABLE_NAME,
 ///                 },
 ///                 Field {
 ///                     name: "b",
 ///                     offset: MaybeUninhabited::new::<u16>(::core::mem::offset_of!(Self, b)),
-///                     ty: ::core::any::type_name::<u16>(),
+///                     ty: <u16 as TypeLayout>::STABLE_NAME,
 ///                 },
 ///             ],
 ///         },
@@ -296,13 +316,24 @@ impl<T: Default> Default for MaybeUninhabited<T> {
 /// ```
 ///
 /// Note that if you implement [`TypeLayout`], you should also implement
-/// [`typeset::ComputeTypeSet`] for it.
+/// [`typeset::ComputeTypeSet`] for it. The [`TypeLayoutInfo::name`] should be
+/// the type's [`TypeLayout::STABLE_NAME`], and every [`Field::ty`] should be
+/// the [`TypeLayout::STABLE_NAME`] of the field's type, such that the names in
+/// a [`TypeLayoutGraph`] refer to each other.
 pub unsafe trait TypeLayout: Sized {
     /// Marker for whether the type is
     /// [inhabited](https://doc.rust-lang.org/reference/glossary.html#inhabited) or
     /// [uninhabited](https://doc.rust-lang.org/reference/glossary.html#uninhabited).
     const INHABITED: MaybeUninhabited;
 
+    /// Stable name of the type, which is used as its [`TypeLayoutInfo::name`]
+    /// and as the [`Field::ty`] of fields of this type, see the [`name`]
+    /// module.
+    ///
+    /// Defaults to the [`core::any::type_name`] of the type, which is not
+    /// stable across compiler versions.
+    const STABLE_NAME: &'static str = ::core::any::type_name::<Self>();
+
     /// Shallow layout of the type.
     const TYPE_LAYOUT: TypeLayoutInfo<'static>;
 }
@@ -311,10 +342,17 @@ pub unsafe trait TypeLayout: Sized {
 pub trait TypeGraphLayout: TypeLayout + typeset::ComputeTypeSet {
     /// Shallow layout of the type.
     const TYPE_GRAPH: TypeLayoutGraph<'static>;
+
+    /// Hash of the type's [`TypeGraphLayout::TYPE_GRAPH`] using the
+    /// [`hash::DefaultHasher`], which is only computed once per type.
+    ///
+    /// Use [`hash::HashedTypeGraph`] to hash the graph with another hasher.
+    const TYPE_HASH: u128;
 }
 
 impl<T: TypeLayout + typeset::ComputeTypeSet> TypeGraphLayout for T {
     const TYPE_GRAPH: TypeLayoutGraph<'static> = TypeLayoutGraph::new::<T>();
+    const TYPE_HASH: u128 = hash::DefaultHasher::hash_graph(&Self::TYPE_GRAPH);
 }
 
 #[must_use]
@@ -328,6 +366,9 @@ pub const fn serialised_type_graph_len<T: TypeGraphLayout>() -> usize {
 #[must_use]
 /// Serialise this type's [`TypeLayoutGraph`] into an array of bytes of length
 /// [`serialised_type_graph_len`].
+///
+/// Use [`serialised_type_graph!`] instead to serialise the graph without the
+/// incomplete `generic_const_exprs` feature.
 pub const fn serialise_type_graph<T: TypeGraphLayout>() -> [u8; serialised_type_graph_len::<T>()] {
     let mut bytes = [0_u8; serialised_type_graph_len::<T>()];
 
@@ -336,16 +377,76 @@ pub const fn serialise_type_graph<T: TypeGraphLayout>() -> [u8; serialised_type_
     bytes
 }
 
+/// Serialise the [`TypeLayoutGraph`] of a type `T` into a `&'static [u8]` at
+/// compile time.
+///
+/// Unlike [`serialise_type_graph`], this macro does not require the
+/// incomplete `generic_const_exprs` feature, since it first computes the
+/// [`serialised_type_graph_len`] into a const and then serialises the graph
+/// into an array of that length. The type `T` can therefore not be generic.
+///
+/// ```rust
+/// # #![feature(const_type_name)]
+/// use const_type_layout::{serialised_type_graph, TypeGraphLayout};
+///
+/// type Ty = Option<&'static u32>;
+///
+/// static SERIALISED: &[u8] = serialised_type_graph!(Ty);
+///
+/// let mut bytes = vec![0_u8; Ty::TYPE_GRAPH.serialised_len()];
+/// Ty::TYPE_GRAPH.serialise(&mut bytes);
+///
+/// assert_eq!(SERIALISED, bytes);
+/// ```
+pub macro serialised_type_graph($T:ty) {{
+    const LEN: usize = $crate::serialised_type_graph_len::<$T>();
+    const SERIALISED: &[u8] = &$crate::section::serialise::<$T, LEN>();
+
+    SERIALISED
+}}
+
+/// Check that this type's [`TypeLayoutGraph`] is internally consistent, e.g.
+/// to validate hand-written [`TypeLayout`] and [`typeset::ComputeTypeSet`]
+/// implementations.
+///
+/// The check validates that
+/// - every type's size is a multiple of its alignment, which is a power of two
+/// - every field lies inside its type's size
+/// - every field's offset respects the field type's alignment
+/// - the fields of a struct or enum variant do not overlap
+/// - every [`Field::ty`] has a layout inside [`TypeLayoutGraph::tys`]
+/// - no two types inside [`TypeLayoutGraph::tys`] share the same name
+///
+/// The check can be run at compile time:
+///
+/// ```rust
+/// # #![feature(const_type_name)]
+/// # use const_type_layout::verify_type_layout;
+/// const _: () = assert!(verify_type_layout::<(u8, u16, Option<&u32>)>().is_ok());
+/// ```
+///
+/// # Errors
+///
+/// Returns the first [`verify::Error`] that is found in the graph.
+pub const fn verify_type_layout<T: TypeGraphLayout>() -> Result<(), verify::Error<'static>> {
+    T::TYPE_GRAPH.verify()
+}
+
 #[must_use]
 /// Hash this type's [`TypeLayoutGraph`] using the provided `seed`.
 ///
-/// The hash is produced over the serialised form of the [`TypeLayoutGraph`], as
-/// computed by [`serialise_type_graph`].
+/// The hash is produced over the canonical form of the [`TypeLayoutGraph`],
+/// which is independent of the binary format that is produced by
+/// [`serialise_type_graph`], see [`TypeLayoutGraph::hash`].
+///
+/// Use [`TypeGraphLayout::TYPE_HASH`] instead for a cached and more
+/// collision-resistant 128-bit hash, or the [`hash`] module to select another
+/// hasher.
 pub const fn hash_type_graph<T: TypeGraphLayout>(seed: u64) -> u64 {
     T::TYPE_GRAPH.hash(seed)
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", allow(clippy::unsafe_derive_deserialize))]
 #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
 /// Description of the deep layout of a type.
@@ -362,10 +463,14 @@ pub struct TypeLayoutGraph<
     pub ty: &'a str,
     /// The list of types that make up the complete graph describing the deep
     /// layout of this type.
+    ///
+    /// If the graph is constructed with [`TypeLayoutGraph::new`], the list is
+    /// in canonical order, in which the root type [`Self::ty`] comes first,
+    /// followed by all other types in the order of their names.
     pub tys: G,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Copy, Debug, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
 /// Description of the shallow layout of a type.
 pub struct TypeLayoutInfo<
@@ -386,7 +491,7 @@ pub struct TypeLayoutInfo<
     pub structure: TypeStructure<'a, F, D, V>,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Copy, Debug, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
 /// Description of the shallow structure of a type.
 pub enum TypeStructure<
@@ -425,7 +530,7 @@ pub enum TypeStructure<
     },
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Copy, Debug, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
 /// Description of the shallow layout of a variant
 pub struct Variant<
@@ -444,7 +549,7 @@ pub struct Variant<
     pub fields: F,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Copy, Debug, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
 /// Descriptor of the shallow layout of a field.
 pub struct Field<'a> {
@@ -467,31 +572,165 @@ impl TypeLayoutGraph<'static> {
     pub const fn new<T: TypeLayout + typeset::ComputeTypeSet>() -> Self {
         Self {
             ty: <T as TypeLayout>::TYPE_LAYOUT.name,
-            // SAFETY:
-            // - ComputeSet is a sealed trait and its TYS const is always a HList made of only Cons
-            //   of &'static TypeLayoutInfo and Empty
-            // - Cons is a repr(C) struct with a head followed by a tail, Empty is a zero-sized
-            //   repr(C) struct
-            // - the HList is layout-equivalent to an array of the same length as ComputeSet::LEN
-            // - ComputeSet::TYS provides a static non-dangling reference that we can use to produce
-            //   the data pointer for a slice
-            tys: unsafe {
-                core::slice::from_raw_parts(
-                    core::ptr::from_ref(<typeset::TypeSet<T> as typeset::ComputeSet>::TYS).cast(),
-                    <typeset::TypeSet<T> as typeset::ComputeSet>::LEN,
-                )
-            },
+            tys: typeset::type_set::<T>(),
         }
     }
 }
 
+impl<'a> TypeLayoutGraph<'a> {
+    #[must_use]
+    /// Find the layout of the type with the fully-qualified `name` inside
+    /// [`Self::tys`] using a binary search.
+    ///
+    /// The lookup relies on the canonical order of [`Self::tys`], in which the
+    /// root type [`Self::ty`] comes first, followed by all other types in the
+    /// order of their names. Every [`TypeLayoutGraph`] that is constructed
+    /// with [`TypeLayoutGraph::new`] is in this order.
+    ///
+    /// ```rust
+    /// # #![feature(const_type_name)]
+    /// # use const_type_layout::TypeGraphLayout;
+    /// const U16: Option<&str> = match <(u8, u16, u32)>::TYPE_GRAPH.get("u16") {
+    ///     Some(layout) => Some(layout.name),
+    ///     None => None,
+    /// };
+    ///
+    /// assert_eq!(U16, Some("u16"));
+    /// ```
+    pub const fn get(&self, name: &str) -> Option<&'a TypeLayoutInfo<'a>> {
+        match self.search_type_index(name) {
+            Some(i) => Some(self.tys[i]),
+            None => None,
+        }
+    }
+
+    /// Find the layout of the type with the fully-qualified `name` inside
+    /// [`Self::tys`], which may not be in canonical order, e.g. if the graph
+    /// was written by hand.
+    pub(crate) const fn find_type(&self, name: &str) -> Option<&'a TypeLayoutInfo<'a>> {
+        match self.find_type_index(name) {
+            Some(i) => Some(self.tys[i]),
+            None => None,
+        }
+    }
+
+    /// Find the index of the type with the fully-qualified `name` inside
+    /// [`Self::tys`], which may not be in canonical order.
+    pub(crate) const fn find_type_index(&self, name: &str) -> Option<usize> {
+        if let Some(i) = self.search_type_index(name) {
+            return Some(i);
+        }
+
+        let mut i = 0;
+
+        while i < self.tys.len() {
+            if repr::bytes_eq(self.tys[i].name.as_bytes(), name.as_bytes()) {
+                return Some(i);
+            }
+
+            i += 1;
+        }
+
+        None
+    }
+
+    /// Binary search for the index of the type with the fully-qualified
+    /// `name` inside [`Self::tys`], which must be in canonical order.
+    pub(crate) const fn search_type_index(&self, name: &str) -> Option<usize> {
+        if self.tys.is_empty() {
+            return None;
+        }
+
+        if repr::bytes_eq(self.tys[0].name.as_bytes(), name.as_bytes()) {
+            return Some(0);
+        }
+
+        let mut lo = 1;
+        let mut hi = self.tys.len();
+
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+
+            match repr::bytes_cmp(self.tys[mid].name.as_bytes(), name.as_bytes()) {
+                core::cmp::Ordering::Less => lo = mid + 1,
+                core::cmp::Ordering::Equal => return Some(mid),
+                core::cmp::Ordering::Greater => hi = mid,
+            }
+        }
+
+        None
+    }
+
+    /// Check that this [`TypeLayoutGraph`] is internally consistent.
+    ///
+    /// Use [`verify_type_layout`] instead to check the [`TypeLayoutGraph`] of
+    /// a type `T`, which lists all checks that are performed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`verify::Error`] that is found in the graph.
+    pub const fn verify(&self) -> Result<(), verify::Error<'a>> {
+        verify::graph(self)
+    }
+}
+
 impl TypeLayoutGraph<'_> {
     #[must_use]
     /// Compute the number of bytes that this [`TypeLayoutGraph`] serialises
-    /// into.
+    /// into with [`Self::serialise`].
     pub const fn serialised_len(&self) -> usize {
+        self.versioned_serialised_len(wire::CANONICAL_VERSION)
+    }
+
+    /// Serialise this [`TypeLayoutGraph`] into the mutable byte slice.
+    /// `bytes` must have a length of at least [`Self::serialised_len`].
+    ///
+    /// The graph is serialised in its canonical form, i.e. in the format
+    /// [`wire::CANONICAL_VERSION`] that is described in the [`wire`] module.
+    /// Use [`Self::serialise_compact`] to serialise the graph in the more
+    /// compact format [`wire::COMPACT_VERSION`] instead.
+    ///
+    /// Use [`serialise_type_graph`] instead to serialise the
+    /// [`TypeLayoutGraph`] of a type `T` into a byte array of the
+    /// appropriate length.
+    ///
+    /// # Panics
+    ///
+    /// This method panics iff `bytes` has a length of less than
+    /// [`Self::serialised_len`].
+    pub const fn serialise(&self, bytes: &mut [u8]) {
+        self.versioned_serialise(wire::CANONICAL_VERSION, bytes);
+    }
+
+    #[must_use]
+    /// Compute the number of bytes that this [`TypeLayoutGraph`] serialises
+    /// into with [`Self::serialise_compact`].
+    pub const fn serialised_compact_len(&self) -> usize {
+        self.versioned_serialised_len(wire::COMPACT_VERSION)
+    }
+
+    /// Serialise this [`TypeLayoutGraph`] into the mutable byte slice.
+    /// `bytes` must have a length of at least
+    /// [`Self::serialised_compact_len`].
+    ///
+    /// The graph is serialised in the format [`wire::COMPACT_VERSION`] that
+    /// is described in the [`wire`] module, which deduplicates type names and
+    /// other strings, and is thus smaller than the canonical form that is
+    /// produced by [`Self::serialise`] for large graphs.
+    ///
+    /// # Panics
+    ///
+    /// This method panics iff `bytes` has a length of less than
+    /// [`Self::serialised_compact_len`].
+    pub const fn serialise_compact(&self, bytes: &mut [u8]) {
+        self.versioned_serialise(wire::COMPACT_VERSION, bytes);
+    }
+
+    const fn versioned_serialised_len(&self, version: usize) -> usize {
         let mut counter = ser::Serialiser::counter(0);
-        counter.serialise_type_layout_graph(self);
+        counter.write_bytes(&wire::MAGIC);
+        counter.serialise_usize(version);
+        counter.serialise_versioned_type_layout_graph(version, self);
         let len = counter.cursor();
 
         let mut last_full_len = len;
@@ -511,36 +750,47 @@ impl TypeLayoutGraph<'_> {
         full_len
     }
 
-    /// Serialise this [`TypeLayoutGraph`] into the mutable byte slice.
-    /// `bytes` must have a length of at least [`Self::serialised_len`].
-    ///
-    /// Use [`serialise_type_graph`] instead to serialise the
-    /// [`TypeLayoutGraph`] of a type `T` into a byte array of the
-    /// appropriate length.
-    ///
-    /// # Panics
-    ///
-    /// This method panics iff `bytes` has a length of less than
-    /// [`Self::serialised_len`].
-    pub const fn serialise(&self, bytes: &mut [u8]) {
+    const fn versioned_serialise(&self, version: usize, bytes: &mut [u8]) {
         let mut writer = ser::Serialiser::writer(bytes, 0);
-        writer.serialise_usize(self.serialised_len());
-        writer.serialise_type_layout_graph(self);
+        writer.serialise_header(version, self.versioned_serialised_len(version));
+        writer.serialise_versioned_type_layout_graph(version, self);
     }
 
     #[must_use]
-    /// Hash this [`TypeLayoutGraph`] using the provided `hasher`.
+    /// Hash this [`TypeLayoutGraph`] using the provided `seed`.
+    ///
+    /// The hash is produced over the canonical form of this
+    /// [`TypeLayoutGraph`], which is the binary format
+    /// [`wire::CANONICAL_VERSION`] without its header, as described in the
+    /// [`wire`] module. The hash thus does not depend on the format in which
+    /// the graph is serialised.
     ///
-    /// The hash is produced over the serialised form of this
-    /// [`TypeLayoutGraph`], as computed by [`Self::serialise`].
+    /// The hash uses the 64-bit [`hash::Fnv1a64`] hasher.
+    ///
+    /// This is a breaking change from version 0.3.2, which hashed the
+    /// serialised graph including its length prefix and the crate version, so
+    /// all hashes have changed.
     pub const fn hash(&self, seed: u64) -> u64 {
         let mut hasher = ser::Serialiser::hasher(seed);
 
-        hasher.serialise_usize(self.serialised_len());
         hasher.serialise_type_layout_graph(self);
 
         hasher.hash()
     }
+
+    #[must_use]
+    /// Hash this [`TypeLayoutGraph`] using the 128-bit [`hash::SipHash128`]
+    /// hasher with the key `(k0, k1)`.
+    ///
+    /// Like [`Self::hash`], the hash is produced over the canonical form of
+    /// this [`TypeLayoutGraph`].
+    pub const fn hash128(&self, k0: u64, k1: u64) -> u128 {
+        let mut hasher = ser::Serialiser::sip_hasher(k0, k1);
+
+        hasher.serialise_type_layout_graph(self);
+
+        hasher.hash128()
+    }
 }
 
 impl<
@@ -556,3 +806,116 @@ impl<
         fmt.write_fmt(format_args!("TypeLayoutGraph<{}>({:?})", self.ty, self.tys))
     }
 }
+
+impl<
+        'a,
+        'b,
+        F: Deref<Target = [Field<'a>]>,
+        D: Deref<Target = [u8]>,
+        V: Deref<Target = [Variant<'a, F, D>]>,
+        I: Deref<Target = TypeLayoutInfo<'a, F, D, V>>,
+        G: Deref<Target = [I]>,
+        F2: Deref<Target = [Field<'b>]>,
+        D2: Deref<Target = [u8]>,
+        V2: Deref<Target = [Variant<'b, F2, D2>]>,
+        I2: Deref<Target = TypeLayoutInfo<'b, F2, D2, V2>>,
+        G2: Deref<Target = [I2]>,
+    > PartialEq<TypeLayoutGraph<'b, F2, D2, V2, I2, G2>> for TypeLayoutGraph<'a, F, D, V, I, G>
+{
+    fn eq(&self, other: &TypeLayoutGraph<'b, F2, D2, V2, I2, G2>) -> bool {
+        self.ty == other.ty
+            && self.tys.len() == other.tys.len()
+            && self
+                .tys
+                .iter()
+                .zip(other.tys.iter())
+                .all(|(ty, other)| **ty == **other)
+    }
+}
+
+impl<
+        'a,
+        'b,
+        F: Deref<Target = [Field<'a>]>,
+        D: Deref<Target = [u8]>,
+        V: Deref<Target = [Variant<'a, F, D>]>,
+        F2: Deref<Target = [Field<'b>]>,
+        D2: Deref<Target = [u8]>,
+        V2: Deref<Target = [Variant<'b, F2, D2>]>,
+    > PartialEq<TypeLayoutInfo<'b, F2, D2, V2>> for TypeLayoutInfo<'a, F, D, V>
+{
+    fn eq(&self, other: &TypeLayoutInfo<'b, F2, D2, V2>) -> bool {
+        self.name == other.name
+            && self.size == other.size
+            && self.alignment == other.alignment
+            && self.structure == other.structure
+    }
+}
+
+impl<
+        'a,
+        'b,
+        F: Deref<Target = [Field<'a>]>,
+        D: Deref<Target = [u8]>,
+        V: Deref<Target = [Variant<'a, F, D>]>,
+        F2: Deref<Target = [Field<'b>]>,
+        D2: Deref<Target = [u8]>,
+        V2: Deref<Target = [Variant<'b, F2, D2>]>,
+    > PartialEq<TypeStructure<'b, F2, D2, V2>> for TypeStructure<'a, F, D, V>
+{
+    fn eq(&self, other: &TypeStructure<'b, F2, D2, V2>) -> bool {
+        match (self, other) {
+            (Self::Primitive, TypeStructure::Primitive) => true,
+            (
+                Self::Struct { repr, fields },
+                TypeStructure::Struct {
+                    repr: other_repr,
+                    fields: other_fields,
+                },
+            )
+            | (
+                Self::Union { repr, fields },
+                TypeStructure::Union {
+                    repr: other_repr,
+                    fields: other_fields,
+                },
+            ) => repr == other_repr && **fields == **other_fields,
+            (
+                Self::Enum { repr, variants },
+                TypeStructure::Enum {
+                    repr: other_repr,
+                    variants: other_variants,
+                },
+            ) => repr == other_repr && **variants == **other_variants,
+            _ => false,
+        }
+    }
+}
+
+impl<
+        'a,
+        'b,
+        F: Deref<Target = [Field<'a>]>,
+        D: Deref<Target = [u8]>,
+        F2: Deref<Target = [Field<'b>]>,
+        D2: Deref<Target = [u8]>,
+    > PartialEq<Variant<'b, F2, D2>> for Variant<'a, F, D>
+{
+    fn eq(&self, other: &Variant<'b, F2, D2>) -> bool {
+        let discriminant_eq = match (&self.discriminant, &other.discriminant) {
+            (MaybeUninhabited::Inhabited(discriminant), MaybeUninhabited::Inhabited(other)) => {
+                discriminant == other
+            },
+            (MaybeUninhabited::Uninhabited, MaybeUninhabited::Uninhabited) => true,
+            _ => false,
+        };
+
+        self.name == other.name && discriminant_eq && *self.fields == *other.fields
+    }
+}
+
+impl<'a, 'b> PartialEq<Field<'b>> for Field<'a> {
+    fn eq(&self, other: &Field<'b>) -> bool {
+        self.name == other.name && self.offset == other.offset && self.ty == other.ty
+    }
+}
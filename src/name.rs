@@ -0,0 +1,327 @@
+//! Helper module to compose the stable names of types, see
+//! [`TypeLayout::STABLE_NAME`].
+//!
+//! By default, the name of a type is its [`core::any::type_name`], whose
+//! output is not guaranteed to be stable across compiler versions and which
+//! includes the module path of the type, which changes when the type is
+//! moved. The types from [`core`] instead have fixed stable names, and generic
+//! types compose their stable name from the stable names of their parameters,
+//! e.g. `core::option::Option<u8>`. The fixed names are the type names that
+//! Rust 1.78 produces, which match the names in the DWARF debug information of
+//! current compilers, but they do not change when [`core::any::type_name`]
+//! changes.
+//!
+//! Stable names have no length limit. In the rare case that a composed name
+//! is longer than 16 KiB, the type's [`core::any::type_name`] is used instead.
+//!
+//! Since the stable names are used for both [`TypeLayoutInfo::name`] and
+//! [`Field::ty`], the [`TypeLayoutGraph`]s, their serialised forms, and their
+//! hashes only depend on the stable names, and not on the output of
+//! [`core::any::type_name`], if all types have stable names.
+//!
+//! [`TypeLayout::STABLE_NAME`]: crate::TypeLayout::STABLE_NAME
+//! [`TypeLayoutInfo::name`]: crate::TypeLayoutInfo::name
+//! [`Field::ty`]: crate::Field::ty
+//! [`TypeLayoutGraph`]: crate::TypeLayoutGraph
+#![cfg_attr(
+    feature = "derive",
+    doc = r#"
+
+The derive sets the stable name of a type with the `#[layout(name = "...")]`
+attribute, to which the stable names of the type's type parameters are
+appended:
+
+```rust
+# #![feature(const_type_name)]
+use const_type_layout::{TypeGraphLayout, TypeLayout};
+
+#[derive(TypeLayout)]
+#[layout(name = "acme.net.PacketHeader")]
+#[repr(C)]
+struct PacketHeader<T> {
+    len: u16,
+    payload: Option<T>,
+}
+
+assert_eq!(
+    <PacketHeader<u8>>::STABLE_NAME,
+    "acme.net.PacketHeader<u8>"
+);
+assert_eq!(
+    <PacketHeader<u8>>::TYPE_LAYOUT.name,
+    "acme.net.PacketHeader<u8>"
+);
+assert_eq!(
+    <Option<PacketHeader<u8>>>::STABLE_NAME,
+    "core::option::Option<acme.net.PacketHeader<u8>>"
+);
+assert!(<Option<PacketHeader<u8>>>::TYPE_GRAPH.verify().is_ok());
+```
+"#
+)]
+
+/// Compose a stable name at compile time from its first `part`, followed by
+/// a chain of [`Composed`] method calls, e.g.
+/// `compose!("core::option::Option"; .generic(&[T::STABLE_NAME]))`.
+///
+/// The name is first composed to count its length, and then composed again
+/// into the smallest of several buffer sizes that fits it, such that the
+/// interned name does not waste more than a few bytes for short names. Names
+/// that are longer than the largest buffer fall back to the
+/// [`core::any::type_name`] of `Self`, which has no length limit.
+#[doc(hidden)]
+pub macro compose($part:expr; $($chain:tt)*) {
+    match $crate::name::Composed::<0>::new($part) $($chain)* .len() {
+        0..=16 => $crate::name::Composed::<16>::new($part) $($chain)* .as_str(),
+        0..=64 => $crate::name::Composed::<64>::new($part) $($chain)* .as_str(),
+        0..=256 => $crate::name::Composed::<256>::new($part) $($chain)* .as_str(),
+        0..=1024 => $crate::name::Composed::<1024>::new($part) $($chain)* .as_str(),
+        0..=4096 => $crate::name::Composed::<4096>::new($part) $($chain)* .as_str(),
+        0..=16384 => $crate::name::Composed::<16384>::new($part) $($chain)* .as_str(),
+        _ => ::core::any::type_name::<Self>(),
+    }
+}
+
+#[doc(hidden)]
+#[derive(Clone, Copy)]
+/// Stable name that is composed from several parts at compile time into a
+/// buffer of `N` bytes.
+///
+/// The name keeps track of its full length even if it does not fit into the
+/// buffer, such that a [`Composed<0>`] only counts the length of the name.
+pub struct Composed<const N: usize> {
+    bytes: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> Composed<N> {
+    #[must_use]
+    /// Start composing a stable name with the `part`.
+    pub const fn new(part: &str) -> Self {
+        Self {
+            bytes: [0_u8; N],
+            len: 0,
+        }
+        .str(part)
+    }
+
+    #[must_use]
+    /// Append the `part` to the name.
+    pub const fn str(mut self, part: &str) -> Self {
+        let part = part.as_bytes();
+
+        let mut i = 0;
+
+        while i < part.len() {
+            if self.len < N {
+                self.bytes[self.len] = part[i];
+            }
+
+            self.len += 1;
+            i += 1;
+        }
+
+        self
+    }
+
+    #[must_use]
+    /// Append the `parts` to the name, separated by `", "`.
+    pub const fn join(mut self, parts: &[&str]) -> Self {
+        let mut i = 0;
+
+        while i < parts.len() {
+            if i > 0 {
+                self = self.str(", ");
+            }
+
+            self = self.str(parts[i]);
+
+            i += 1;
+        }
+
+        self
+    }
+
+    #[must_use]
+    /// Append the generic `params` to the name, i.e. `<A, B>`.
+    pub const fn generic(self, params: &[&str]) -> Self {
+        self.str("<").join(params).str(">")
+    }
+
+    #[must_use]
+    /// Append the tuple of the `elements` to the name, i.e. `(A,)` or `(A, B)`.
+    pub const fn tuple(self, elements: &[&str]) -> Self {
+        self.str("(")
+            .join(elements)
+            .str(if elements.len() == 1 { ",)" } else { ")" })
+    }
+
+    #[must_use]
+    /// Append the decimal `value` to the name.
+    pub const fn usize(self, value: usize) -> Self {
+        const DIGITS: [&str; 10] = ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+
+        let mut digits = [0_usize; 20];
+        let mut start = digits.len();
+        let mut rem = value;
+
+        loop {
+            start -= 1;
+            digits[start] = rem % 10;
+            rem /= 10;
+
+            if rem == 0 {
+                break;
+            }
+        }
+
+        let mut name = self;
+
+        while start < digits.len() {
+            name = name.str(DIGITS[digits[start]]);
+            start += 1;
+        }
+
+        name
+    }
+
+    #[must_use]
+    /// Append the `extern "abi" ` qualifier of a function pointer to the name,
+    /// which is omitted for the default `"Rust"` ABI.
+    pub const fn abi(self, abi: &str) -> Self {
+        if crate::repr::bytes_eq(abi.as_bytes(), b"Rust") {
+            self
+        } else {
+            self.str("extern \"").str(abi).str("\" ")
+        }
+    }
+
+    #[must_use]
+    /// Append the ` -> ret` return type of a function pointer to the name,
+    /// which is omitted if `ret` is the unit type.
+    pub const fn ret(self, ret: &str) -> Self {
+        if crate::repr::bytes_eq(ret.as_bytes(), b"()") {
+            self
+        } else {
+            self.str(" -> ").str(ret)
+        }
+    }
+
+    #[must_use]
+    /// The length in bytes of the full name.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    /// Whether the name is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[must_use]
+    /// The composed name.
+    ///
+    /// # Panics
+    ///
+    /// This method panics iff the name exceeds `N` bytes.
+    pub const fn as_str(&self) -> &str {
+        assert!(
+            self.len <= N,
+            "[const-type-layout]: stable type name exceeds its buffer"
+        );
+
+        // SAFETY: the first len bytes of the name have been initialised
+        let bytes = unsafe { core::slice::from_raw_parts(self.bytes.as_ptr(), self.len) };
+
+        // SAFETY: the name was composed from UTF-8 strings
+        unsafe { core::str::from_utf8_unchecked(bytes) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{format, string::String};
+
+    use crate::TypeLayout;
+
+    use super::Composed;
+
+    macro_rules! assert_stable_names {
+        ($(($T:ty, $name:literal)),* $(,)?) => {
+            $(assert_eq!(<$T as TypeLayout>::STABLE_NAME, $name);)*
+        };
+    }
+
+    #[test]
+    fn core_stable_names() {
+        // the stable names must never change, even if the
+        //  `core::any::type_name` of the types changes
+        assert_stable_names!(
+            (u8, "u8"),
+            ((), "()"),
+            ((u8,), "(u8,)"),
+            ((u8, i16, bool), "(u8, i16, bool)"),
+            ([u8; 3], "[u8; 3]"),
+            ([(u8, char); 12], "[(u8, char); 12]"),
+            (&'static u8, "&u8"),
+            (&'static mut [u8; 2], "&mut [u8; 2]"),
+            (*const u8, "*const u8"),
+            (Option<u8>, "core::option::Option<u8>"),
+            (Result<u8, core::convert::Infallible>, "core::result::Result<u8, core::convert::Infallible>"),
+            (core::num::NonZeroU32, "core::num::nonzero::NonZero<u32>"),
+            (core::mem::ManuallyDrop<u8>, "core::mem::manually_drop::ManuallyDrop<u8>"),
+            (core::ops::Range<u16>, "core::ops::range::Range<u16>"),
+            (core::ops::RangeFull, "core::ops::range::RangeFull"),
+            (core::ops::ControlFlow<u8, u16>, "core::ops::control_flow::ControlFlow<u8, u16>"),
+            (core::ptr::NonNull<u8>, "core::ptr::non_null::NonNull<u8>"),
+            (fn(), "fn()"),
+            (fn(u8) -> u16, "fn(u8) -> u16"),
+            (unsafe fn() -> (u8,), "unsafe fn() -> (u8,)"),
+            (extern "C" fn(u8, u16) -> u8, "extern \"C\" fn(u8, u16) -> u8"),
+            (unsafe extern "C" fn(u8, ...), "unsafe extern \"C\" fn(u8, ...)"),
+        );
+    }
+
+    #[test]
+    fn long_stable_names() {
+        type O = Option<
+            core::num::Saturating<
+                core::mem::ManuallyDrop<core::num::Wrapping<core::cmp::Reverse<u8>>>,
+            >,
+        >;
+        type T = (O, O, O, O, O, O, O, O, O, O, O, O);
+        type U = (T, T, T, T, T, T, T, T, T, T, T, T);
+        type V = (U, U);
+
+        let o = "core::option::Option<core::num::saturating::Saturating<core::mem::manually_drop::\
+                 ManuallyDrop<core::num::wrapping::Wrapping<core::cmp::Reverse<u8>>>>>";
+        let t = format!("({})", [o; 12].join(", "));
+
+        assert_eq!(<O as TypeLayout>::STABLE_NAME, o);
+        assert_eq!(<T as TypeLayout>::STABLE_NAME, t);
+        assert!(<T as TypeLayout>::STABLE_NAME.len() > 1024);
+        assert_eq!(
+            <U as TypeLayout>::STABLE_NAME,
+            format!("({})", [t.as_str(); 12].join(", "))
+        );
+
+        // names that are too long to be composed fall back to their type name
+        assert!(String::from(core::any::type_name::<V>()).len() > 16384);
+        assert_eq!(<V as TypeLayout>::STABLE_NAME, core::any::type_name::<V>());
+    }
+
+    #[test]
+    fn compose() {
+        assert_eq!(Composed::<2>::new("a").usize(0).as_str(), "a0");
+        assert_eq!(
+            Composed::<16>::new("b")
+                .generic(&["c", "d"])
+                .usize(1024)
+                .as_str(),
+            "b<c, d>1024"
+        );
+        assert_eq!(Composed::<2>::new("").tuple(&[]).as_str(), "()");
+        assert_eq!(Composed::<0>::new("e").generic(&["f"]).len(), 4);
+    }
+}
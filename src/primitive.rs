@@ -0,0 +1,392 @@
+//! Helper module to classify the [`crate::TypeStructure::Primitive`] types by
+//! their name.
+
+use crate::{repr::bytes_eq, TypeLayoutInfo, TypeStructure};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Kind of a [`crate::TypeStructure::Primitive`] type.
+pub enum Primitive<'a> {
+    /// The unit type `()`.
+    Unit,
+    /// The never type `!`.
+    Never,
+    /// The boolean type `bool`.
+    Bool,
+    /// The character type `char`.
+    Char,
+    /// A signed or unsigned integer type, e.g. `u8` or `isize`.
+    Int {
+        /// Whether the integer is signed.
+        signed: bool,
+    },
+    /// A floating point type, e.g. `f32`.
+    Float,
+    /// A raw pointer type, e.g. `*const T` or `*mut T`.
+    Pointer {
+        /// The fully-qualified name of the pointee type.
+        pointee: &'a str,
+        /// Whether the pointer is `*mut`.
+        mutable: bool,
+    },
+    /// A reference type, e.g. `&T` or `&mut T`.
+    Reference {
+        /// The fully-qualified name of the referenced type.
+        referent: &'a str,
+        /// Whether the reference is `&mut`.
+        mutable: bool,
+    },
+    /// A function pointer type, e.g. `unsafe extern "C" fn(i32) -> bool`.
+    FnPointer {
+        /// The full signature of the function pointer.
+        signature: &'a str,
+    },
+    /// An array type, e.g. `[T; N]`.
+    Array {
+        /// The fully-qualified name of the element type.
+        element: &'a str,
+        /// The number of elements.
+        len: usize,
+    },
+    /// Any other primitive type whose bytes are opaque, e.g.
+    /// [`core::mem::Discriminant<T>`].
+    Opaque,
+}
+
+impl<'a> Primitive<'a> {
+    #[must_use]
+    /// Classify the type described by `layout`, iff it is a
+    /// [`crate::TypeStructure::Primitive`] type.
+    pub const fn of(layout: &TypeLayoutInfo<'a>) -> Option<Self> {
+        match layout.structure {
+            TypeStructure::Primitive => Some(Self::parse(layout.name)),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    /// Classify a primitive type by its fully-qualified `name`, as produced by
+    /// [`core::any::type_name`].
+    pub const fn parse(name: &'a str) -> Self {
+        const SIGNED: [&str; 6] = ["i8", "i16", "i32", "i64", "i128", "isize"];
+        const UNSIGNED: [&str; 6] = ["u8", "u16", "u32", "u64", "u128", "usize"];
+
+        let bytes = name.as_bytes();
+
+        if bytes_eq(bytes, b"()") {
+            return Self::Unit;
+        }
+        if bytes_eq(bytes, b"!") {
+            return Self::Never;
+        }
+        if bytes_eq(bytes, b"bool") {
+            return Self::Bool;
+        }
+        if bytes_eq(bytes, b"char") {
+            return Self::Char;
+        }
+        if bytes_eq(bytes, b"f32") || bytes_eq(bytes, b"f64") {
+            return Self::Float;
+        }
+
+        let mut i = 0;
+        while i < SIGNED.len() {
+            if bytes_eq(bytes, SIGNED[i].as_bytes()) {
+                return Self::Int { signed: true };
+            }
+            if bytes_eq(bytes, UNSIGNED[i].as_bytes()) {
+                return Self::Int { signed: false };
+            }
+            i += 1;
+        }
+
+        if let Some(pointee) = strip_prefix(name, "*const ") {
+            return Self::Pointer {
+                pointee,
+                mutable: false,
+            };
+        }
+        if let Some(pointee) = strip_prefix(name, "*mut ") {
+            return Self::Pointer {
+                pointee,
+                mutable: true,
+            };
+        }
+        if let Some(referent) = strip_prefix(name, "&mut ") {
+            return Self::Reference {
+                referent,
+                mutable: true,
+            };
+        }
+        if let Some(referent) = strip_prefix(name, "&") {
+            return Self::Reference {
+                referent,
+                mutable: false,
+            };
+        }
+
+        if strip_prefix(name, "fn(").is_some()
+            || strip_prefix(name, "unsafe fn(").is_some()
+            || strip_prefix(name, "extern \"").is_some()
+            || strip_prefix(name, "unsafe extern \"").is_some()
+        {
+            return Self::FnPointer { signature: name };
+        }
+
+        if let Some((element, len)) = split_array(name) {
+            return Self::Array { element, len };
+        }
+
+        Self::Opaque
+    }
+
+    #[must_use]
+    /// Returns the fully-qualified name of the type that this pointer,
+    /// reference, or array primitive points to or contains.
+    pub const fn inner(&self) -> Option<&'a str> {
+        match self {
+            Self::Pointer { pointee: inner, .. }
+            | Self::Reference {
+                referent: inner, ..
+            }
+            | Self::Array { element: inner, .. } => Some(inner),
+            _ => None,
+        }
+    }
+}
+
+/// Checks if the type is guaranteed to never be all-zero, e.g. references,
+/// function pointers, [`core::ptr::NonNull`], and [`core::num::NonZeroU8`],
+/// which enables the null-pointer optimisation for `Option`-like enums.
+pub(crate) const fn is_non_nullable(layout: &TypeLayoutInfo) -> bool {
+    match Primitive::of(layout) {
+        Some(Primitive::Reference { .. } | Primitive::FnPointer { .. }) => true,
+        Some(_) => false,
+        None => is_non_null_pointer(layout.name) || is_non_zero_integer(layout.name),
+    }
+}
+
+/// Checks if the type is a [`core::ptr::NonNull`] pointer.
+pub(crate) const fn is_non_null_pointer(name: &str) -> bool {
+    strip_prefix(name, "core::ptr::non_null::NonNull<").is_some()
+}
+
+/// Checks if the type is a non-zero integer, e.g. [`core::num::NonZeroU8`].
+pub(crate) const fn is_non_zero_integer(name: &str) -> bool {
+    strip_prefix(name, "core::num::nonzero::NonZero").is_some()
+}
+
+pub(crate) const fn strip_prefix<'a>(name: &'a str, prefix: &str) -> Option<&'a str> {
+    if name.len() < prefix.len() {
+        return None;
+    }
+
+    let (head, tail) = name.as_bytes().split_at(prefix.len());
+
+    if !bytes_eq(head, prefix.as_bytes()) {
+        return None;
+    }
+
+    match core::str::from_utf8(tail) {
+        Ok(tail) => Some(tail),
+        Err(_) => None,
+    }
+}
+
+/// Splits an array type name `[T; N]` into `T` and `N`.
+const fn split_array(name: &str) -> Option<(&str, usize)> {
+    let bytes = name.as_bytes();
+
+    let Some((b'[', inner)) = bytes.split_first() else {
+        return None;
+    };
+    let Some((b']', inner)) = inner.split_last() else {
+        return None;
+    };
+
+    // the length is a plain number, so the last `;` must separate it from the
+    //  element type
+    let mut separator = inner.len();
+    while separator > 0 {
+        separator -= 1;
+
+        if inner[separator] == b';' {
+            break;
+        }
+    }
+
+    if inner.is_empty() || inner[separator] != b';' {
+        return None;
+    }
+
+    let (element, len) = inner.split_at(separator);
+    let Some((_semicolon, len)) = len.split_first() else {
+        return None;
+    };
+    let Some((b' ', len)) = len.split_first() else {
+        return None;
+    };
+
+    if len.is_empty() {
+        return None;
+    }
+
+    let mut value = 0_usize;
+    let mut i = 0;
+
+    while i < len.len() {
+        if !len[i].is_ascii_digit() {
+            return None;
+        }
+
+        value = match value.checked_mul(10) {
+            Some(value) => match value.checked_add((len[i] - b'0') as usize) {
+                Some(value) => value,
+                None => return None,
+            },
+            None => return None,
+        };
+
+        i += 1;
+    }
+
+    match core::str::from_utf8(element) {
+        Ok(element) => Some((element, value)),
+        Err(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{TypeLayout, TypeLayoutInfo, TypeStructure};
+
+    use super::{is_non_nullable, Primitive};
+
+    #[test]
+    fn parse() {
+        assert_eq!(Primitive::parse("()"), Primitive::Unit);
+        assert_eq!(Primitive::parse("!"), Primitive::Never);
+        assert_eq!(Primitive::parse("bool"), Primitive::Bool);
+        assert_eq!(Primitive::parse("char"), Primitive::Char);
+        assert_eq!(Primitive::parse("f64"), Primitive::Float);
+        assert_eq!(Primitive::parse("i128"), Primitive::Int { signed: true });
+        assert_eq!(Primitive::parse("usize"), Primitive::Int { signed: false });
+        assert_eq!(
+            Primitive::parse("*const u8"),
+            Primitive::Pointer {
+                pointee: "u8",
+                mutable: false,
+            }
+        );
+        assert_eq!(
+            Primitive::parse("*mut *const u8"),
+            Primitive::Pointer {
+                pointee: "*const u8",
+                mutable: true,
+            }
+        );
+        assert_eq!(
+            Primitive::parse("&mut [u8]"),
+            Primitive::Reference {
+                referent: "[u8]",
+                mutable: true,
+            }
+        );
+        assert_eq!(
+            Primitive::parse("&&str"),
+            Primitive::Reference {
+                referent: "&str",
+                mutable: false,
+            }
+        );
+        assert_eq!(
+            Primitive::parse("unsafe extern \"C\" fn(i32) -> bool"),
+            Primitive::FnPointer {
+                signature: "unsafe extern \"C\" fn(i32) -> bool",
+            }
+        );
+        assert_eq!(
+            Primitive::parse("fn()"),
+            Primitive::FnPointer { signature: "fn()" }
+        );
+        assert_eq!(
+            Primitive::parse("core::mem::Discriminant<u8>"),
+            Primitive::Opaque
+        );
+        assert_eq!(Primitive::parse("u8x"), Primitive::Opaque);
+    }
+
+    #[test]
+    fn parse_array() {
+        assert_eq!(
+            Primitive::parse("[u8; 4]"),
+            Primitive::Array {
+                element: "u8",
+                len: 4,
+            }
+        );
+        assert_eq!(
+            Primitive::parse("[[u8; 2]; 3]"),
+            Primitive::Array {
+                element: "[u8; 2]",
+                len: 3,
+            }
+        );
+        assert_eq!(
+            Primitive::parse("[(); 0]"),
+            Primitive::Array {
+                element: "()",
+                len: 0,
+            }
+        );
+        assert_eq!(
+            Primitive::parse(&alloc::format!("[u8; {}]", usize::MAX)),
+            Primitive::Array {
+                element: "u8",
+                len: usize::MAX,
+            }
+        );
+        assert_eq!(
+            Primitive::parse("[u8; 99999999999999999999999999]"),
+            Primitive::Opaque
+        );
+        assert_eq!(Primitive::parse("[u8]"), Primitive::Opaque);
+        assert_eq!(Primitive::parse("[u8; ]"), Primitive::Opaque);
+        assert_eq!(Primitive::parse("[u8;4]"), Primitive::Opaque);
+        assert_eq!(Primitive::parse("[u8; N]"), Primitive::Opaque);
+        assert_eq!(Primitive::parse("[]"), Primitive::Opaque);
+    }
+
+    #[test]
+    fn of() {
+        assert_eq!(
+            Primitive::of(&<u8 as TypeLayout>::TYPE_LAYOUT),
+            Some(Primitive::Int { signed: false })
+        );
+        assert_eq!(Primitive::of(&<(u8,) as TypeLayout>::TYPE_LAYOUT), None);
+        assert_eq!(Primitive::parse("[u8; 4]").inner(), Some("u8"));
+        assert_eq!(Primitive::parse("u8").inner(), None);
+    }
+
+    #[test]
+    fn non_nullable() {
+        assert!(is_non_nullable(&<&u8 as TypeLayout>::TYPE_LAYOUT));
+        assert!(is_non_nullable(&<fn() as TypeLayout>::TYPE_LAYOUT));
+        assert!(is_non_nullable(
+            &<core::ptr::NonNull<u8> as TypeLayout>::TYPE_LAYOUT
+        ));
+        assert!(is_non_nullable(
+            &<core::num::NonZeroU32 as TypeLayout>::TYPE_LAYOUT
+        ));
+        assert!(!is_non_nullable(&<*const u8 as TypeLayout>::TYPE_LAYOUT));
+        assert!(!is_non_nullable(&<u32 as TypeLayout>::TYPE_LAYOUT));
+        assert!(!is_non_nullable(&TypeLayoutInfo {
+            name: "NonNull",
+            size: 8,
+            alignment: 8,
+            structure: TypeStructure::Struct {
+                repr: "",
+                fields: &[],
+            },
+        }));
+    }
+}
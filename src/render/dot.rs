@@ -0,0 +1,477 @@
+//! Helper module to export the type dependency graph inside a
+//! [`TypeLayoutGraph`] in the [Graphviz](https://graphviz.org) DOT language.
+
+use alloc::{collections::VecDeque, vec, vec::Vec};
+use core::fmt;
+
+use crate::{primitive::Primitive, Field, MaybeUninhabited, TypeLayoutGraph, TypeStructure};
+
+#[derive(Clone, Copy)]
+/// Formatter that renders the types inside a [`TypeLayoutGraph`] as a
+/// Graphviz DOT digraph.
+///
+/// Every type is rendered as a record node that lists its size, alignment,
+/// and its fields with their offsets and sizes. Enum variants are listed with
+/// their discriminants. Edges lead from every field to the node of its type,
+/// where
+/// - solid edges show by-value containment, including array elements
+/// - dashed edges show the indirection through a pointer or reference
+/// - bold red edges, which are labelled `recursive`, close a cycle back to a
+///   type that is already on the path from the root, e.g. inside a linked list
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(const_type_name)]
+/// use const_type_layout::{render::dot::Digraph, TypeGraphLayout};
+///
+/// let graph = <Option<&'static core::ops::Range<u8>>>::TYPE_GRAPH;
+///
+/// let dot = Digraph::new(&graph).collapse_primitives(true).to_string();
+///
+/// assert!(dot.starts_with("digraph {\n"));
+/// assert!(dot.contains(" [style=dashed];"));
+/// ```
+pub struct Digraph<'a, 'b> {
+    graph: &'b TypeLayoutGraph<'a>,
+    collapse_primitives: bool,
+    max_depth: Option<usize>,
+}
+
+impl<'a, 'b> Digraph<'a, 'b> {
+    #[must_use]
+    /// Construct a formatter that renders all types inside the `graph`.
+    pub const fn new(graph: &'b TypeLayoutGraph<'a>) -> Self {
+        Self {
+            graph,
+            collapse_primitives: false,
+            max_depth: None,
+        }
+    }
+
+    #[must_use]
+    /// Do not render primitive types as separate nodes, but instead connect
+    /// fields directly to the struct, union, or enum types that they contain
+    /// or point to, e.g. through an array or reference.
+    pub const fn collapse_primitives(mut self, collapse: bool) -> Self {
+        self.collapse_primitives = collapse;
+        self
+    }
+
+    #[must_use]
+    /// Only render types that are reachable from the graph's root type along
+    /// at most `max_depth` edges.
+    pub const fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+}
+
+impl fmt::Display for Digraph<'_, '_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let edges = self.edges();
+        let starts = self.edge_starts(&edges);
+        let included = self.included(&edges, &starts);
+        let recursive = self.recursive(&edges, &starts, &included);
+
+        fmt.write_str("digraph {\n")?;
+        fmt.write_str("    node [shape=record];\n")?;
+
+        for (node, layout) in self.graph.tys.iter().enumerate() {
+            if !included[node] {
+                continue;
+            }
+
+            fmt.write_fmt(format_args!("    n{node} [label=\"{{"))?;
+            fmt_escaped(fmt, layout.name)?;
+            fmt.write_fmt(format_args!(
+                "|size: {}, align: {}",
+                layout.size, layout.alignment
+            ))?;
+
+            match &layout.structure {
+                TypeStructure::Primitive => (),
+                TypeStructure::Struct { fields, .. } | TypeStructure::Union { fields, .. } => {
+                    for (i, field) in fields.iter().enumerate() {
+                        self.fmt_field(fmt, field, 0, i)?;
+                    }
+                },
+                TypeStructure::Enum { variants, .. } => {
+                    for (v, variant) in variants.iter().enumerate() {
+                        fmt.write_str("|")?;
+                        fmt_escaped(fmt, variant.name)?;
+
+                        match &variant.discriminant {
+                            MaybeUninhabited::Inhabited(discriminant) => fmt.write_fmt(
+                                format_args!(" = {}", discriminant.to_wrapping_i128()),
+                            )?,
+                            MaybeUninhabited::Uninhabited => fmt.write_str(" (uninhabited)")?,
+                        }
+
+                        for (i, field) in variant.fields.iter().enumerate() {
+                            self.fmt_field(fmt, field, v + 1, i)?;
+                        }
+                    }
+                },
+            }
+
+            fmt.write_str("}\"];\n")?;
+        }
+
+        for (edge, is_recursive) in edges.iter().zip(recursive) {
+            if !included[edge.from] || !included[edge.to] {
+                continue;
+            }
+
+            fmt.write_fmt(format_args!("    n{}", edge.from))?;
+
+            if let Some((variant, field)) = edge.field {
+                fmt.write_fmt(format_args!(":f{variant}_{field}"))?;
+            }
+
+            fmt.write_fmt(format_args!(" -> n{}", edge.to))?;
+
+            match (edge.indirect, is_recursive) {
+                (false, false) => (),
+                (true, false) => fmt.write_str(" [style=dashed]")?,
+                (false, true) => {
+                    fmt.write_str(" [color=red, style=bold, label=\"recursive\"]")?;
+                },
+                (true, true) => {
+                    fmt.write_str(" [color=red, style=\"bold,dashed\", label=\"recursive\"]")?;
+                },
+            }
+
+            fmt.write_str(";\n")?;
+        }
+
+        fmt.write_str("}\n")
+    }
+}
+
+impl fmt::Debug for Digraph<'_, '_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, fmt)
+    }
+}
+
+/// Edge from a type, or one of its fields, to another type.
+struct Edge {
+    from: usize,
+    /// The field's variant index plus one, or zero, and its index.
+    field: Option<(usize, usize)>,
+    to: usize,
+    indirect: bool,
+}
+
+impl<'a> Digraph<'a, '_> {
+    fn fmt_field(
+        &self,
+        fmt: &mut fmt::Formatter,
+        field: &Field<'a>,
+        variant: usize,
+        index: usize,
+    ) -> fmt::Result {
+        fmt.write_fmt(format_args!("|<f{variant}_{index}> "))?;
+
+        match field.offset {
+            MaybeUninhabited::Inhabited(offset) => fmt.write_fmt(format_args!("+{offset} "))?,
+            MaybeUninhabited::Uninhabited => fmt.write_str("! ")?,
+        }
+
+        fmt_escaped(fmt, field.name)?;
+        fmt.write_str(": ")?;
+        fmt_escaped(fmt, field.ty)?;
+
+        self.graph.find_type(field.ty).map_or(Ok(()), |layout| {
+            fmt.write_fmt(format_args!(" (size: {})", layout.size))
+        })
+    }
+
+    fn index(&self, ty: &str) -> Option<usize> {
+        self.graph.tys.iter().position(|layout| layout.name == ty)
+    }
+
+    const fn is_primitive(&self, node: usize) -> bool {
+        matches!(self.graph.tys[node].structure, TypeStructure::Primitive)
+    }
+
+    /// Resolves the target of an edge to the type `ty`, which skips over
+    /// primitive types if they are collapsed.
+    fn target(&self, ty: &'a str, mut indirect: bool) -> Option<(usize, bool)> {
+        let mut ty = ty;
+
+        // bound the number of steps to guard against malformed graphs
+        for _ in 0..=self.graph.tys.len() {
+            let node = self.index(ty)?;
+
+            if !self.collapse_primitives || !self.is_primitive(node) {
+                return Some((node, indirect));
+            }
+
+            let (inner, inner_indirect) = inner(ty)?;
+            ty = inner;
+            indirect |= inner_indirect;
+        }
+
+        None
+    }
+
+    fn edges(&self) -> Vec<Edge> {
+        let mut edges = Vec::new();
+
+        for (from, layout) in self.graph.tys.iter().enumerate() {
+            let mut push_field = |variant: usize, index: usize, field: &Field<'a>| {
+                if let Some((to, indirect)) = self.target(field.ty, false) {
+                    edges.push(Edge {
+                        from,
+                        field: Some((variant, index)),
+                        to,
+                        indirect,
+                    });
+                }
+            };
+
+            match &layout.structure {
+                TypeStructure::Primitive => {
+                    if self.collapse_primitives {
+                        continue;
+                    }
+
+                    if let Some((inner, indirect)) = inner(layout.name) {
+                        if let Some((to, indirect)) = self.target(inner, indirect) {
+                            edges.push(Edge {
+                                from,
+                                field: None,
+                                to,
+                                indirect,
+                            });
+                        }
+                    }
+                },
+                TypeStructure::Struct { fields, .. } | TypeStructure::Union { fields, .. } => {
+                    for (i, field) in fields.iter().enumerate() {
+                        push_field(0, i, field);
+                    }
+                },
+                TypeStructure::Enum { variants, .. } => {
+                    for (v, variant) in variants.iter().enumerate() {
+                        for (i, field) in variant.fields.iter().enumerate() {
+                            push_field(v + 1, i, field);
+                        }
+                    }
+                },
+            }
+        }
+
+        edges
+    }
+
+    /// Returns where the edges from each node start in the `edges`, which are
+    /// grouped by the node they start from, such that the edges from `node`
+    /// are `edges[starts[node]..starts[node + 1]]`.
+    fn edge_starts(&self, edges: &[Edge]) -> Vec<usize> {
+        debug_assert!(edges.windows(2).all(|pair| pair[0].from <= pair[1].from));
+
+        let mut starts = vec![0; self.graph.tys.len() + 1];
+
+        for edge in edges {
+            starts[edge.from + 1] += 1;
+        }
+
+        for node in 0..self.graph.tys.len() {
+            starts[node + 1] += starts[node];
+        }
+
+        starts
+    }
+
+    /// Returns which nodes are rendered, i.e. which are reachable from the
+    /// root within the maximum depth and are not collapsed.
+    fn included(&self, edges: &[Edge], starts: &[usize]) -> Vec<bool> {
+        let mut included = vec![false; self.graph.tys.len()];
+
+        let Some(root) = self.index(self.graph.ty) else {
+            return included;
+        };
+
+        let mut queue = VecDeque::from([(root, 0_usize)]);
+
+        while let Some((node, depth)) = queue.pop_front() {
+            if included[node] {
+                continue;
+            }
+
+            included[node] = true;
+
+            if self.max_depth.map_or(false, |max_depth| depth >= max_depth) {
+                continue;
+            }
+
+            for edge in &edges[starts[node]..starts[node + 1]] {
+                queue.push_back((edge.to, depth + 1));
+            }
+        }
+
+        // the root is always rendered, even if it is a collapsed primitive
+        if self.collapse_primitives {
+            for (node, included) in included.iter_mut().enumerate() {
+                *included &= node == root || !self.is_primitive(node);
+            }
+        }
+
+        included
+    }
+
+    /// Returns which edges close a cycle, i.e. lead back to a node that is on
+    /// the depth-first search path from the root.
+    fn recursive(&self, edges: &[Edge], starts: &[usize], included: &[bool]) -> Vec<bool> {
+        let mut recursive = vec![false; edges.len()];
+
+        let Some(root) = self.index(self.graph.ty) else {
+            return recursive;
+        };
+
+        let mut visited = vec![false; self.graph.tys.len()];
+        let mut on_path = vec![false; self.graph.tys.len()];
+
+        // stack of the nodes on the path and the index of their next edge
+        let mut stack = vec![(root, starts[root])];
+        visited[root] = true;
+        on_path[root] = true;
+
+        while let Some((node, next)) = stack.last_mut() {
+            let node = *node;
+
+            let Some(i) = (*next..starts[node + 1]).find(|&i| included[edges[i].to]) else {
+                on_path[node] = false;
+                stack.pop();
+                continue;
+            };
+
+            *next = i + 1;
+
+            let edge = &edges[i];
+
+            if on_path[edge.to] {
+                recursive[i] = true;
+            } else if !visited[edge.to] {
+                visited[edge.to] = true;
+                on_path[edge.to] = true;
+                stack.push((edge.to, starts[edge.to]));
+            }
+        }
+
+        recursive
+    }
+}
+
+/// Returns the type that a value of the primitive type `ty` contains by value
+/// or links to, and whether it is linked through an indirection.
+const fn inner(ty: &str) -> Option<(&str, bool)> {
+    match Primitive::parse(ty) {
+        Primitive::Pointer { pointee, .. } => Some((pointee, true)),
+        Primitive::Reference { referent, .. } => Some((referent, true)),
+        Primitive::Array { element, .. } => Some((element, false)),
+        _ => None,
+    }
+}
+
+/// Escapes the characters that have a special meaning inside a record label.
+fn fmt_escaped(fmt: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    for c in s.chars() {
+        if matches!(c, '{' | '}' | '|' | '<' | '>' | '"' | '\\') {
+            fmt.write_str("\\")?;
+        }
+
+        fmt.write_fmt(format_args!("{c}"))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use crate::{Field, MaybeUninhabited, TypeLayoutGraph, TypeLayoutInfo, TypeStructure};
+
+    use super::Digraph;
+
+    const LIST: TypeLayoutGraph<'static> = TypeLayoutGraph {
+        ty: "List",
+        tys: &[
+            &TypeLayoutInfo {
+                name: "List",
+                size: 16,
+                alignment: 8,
+                structure: TypeStructure::Struct {
+                    repr: "C",
+                    fields: &[
+                        Field {
+                            name: "value",
+                            offset: MaybeUninhabited::Inhabited(0),
+                            ty: "u64",
+                        },
+                        Field {
+                            name: "next",
+                            offset: MaybeUninhabited::Inhabited(8),
+                            ty: "*const List",
+                        },
+                    ],
+                },
+            },
+            &TypeLayoutInfo {
+                name: "u64",
+                size: 8,
+                alignment: 8,
+                structure: TypeStructure::Primitive,
+            },
+            &TypeLayoutInfo {
+                name: "*const List",
+                size: 8,
+                alignment: 8,
+                structure: TypeStructure::Primitive,
+            },
+        ],
+    };
+
+    #[test]
+    fn recursive() {
+        assert_eq!(
+            Digraph::new(&LIST).to_string(),
+            "digraph {
+    node [shape=record];
+    n0 [label=\"{List|size: 16, align: 8|<f0_0> +0 value: u64 (size: 8)|<f0_1> +8 next: *const \
+             List (size: 8)}\"];
+    n1 [label=\"{u64|size: 8, align: 8}\"];
+    n2 [label=\"{*const List|size: 8, align: 8}\"];
+    n0:f0_0 -> n1;
+    n0:f0_1 -> n2;
+    n2 -> n0 [color=red, style=\"bold,dashed\", label=\"recursive\"];
+}
+"
+        );
+
+        assert_eq!(
+            Digraph::new(&LIST).collapse_primitives(true).to_string(),
+            "digraph {
+    node [shape=record];
+    n0 [label=\"{List|size: 16, align: 8|<f0_0> +0 value: u64 (size: 8)|<f0_1> +8 next: *const \
+             List (size: 8)}\"];
+    n0:f0_1 -> n0 [color=red, style=\"bold,dashed\", label=\"recursive\"];
+}
+"
+        );
+
+        assert_eq!(
+            Digraph::new(&LIST).with_max_depth(0).to_string(),
+            "digraph {
+    node [shape=record];
+    n0 [label=\"{List|size: 16, align: 8|<f0_0> +0 value: u64 (size: 8)|<f0_1> +8 next: *const \
+             List (size: 8)}\"];
+}
+"
+        );
+    }
+}
@@ -0,0 +1,8 @@
+//! Helper module to render [`crate::TypeLayoutGraph`]s, and the values whose
+//! layout they describe, in a human-readable form.
+
+pub mod dot;
+pub mod pahole;
+pub mod raw;
+pub mod svg;
+pub mod text;
@@ -0,0 +1,478 @@
+//! Helper module to render the layouts inside a [`TypeLayoutGraph`] as
+//! [pahole](https://linux.die.net/man/1/pahole)-style tables, which show the
+//! padding holes between fields.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{
+    analysis::reorder::reorder,
+    repr::{EnumEncoding, Repr},
+    Field, MaybeUninhabited, TypeLayoutGraph, TypeLayoutInfo, TypeStructure, Variant,
+};
+
+#[derive(Clone, Copy)]
+/// Formatter that renders the layouts of the types inside a
+/// [`TypeLayoutGraph`] as pahole-style tables.
+///
+/// Every struct, union, and enum is rendered as a block, in which
+/// - every field is shown with its offset, size, alignment, name, and type,
+///   ordered by its offset
+/// - every padding hole between fields, and the tail padding, is shown as a `/*
+///   XXX N bytes hole */` line
+/// - every enum variant is shown as a nested block with its discriminant, where
+///   the discriminant's tag is shown as a `<discriminant>` field if its
+///   position is known
+/// - a summary shows the total size, the number of holes, the number of wasted
+///   bytes, and the field that drives the type's alignment
+/// - if reordering a struct's fields by decreasing alignment would make it
+///   smaller, the reordered fields and the savings are shown
+///
+/// Primitive types are not rendered.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(const_type_name)]
+/// use const_type_layout::{render::pahole::Report, TypeGraphLayout};
+///
+/// let graph = <(u8, u16, u8)>::TYPE_GRAPH;
+///
+/// assert_eq!(
+///     Report::graph(&graph).to_string(),
+///     "struct (u8, u16, u8) {
+///     /* offset  size align */
+///     /*      0     2     2 */ 1: u16,
+///     /*      2     1     1 */ 0: u8,
+///     /*      3     1     1 */ 2: u8,
+///
+///     /* size: 4, align: 2, members: 3, sum members: 4 */
+///     /* holes: 0, sum holes: 0, padding: 0, wasted: 0 */
+///     /* alignment driven by `1: u16` */
+/// };
+/// "
+/// );
+/// ```
+pub struct Report<'a, 'b> {
+    graph: &'b TypeLayoutGraph<'a>,
+    layout: Option<&'b TypeLayoutInfo<'a>>,
+}
+
+impl<'a, 'b> Report<'a, 'b> {
+    #[must_use]
+    /// Construct a formatter that renders the layouts of all struct, union,
+    /// and enum types inside the `graph`.
+    pub const fn graph(graph: &'b TypeLayoutGraph<'a>) -> Self {
+        Self {
+            graph,
+            layout: None,
+        }
+    }
+
+    #[must_use]
+    /// Construct a formatter that only renders the `layout`, whose fields'
+    /// types must be included in the `graph`.
+    pub const fn layout(graph: &'b TypeLayoutGraph<'a>, layout: &'b TypeLayoutInfo<'a>) -> Self {
+        Self {
+            graph,
+            layout: Some(layout),
+        }
+    }
+}
+
+impl fmt::Display for Report<'_, '_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(layout) = self.layout {
+            return self.fmt_layout(fmt, layout);
+        }
+
+        let mut first = true;
+
+        for layout in self.graph.tys {
+            if matches!(layout.structure, TypeStructure::Primitive) {
+                continue;
+            }
+
+            if !first {
+                fmt.write_str("\n")?;
+            }
+            first = false;
+
+            self.fmt_layout(fmt, layout)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Report<'_, '_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, fmt)
+    }
+}
+
+/// Statistics about the fields of a struct, union, or enum.
+#[derive(Default)]
+struct Summary<'f, 'a> {
+    members: usize,
+    sum_members: usize,
+    holes: usize,
+    sum_holes: usize,
+    padding: usize,
+    driver: Option<&'f Field<'a>>,
+}
+
+impl<'a> Report<'a, '_> {
+    fn fmt_layout(&self, fmt: &mut fmt::Formatter, layout: &TypeLayoutInfo<'a>) -> fmt::Result {
+        let (kind, repr) = match layout.structure {
+            TypeStructure::Primitive => ("primitive", ""),
+            TypeStructure::Struct { repr, .. } => ("struct", repr),
+            TypeStructure::Union { repr, .. } => ("union", repr),
+            TypeStructure::Enum { repr, .. } => ("enum", repr),
+        };
+
+        if !repr.is_empty() {
+            fmt.write_fmt(format_args!("#[repr({repr})]\n"))?;
+        }
+
+        fmt.write_fmt(format_args!("{kind} {} {{\n", layout.name))?;
+        fmt.write_str("    /* offset  size align */\n")?;
+
+        let mut summary = Summary::default();
+
+        match &layout.structure {
+            TypeStructure::Primitive => (),
+            TypeStructure::Struct { fields, .. } => {
+                let end = self.fmt_fields(fmt, fields, 0, "    ", &mut summary)?;
+                summary.padding = fmt_tail(fmt, layout, end, "    ")?;
+            },
+            TypeStructure::Union { fields, .. } => {
+                let mut end = 0;
+
+                for field in *fields {
+                    end = end.max(self.fmt_field(fmt, field, "    ", &mut summary)?);
+                }
+
+                summary.padding = fmt_tail(fmt, layout, end, "    ")?;
+            },
+            TypeStructure::Enum { repr, variants } => {
+                self.fmt_variants(fmt, layout, Repr::parse(repr), variants, &mut summary)?;
+            },
+        }
+
+        fmt.write_fmt(format_args!(
+            "\n    /* size: {}, align: {}, members: {}, sum members: {} */\n",
+            layout.size, layout.alignment, summary.members, summary.sum_members,
+        ))?;
+        fmt.write_fmt(format_args!(
+            "    /* holes: {}, sum holes: {}, padding: {}, wasted: {} */\n",
+            summary.holes,
+            summary.sum_holes,
+            summary.padding,
+            summary.sum_holes + summary.padding,
+        ))?;
+
+        // the representation, e.g. `#[repr(align(N))]`, may raise the alignment
+        let driver = summary.driver.filter(|driver| {
+            self.graph
+                .find_type(driver.ty)
+                .map_or(false, |driver| driver.alignment == layout.alignment)
+        });
+
+        match driver {
+            Some(field) => fmt.write_fmt(format_args!(
+                "    /* alignment driven by `{}: {}` */\n",
+                field.name, field.ty
+            ))?,
+            None if layout.alignment > 1 => {
+                fmt.write_str("    /* alignment driven by the representation */\n")?;
+            },
+            None => (),
+        }
+
+        if let Some(reordering) = reorder(self.graph, layout) {
+            if reordering.savings() > 0 {
+                fmt.write_str("    /* reordering as `")?;
+
+                for (i, field) in reordering.fields.iter().enumerate() {
+                    if i > 0 {
+                        fmt.write_str(", ")?;
+                    }
+
+                    fmt.write_str(field.name)?;
+                }
+
+                fmt.write_fmt(format_args!(
+                    "` saves {} bytes, size: {} */\n",
+                    reordering.savings(),
+                    reordering.reordered_size
+                ))?;
+            }
+        }
+
+        fmt.write_str("};\n")
+    }
+
+    fn fmt_variants(
+        &self,
+        fmt: &mut fmt::Formatter,
+        layout: &TypeLayoutInfo<'a>,
+        repr: Repr,
+        variants: &'a [Variant<'a>],
+        summary: &mut Summary<'a, 'a>,
+    ) -> fmt::Result {
+        let (tag, niche) = match EnumEncoding::of(self.graph, layout, repr, variants) {
+            EnumEncoding::Tagged { size } => (Some(size), None),
+            // the field-less variant is encoded in the other variant's niche
+            EnumEncoding::NullPointer { none, .. } => (None, Some(none)),
+            _ => (None, None),
+        };
+
+        // only the tail padding that is shared by all inhabited variants is
+        //  wasted
+        let mut padding = None;
+
+        for (i, variant) in variants.iter().enumerate() {
+            fmt.write_str("    ")?;
+            fmt.write_str(variant.name)?;
+
+            match &variant.discriminant {
+                MaybeUninhabited::Inhabited(discriminant) => {
+                    fmt.write_fmt(format_args!(" = {} {{\n", discriminant.to_wrapping_i128()))?;
+                },
+                MaybeUninhabited::Uninhabited => fmt.write_str(" /* uninhabited */ {\n")?,
+            }
+
+            let start = match tag {
+                Some(size) => {
+                    fmt.write_fmt(format_args!(
+                        "        /* {:>6} {size:>5} {size:>5} */ <discriminant>,\n",
+                        0
+                    ))?;
+                    size
+                },
+                None => 0,
+            };
+
+            let end = self.fmt_fields(fmt, variant.fields, start, "        ", summary)?;
+
+            if matches!(variant.discriminant, MaybeUninhabited::Inhabited(_)) && niche != Some(i) {
+                let tail = fmt_tail(fmt, layout, end, "        ")?;
+                padding = Some(padding.map_or(tail, |padding: usize| padding.min(tail)));
+            }
+
+            fmt.write_str("    },\n")?;
+        }
+
+        summary.padding = padding.unwrap_or(0);
+
+        Ok(())
+    }
+
+    /// Renders the `fields`, ordered by their offsets, and the holes between
+    /// them, starting at the `start` offset, and returns the end of the last
+    /// field.
+    fn fmt_fields(
+        &self,
+        fmt: &mut fmt::Formatter,
+        fields: &'a [Field<'a>],
+        start: usize,
+        indent: &str,
+        summary: &mut Summary<'a, 'a>,
+    ) -> Result<usize, fmt::Error> {
+        let mut sorted = fields.iter().collect::<Vec<_>>();
+        sorted.sort_by_key(|field| match field.offset {
+            MaybeUninhabited::Inhabited(offset) => (false, offset),
+            MaybeUninhabited::Uninhabited => (true, 0),
+        });
+
+        let mut end = start;
+
+        for field in sorted {
+            if let MaybeUninhabited::Inhabited(offset) = field.offset {
+                if offset > end {
+                    fmt_hole(fmt, offset - end, indent, "hole")?;
+                    summary.holes += 1;
+                    summary.sum_holes += offset - end;
+                }
+            }
+
+            end = end.max(self.fmt_field(fmt, field, indent, summary)?);
+        }
+
+        Ok(end)
+    }
+
+    /// Renders the `field` and returns its end offset.
+    fn fmt_field(
+        &self,
+        fmt: &mut fmt::Formatter,
+        field: &'a Field<'a>,
+        indent: &str,
+        summary: &mut Summary<'a, 'a>,
+    ) -> Result<usize, fmt::Error> {
+        let layout = self.graph.find_type(field.ty);
+
+        fmt.write_str(indent)?;
+
+        match field.offset {
+            MaybeUninhabited::Inhabited(offset) => {
+                fmt.write_fmt(format_args!("/* {offset:>6} "))?;
+            },
+            MaybeUninhabited::Uninhabited => fmt.write_str("/*      ! ")?,
+        }
+
+        match layout {
+            Some(layout) => {
+                fmt.write_fmt(format_args!(
+                    "{:>5} {:>5} */ ",
+                    layout.size, layout.alignment
+                ))?;
+            },
+            None => fmt.write_str("    ?     ? */ ")?,
+        }
+
+        fmt.write_fmt(format_args!("{}: {},\n", field.name, field.ty))?;
+
+        summary.members += 1;
+
+        let Some(layout) = layout else {
+            return Ok(0);
+        };
+
+        summary.sum_members += layout.size;
+
+        let driver_alignment = summary
+            .driver
+            .and_then(|driver| self.graph.find_type(driver.ty))
+            .map_or(1, |driver| driver.alignment);
+
+        if layout.alignment > driver_alignment {
+            summary.driver = Some(field);
+        }
+
+        Ok(match field.offset {
+            MaybeUninhabited::Inhabited(offset) => offset + layout.size,
+            MaybeUninhabited::Uninhabited => 0,
+        })
+    }
+}
+
+/// Renders the tail padding after the `end` of the last field and returns its
+/// size.
+fn fmt_tail(
+    fmt: &mut fmt::Formatter,
+    layout: &TypeLayoutInfo,
+    end: usize,
+    indent: &str,
+) -> Result<usize, fmt::Error> {
+    if layout.size <= end {
+        return Ok(0);
+    }
+
+    fmt_hole(fmt, layout.size - end, indent, "padding")?;
+
+    Ok(layout.size - end)
+}
+
+fn fmt_hole(fmt: &mut fmt::Formatter, size: usize, indent: &str, kind: &str) -> fmt::Result {
+    let plural = if size == 1 { "" } else { "s" };
+
+    fmt.write_fmt(format_args!(
+        "{indent}/* XXX {size} byte{plural} {kind} */\n"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use crate::{
+        Field, MaybeUninhabited, TypeGraphLayout, TypeLayout, TypeLayoutGraph, TypeLayoutInfo,
+        TypeStructure,
+    };
+
+    use super::Report;
+
+    #[test]
+    fn holes() {
+        const GRAPH: TypeLayoutGraph<'static> = TypeLayoutGraph {
+            ty: "Holes",
+            tys: &[
+                &TypeLayoutInfo {
+                    name: "Holes",
+                    size: 12,
+                    alignment: 4,
+                    structure: TypeStructure::Struct {
+                        repr: "C",
+                        fields: &[
+                            Field {
+                                name: "a",
+                                offset: MaybeUninhabited::Inhabited(0),
+                                ty: "u8",
+                            },
+                            Field {
+                                name: "b",
+                                offset: MaybeUninhabited::Inhabited(4),
+                                ty: "u32",
+                            },
+                            Field {
+                                name: "c",
+                                offset: MaybeUninhabited::Inhabited(8),
+                                ty: "u16",
+                            },
+                        ],
+                    },
+                },
+                &<u8 as TypeLayout>::TYPE_LAYOUT,
+                &<u32 as TypeLayout>::TYPE_LAYOUT,
+                &<u16 as TypeLayout>::TYPE_LAYOUT,
+            ],
+        };
+
+        assert_eq!(
+            Report::graph(&GRAPH).to_string(),
+            "#[repr(C)]
+struct Holes {
+    /* offset  size align */
+    /*      0     1     1 */ a: u8,
+    /* XXX 3 bytes hole */
+    /*      4     4     4 */ b: u32,
+    /*      8     2     2 */ c: u16,
+    /* XXX 2 bytes padding */
+
+    /* size: 12, align: 4, members: 3, sum members: 7 */
+    /* holes: 1, sum holes: 3, padding: 2, wasted: 5 */
+    /* alignment driven by `b: u32` */
+    /* reordering as `b, c, a` saves 4 bytes, size: 8 */
+};
+"
+        );
+    }
+
+    #[test]
+    fn variants() {
+        let graph = <core::cmp::Ordering>::TYPE_GRAPH;
+
+        assert_eq!(
+            Report::layout(&graph, graph.find_type(graph.ty).unwrap()).to_string(),
+            "#[repr(i8)]
+enum core::cmp::Ordering {
+    /* offset  size align */
+    Less = -1 {
+        /*      0     1     1 */ <discriminant>,
+    },
+    Equal = 0 {
+        /*      0     1     1 */ <discriminant>,
+    },
+    Greater = 1 {
+        /*      0     1     1 */ <discriminant>,
+    },
+
+    /* size: 1, align: 1, members: 0, sum members: 0 */
+    /* holes: 0, sum holes: 0, padding: 0, wasted: 0 */
+};
+"
+        );
+    }
+}
@@ -0,0 +1,883 @@
+//! Helper module to render raw bytes as an annotated tree of the value they
+//! contain, using the layout information from a [`TypeLayoutGraph`].
+//!
+//! Unlike a `#[derive(Debug)]` implementation, the [`MemoryDump`] does not
+//! require a live reference to a value, so it can be used to inspect bytes
+//! that were read from a file, from shared memory, or from another process.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{
+    primitive::Primitive,
+    repr::{EnumEncoding, Repr},
+    Field, MaybeUninhabited, TypeLayoutGraph, TypeLayoutInfo, TypeStructure, Variant,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Byte order in which multi-byte primitive values are stored.
+pub enum Endian {
+    /// Least significant byte first.
+    Little,
+    /// Most significant byte first.
+    Big,
+}
+
+impl Endian {
+    /// The byte order of the host.
+    pub const NATIVE: Self = if cfg!(target_endian = "big") {
+        Self::Big
+    } else {
+        Self::Little
+    };
+}
+
+#[derive(Clone, Copy)]
+/// Formatter that renders raw bytes as an annotated tree of the value whose
+/// layout is described by a [`TypeLayoutGraph`].
+///
+/// The rendered tree shows
+/// - every field's name, type, and absolute byte offset
+/// - the decoded value of every primitive, e.g. integers, `bool`s, `char`s,
+///   floats, and pointers
+/// - every padding byte that is not covered by any field
+/// - the active variant of enums, identified via their discriminant
+/// - all fields of unions
+///
+/// The active variant of an enum can only be identified if the enum has a
+/// `#[repr(C)]` or primitive integer representation, is field-less, has only
+/// one inhabited variant, or uses the guaranteed null-pointer optimisation
+/// (e.g. `Option<&T>`). Otherwise, the enum's bytes are shown in hex.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(const_type_name)]
+/// use const_type_layout::{render::raw::MemoryDump, TypeGraphLayout};
+///
+/// let graph = <core::cmp::Ordering>::TYPE_GRAPH;
+///
+/// assert_eq!(
+///     MemoryDump::new(&graph, graph.ty, &[0xff]).to_string(),
+///     "core::cmp::Ordering = Less (discriminant -1) {
+///     0x0000 <discriminant> = [ff]
+/// }"
+/// );
+/// ```
+pub struct MemoryDump<'a, 'b> {
+    graph: &'b TypeLayoutGraph<'a>,
+    ty: &'b str,
+    bytes: &'b [u8],
+    endian: Endian,
+}
+
+impl<'a, 'b> MemoryDump<'a, 'b> {
+    #[must_use]
+    /// Construct a formatter that renders `bytes` as a value of the type `ty`,
+    /// whose layout must be included in the `graph`.
+    ///
+    /// Multi-byte primitives are decoded in the [`Endian::NATIVE`] byte order.
+    pub const fn new(graph: &'b TypeLayoutGraph<'a>, ty: &'b str, bytes: &'b [u8]) -> Self {
+        Self {
+            graph,
+            ty,
+            bytes,
+            endian: Endian::NATIVE,
+        }
+    }
+
+    #[must_use]
+    /// Decode multi-byte primitives in the `endian` byte order instead, e.g.
+    /// when the bytes were produced on a different machine.
+    pub const fn with_endian(mut self, endian: Endian) -> Self {
+        self.endian = endian;
+        self
+    }
+}
+
+impl fmt::Display for MemoryDump<'_, '_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_fmt(format_args!("{} = ", self.ty))?;
+        self.fmt_value(fmt, self.ty, 0, self.bytes.len(), 0)
+    }
+}
+
+impl fmt::Debug for MemoryDump<'_, '_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, fmt)
+    }
+}
+
+enum Entry<'f, 'a> {
+    Field {
+        offset: usize,
+        size: usize,
+        field: &'f Field<'a>,
+    },
+    Tag {
+        offset: usize,
+        size: usize,
+    },
+    Padding {
+        offset: usize,
+        size: usize,
+    },
+}
+
+enum VariantMatch {
+    Found { variant: usize, tag: Option<usize> },
+    InvalidTag { value: u128 },
+    Unknown,
+}
+
+impl<'a> MemoryDump<'a, '_> {
+    /// Formats the value of type `ty` at `offset`, which occupies at most the
+    /// next `extent` bytes, which are shown in hex if the layout is unknown.
+    fn fmt_value(
+        &self,
+        fmt: &mut fmt::Formatter,
+        ty: &str,
+        offset: usize,
+        extent: usize,
+        depth: usize,
+    ) -> fmt::Result {
+        // bound the nesting to guard against malformed graphs, in which a type
+        // contains itself by value
+        if depth > self.graph.tys.len() {
+            fmt.write_str("<recursive layout> ")?;
+            return self.fmt_hex(fmt, offset, extent);
+        }
+
+        let Some(layout) = self.graph.find_type(ty) else {
+            fmt.write_str("<unknown layout> ")?;
+            return self.fmt_hex(fmt, offset, extent);
+        };
+
+        match &layout.structure {
+            TypeStructure::Primitive => self.fmt_primitive(fmt, layout, offset, depth),
+            TypeStructure::Struct { fields, .. } => {
+                let entries = self.entries(fields, offset, layout.size, None, true);
+                self.fmt_entries(fmt, &entries, depth)
+            },
+            TypeStructure::Union { fields, .. } => {
+                let entries = self.entries(fields, offset, layout.size, None, false);
+                fmt.write_str("union ")?;
+                self.fmt_entries(fmt, &entries, depth)
+            },
+            TypeStructure::Enum { repr, variants } => {
+                self.fmt_enum(fmt, layout, Repr::parse(repr), variants, offset, depth)
+            },
+        }
+    }
+
+    fn fmt_enum(
+        &self,
+        fmt: &mut fmt::Formatter,
+        layout: &TypeLayoutInfo<'a>,
+        repr: Repr,
+        variants: &[Variant<'a>],
+        offset: usize,
+        depth: usize,
+    ) -> fmt::Result {
+        match self.identify_variant(layout, repr, variants, offset) {
+            VariantMatch::Found { variant, tag } => {
+                let variant = &variants[variant];
+
+                fmt.write_str(variant.name)?;
+                if let MaybeUninhabited::Inhabited(discriminant) = &variant.discriminant {
+                    fmt.write_fmt(format_args!(
+                        " (discriminant {}) ",
+                        discriminant.to_wrapping_i128()
+                    ))?;
+                }
+
+                let entries = self.entries(variant.fields, offset, layout.size, tag, true);
+                self.fmt_entries(fmt, &entries, depth)
+            },
+            VariantMatch::InvalidTag { value } => {
+                fmt.write_fmt(format_args!("<invalid discriminant {value}> "))?;
+                self.fmt_hex(fmt, offset, layout.size)
+            },
+            VariantMatch::Unknown => {
+                fmt.write_str("<unknown variant> ")?;
+                self.fmt_hex(fmt, offset, layout.size)
+            },
+        }
+    }
+
+    fn identify_variant(
+        &self,
+        layout: &TypeLayoutInfo<'a>,
+        repr: Repr,
+        variants: &[Variant<'a>],
+        offset: usize,
+    ) -> VariantMatch {
+        match EnumEncoding::of(self.graph, layout, repr, variants) {
+            EnumEncoding::Tagged { size } => {
+                let Some(tag) = self.read(offset, size) else {
+                    return VariantMatch::Unknown;
+                };
+                let value = self.read_uint(tag);
+
+                for (i, variant) in variants.iter().enumerate() {
+                    if let MaybeUninhabited::Inhabited(discriminant) = &variant.discriminant {
+                        #[allow(clippy::cast_sign_loss)]
+                        let discriminant = discriminant.to_wrapping_i128() as u128;
+
+                        if truncate(discriminant, size) == value {
+                            return VariantMatch::Found {
+                                variant: i,
+                                tag: Some(size),
+                            };
+                        }
+                    }
+                }
+
+                VariantMatch::InvalidTag { value }
+            },
+            EnumEncoding::Single { variant } => VariantMatch::Found { variant, tag: None },
+            EnumEncoding::NullPointer { none, some } => {
+                let Some(bytes) = self.read(offset, layout.size) else {
+                    return VariantMatch::Unknown;
+                };
+
+                if bytes.iter().all(|b| *b == 0) {
+                    // the all-zero niche encodes the field-less variant
+                    VariantMatch::Found {
+                        variant: none,
+                        tag: Some(layout.size),
+                    }
+                } else {
+                    VariantMatch::Found {
+                        variant: some,
+                        tag: None,
+                    }
+                }
+            },
+            EnumEncoding::Unknown => VariantMatch::Unknown,
+        }
+    }
+
+    fn fmt_primitive(
+        &self,
+        fmt: &mut fmt::Formatter,
+        layout: &TypeLayoutInfo<'a>,
+        offset: usize,
+        depth: usize,
+    ) -> fmt::Result {
+        let primitive = Primitive::parse(layout.name);
+
+        if let Primitive::Array { element, len } = primitive {
+            return self.fmt_array(fmt, element, len, offset, layout.size, depth);
+        }
+
+        let Some(bytes) = self.read(offset, layout.size) else {
+            return self.fmt_hex(fmt, offset, layout.size);
+        };
+
+        match primitive {
+            Primitive::Unit => fmt.write_str("()"),
+            Primitive::Never => fmt.write_str("<uninhabited>"),
+            Primitive::Bool => match bytes {
+                [0] => fmt.write_str("false"),
+                [1] => fmt.write_str("true"),
+                _ => {
+                    fmt.write_str("<invalid bool> ")?;
+                    self.fmt_hex(fmt, offset, layout.size)
+                },
+            },
+            Primitive::Char if bytes.len() == 4 => {
+                #[allow(clippy::cast_possible_truncation)]
+                let value = self.read_uint(bytes) as u32;
+
+                match char::from_u32(value) {
+                    Some(c) => fmt.write_fmt(format_args!("{c:?}")),
+                    None => fmt.write_fmt(format_args!("<invalid char {value:#x}>")),
+                }
+            },
+            Primitive::Int { signed } if bytes.len() <= 16 => {
+                let value = self.read_uint(bytes);
+
+                if signed {
+                    fmt.write_fmt(format_args!("{}", sign_extend(value, bytes.len())))
+                } else {
+                    fmt.write_fmt(format_args!("{value}"))
+                }
+            },
+            Primitive::Float if bytes.len() == 4 => {
+                #[allow(clippy::cast_possible_truncation)]
+                let value = f32::from_bits(self.read_uint(bytes) as u32);
+                fmt.write_fmt(format_args!("{value:?}"))
+            },
+            Primitive::Float if bytes.len() == 8 => {
+                #[allow(clippy::cast_possible_truncation)]
+                let value = f64::from_bits(self.read_uint(bytes) as u64);
+                fmt.write_fmt(format_args!("{value:?}"))
+            },
+            Primitive::Pointer { .. }
+            | Primitive::Reference { .. }
+            | Primitive::FnPointer { .. }
+                if bytes.len() <= 16 =>
+            {
+                fmt.write_fmt(format_args!(
+                    "{:#0width$x}",
+                    self.read_uint(bytes),
+                    width = 2 + bytes.len() * 2
+                ))
+            },
+            _ => self.fmt_hex(fmt, offset, layout.size),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn fmt_array(
+        &self,
+        fmt: &mut fmt::Formatter,
+        element: &str,
+        len: usize,
+        offset: usize,
+        size: usize,
+        depth: usize,
+    ) -> fmt::Result {
+        let Some(element_layout) = self.graph.find_type(element) else {
+            fmt.write_str("<unknown layout> ")?;
+            return self.fmt_hex(fmt, offset, size);
+        };
+
+        // all elements of a zero-sized array are the same, and there may be
+        //  arbitrarily many of them
+        if element_layout.size == 0 && len > 1 {
+            fmt.write_str("[")?;
+            self.fmt_value(fmt, element, offset, 0, depth)?;
+            return fmt.write_fmt(format_args!("; {len}]"));
+        }
+
+        let inline = matches!(
+            Primitive::of(element_layout),
+            Some(
+                Primitive::Unit
+                    | Primitive::Bool
+                    | Primitive::Char
+                    | Primitive::Int { .. }
+                    | Primitive::Float
+            )
+        );
+
+        fmt.write_str("[")?;
+
+        for i in 0..len {
+            let element_offset = offset + i * element_layout.size;
+
+            if inline {
+                if i > 0 {
+                    fmt.write_str(", ")?;
+                }
+            } else {
+                fmt_line_prefix(fmt, element_offset, depth + 1)?;
+            }
+
+            // summarise the remaining elements once the bytes have run out
+            if i > 0 && element_offset >= self.bytes.len() {
+                let missing = len - i;
+                fmt.write_fmt(format_args!(
+                    "<{missing} element{} missing>",
+                    if missing == 1 { "" } else { "s" }
+                ))?;
+                break;
+            }
+
+            if !inline {
+                fmt.write_fmt(format_args!("[{i}]: {element} = "))?;
+            }
+
+            self.fmt_value(fmt, element, element_offset, element_layout.size, depth + 1)?;
+        }
+
+        if !inline && len > 0 {
+            fmt.write_str("\n")?;
+            fmt_indent(fmt, depth)?;
+        }
+
+        fmt.write_str("]")
+    }
+
+    fn entries<'f>(
+        &self,
+        fields: &'f [Field<'a>],
+        offset: usize,
+        size: usize,
+        tag: Option<usize>,
+        sort: bool,
+    ) -> Vec<Entry<'f, 'a>> {
+        let mut covered = Vec::with_capacity(fields.len() + 1);
+        let mut entries = Vec::with_capacity(fields.len() + 1);
+
+        if let Some(tag) = tag {
+            covered.push((offset, tag));
+            entries.push((offset, Entry::Tag { offset, size: tag }));
+        }
+
+        let mut uninhabited = Vec::new();
+
+        for field in fields {
+            match field.offset {
+                MaybeUninhabited::Inhabited(field_offset) => {
+                    // a field with an unknown layout extends until the next
+                    //  field or the end of the value
+                    let field_size = self.graph.find_type(field.ty).map_or_else(
+                        || {
+                            fields
+                                .iter()
+                                .filter_map(|other| match other.offset {
+                                    MaybeUninhabited::Inhabited(other) => Some(other),
+                                    MaybeUninhabited::Uninhabited => None,
+                                })
+                                .filter(|other| *other > field_offset)
+                                .fold(size, usize::min)
+                                .saturating_sub(field_offset)
+                        },
+                        |ty| ty.size,
+                    );
+
+                    covered.push((offset + field_offset, field_size));
+                    entries.push((
+                        offset + field_offset,
+                        Entry::Field {
+                            offset: offset + field_offset,
+                            size: field_size,
+                            field,
+                        },
+                    ));
+                },
+                MaybeUninhabited::Uninhabited => uninhabited.push(Entry::Field {
+                    offset,
+                    size: 0,
+                    field,
+                }),
+            }
+        }
+
+        covered.sort_unstable();
+
+        let mut paddings = Vec::new();
+        let mut cursor = offset;
+
+        for (start, len) in covered {
+            if start > cursor {
+                paddings.push((
+                    cursor,
+                    Entry::Padding {
+                        offset: cursor,
+                        size: start - cursor,
+                    },
+                ));
+            }
+
+            cursor = cursor.max(start + len);
+        }
+
+        if (offset + size) > cursor {
+            paddings.push((
+                cursor,
+                Entry::Padding {
+                    offset: cursor,
+                    size: offset + size - cursor,
+                },
+            ));
+        }
+
+        entries.extend(paddings);
+
+        if sort {
+            // stable sort to keep zero-sized fields in declaration order
+            entries.sort_by_key(|(offset, _)| *offset);
+        }
+
+        entries
+            .into_iter()
+            .map(|(_, entry)| entry)
+            .chain(uninhabited)
+            .collect()
+    }
+
+    fn fmt_entries(
+        &self,
+        fmt: &mut fmt::Formatter,
+        entries: &[Entry<'_, 'a>],
+        depth: usize,
+    ) -> fmt::Result {
+        fmt.write_str("{")?;
+
+        for entry in entries {
+            match entry {
+                Entry::Field {
+                    offset,
+                    size,
+                    field:
+                        Field {
+                            name,
+                            offset: MaybeUninhabited::Inhabited(_),
+                            ty,
+                        },
+                } => {
+                    fmt_line_prefix(fmt, *offset, depth + 1)?;
+                    fmt.write_fmt(format_args!("{name}: {ty} = "))?;
+                    self.fmt_value(fmt, ty, *offset, *size, depth + 1)?;
+                },
+                Entry::Field {
+                    offset: _,
+                    size: _,
+                    field:
+                        Field {
+                            name,
+                            offset: MaybeUninhabited::Uninhabited,
+                            ty,
+                        },
+                } => {
+                    fmt.write_str("\n")?;
+                    fmt_indent(fmt, depth + 1)?;
+                    fmt.write_fmt(format_args!("0x???? {name}: {ty} = <uninhabited>"))?;
+                },
+                Entry::Tag { offset, size } => {
+                    fmt_line_prefix(fmt, *offset, depth + 1)?;
+                    fmt.write_str("<discriminant> = ")?;
+                    self.fmt_hex(fmt, *offset, *size)?;
+                },
+                Entry::Padding { offset, size } => {
+                    fmt_line_prefix(fmt, *offset, depth + 1)?;
+                    fmt.write_fmt(format_args!(
+                        "<padding: {size} byte{}> = ",
+                        if *size == 1 { "" } else { "s" }
+                    ))?;
+                    self.fmt_hex(fmt, *offset, *size)?;
+
+                    if self
+                        .read(*offset, *size)
+                        .map_or(false, |bytes| bytes.iter().any(|b| *b != 0))
+                    {
+                        fmt.write_str(" /* non-zero */")?;
+                    }
+                },
+            }
+        }
+
+        fmt.write_str("\n")?;
+        fmt_indent(fmt, depth)?;
+        fmt.write_str("}")
+    }
+
+    fn fmt_hex(&self, fmt: &mut fmt::Formatter, offset: usize, size: usize) -> fmt::Result {
+        let start = offset.min(self.bytes.len());
+        let end = (offset + size).min(self.bytes.len());
+
+        fmt.write_str("[")?;
+
+        for (i, byte) in self.bytes[start..end].iter().enumerate() {
+            if i > 0 {
+                fmt.write_str(" ")?;
+            }
+            fmt.write_fmt(format_args!("{byte:02x}"))?;
+        }
+
+        let missing = size - (end - start);
+        if missing > 0 {
+            if end > start {
+                fmt.write_str(" ")?;
+            }
+            fmt.write_fmt(format_args!(
+                "<{missing} byte{} missing>",
+                if missing == 1 { "" } else { "s" }
+            ))?;
+        }
+
+        fmt.write_str("]")
+    }
+
+    fn read(&self, offset: usize, size: usize) -> Option<&[u8]> {
+        self.bytes.get(offset..offset.checked_add(size)?)
+    }
+
+    fn read_uint(&self, bytes: &[u8]) -> u128 {
+        let fold = |value: u128, byte: &u8| (value << 8) | u128::from(*byte);
+
+        match self.endian {
+            Endian::Little => bytes.iter().rev().fold(0, fold),
+            Endian::Big => bytes.iter().fold(0, fold),
+        }
+    }
+}
+
+fn fmt_indent(fmt: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+    for _ in 0..depth {
+        fmt.write_str("    ")?;
+    }
+
+    Ok(())
+}
+
+fn fmt_line_prefix(fmt: &mut fmt::Formatter, offset: usize, depth: usize) -> fmt::Result {
+    fmt.write_str("\n")?;
+    fmt_indent(fmt, depth)?;
+    fmt.write_fmt(format_args!("{offset:#06x} "))
+}
+
+const fn truncate(value: u128, size: usize) -> u128 {
+    if size >= 16 {
+        value
+    } else {
+        value & ((1_u128 << (size * 8)) - 1)
+    }
+}
+
+#[allow(clippy::cast_possible_wrap)]
+const fn sign_extend(value: u128, size: usize) -> i128 {
+    if size == 0 || size >= 16 {
+        return value as i128;
+    }
+
+    let shift = 128 - (size * 8);
+
+    ((value << shift) as i128) >> shift
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::{String, ToString};
+
+    use crate::{
+        Field, MaybeUninhabited, TypeGraphLayout, TypeLayout, TypeLayoutGraph, TypeLayoutInfo,
+        TypeStructure,
+    };
+
+    use super::{Endian, MemoryDump};
+
+    const GRAPH: TypeLayoutGraph<'static> = TypeLayoutGraph {
+        ty: "Holes",
+        tys: &[
+            &TypeLayoutInfo {
+                name: "Holes",
+                size: 12,
+                alignment: 4,
+                structure: TypeStructure::Struct {
+                    repr: "C",
+                    fields: &[
+                        Field {
+                            name: "a",
+                            offset: MaybeUninhabited::Inhabited(0),
+                            ty: "u8",
+                        },
+                        Field {
+                            name: "b",
+                            offset: MaybeUninhabited::Inhabited(4),
+                            ty: "Mystery",
+                        },
+                        Field {
+                            name: "c",
+                            offset: MaybeUninhabited::Inhabited(8),
+                            ty: "u16",
+                        },
+                    ],
+                },
+            },
+            &<u8 as TypeLayout>::TYPE_LAYOUT,
+            &<u16 as TypeLayout>::TYPE_LAYOUT,
+        ],
+    };
+
+    fn dump<T: TypeGraphLayout>(bytes: &[u8]) -> String {
+        let graph = T::TYPE_GRAPH;
+        MemoryDump::new(&graph, graph.ty, bytes).to_string()
+    }
+
+    #[test]
+    fn padding() {
+        assert_eq!(
+            MemoryDump::new(&GRAPH, GRAPH.ty, &[1, 0xaa, 0, 0, 4, 3, 2, 1, 2, 0, 0, 0])
+                .with_endian(Endian::Little)
+                .to_string(),
+            "Holes = {
+    0x0000 a: u8 = 1
+    0x0001 <padding: 3 bytes> = [aa 00 00] /* non-zero */
+    0x0004 b: Mystery = <unknown layout> [04 03 02 01]
+    0x0008 c: u16 = 2
+    0x000a <padding: 2 bytes> = [00 00]
+}"
+        );
+    }
+
+    #[test]
+    fn unknown_layout() {
+        assert_eq!(
+            MemoryDump::new(&GRAPH, "Other", &[1, 2, 3]).to_string(),
+            "Other = <unknown layout> [01 02 03]"
+        );
+    }
+
+    #[test]
+    fn recursive_layout() {
+        const LOOP: TypeLayoutGraph = TypeLayoutGraph {
+            ty: "Loop",
+            tys: &[&TypeLayoutInfo {
+                name: "Loop",
+                size: 1,
+                alignment: 1,
+                structure: TypeStructure::Struct {
+                    repr: "",
+                    fields: &[Field {
+                        name: "inner",
+                        offset: MaybeUninhabited::Inhabited(0),
+                        ty: "Loop",
+                    }],
+                },
+            }],
+        };
+
+        assert_eq!(
+            MemoryDump::new(&LOOP, LOOP.ty, &[7]).to_string(),
+            "Loop = {
+    0x0000 inner: Loop = {
+        0x0000 inner: Loop = <recursive layout> [07]
+    }
+}"
+        );
+    }
+
+    #[test]
+    fn union() {
+        assert_eq!(
+            dump::<core::mem::MaybeUninit<u8>>(&[42]),
+            "core::mem::maybe_uninit::MaybeUninit<u8> = union {
+    0x0000 uninit: () = ()
+    0x0000 value: core::mem::manually_drop::ManuallyDrop<u8> = {
+        0x0000 value: u8 = 42
+    }
+}"
+        );
+    }
+
+    #[test]
+    fn tagged_enum() {
+        assert_eq!(
+            dump::<core::cmp::Ordering>(&[1]),
+            "core::cmp::Ordering = Greater (discriminant 1) {
+    0x0000 <discriminant> = [01]
+}"
+        );
+        assert_eq!(
+            dump::<core::cmp::Ordering>(&[5]),
+            "core::cmp::Ordering = <invalid discriminant 5> [05]"
+        );
+        assert_eq!(
+            dump::<core::cmp::Ordering>(&[]),
+            "core::cmp::Ordering = <unknown variant> [<1 byte missing>]"
+        );
+    }
+
+    #[test]
+    fn niche_enum() {
+        let none = [0; core::mem::size_of::<usize>()];
+        let mut some = none;
+        some[0] = 8;
+
+        assert_eq!(
+            dump::<Option<&'static u16>>(&none),
+            alloc::format!(
+                "core::option::Option<&u16> = None (discriminant 0) {{
+    0x0000 <discriminant> = [{}]
+}}",
+                ["00"; core::mem::size_of::<usize>()].join(" ")
+            )
+        );
+        assert_eq!(
+            MemoryDump::new(
+                &<Option<&'static u16>>::TYPE_GRAPH,
+                "core::option::Option<&u16>",
+                &some
+            )
+            .with_endian(Endian::Little)
+            .to_string(),
+            alloc::format!(
+                "core::option::Option<&u16> = Some (discriminant 1) {{
+    0x0000 0: &u16 = {:#0width$x}
+}}",
+                8,
+                width = 2 + core::mem::size_of::<usize>() * 2
+            )
+        );
+        assert_eq!(
+            dump::<Option<u8>>(&[1, 7]),
+            "core::option::Option<u8> = <unknown variant> [01 07]"
+        );
+    }
+
+    #[test]
+    fn invalid_primitives() {
+        assert_eq!(
+            dump::<[bool; 3]>(&[0, 1, 2]),
+            "[bool; 3] = [false, true, <invalid bool> [02]]"
+        );
+
+        let mut chars = [0; 8];
+        chars[..4].copy_from_slice(&u32::from('A').to_ne_bytes());
+        chars[4..].copy_from_slice(&0xd800_u32.to_ne_bytes());
+
+        assert_eq!(
+            dump::<[char; 2]>(&chars),
+            "[char; 2] = ['A', <invalid char 0xd800>]"
+        );
+    }
+
+    #[test]
+    fn endianness() {
+        let graph = <u32>::TYPE_GRAPH;
+        let dump = MemoryDump::new(&graph, graph.ty, &[1, 2, 3, 4]);
+
+        assert_eq!(
+            dump.with_endian(Endian::Little).to_string(),
+            "u32 = 67305985"
+        );
+        assert_eq!(dump.with_endian(Endian::Big).to_string(), "u32 = 16909060");
+
+        let graph = <i16>::TYPE_GRAPH;
+        let dump = MemoryDump::new(&graph, graph.ty, &[0xfe, 0xff]);
+
+        assert_eq!(dump.with_endian(Endian::Little).to_string(), "i16 = -2");
+        assert_eq!(dump.with_endian(Endian::Big).to_string(), "i16 = -257");
+    }
+
+    #[test]
+    fn arrays() {
+        assert_eq!(
+            dump::<[(); usize::MAX]>(&[]),
+            alloc::format!("[(); {0}] = [(); {0}]", usize::MAX)
+        );
+        assert_eq!(
+            dump::<[[u8; 0]; usize::MAX]>(&[]),
+            alloc::format!("[[u8; 0]; {0}] = [[]; {0}]", usize::MAX)
+        );
+
+        let graph = <[u16; 1000]>::TYPE_GRAPH;
+        assert_eq!(
+            MemoryDump::new(&graph, graph.ty, &[1, 0, 2, 0, 3])
+                .with_endian(Endian::Little)
+                .to_string(),
+            "[u16; 1000] = [1, 2, [03 <1 byte missing>], <997 elements missing>]"
+        );
+
+        let graph = <[(u8, u8); 1000]>::TYPE_GRAPH;
+        assert_eq!(
+            MemoryDump::new(&graph, graph.ty, &[1, 2, 3]).to_string(),
+            "[(u8, u8); 1000] = [
+    0x0000 [0]: (u8, u8) = {
+        0x0000 0: u8 = 1
+        0x0001 1: u8 = 2
+    }
+    0x0002 [1]: (u8, u8) = {
+        0x0002 0: u8 = 3
+        0x0003 1: u8 = [<1 byte missing>]
+    }
+    0x0004 <998 elements missing>
+]"
+        );
+    }
+}
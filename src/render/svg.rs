@@ -0,0 +1,596 @@
+//! Helper module to draw the layout of a type inside a [`TypeLayoutGraph`] as
+//! a grid of bytes in a standalone SVG image or HTML page.
+
+use alloc::{format, string::String, vec, vec::Vec};
+use core::fmt;
+
+use crate::{
+    repr::{EnumEncoding, Repr},
+    Field, MaybeUninhabited, TypeLayoutGraph, TypeLayoutInfo, TypeStructure,
+};
+
+#[derive(Clone, Copy)]
+/// Formatter that draws the layout of a type inside a [`TypeLayoutGraph`] as
+/// a grid of bytes, with a fixed number of bytes per row, in a standalone SVG
+/// image or HTML page that needs no external assets.
+///
+/// - every field is drawn as a coloured block of bytes that is labelled with
+///   the field's name, and whose tooltip shows its type, offset, and size
+/// - padding bytes, which are not covered by any field, are hatched
+/// - every union field and enum variant is drawn as an alternative overlay of
+///   the same bytes, where the enum's discriminant or niche is shown as a
+///   `<discriminant>` or `<niche>` block if its position is known
+/// - optionally, cache-line boundaries are marked with red dashed lines
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(const_type_name)]
+/// use const_type_layout::{render::svg::ByteGrid, TypeGraphLayout};
+///
+/// let graph = <(u64, u8)>::TYPE_GRAPH;
+///
+/// let svg = ByteGrid::graph(&graph)
+///     .with_bytes_per_row(8)
+///     .with_cache_lines(8)
+///     .to_string();
+///
+/// assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+/// assert!(svg.contains("<title>padding: 7 bytes at offset 9</title>"));
+/// assert!(svg.contains("<title>cache-line boundary at offset 8</title>"));
+/// ```
+pub struct ByteGrid<'a, 'b> {
+    graph: &'b TypeLayoutGraph<'a>,
+    layout: Option<&'b TypeLayoutInfo<'a>>,
+    bytes_per_row: usize,
+    cache_line: Option<usize>,
+    html: bool,
+}
+
+impl<'a, 'b> ByteGrid<'a, 'b> {
+    #[must_use]
+    /// Construct a formatter that draws the layout of the `graph`'s root
+    /// type.
+    pub const fn graph(graph: &'b TypeLayoutGraph<'a>) -> Self {
+        Self {
+            graph,
+            layout: None,
+            bytes_per_row: 16,
+            cache_line: None,
+            html: false,
+        }
+    }
+
+    #[must_use]
+    /// Construct a formatter that draws the `layout`, whose fields' types
+    /// must be included in the `graph`.
+    pub const fn layout(graph: &'b TypeLayoutGraph<'a>, layout: &'b TypeLayoutInfo<'a>) -> Self {
+        Self {
+            graph,
+            layout: Some(layout),
+            bytes_per_row: 16,
+            cache_line: None,
+            html: false,
+        }
+    }
+
+    #[must_use]
+    /// Draw `bytes_per_row` bytes in every row of the grid, 16 by default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes_per_row` is zero.
+    pub const fn with_bytes_per_row(mut self, bytes_per_row: usize) -> Self {
+        assert!(bytes_per_row > 0, "a byte grid needs at least one column");
+
+        self.bytes_per_row = bytes_per_row;
+        self
+    }
+
+    #[must_use]
+    /// Mark the boundaries between cache lines of `cache_line` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cache_line` is zero.
+    pub const fn with_cache_lines(mut self, cache_line: usize) -> Self {
+        assert!(cache_line > 0, "a cache line must not be empty");
+
+        self.cache_line = Some(cache_line);
+        self
+    }
+
+    #[must_use]
+    /// Embed the SVG image inside a standalone HTML page.
+    pub const fn html(mut self, html: bool) -> Self {
+        self.html = html;
+        self
+    }
+}
+
+const CELL_WIDTH: usize = 36;
+const ROW_HEIGHT: usize = 28;
+const MARGIN: usize = 16;
+const OFFSETS_WIDTH: usize = 48;
+const TITLE_HEIGHT: usize = 28;
+const COLUMNS_HEIGHT: usize = 16;
+const LABEL_HEIGHT: usize = 20;
+const OVERLAY_GAP: usize = 12;
+/// Approximate width of a character in the monospace labels.
+const CHAR_WIDTH: usize = 7;
+
+const PALETTE: [&str; 10] = [
+    "#8dd3c7", "#ffffb3", "#bebada", "#fb8072", "#80b1d3", "#fdb462", "#b3de69", "#fccde5",
+    "#bc80bd", "#ccebc5",
+];
+const TAG_COLOUR: &str = "#d9d9d9";
+
+/// Contiguous bytes that are covered by one field.
+struct Block<'a> {
+    name: &'a str,
+    ty: &'a str,
+    offset: usize,
+    size: usize,
+    colour: &'static str,
+}
+
+/// One alternative view of the bytes of a type, e.g. an enum variant.
+struct Overlay<'a> {
+    label: Option<String>,
+    blocks: Vec<Block<'a>>,
+}
+
+impl fmt::Display for ByteGrid<'_, '_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let layout = self.layout.or_else(|| self.graph.find_type(self.graph.ty));
+
+        if self.html {
+            fmt.write_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>")?;
+            fmt_escaped(fmt, layout.map_or(self.graph.ty, |layout| layout.name))?;
+            fmt.write_str("</title>\n</head>\n<body>\n")?;
+        }
+
+        match layout {
+            Some(layout) => self.fmt_svg(fmt, layout)?,
+            None => fmt_unknown(fmt, self.graph.ty)?,
+        }
+
+        if self.html {
+            fmt.write_str("</body>\n</html>\n")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ByteGrid<'_, '_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, fmt)
+    }
+}
+
+impl<'a> ByteGrid<'a, '_> {
+    fn fmt_svg(&self, fmt: &mut fmt::Formatter, layout: &TypeLayoutInfo<'a>) -> fmt::Result {
+        let overlays = self.overlays(layout);
+
+        let rows = layout.size.div_ceil(self.bytes_per_row);
+        let grid_height = rows * ROW_HEIGHT;
+
+        let width = MARGIN * 2 + OFFSETS_WIDTH + self.bytes_per_row * CELL_WIDTH;
+        let height = MARGIN * 2
+            + TITLE_HEIGHT
+            + COLUMNS_HEIGHT
+            + overlays
+                .iter()
+                .map(|overlay| {
+                    overlay.label.as_ref().map_or(0, |_| LABEL_HEIGHT) + grid_height + OVERLAY_GAP
+                })
+                .sum::<usize>();
+
+        fmt.write_fmt(format_args!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+             viewBox=\"0 0 {width} {height}\" font-family=\"monospace\" font-size=\"12\">\n"
+        ))?;
+        fmt.write_str(
+            "<defs><pattern id=\"padding\" width=\"6\" height=\"6\" \
+             patternUnits=\"userSpaceOnUse\" patternTransform=\"rotate(45)\"><rect width=\"6\" \
+             height=\"6\" fill=\"#ffffff\"/><line x1=\"0\" y1=\"0\" x2=\"0\" y2=\"6\" \
+             stroke=\"#999999\" stroke-width=\"2\"/></pattern></defs>\n",
+        )?;
+        fmt.write_fmt(format_args!(
+            "<rect width=\"{width}\" height=\"{height}\" fill=\"#ffffff\"/>\n"
+        ))?;
+
+        let left = MARGIN + OFFSETS_WIDTH;
+        let mut y = MARGIN;
+
+        fmt.write_fmt(format_args!(
+            "<text x=\"{MARGIN}\" y=\"{}\" font-size=\"14\" font-weight=\"bold\">",
+            y + 16
+        ))?;
+        fmt_escaped(fmt, layout.name)?;
+        fmt.write_fmt(format_args!(
+            " (size: {}, align: {})</text>\n",
+            layout.size, layout.alignment
+        ))?;
+        y += TITLE_HEIGHT;
+
+        for column in 0..self.bytes_per_row {
+            fmt.write_fmt(format_args!(
+                "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" fill=\"#666666\">{column}</text>\n",
+                left + column * CELL_WIDTH + CELL_WIDTH / 2,
+                y + 12
+            ))?;
+        }
+        y += COLUMNS_HEIGHT;
+
+        for overlay in &overlays {
+            if let Some(label) = &overlay.label {
+                fmt.write_fmt(format_args!("<text x=\"{left}\" y=\"{}\">", y + 14))?;
+                fmt_escaped(fmt, label)?;
+                fmt.write_str("</text>\n")?;
+                y += LABEL_HEIGHT;
+            }
+
+            self.fmt_grid(fmt, layout, overlay, y)?;
+            y += grid_height + OVERLAY_GAP;
+        }
+
+        fmt.write_str("</svg>\n")
+    }
+
+    /// Draws the bytes of the `layout` as covered by the `overlay`'s blocks,
+    /// starting at the vertical position `top`.
+    fn fmt_grid(
+        &self,
+        fmt: &mut fmt::Formatter,
+        layout: &TypeLayoutInfo<'a>,
+        overlay: &Overlay<'a>,
+        top: usize,
+    ) -> fmt::Result {
+        let left = MARGIN + OFFSETS_WIDTH;
+
+        // every byte is covered by the first block that contains it
+        let mut coverage = vec![None; layout.size];
+        for (i, block) in overlay.blocks.iter().enumerate() {
+            for byte in &mut coverage
+                [block.offset.min(layout.size)..(block.offset + block.size).min(layout.size)]
+            {
+                *byte = byte.or(Some(i));
+            }
+        }
+
+        let mut start = 0;
+
+        while start < layout.size {
+            let row = start / self.bytes_per_row;
+            let row_end = ((row + 1) * self.bytes_per_row).min(layout.size);
+
+            let mut end = start + 1;
+            while end < row_end && coverage[end] == coverage[start] {
+                end += 1;
+            }
+
+            let x = left + (start % self.bytes_per_row) * CELL_WIDTH;
+            let y = top + row * ROW_HEIGHT;
+            let width = (end - start) * CELL_WIDTH;
+
+            if start % self.bytes_per_row == 0 {
+                fmt.write_fmt(format_args!(
+                    "<text x=\"{}\" y=\"{}\" text-anchor=\"end\" fill=\"#666666\">{start}</text>\n",
+                    left - 6,
+                    y + ROW_HEIGHT / 2 + 4
+                ))?;
+            }
+
+            if let Some(i) = coverage[start] {
+                let block = &overlay.blocks[i];
+
+                fmt.write_fmt(format_args!(
+                    "<g><title>{}: {} at offset {}, {} byte{}</title><rect x=\"{x}\" y=\"{y}\" \
+                     width=\"{width}\" height=\"{ROW_HEIGHT}\" fill=\"{}\" stroke=\"#444444\"/>",
+                    Escaped(block.name),
+                    Escaped(block.ty),
+                    block.offset,
+                    block.size,
+                    if block.size == 1 { "" } else { "s" },
+                    block.colour,
+                ))?;
+                fmt.write_fmt(format_args!(
+                    "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">",
+                    x + width / 2,
+                    y + ROW_HEIGHT / 2 + 4
+                ))?;
+                fmt_truncated(fmt, block.name, (width - 4) / CHAR_WIDTH)?;
+                fmt.write_str("</text></g>\n")?;
+            } else {
+                // report the entire padding run, which may span rows
+                let run_start = coverage[..start]
+                    .iter()
+                    .rposition(Option::is_some)
+                    .map_or(0, |i| i + 1);
+                let run_end = coverage[start..]
+                    .iter()
+                    .position(Option::is_some)
+                    .map_or(layout.size, |i| start + i);
+                let run = run_end - run_start;
+
+                fmt.write_fmt(format_args!(
+                    "<g><title>padding: {run} byte{} at offset {run_start}</title><rect x=\"{x}\" \
+                     y=\"{y}\" width=\"{width}\" height=\"{ROW_HEIGHT}\" fill=\"url(#padding)\" \
+                     stroke=\"#444444\"/></g>\n",
+                    if run == 1 { "" } else { "s" },
+                ))?;
+            }
+
+            // separate the individual bytes inside the block
+            for byte in (start + 1)..end {
+                let x = left + (byte % self.bytes_per_row) * CELL_WIDTH;
+                fmt.write_fmt(format_args!(
+                    "<line x1=\"{x}\" y1=\"{y}\" x2=\"{x}\" y2=\"{}\" stroke=\"#444444\" \
+                     stroke-opacity=\"0.25\"/>\n",
+                    y + ROW_HEIGHT
+                ))?;
+            }
+
+            start = end;
+        }
+
+        self.fmt_cache_lines(fmt, layout, top)
+    }
+
+    fn fmt_cache_lines(
+        &self,
+        fmt: &mut fmt::Formatter,
+        layout: &TypeLayoutInfo<'a>,
+        top: usize,
+    ) -> fmt::Result {
+        let Some(cache_line) = self.cache_line else {
+            return Ok(());
+        };
+
+        let left = MARGIN + OFFSETS_WIDTH;
+
+        for offset in (cache_line..layout.size).step_by(cache_line) {
+            let row = offset / self.bytes_per_row;
+            let column = offset % self.bytes_per_row;
+
+            let (x1, y1) = (left + column * CELL_WIDTH, top + row * ROW_HEIGHT);
+            let (x2, y2) = if column == 0 {
+                (left + self.bytes_per_row * CELL_WIDTH, y1)
+            } else {
+                (x1, y1 + ROW_HEIGHT)
+            };
+
+            fmt.write_fmt(format_args!(
+                "<g><title>cache-line boundary at offset {offset}</title><line x1=\"{x1}\" \
+                 y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\" stroke=\"#e41a1c\" stroke-width=\"3\" \
+                 stroke-dasharray=\"6,3\"/></g>\n"
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the alternative views of the bytes of the `layout`.
+    fn overlays(&self, layout: &TypeLayoutInfo<'a>) -> Vec<Overlay<'a>> {
+        match &layout.structure {
+            TypeStructure::Primitive => vec![Overlay {
+                label: None,
+                blocks: vec![Block {
+                    name: layout.name,
+                    ty: layout.name,
+                    offset: 0,
+                    size: layout.size,
+                    colour: PALETTE[0],
+                }],
+            }],
+            TypeStructure::Struct { fields, .. } => vec![Overlay {
+                label: None,
+                blocks: self.blocks(fields),
+            }],
+            TypeStructure::Union { fields, .. } => fields
+                .iter()
+                .enumerate()
+                .map(|(i, field)| Overlay {
+                    label: Some(format!("{}: {}", field.name, field.ty)),
+                    blocks: self
+                        .blocks(core::slice::from_ref(field))
+                        .into_iter()
+                        .map(|block| Block {
+                            colour: PALETTE[i % PALETTE.len()],
+                            ..block
+                        })
+                        .collect(),
+                })
+                .collect(),
+            TypeStructure::Enum { repr, variants } => {
+                let encoding = EnumEncoding::of(self.graph, layout, Repr::parse(repr), variants);
+
+                variants
+                    .iter()
+                    .enumerate()
+                    .map(|(i, variant)| {
+                        let mut blocks = Vec::new();
+
+                        match encoding {
+                            EnumEncoding::Tagged { size } => blocks.push(Block {
+                                name: "<discriminant>",
+                                ty: repr,
+                                offset: 0,
+                                size,
+                                colour: TAG_COLOUR,
+                            }),
+                            EnumEncoding::NullPointer { none, some } if none == i => {
+                                // the niche is the all-zero pointer in the other variant
+                                if let Some(niche) = self.blocks(variants[some].fields).pop() {
+                                    blocks.push(Block {
+                                        name: "<niche>",
+                                        colour: TAG_COLOUR,
+                                        ..niche
+                                    });
+                                }
+                            },
+                            _ => (),
+                        }
+
+                        blocks.extend(self.blocks(variant.fields));
+
+                        Overlay {
+                            label: Some(match &variant.discriminant {
+                                MaybeUninhabited::Inhabited(discriminant) => {
+                                    format!(
+                                        "{} = {}",
+                                        variant.name,
+                                        discriminant.to_wrapping_i128()
+                                    )
+                                },
+                                MaybeUninhabited::Uninhabited => {
+                                    format!("{} (uninhabited)", variant.name)
+                                },
+                            }),
+                            blocks,
+                        }
+                    })
+                    .collect()
+            },
+        }
+    }
+
+    /// Returns the non-empty blocks that are covered by the inhabited
+    /// `fields`, whose types are known.
+    fn blocks(&self, fields: &[Field<'a>]) -> Vec<Block<'a>> {
+        fields
+            .iter()
+            .enumerate()
+            .filter_map(|(i, field)| {
+                let MaybeUninhabited::Inhabited(offset) = field.offset else {
+                    return None;
+                };
+
+                let size = self.graph.find_type(field.ty)?.size;
+
+                (size > 0).then_some(Block {
+                    name: field.name,
+                    ty: field.ty,
+                    offset,
+                    size,
+                    colour: PALETTE[i % PALETTE.len()],
+                })
+            })
+            .collect()
+    }
+}
+
+/// Text whose XML special characters are escaped when it is formatted.
+struct Escaped<'s>(&'s str);
+
+impl fmt::Display for Escaped<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt_escaped(fmt, self.0)
+    }
+}
+
+/// Draws a placeholder image that reports that the layout of the type `ty` is
+/// not included in the graph.
+fn fmt_unknown(fmt: &mut fmt::Formatter, ty: &str) -> fmt::Result {
+    let message = "unknown layout of ";
+
+    let width = MARGIN * 2 + (message.chars().count() + ty.chars().count()) * CHAR_WIDTH;
+    let height = MARGIN * 2 + TITLE_HEIGHT;
+
+    fmt.write_fmt(format_args!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\" font-family=\"monospace\" font-size=\"12\">\n"
+    ))?;
+    fmt.write_fmt(format_args!(
+        "<rect width=\"{width}\" height=\"{height}\" fill=\"#ffffff\"/>\n"
+    ))?;
+    fmt.write_fmt(format_args!(
+        "<text x=\"{MARGIN}\" y=\"{}\" font-size=\"14\" font-weight=\"bold\" \
+         fill=\"#cc0000\">{message}",
+        MARGIN + 16
+    ))?;
+    fmt_escaped(fmt, ty)?;
+    fmt.write_str("</text>\n</svg>\n")
+}
+
+fn fmt_escaped(fmt: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    for c in s.chars() {
+        match c {
+            '&' => fmt.write_str("&amp;")?,
+            '<' => fmt.write_str("&lt;")?,
+            '>' => fmt.write_str("&gt;")?,
+            '"' => fmt.write_str("&quot;")?,
+            c => fmt.write_fmt(format_args!("{c}"))?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Renders the escaped `s`, which is shortened with an ellipsis if it is
+/// longer than `max` characters.
+fn fmt_truncated(fmt: &mut fmt::Formatter, s: &str, max: usize) -> fmt::Result {
+    if s.chars().count() <= max {
+        return fmt_escaped(fmt, s);
+    }
+
+    if max == 0 {
+        return Ok(());
+    }
+
+    let end = s.char_indices().nth(max - 1).map_or(s.len(), |(i, _)| i);
+
+    fmt_escaped(fmt, &s[..end])?;
+    fmt.write_str("\u{2026}")
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use crate::{TypeGraphLayout, TypeLayoutGraph};
+
+    use super::ByteGrid;
+
+    #[test]
+    fn overlays() {
+        let graph = <core::cmp::Ordering>::TYPE_GRAPH;
+
+        let html = ByteGrid::graph(&graph).html(true).to_string();
+
+        assert!(html.starts_with("<!DOCTYPE html>\n"));
+        assert!(html.ends_with("</svg>\n</body>\n</html>\n"));
+
+        for label in ["Less = -1", "Equal = 0", "Greater = 1"] {
+            assert!(html.contains(&alloc::format!(">{label}</text>")), "{label}");
+        }
+
+        assert_eq!(
+            html.matches("<title>&lt;discriminant&gt;: i8 at offset 0, 1 byte</title>")
+                .count(),
+            3
+        );
+        assert!(!html.contains("padding:"));
+    }
+
+    #[test]
+    fn unknown_layout() {
+        let graph: TypeLayoutGraph = TypeLayoutGraph {
+            ty: "Option<&u8>",
+            tys: &[],
+        };
+
+        let svg = ByteGrid::graph(&graph).to_string();
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.contains(">unknown layout of Option&lt;&amp;u8&gt;</text>"));
+        assert!(svg.ends_with("</svg>\n"));
+
+        let html = ByteGrid::graph(&graph).html(true).to_string();
+
+        assert!(html.contains("<title>Option&lt;&amp;u8&gt;</title>"));
+        assert!(html.ends_with("</svg>\n</body>\n</html>\n"));
+    }
+}
@@ -0,0 +1,403 @@
+//! Helper module to render [`TypeLayoutGraph`]s and [`TypeLayoutInfo`]s in a
+//! compact text form at compile time, without allocating.
+//!
+//! Every type is rendered as one line, which contains its name, size,
+//! alignment, and structure, including the names, types, and offsets of its
+//! fields, e.g.
+//!
+//! ```text
+//! struct Foo (size 8, align 4, repr(C)) { a: u8 @ 0, b: u32 @ 4 }
+//! enum Option<u8> (size 2, align 1) { None = 0, Some = 1 { 0: u8 @ 1 } }
+//! primitive u8 (size 1, align 1)
+//! ```
+//!
+//! where uninhabited field offsets and variant discriminants are rendered as
+//! `!`. A [`TypeLayoutGraph`] is rendered as the lines of all of its types,
+//! each terminated by a newline.
+//!
+//! Like the binary format, the text is rendered in two passes, which first
+//! count the length of the text and then write it into a buffer of that
+//! length. The [`rendered_type_graph!`] and [`rendered_type_layout!`] macros
+//! render a type's layout into a `&'static str`, which can be embedded in a
+//! binary or be printed in a const panic message:
+//!
+//! ```rust
+//! # #![feature(const_type_name)]
+//! use const_type_layout::render::text::rendered_type_layout;
+//!
+//! const LAYOUT: &str = rendered_type_layout!(core::ops::Range<u16>);
+//!
+//! const _: () = assert!(
+//!     core::mem::size_of::<core::ops::Range<u16>>() == 4,
+//!     "{}",
+//!     LAYOUT
+//! );
+//!
+//! assert_eq!(
+//!     LAYOUT,
+//!     "struct core::ops::range::Range<u16> (size 4, align 2) { start: u16 @ 0, end: u16 @ 2 }"
+//! );
+//! ```
+
+use crate::{Field, MaybeUninhabited, TypeLayoutGraph, TypeLayoutInfo, TypeStructure};
+
+#[must_use]
+/// Compute the length in bytes of the text form of the `graph`.
+pub const fn graph_len(graph: &TypeLayoutGraph) -> usize {
+    let mut counter = Renderer::Counter { cursor: 0 };
+    counter.render_graph(graph);
+    counter.cursor()
+}
+
+#[must_use]
+/// Compute the length in bytes of the text form of the `layout`.
+pub const fn layout_len(layout: &TypeLayoutInfo) -> usize {
+    let mut counter = Renderer::Counter { cursor: 0 };
+    counter.render_layout(layout);
+    counter.cursor()
+}
+
+/// Render the `graph` into the start of the `buffer`, which must have a
+/// length of at least [`graph_len`], and return the rendered text.
+///
+/// # Panics
+///
+/// This function panics iff `buffer` has a length of less than
+/// [`graph_len`].
+pub const fn render_graph<'b>(graph: &TypeLayoutGraph, buffer: &'b mut [u8]) -> &'b str {
+    let mut writer = Renderer::Writer { buffer, cursor: 0 };
+    writer.render_graph(graph);
+    writer.into_str()
+}
+
+/// Render the `layout` into the start of the `buffer`, which must have a
+/// length of at least [`layout_len`], and return the rendered text.
+///
+/// # Panics
+///
+/// This function panics iff `buffer` has a length of less than
+/// [`layout_len`].
+pub const fn render_layout<'b>(layout: &TypeLayoutInfo, buffer: &'b mut [u8]) -> &'b str {
+    let mut writer = Renderer::Writer { buffer, cursor: 0 };
+    writer.render_layout(layout);
+    writer.into_str()
+}
+
+/// Render the [`TypeLayoutGraph`] of a type `T` into a `&'static str` at
+/// compile time, see [`render_graph`].
+///
+/// The type `T` cannot be generic, since the length of the text depends on
+/// it.
+pub macro rendered_type_graph($T:ty) {{
+    const LEN: usize =
+        $crate::render::text::graph_len(&<$T as $crate::TypeGraphLayout>::TYPE_GRAPH);
+    const RENDERED: &$crate::render::text::Rendered<LEN> =
+        &$crate::render::text::Rendered::graph(&<$T as $crate::TypeGraphLayout>::TYPE_GRAPH);
+    const TEXT: &str = RENDERED.as_str();
+
+    TEXT
+}}
+
+/// Render the [`TypeLayoutInfo`] of a type `T` into a `&'static str` at
+/// compile time, see [`render_layout`].
+///
+/// The type `T` cannot be generic, since the length of the text depends on
+/// it.
+pub macro rendered_type_layout($T:ty) {{
+    const LEN: usize = $crate::render::text::layout_len(&<$T as $crate::TypeLayout>::TYPE_LAYOUT);
+    const RENDERED: &$crate::render::text::Rendered<LEN> =
+        &$crate::render::text::Rendered::layout(&<$T as $crate::TypeLayout>::TYPE_LAYOUT);
+    const TEXT: &str = RENDERED.as_str();
+
+    TEXT
+}}
+
+#[doc(hidden)]
+/// Text form of a [`TypeLayoutGraph`] or [`TypeLayoutInfo`], which is
+/// rendered into an array of its exact length `N`.
+pub struct Rendered<const N: usize>([u8; N]);
+
+impl<const N: usize> Rendered<N> {
+    #[must_use]
+    /// Render the `graph` into an array of its exact [`graph_len`] `N`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics iff `N` is not the length of the text form.
+    pub const fn graph(graph: &TypeLayoutGraph) -> Self {
+        assert!(
+            N == graph_len(graph),
+            "[const-type-layout]: invalid rendered type graph length"
+        );
+
+        let mut bytes = [0_u8; N];
+        render_graph(graph, &mut bytes);
+        Self(bytes)
+    }
+
+    #[must_use]
+    /// Render the `layout` into an array of its exact [`layout_len`] `N`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics iff `N` is not the length of the text form.
+    pub const fn layout(layout: &TypeLayoutInfo) -> Self {
+        assert!(
+            N == layout_len(layout),
+            "[const-type-layout]: invalid rendered type layout length"
+        );
+
+        let mut bytes = [0_u8; N];
+        render_layout(layout, &mut bytes);
+        Self(bytes)
+    }
+
+    #[must_use]
+    pub const fn as_str(&self) -> &str {
+        // SAFETY: the bytes were rendered from UTF-8 strings and ASCII text
+        unsafe { core::str::from_utf8_unchecked(&self.0) }
+    }
+}
+
+enum Renderer<'a> {
+    Writer { buffer: &'a mut [u8], cursor: usize },
+    Counter { cursor: usize },
+}
+
+impl<'a> Renderer<'a> {
+    const fn cursor(self) -> usize {
+        let (Self::Counter { cursor } | Self::Writer { buffer: _, cursor }) = self;
+
+        cursor
+    }
+
+    const fn into_str(self) -> &'a str {
+        let Self::Writer { buffer, cursor } = self else {
+            panic!("not a writer");
+        };
+
+        // SAFETY: the first cursor bytes of the buffer were written by this
+        //  writer and are thus in bounds
+        let bytes = unsafe { core::slice::from_raw_parts(buffer.as_ptr(), cursor) };
+
+        // SAFETY: only UTF-8 strings and ASCII text are rendered
+        unsafe { core::str::from_utf8_unchecked(bytes) }
+    }
+
+    const fn write_bytes(&mut self, bytes: &[u8]) {
+        match self {
+            Self::Writer { buffer, cursor } => {
+                assert!(
+                    (*cursor + bytes.len()) <= buffer.len(),
+                    "writer buffer exceeded"
+                );
+
+                let mut i = 0;
+
+                while i < bytes.len() {
+                    buffer[*cursor] = bytes[i];
+                    *cursor += 1;
+                    i += 1;
+                }
+            },
+            Self::Counter { cursor } => *cursor += bytes.len(),
+        }
+    }
+
+    const fn write_str(&mut self, value: &str) {
+        self.write_bytes(value.as_bytes());
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    const fn write_u128(&mut self, value: u128) {
+        let mut digits = [0_u8; 39];
+        let mut start = digits.len();
+        let mut rem = value;
+
+        loop {
+            start -= 1;
+            digits[start] = b'0' + ((rem % 10) as u8);
+            rem /= 10;
+
+            if rem == 0 {
+                break;
+            }
+        }
+
+        while start < digits.len() {
+            self.write_bytes(&[digits[start]]);
+            start += 1;
+        }
+    }
+
+    const fn write_usize(&mut self, value: usize) {
+        self.write_u128(value as u128);
+    }
+
+    const fn write_i128(&mut self, value: i128) {
+        if value < 0 {
+            self.write_str("-");
+        }
+
+        self.write_u128(value.unsigned_abs());
+    }
+
+    const fn render_graph(&mut self, graph: &TypeLayoutGraph) {
+        let mut i = 0;
+
+        while i < graph.tys.len() {
+            self.render_layout(graph.tys[i]);
+            self.write_str("\n");
+
+            i += 1;
+        }
+    }
+
+    const fn render_layout(&mut self, layout: &TypeLayoutInfo) {
+        let repr = match layout.structure {
+            TypeStructure::Primitive => {
+                self.write_str("primitive ");
+                None
+            },
+            TypeStructure::Struct { repr, .. } => {
+                self.write_str("struct ");
+                Some(repr)
+            },
+            TypeStructure::Union { repr, .. } => {
+                self.write_str("union ");
+                Some(repr)
+            },
+            TypeStructure::Enum { repr, .. } => {
+                self.write_str("enum ");
+                Some(repr)
+            },
+        };
+
+        self.write_str(layout.name);
+        self.write_str(" (size ");
+        self.write_usize(layout.size);
+        self.write_str(", align ");
+        self.write_usize(layout.alignment);
+
+        if let Some(repr) = repr {
+            if !repr.is_empty() {
+                self.write_str(", repr(");
+                self.write_str(repr);
+                self.write_str(")");
+            }
+        }
+
+        self.write_str(")");
+
+        match layout.structure {
+            TypeStructure::Primitive => (),
+            TypeStructure::Struct { fields, .. } | TypeStructure::Union { fields, .. } => {
+                self.write_str(" ");
+                self.render_fields(fields);
+            },
+            TypeStructure::Enum { variants, .. } => {
+                self.write_str(" {");
+
+                let mut i = 0;
+
+                while i < variants.len() {
+                    let variant = &variants[i];
+
+                    if i > 0 {
+                        self.write_str(",");
+                    }
+
+                    self.write_str(" ");
+                    self.write_str(variant.name);
+                    self.write_str(" = ");
+
+                    match &variant.discriminant {
+                        MaybeUninhabited::Inhabited(discriminant) => {
+                            self.write_i128(discriminant.to_wrapping_i128());
+                        },
+                        MaybeUninhabited::Uninhabited => self.write_str("!"),
+                    }
+
+                    if !variant.fields.is_empty() {
+                        self.write_str(" ");
+                        self.render_fields(variant.fields);
+                    }
+
+                    i += 1;
+                }
+
+                self.write_str(if variants.is_empty() { "}" } else { " }" });
+            },
+        }
+    }
+
+    const fn render_fields(&mut self, fields: &[Field]) {
+        if fields.is_empty() {
+            self.write_str("{}");
+            return;
+        }
+
+        self.write_str("{");
+
+        let mut i = 0;
+
+        while i < fields.len() {
+            let field = &fields[i];
+
+            if i > 0 {
+                self.write_str(",");
+            }
+
+            self.write_str(" ");
+            self.write_str(field.name);
+            self.write_str(": ");
+            self.write_str(field.ty);
+            self.write_str(" @ ");
+
+            match field.offset {
+                MaybeUninhabited::Inhabited(offset) => self.write_usize(offset),
+                MaybeUninhabited::Uninhabited => self.write_str("!"),
+            }
+
+            i += 1;
+        }
+
+        self.write_str(" }");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TypeGraphLayout;
+
+    use super::{rendered_type_graph, rendered_type_layout};
+
+    #[test]
+    fn layout() {
+        assert_eq!(
+            rendered_type_layout!(Option<core::num::NonZeroI8>),
+            "enum core::option::Option<core::num::nonzero::NonZero<i8>> (size 1, align 1) { None \
+             = 0, Some = 1 { 0: core::num::nonzero::NonZero<i8> @ 0 } }"
+        );
+        assert_eq!(
+            rendered_type_layout!(core::convert::Infallible),
+            "enum core::convert::Infallible (size 0, align 1) {}"
+        );
+        assert_eq!(rendered_type_layout!(()), "primitive () (size 0, align 1)");
+    }
+
+    #[test]
+    fn graph() {
+        const GRAPH: &str = rendered_type_graph!(core::ops::Range<i16>);
+
+        assert_eq!(
+            GRAPH,
+            "struct core::ops::range::Range<i16> (size 4, align 2) { start: i16 @ 0, end: i16 @ 2 \
+             }\nprimitive i16 (size 2, align 2)\n"
+        );
+        assert_eq!(
+            GRAPH.len(),
+            super::graph_len(&<core::ops::Range<i16>>::TYPE_GRAPH)
+        );
+    }
+}
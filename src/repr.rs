@@ -0,0 +1,615 @@
+//! Helper module to interpret the `#[repr(...)]` attributes of a type, as
+//! stored in the `repr` string of a [`crate::TypeStructure`].
+
+use crate::{primitive, MaybeUninhabited, TypeLayoutGraph, TypeLayoutInfo, Variant};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Parsed representation of a type's `#[repr(...)]` attributes.
+///
+/// Unknown attributes inside the `repr` string are ignored.
+pub struct Repr {
+    /// The type is `#[repr(C)]`.
+    pub c: bool,
+    /// The type is `#[repr(transparent)]`.
+    pub transparent: bool,
+    /// The primitive integer representation of an enum's discriminant, e.g.
+    /// `#[repr(u8)]`.
+    pub int: Option<Integer>,
+    /// The maximum alignment of the type's fields, iff the type is
+    /// `#[repr(packed)]` or `#[repr(packed(N))]`.
+    pub packed: Option<usize>,
+    /// The minimum alignment of the type, iff it is `#[repr(align(N))]`.
+    pub align: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Primitive integer representation of an enum's discriminant.
+pub enum Integer {
+    /// `#[repr(u8)]`
+    U8,
+    /// `#[repr(u16)]`
+    U16,
+    /// `#[repr(u32)]`
+    U32,
+    /// `#[repr(u64)]`
+    U64,
+    /// `#[repr(u128)]`
+    U128,
+    /// `#[repr(usize)]`
+    Usize,
+    /// `#[repr(i8)]`
+    I8,
+    /// `#[repr(i16)]`
+    I16,
+    /// `#[repr(i32)]`
+    I32,
+    /// `#[repr(i64)]`
+    I64,
+    /// `#[repr(i128)]`
+    I128,
+    /// `#[repr(isize)]`
+    Isize,
+}
+
+impl Repr {
+    #[must_use]
+    /// Parse the comma-separated `repr` string of a [`crate::TypeStructure`],
+    /// e.g. `"C,align(128)"` or `"u8"`.
+    pub const fn parse(repr: &str) -> Self {
+        let mut this = Self {
+            c: false,
+            transparent: false,
+            int: None,
+            packed: None,
+            align: None,
+        };
+
+        let mut rest = repr.as_bytes();
+
+        while !rest.is_empty() {
+            let mut end = 0;
+
+            while end < rest.len() && rest[end] != b',' {
+                end += 1;
+            }
+
+            let (attr, tail) = rest.split_at(end);
+            this.parse_attribute(trim(attr));
+
+            rest = match tail.split_first() {
+                Some((_comma, tail)) => tail,
+                None => tail,
+            };
+        }
+
+        this
+    }
+
+    #[must_use]
+    /// Returns `true` iff the type uses the default Rust representation,
+    /// i.e. it is neither `#[repr(C)]`, `#[repr(transparent)]`, nor has a
+    /// primitive integer representation.
+    pub const fn is_rust(&self) -> bool {
+        !self.c && !self.transparent && self.int.is_none()
+    }
+
+    const fn parse_attribute(&mut self, attr: &[u8]) {
+        if bytes_eq(attr, b"C") {
+            self.c = true;
+        } else if bytes_eq(attr, b"transparent") {
+            self.transparent = true;
+        } else if bytes_eq(attr, b"packed") {
+            self.packed = Some(1);
+        } else if let Some(n) = parse_call(attr, b"packed") {
+            self.packed = Some(n);
+        } else if let Some(n) = parse_call(attr, b"align") {
+            self.align = Some(n);
+        } else if let Some(int) = Integer::parse(attr) {
+            self.int = Some(int);
+        }
+    }
+}
+
+impl Integer {
+    #[must_use]
+    /// The size of the integer type on the host.
+    pub const fn size(self) -> usize {
+        match self {
+            Self::U8 | Self::I8 => 1,
+            Self::U16 | Self::I16 => 2,
+            Self::U32 | Self::I32 => 4,
+            Self::U64 | Self::I64 => 8,
+            Self::U128 | Self::I128 => 16,
+            Self::Usize | Self::Isize => core::mem::size_of::<usize>(),
+        }
+    }
+
+    #[must_use]
+    /// Returns `true` iff the integer type is signed.
+    pub const fn is_signed(self) -> bool {
+        matches!(
+            self,
+            Self::I8 | Self::I16 | Self::I32 | Self::I64 | Self::I128 | Self::Isize
+        )
+    }
+
+    const fn parse(attr: &[u8]) -> Option<Self> {
+        const INTS: [(&[u8], Integer); 12] = [
+            (b"u8", Integer::U8),
+            (b"u16", Integer::U16),
+            (b"u32", Integer::U32),
+            (b"u64", Integer::U64),
+            (b"u128", Integer::U128),
+            (b"usize", Integer::Usize),
+            (b"i8", Integer::I8),
+            (b"i16", Integer::I16),
+            (b"i32", Integer::I32),
+            (b"i64", Integer::I64),
+            (b"i128", Integer::I128),
+            (b"isize", Integer::Isize),
+        ];
+
+        let mut i = 0;
+
+        while i < INTS.len() {
+            if bytes_eq(attr, INTS[i].0) {
+                return Some(INTS[i].1);
+            }
+
+            i += 1;
+        }
+
+        None
+    }
+}
+
+/// Encoding of an enum's active variant in memory, as far as it can be
+/// inferred from the enum's layout.
+pub(crate) enum EnumEncoding {
+    /// The discriminant is stored as an integer of `size` bytes at the start
+    /// of the enum.
+    Tagged { size: usize },
+    /// The enum only has a single inhabited `variant`, which needs no tag.
+    Single { variant: usize },
+    /// The `Option`-like enum uses the null-pointer optimisation, i.e. the
+    /// all-zero bit-pattern of the only field of the `some` variant encodes
+    /// the field-less `none` variant.
+    NullPointer { none: usize, some: usize },
+    /// The encoding of the variants is unknown, e.g. since the enum uses the
+    /// default Rust representation, which does not guarantee any encoding of
+    /// its discriminant.
+    Unknown,
+}
+
+impl EnumEncoding {
+    pub(crate) const fn of<'a>(
+        graph: &TypeLayoutGraph<'a>,
+        layout: &TypeLayoutInfo<'a>,
+        repr: Repr,
+        variants: &[Variant<'a>],
+    ) -> Self {
+        if let Some(int) = repr.int {
+            return Self::Tagged { size: int.size() };
+        }
+
+        if repr.c {
+            return match Self::c_tag_size(layout, variants) {
+                Some(size) => Self::Tagged { size },
+                None => Self::Unknown,
+            };
+        }
+
+        let mut inhabited = [0_usize; 2];
+        let mut num_inhabited = 0;
+
+        let mut i = 0;
+
+        while i < variants.len() {
+            if let MaybeUninhabited::Inhabited(_) = variants[i].discriminant {
+                if num_inhabited < inhabited.len() {
+                    inhabited[num_inhabited] = i;
+                }
+                num_inhabited += 1;
+            }
+
+            i += 1;
+        }
+
+        let (none, some) = match num_inhabited {
+            1 => {
+                return Self::Single {
+                    variant: inhabited[0],
+                }
+            },
+            2 if variants[inhabited[0]].fields.is_empty()
+                && variants[inhabited[1]].fields.len() == 1 =>
+            {
+                (inhabited[0], inhabited[1])
+            },
+            2 if variants[inhabited[1]].fields.is_empty()
+                && variants[inhabited[0]].fields.len() == 1 =>
+            {
+                (inhabited[1], inhabited[0])
+            },
+            _ => return Self::Unknown,
+        };
+
+        let field = &variants[some].fields[0];
+
+        let Some(field_layout) = graph.find_type(field.ty) else {
+            return Self::Unknown;
+        };
+
+        if !matches!(field.offset, MaybeUninhabited::Inhabited(0))
+            || field_layout.size != layout.size
+            || !primitive::is_non_nullable(field_layout)
+        {
+            return Self::Unknown;
+        }
+
+        Self::NullPointer { none, some }
+    }
+
+    /// The size of the tag of a `#[repr(C)]` enum, which has the same layout
+    /// as a field-less `#[repr(C)]` enum with the same discriminants.
+    ///
+    /// A field-less enum consists of only its tag. The tag of an enum with
+    /// fields is followed by the union of its variants, and so must end before
+    /// the first field. Its size is the smallest integer that fits all
+    /// discriminants, but at least that of a C enum on the target, e.g. one
+    /// byte on targets with short enums.
+    const fn c_tag_size(layout: &TypeLayoutInfo, variants: &[Variant]) -> Option<usize> {
+        #[repr(C)]
+        #[allow(dead_code)]
+        enum CEnum {
+            Zero,
+        }
+
+        let mut first_field = layout.size;
+        let mut min = 0_i128;
+        let mut max = 0_i128;
+
+        let mut i = 0;
+
+        while i < variants.len() {
+            let mut j = 0;
+
+            while j < variants[i].fields.len() {
+                if let MaybeUninhabited::Inhabited(offset) = variants[i].fields[j].offset {
+                    if offset < first_field {
+                        first_field = offset;
+                    }
+                }
+
+                j += 1;
+            }
+
+            if let MaybeUninhabited::Inhabited(discriminant) = &variants[i].discriminant {
+                let value = discriminant.to_wrapping_i128();
+
+                if value < min {
+                    min = value;
+                }
+                if value > max {
+                    max = value;
+                }
+            }
+
+            i += 1;
+        }
+
+        if first_field == layout.size {
+            return match layout.size {
+                1 | 2 | 4 | 8 => Some(layout.size),
+                _ => None,
+            };
+        }
+
+        let mut size = core::mem::size_of::<CEnum>();
+
+        while size < 8 && (min < -(1_i128 << (size * 8 - 1)) || max >= (1_i128 << (size * 8))) {
+            size *= 2;
+        }
+
+        if size > first_field {
+            return None;
+        }
+
+        Some(size)
+    }
+}
+
+const fn trim(mut bytes: &[u8]) -> &[u8] {
+    while let Some((first, rest)) = bytes.split_first() {
+        if !first.is_ascii_whitespace() {
+            break;
+        }
+        bytes = rest;
+    }
+
+    while let Some((last, rest)) = bytes.split_last() {
+        if !last.is_ascii_whitespace() {
+            break;
+        }
+        bytes = rest;
+    }
+
+    bytes
+}
+
+pub(crate) const fn bytes_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+
+        i += 1;
+    }
+
+    true
+}
+
+pub(crate) const fn bytes_cmp(a: &[u8], b: &[u8]) -> core::cmp::Ordering {
+    let mut i = 0;
+
+    while i < a.len() && i < b.len() {
+        if a[i] < b[i] {
+            return core::cmp::Ordering::Less;
+        }
+
+        if a[i] > b[i] {
+            return core::cmp::Ordering::Greater;
+        }
+
+        i += 1;
+    }
+
+    if a.len() < b.len() {
+        core::cmp::Ordering::Less
+    } else if a.len() > b.len() {
+        core::cmp::Ordering::Greater
+    } else {
+        core::cmp::Ordering::Equal
+    }
+}
+
+/// Parses `name(N)`, where whitespace may surround the parentheses and `N`.
+const fn parse_call(attr: &[u8], name: &[u8]) -> Option<usize> {
+    if attr.len() <= name.len() {
+        return None;
+    }
+
+    let (head, args) = attr.split_at(name.len());
+
+    if !bytes_eq(head, name) {
+        return None;
+    }
+
+    let args = trim(args);
+
+    let Some((b'(', args)) = args.split_first() else {
+        return None;
+    };
+    let Some((b')', args)) = args.split_last() else {
+        return None;
+    };
+
+    let digits = trim(args);
+
+    if digits.is_empty() {
+        return None;
+    }
+
+    let mut value = 0_usize;
+    let mut i = 0;
+
+    while i < digits.len() {
+        if !digits[i].is_ascii_digit() {
+            return None;
+        }
+
+        value = match value.checked_mul(10) {
+            Some(value) => match value.checked_add((digits[i] - b'0') as usize) {
+                Some(value) => value,
+                None => return None,
+            },
+            None => return None,
+        };
+
+        i += 1;
+    }
+
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cmp::Ordering;
+
+    use super::{bytes_cmp, EnumEncoding, Integer, Repr};
+    use crate::{Field, MaybeUninhabited, TypeLayoutGraph, TypeLayoutInfo, TypeStructure, Variant};
+
+    /// The encoding of an enum with the `repr`, `size`, `alignment`, and
+    /// `variants`.
+    const fn encoding(
+        repr: &'static str,
+        size: usize,
+        alignment: usize,
+        variants: &'static [Variant<'static>],
+    ) -> EnumEncoding {
+        let layout = TypeLayoutInfo {
+            name: "Enum",
+            size,
+            alignment,
+            structure: TypeStructure::Enum { repr, variants },
+        };
+        let graph: TypeLayoutGraph = TypeLayoutGraph {
+            ty: "Enum",
+            tys: &[],
+        };
+
+        EnumEncoding::of(&graph, &layout, Repr::parse(repr), variants)
+    }
+
+    const FIELDLESS: &[Variant<'static>] = &[
+        Variant {
+            name: "A",
+            discriminant: MaybeUninhabited::Inhabited(crate::discriminant!(0)),
+            fields: &[],
+        },
+        Variant {
+            name: "B",
+            discriminant: MaybeUninhabited::Inhabited(crate::discriminant!(1)),
+            fields: &[],
+        },
+    ];
+
+    macro_rules! with_field {
+        ($discriminant:expr, $offset:expr) => {
+            &[
+                Variant {
+                    name: "A",
+                    discriminant: MaybeUninhabited::Inhabited(crate::discriminant!(0)),
+                    fields: &[],
+                },
+                Variant {
+                    name: "B",
+                    discriminant: MaybeUninhabited::Inhabited(crate::discriminant!($discriminant)),
+                    fields: &[Field {
+                        name: "0",
+                        offset: MaybeUninhabited::Inhabited($offset),
+                        ty: "u64",
+                    }],
+                },
+            ]
+        };
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(Repr::parse(""), Repr::default());
+        assert!(Repr::parse("").is_rust());
+        assert_eq!(
+            Repr::parse("C,u8"),
+            Repr {
+                c: true,
+                int: Some(Integer::U8),
+                ..Repr::default()
+            }
+        );
+        assert_eq!(
+            Repr::parse("transparent"),
+            Repr {
+                transparent: true,
+                ..Repr::default()
+            }
+        );
+        assert_eq!(
+            Repr::parse(" C , align ( 128 ) "),
+            Repr {
+                c: true,
+                align: Some(128),
+                ..Repr::default()
+            }
+        );
+        assert_eq!(
+            Repr::parse("packed"),
+            Repr {
+                packed: Some(1),
+                ..Repr::default()
+            }
+        );
+        assert_eq!(
+            Repr::parse("C,packed(2)"),
+            Repr {
+                c: true,
+                packed: Some(2),
+                ..Repr::default()
+            }
+        );
+        assert!(!Repr::parse("isize").is_rust());
+        assert!(Repr::parse("align(8)").is_rust());
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(Repr::parse(","), Repr::default());
+        assert_eq!(Repr::parse("simd"), Repr::default());
+        assert_eq!(Repr::parse("align()"), Repr::default());
+        assert_eq!(Repr::parse("align(x)"), Repr::default());
+        assert_eq!(Repr::parse("align(8"), Repr::default());
+        assert_eq!(Repr::parse("aligned(8)"), Repr::default());
+        assert_eq!(
+            Repr::parse("packed(99999999999999999999999999)"),
+            Repr::default()
+        );
+        assert_eq!(Repr::parse("U8"), Repr::default());
+    }
+
+    #[test]
+    fn integer() {
+        assert_eq!(Integer::U16.size(), 2);
+        assert_eq!(Integer::I128.size(), 16);
+        assert_eq!(Integer::Usize.size(), core::mem::size_of::<usize>());
+        assert!(Integer::Isize.is_signed());
+        assert!(!Integer::U64.is_signed());
+    }
+
+    #[test]
+    fn enum_encoding() {
+        #[repr(C)]
+        #[allow(dead_code)]
+        enum CEnum {
+            Zero,
+        }
+
+        let c_enum = core::mem::size_of::<CEnum>();
+
+        // the default representation does not guarantee any encoding
+        assert!(matches!(
+            encoding("", 1, 1, FIELDLESS),
+            EnumEncoding::Unknown
+        ));
+        assert!(matches!(
+            encoding("u16", 2, 2, FIELDLESS),
+            EnumEncoding::Tagged { size: 2 }
+        ));
+        // short C enums only take up a single byte
+        assert!(matches!(
+            encoding("C", 1, 1, FIELDLESS),
+            EnumEncoding::Tagged { size: 1 }
+        ));
+        assert!(matches!(
+            encoding("C", 4, 4, FIELDLESS),
+            EnumEncoding::Tagged { size: 4 }
+        ));
+        // the tag is followed by padding up to the field
+        assert!(matches!(
+            encoding("C", 16, 8, with_field!(1, 8)),
+            EnumEncoding::Tagged { size } if size == c_enum
+        ));
+        assert!(matches!(
+            encoding("C", 16, 8, with_field!(1_i64 << 40, 8)),
+            EnumEncoding::Tagged { size: 8 }
+        ));
+        // the discriminant does not fit before the field
+        assert!(matches!(
+            encoding("C", 16, 8, with_field!(1_i64 << 40, 4)),
+            EnumEncoding::Unknown
+        ));
+    }
+
+    #[test]
+    fn compare() {
+        assert_eq!(bytes_cmp(b"a", b"b"), Ordering::Less);
+        assert_eq!(bytes_cmp(b"ab", b"a"), Ordering::Greater);
+        assert_eq!(bytes_cmp(b"", b""), Ordering::Equal);
+        assert_eq!(bytes_cmp(b"ab", b"ab"), Ordering::Equal);
+    }
+}
@@ -0,0 +1,132 @@
+//! Helper module to embed the serialised [`TypeLayoutGraph`]s of types into a
+//! dedicated link section of a binary, from which they can be extracted without
+//! running the binary.
+//!
+//! [`TypeLayoutGraph`]: crate::TypeLayoutGraph
+
+use crate::TypeGraphLayout;
+
+/// Name of the link section into which [`embed_type_graphs!`] places the
+/// serialised [`TypeLayoutGraph`](crate::TypeLayoutGraph)s on ELF and PE
+/// targets.
+pub const NAME: &str = ".const_type_layout";
+
+/// Name of the link section into which [`embed_type_graphs!`] places the
+/// serialised [`TypeLayoutGraph`](crate::TypeLayoutGraph)s on Mach-O targets,
+/// inside the `__DATA` segment.
+pub const MACHO_NAME: &str = "__ctlayout";
+
+/// Helper macro to embed the serialised
+/// [`TypeLayoutGraph`](crate::TypeLayoutGraph)s of a list of types into the
+/// [`NAME`] link section of the binary, static library, or object file that
+/// is being built.
+///
+/// Each graph is serialised with
+/// [`TypeLayoutGraph::serialise`](crate::TypeLayoutGraph::serialise). Since
+/// the serialised form starts with a header that contains its own length, the
+/// graphs can be extracted from the section, into which the linker
+/// concatenates them, one after the other.
+///
+/// The types must not depend on generic parameters from an outer scope, but
+/// the macro can be invoked several times in the same scope.
+///
+/// The graphs are marked as `#[used(linker)]` such that the linker retains
+/// them even though they are never referenced, which requires the crate that
+/// invokes this macro to enable the nightly-only `used_with_arg` feature.
+///
+/// ```rust
+/// # #![feature(const_type_name)]
+/// #![feature(used_with_arg)]
+///
+/// # use const_type_layout::section::embed_type_graphs;
+/// embed_type_graphs![u8, Option<&'static u16>, core::ops::Range<usize>];
+/// ```
+pub macro embed_type_graphs($($ty:ty),* $(,)?) {
+    $(const _: () = {
+        const LEN: usize = $crate::serialised_type_graph_len::<$ty>();
+
+        #[used(linker)]
+        #[cfg_attr(target_vendor = "apple", link_section = "__DATA,__ctlayout")]
+        #[cfg_attr(not(target_vendor = "apple"), link_section = ".const_type_layout")]
+        static TYPE_GRAPH: [u8; LEN] = $crate::section::serialise::<$ty, LEN>();
+    };)*
+}
+
+#[doc(hidden)]
+#[must_use]
+/// Serialise the [`TypeLayoutGraph`](crate::TypeLayoutGraph) of `T` into an
+/// array of its exact [`crate::serialised_type_graph_len`] `N`.
+///
+/// # Panics
+///
+/// This function panics iff `N` is not the serialised length of the graph.
+pub const fn serialise<T: TypeGraphLayout, const N: usize>() -> [u8; N] {
+    assert!(
+        N == T::TYPE_GRAPH.serialised_len(),
+        "[const-type-layout]: invalid serialised type graph length"
+    );
+
+    let mut bytes = [0_u8; N];
+
+    T::TYPE_GRAPH.serialise(&mut bytes);
+
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use crate::{wire::MAGIC, TypeGraphLayout};
+
+    use super::serialise;
+
+    #[test]
+    fn serialise_exact() {
+        type Ty = Option<&'static u16>;
+
+        const LEN: usize = crate::serialised_type_graph_len::<Ty>();
+        const BYTES: [u8; LEN] = serialise::<Ty, LEN>();
+
+        let mut bytes = vec![0_u8; Ty::TYPE_GRAPH.serialised_len()];
+        Ty::TYPE_GRAPH.serialise(&mut bytes);
+
+        assert_eq!(BYTES.as_slice(), bytes);
+        assert!(BYTES.starts_with(&MAGIC));
+    }
+
+    #[test]
+    fn concatenated() {
+        // graphs embedded one after the other can be split by their header
+        let mut section = vec![];
+        section.extend_from_slice(&serialise::<u8, { crate::serialised_type_graph_len::<u8>() }>());
+        section.extend_from_slice(&serialise::<
+            (u8, u16),
+            { crate::serialised_type_graph_len::<(u8, u16)>() },
+        >());
+
+        let mut graphs = vec![];
+        let mut rest = section.as_slice();
+        while !rest.is_empty() {
+            assert!(rest.starts_with(&MAGIC));
+            // version 1 and the lengths of these small graphs fit into one byte
+            assert_eq!(rest[MAGIC.len()], 1);
+            let len = usize::from(rest[MAGIC.len() + 1]);
+            assert!(len < 0x80);
+            let (graph, tail) = rest.split_at(len);
+            graphs.push(graph);
+            rest = tail;
+        }
+
+        assert_eq!(graphs.len(), 2);
+        assert_eq!(graphs[0].len(), u8::TYPE_GRAPH.serialised_len());
+        assert_eq!(graphs[1].len(), <(u8, u16)>::TYPE_GRAPH.serialised_len());
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid serialised type graph length")]
+    fn serialise_wrong_len() {
+        let bytes = serialise::<u8, 1>();
+        unreachable!("serialised {bytes:?}");
+    }
+}
@@ -1,9 +1,86 @@
 use crate::{
-    Discriminant, Field, MaybeUninhabited, TypeLayoutGraph, TypeLayoutInfo, TypeStructure, Variant,
+    repr::bytes_eq, wire, Discriminant, Field, MaybeUninhabited, TypeLayoutGraph, TypeLayoutInfo,
+    TypeStructure, Variant,
 };
 
+#[derive(Clone, Copy)]
+/// Streaming state of the 128-bit SipHash-2-4.
+pub struct SipState {
+    v0: u64,
+    v1: u64,
+    v2: u64,
+    v3: u64,
+    tail: u64,
+    len: usize,
+}
+
+impl SipState {
+    pub const fn new(k0: u64, k1: u64) -> Self {
+        Self {
+            v0: k0 ^ 0x736f_6d65_7073_6575,
+            v1: k1 ^ 0x646f_7261_6e64_6f6d ^ 0xee,
+            v2: k0 ^ 0x6c79_6765_6e65_7261,
+            v3: k1 ^ 0x7465_6462_7974_6573,
+            tail: 0,
+            len: 0,
+        }
+    }
+
+    const fn round(&mut self) {
+        self.v0 = self.v0.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(13) ^ self.v0;
+        self.v0 = self.v0.rotate_left(32);
+        self.v2 = self.v2.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(16) ^ self.v2;
+        self.v0 = self.v0.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(21) ^ self.v0;
+        self.v2 = self.v2.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(17) ^ self.v2;
+        self.v2 = self.v2.rotate_left(32);
+    }
+
+    const fn compress(&mut self, m: u64) {
+        self.v3 ^= m;
+        self.round();
+        self.round();
+        self.v0 ^= m;
+    }
+
+    pub const fn write_byte(&mut self, byte: u8) {
+        self.tail |= (byte as u64) << ((self.len % 8) * 8);
+        self.len += 1;
+
+        if self.len % 8 == 0 {
+            self.compress(self.tail);
+            self.tail = 0;
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn finish(mut self) -> u128 {
+        self.compress(self.tail | ((self.len as u64) << 56));
+
+        self.v2 ^= 0xee;
+        self.round();
+        self.round();
+        self.round();
+        self.round();
+        let lo = self.v0 ^ self.v1 ^ self.v2 ^ self.v3;
+
+        self.v1 ^= 0xdd;
+        self.round();
+        self.round();
+        self.round();
+        self.round();
+        let hi = self.v0 ^ self.v1 ^ self.v2 ^ self.v3;
+
+        ((hi as u128) << 64) | (lo as u128)
+    }
+}
+
 pub enum Serialiser<'a> {
     Hasher { state: u64 },
+    SipHasher { state: SipState },
     Writer { buffer: &'a mut [u8], cursor: usize },
     Counter { cursor: usize },
 }
@@ -30,6 +107,22 @@ impl<'a> Serialiser<'a> {
         state
     }
 
+    #[must_use]
+    pub const fn sip_hasher(k0: u64, k1: u64) -> Self {
+        Self::SipHasher {
+            state: SipState::new(k0, k1),
+        }
+    }
+
+    #[must_use]
+    pub const fn hash128(self) -> u128 {
+        let Self::SipHasher { state } = self else {
+            panic!("not a SipHash hasher");
+        };
+
+        state.finish()
+    }
+
     #[must_use]
     pub const fn writer(buffer: &'a mut [u8], cursor: usize) -> Self {
         Self::Writer { buffer, cursor }
@@ -63,6 +156,14 @@ impl Serialiser<'_> {
                     i += 1;
                 }
             },
+            Self::SipHasher { state } => {
+                let mut i = 0;
+
+                while i < bytes.len() {
+                    state.write_byte(bytes[i]);
+                    i += 1;
+                }
+            },
             Self::Writer { buffer, cursor } => {
                 assert!(
                     (*cursor + bytes.len()) <= buffer.len(),
@@ -88,6 +189,7 @@ impl Serialiser<'_> {
                 *state ^= byte as u64;
                 *state = state.wrapping_mul(Self::FNV_PRIME);
             },
+            Self::SipHasher { state } => state.write_byte(byte),
             Self::Writer { buffer, cursor } => {
                 assert!((*cursor + 1) <= buffer.len(), "writer buffer exceeded");
 
@@ -246,10 +348,28 @@ impl Serialiser<'_> {
         self.serialise_type_structure(&value.structure);
     }
 
-    pub const fn serialise_type_layout_graph(&mut self, value: &TypeLayoutGraph) {
-        // Include the crate version of `type_layout` for cross-version comparison
-        self.serialise_str(env!("CARGO_PKG_VERSION"));
+    pub const fn serialise_header(&mut self, version: usize, len: usize) {
+        self.write_bytes(&wire::MAGIC);
+        self.serialise_usize(version);
+        self.serialise_usize(len);
+    }
+
+    /// Serialises the graph `value` in the body of the binary format
+    /// `version`, which must be either [`wire::CANONICAL_VERSION`] or
+    /// [`wire::COMPACT_VERSION`].
+    pub const fn serialise_versioned_type_layout_graph(
+        &mut self,
+        version: usize,
+        value: &TypeLayoutGraph,
+    ) {
+        match version {
+            wire::CANONICAL_VERSION => self.serialise_type_layout_graph(value),
+            wire::COMPACT_VERSION => self.serialise_compact_type_layout_graph(value),
+            _ => panic!("unsupported binary format version"),
+        }
+    }
 
+    pub const fn serialise_type_layout_graph(&mut self, value: &TypeLayoutGraph) {
         self.serialise_str(value.ty);
 
         self.serialise_usize(value.tys.len());
@@ -262,4 +382,277 @@ impl Serialiser<'_> {
             i += 1;
         }
     }
+
+    pub const fn serialise_compact_type_layout_graph(&mut self, value: &TypeLayoutGraph) {
+        self.serialise_usize(value.tys.len());
+
+        let mut i = 0;
+
+        while i < value.tys.len() {
+            self.serialise_str(value.tys[i].name);
+
+            i += 1;
+        }
+
+        self.serialise_type_ref(value, value.ty);
+
+        let mut strings = StringTable::new();
+
+        let mut i = 0;
+
+        while i < value.tys.len() {
+            let layout = value.tys[i];
+
+            self.serialise_usize(layout.size);
+            self.serialise_usize(layout.alignment);
+
+            match layout.structure {
+                TypeStructure::Primitive => self.serialise_byte(b'p'),
+                TypeStructure::Struct { repr, fields } => {
+                    self.serialise_byte(b's');
+                    self.serialise_string_ref(repr, &mut strings);
+                    self.serialise_compact_fields(value, fields, &mut strings);
+                },
+                TypeStructure::Union { repr, fields } => {
+                    self.serialise_byte(b'u');
+                    self.serialise_string_ref(repr, &mut strings);
+                    self.serialise_compact_fields(value, fields, &mut strings);
+                },
+                TypeStructure::Enum { repr, variants } => {
+                    self.serialise_byte(b'e');
+                    self.serialise_string_ref(repr, &mut strings);
+                    self.serialise_usize(variants.len());
+
+                    let mut v = 0;
+
+                    while v < variants.len() {
+                        let variant = &variants[v];
+
+                        self.serialise_string_ref(variant.name, &mut strings);
+                        self.serialise_maybe_uninhabited(match variant.discriminant {
+                            MaybeUninhabited::Inhabited(_) => MaybeUninhabited::Inhabited(()),
+                            MaybeUninhabited::Uninhabited => MaybeUninhabited::Uninhabited,
+                        });
+                        match &variant.discriminant {
+                            MaybeUninhabited::Inhabited(discriminant) => {
+                                self.serialise_discriminant(discriminant);
+                            },
+                            MaybeUninhabited::Uninhabited => (),
+                        };
+                        self.serialise_compact_fields(value, variant.fields, &mut strings);
+
+                        v += 1;
+                    }
+                },
+            }
+
+            i += 1;
+        }
+    }
+
+    const fn serialise_compact_fields<'s>(
+        &mut self,
+        graph: &TypeLayoutGraph,
+        value: &[Field<'s>],
+        strings: &mut StringTable<'s>,
+    ) {
+        self.serialise_usize(value.len());
+
+        let mut i = 0;
+
+        while i < value.len() {
+            let field = &value[i];
+
+            self.serialise_string_ref(field.name, strings);
+            self.serialise_maybe_uninhabited(match field.offset {
+                MaybeUninhabited::Inhabited(_) => MaybeUninhabited::Inhabited(()),
+                MaybeUninhabited::Uninhabited => MaybeUninhabited::Uninhabited,
+            });
+            match field.offset {
+                MaybeUninhabited::Inhabited(offset) => self.serialise_usize(offset),
+                MaybeUninhabited::Uninhabited => (),
+            };
+            self.serialise_type_ref(graph, field.ty);
+
+            i += 1;
+        }
+    }
+
+    /// Serialises a reference to the type `name`, which is either its index
+    /// in the graph's type name table, or the table's length followed by the
+    /// name if the type is missing from the graph.
+    const fn serialise_type_ref(&mut self, graph: &TypeLayoutGraph, name: &str) {
+        if let Some(i) = graph.find_type_index(name) {
+            self.serialise_usize(i);
+        } else {
+            self.serialise_usize(graph.tys.len());
+            self.serialise_str(name);
+        }
+    }
+
+    /// Serialises the string `value` in the next string slot, either as a
+    /// reference `k + 1` to the earlier slot `k` with the same string, or as
+    /// a zero followed by the string itself.
+    const fn serialise_string_ref<'s>(&mut self, value: &'s str, strings: &mut StringTable<'s>) {
+        if let Some(k) = strings.intern(value) {
+            self.serialise_usize(k + 1);
+        } else {
+            self.serialise_usize(0);
+            self.serialise_str(value);
+        }
+    }
+}
+
+/// The maximum number of distinct strings that [`StringTable`] deduplicates.
+const STRING_TABLE_CAPACITY: usize = 1024;
+
+/// Hash table from the strings of a graph to the first string slot that
+/// contains them, which deduplicates the reprs, variant names, and field
+/// names of the compact binary format in a single pass.
+///
+/// The table only stores up to [`STRING_TABLE_CAPACITY`] distinct strings,
+/// after which new strings are always serialised inline.
+pub struct StringTable<'s> {
+    entries: [Option<(&'s str, usize)>; STRING_TABLE_CAPACITY * 2],
+    len: usize,
+    slot: usize,
+}
+
+impl<'s> StringTable<'s> {
+    const fn new() -> Self {
+        Self {
+            entries: [None; STRING_TABLE_CAPACITY * 2],
+            len: 0,
+            slot: 0,
+        }
+    }
+
+    /// Assigns the next string slot to the string `value` and returns the
+    /// first earlier slot that contains the same string, if any.
+    #[allow(clippy::cast_possible_truncation)]
+    const fn intern(&mut self, value: &'s str) -> Option<usize> {
+        let slot = self.slot;
+        self.slot += 1;
+
+        let mut hasher = Serialiser::hasher(0);
+        hasher.write_bytes(value.as_bytes());
+
+        // the table is at most half full, so the probing always ends
+        let mut i = (hasher.hash() % (self.entries.len() as u64)) as usize;
+
+        loop {
+            match self.entries[i] {
+                Some((string, first)) if bytes_eq(string.as_bytes(), value.as_bytes()) => {
+                    return Some(first);
+                },
+                Some(_) => i = (i + 1) % self.entries.len(),
+                None => {
+                    if self.len < STRING_TABLE_CAPACITY {
+                        self.entries[i] = Some((value, slot));
+                        self.len += 1;
+                    }
+
+                    return None;
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Field, MaybeUninhabited, TypeLayoutGraph, TypeLayoutInfo, TypeStructure};
+
+    macro_rules! layout {
+        ($name:literal, $ty:literal) => {
+            TypeLayoutInfo {
+                name: $name,
+                size: 0,
+                alignment: 1,
+                structure: TypeStructure::Struct {
+                    repr: "C",
+                    fields: &fields!($name, $ty; f0 f1 f2 f3 f4 f5 f6 f7 f8 f9 f10 f11 f12 f13 f14 f15 f16 f17 f18 f19 f20 f21 f22 f23 f24 f25 f26 f27 f28 f29 f30 f31 f32 f33 f34 f35 f36 f37 f38 f39),
+                },
+            }
+        };
+    }
+
+    macro_rules! fields {
+        ($name:literal, $ty:literal; $($field:ident)*) => {
+            [$(Field {
+                name: concat!($name, "::", stringify!($field)),
+                offset: MaybeUninhabited::Inhabited(0),
+                ty: $ty,
+            }),*]
+        };
+    }
+
+    /// Graph of 40 structs with 40 uniquely named fields each, which has
+    /// more distinct strings than the string table deduplicates.
+    const LARGE: TypeLayoutGraph<'static> = TypeLayoutGraph {
+        ty: "T39",
+        tys: &[
+            &layout!("T39", "T38"),
+            &layout!("T00", "u8"),
+            &layout!("T01", "T00"),
+            &layout!("T02", "T01"),
+            &layout!("T03", "T02"),
+            &layout!("T04", "T03"),
+            &layout!("T05", "T04"),
+            &layout!("T06", "T05"),
+            &layout!("T07", "T06"),
+            &layout!("T08", "T07"),
+            &layout!("T09", "T08"),
+            &layout!("T10", "T09"),
+            &layout!("T11", "T10"),
+            &layout!("T12", "T11"),
+            &layout!("T13", "T12"),
+            &layout!("T14", "T13"),
+            &layout!("T15", "T14"),
+            &layout!("T16", "T15"),
+            &layout!("T17", "T16"),
+            &layout!("T18", "T17"),
+            &layout!("T19", "T18"),
+            &layout!("T20", "T19"),
+            &layout!("T21", "T20"),
+            &layout!("T22", "T21"),
+            &layout!("T23", "T22"),
+            &layout!("T24", "T23"),
+            &layout!("T25", "T24"),
+            &layout!("T26", "T25"),
+            &layout!("T27", "T26"),
+            &layout!("T28", "T27"),
+            &layout!("T29", "T28"),
+            &layout!("T30", "T29"),
+            &layout!("T31", "T30"),
+            &layout!("T32", "T31"),
+            &layout!("T33", "T32"),
+            &layout!("T34", "T33"),
+            &layout!("T35", "T34"),
+            &layout!("T36", "T35"),
+            &layout!("T37", "T36"),
+            &layout!("T38", "T37"),
+            &TypeLayoutInfo {
+                name: "u8",
+                size: 1,
+                alignment: 1,
+                structure: TypeStructure::Primitive,
+            },
+        ],
+    };
+
+    #[test]
+    fn large_graph() {
+        // both lengths are computed at compile time
+        const LEN: usize = LARGE.serialised_len();
+        const COMPACT_LEN: usize = LARGE.serialised_compact_len();
+
+        let mut bytes = [0_u8; COMPACT_LEN];
+        LARGE.serialise_compact(&mut bytes);
+
+        let mut canonical = [0_u8; LEN];
+        LARGE.serialise(&mut canonical);
+
+        assert!(bytes.len() < canonical.len());
+    }
 }
@@ -1,22 +1,65 @@
 //! Helper module to compute the set of types that a type links to and expand it
 //! into the complete type graph.
 
+use crate::TypeLayoutInfo;
+
 #[doc(hidden)]
 pub trait ComputeSet: sealed::ComputeSet {
+    type Output<H: ComputeTypeSet>: ExpandTypeSet;
+
     const LEN: usize;
-    type Len;
+    /// The number of types at the front of [`ComputeSet::TYS`] that were
+    /// visited, which are followed by the witnesses.
+    #[cfg(feature = "typeset-without-specialization")]
+    const VISITED: usize;
 
-    type Output<H: ComputeTypeSet>: ExpandTypeSet;
+    type TyHList: 'static + Copy;
+    const TYS: Self::TyHList;
 
-    type TyHList: 'static + Copy + core::marker::Freeze;
-    const TYS: &'static Self::TyHList;
+    /// Insert the types of `Self` into the set `S` if `Self` was produced by
+    /// the deprecated [`tset`] syntax without a tail, otherwise `Self`.
+    type Rebase<S: ExpandTypeSet>: ExpandTypeSet;
 }
 
 mod sealed {
     pub trait ComputeSet {}
 
-    impl ComputeSet for super::private::Empty {}
-    impl<H2: super::ComputeTypeSet, T: ComputeSet> ComputeSet for super::private::Cons<H2, T> {}
+    impl<V: super::private::Trie, F: super::private::Tree, W: super::private::Tree> ComputeSet
+        for super::private::Expand<V, F, W>
+    {
+    }
+
+    impl<L> ComputeSet for super::private::Detached<L> {}
+
+    pub trait TypeKey {}
+
+    impl TypeKey for super::key::Nil {}
+
+    impl<D: super::key::Digit, K: super::TypeKey> TypeKey for super::key::Cons<D, K> {}
+
+    /// The [`ComputeTypeKey::Key`](super::ComputeTypeKey::Key) of a type, or
+    /// the key of the empty name for all types that do not implement
+    /// [`ComputeTypeKey`](super::ComputeTypeKey).
+    #[cfg(not(feature = "typeset-without-specialization"))]
+    pub trait KeyOf {
+        type Key: super::TypeKey;
+    }
+
+    /// The [`ComputeTypeKey::Key`](super::ComputeTypeKey::Key) of a type,
+    /// which every type must provide without specialisation.
+    #[cfg(feature = "typeset-without-specialization")]
+    pub trait KeyOf: super::ComputeTypeKey {
+        type Key: super::TypeKey;
+    }
+
+    #[cfg(not(feature = "typeset-without-specialization"))]
+    impl<T> KeyOf for T {
+        default type Key = super::tkey![""];
+    }
+
+    impl<T: super::ComputeTypeKey> KeyOf for T {
+        type Key = T::Key;
+    }
 }
 
 type Set<H, T> = <T as ComputeSet>::Output<H>;
@@ -40,7 +83,7 @@ type Set<H, T> = <T as ComputeSet>::Output<H>;
 /// #    Field, MaybeUninhabited, TypeLayout, TypeLayoutInfo, TypeStructure,
 /// # };
 /// # use const_type_layout::inhabited;
-/// # use const_type_layout::typeset::{ComputeTypeSet, ExpandTypeSet, tset};
+/// # use const_type_layout::typeset::{ComputeTypeKey, ComputeTypeSet, ExpandTypeSet, tkey, tset};
 /// struct Foo {
 ///     a: u8,
 ///     b: u16,
@@ -72,13 +115,19 @@ type Set<H, T> = <T as ComputeSet>::Output<H>;
 /// # }
 ///
 /// unsafe impl ComputeTypeSet for Foo {
-///     type Output<T: ExpandTypeSet> = tset![u8, u16];
+///     type Output<T: ExpandTypeSet> = tset![u8, u16, .. @ T];
+/// }
+///
+/// impl ComputeTypeKey for Foo {
+///     type Key = tkey!["Foo"];
 /// }
 /// ```
 ///
 /// Note that to you implement [`ComputeTypeSet`] you must also implement
-/// [`crate::TypeLayout`] for it.
-pub unsafe trait ComputeTypeSet: crate::TypeLayout {
+/// [`crate::TypeLayout`] for it, and should implement [`ComputeTypeKey`]. With
+/// the `typeset-without-specialization` feature, implementing
+/// [`ComputeTypeKey`] is required.
+pub unsafe trait ComputeTypeSet: crate::TypeLayout + sealed::KeyOf {
     /// Extend the set `T` into a (larger) set containing also the types this
     /// type links to.
     ///
@@ -88,116 +137,1597 @@ pub unsafe trait ComputeTypeSet: crate::TypeLayout {
     type Output<T: ExpandTypeSet>: ExpandTypeSet;
 }
 
-/// Helper macro to expand a list of types, e.g. `H, R1, R2`, and an optional
-/// tail, `.. @ T`, into a set of types.
+/// Provides the key by which a type is looked up in the set of types that
+/// have already been visited while computing a type set.
+///
+/// Types with different keys are found in a logarithmic number of steps,
+/// while types with the same key, e.g. all types that do not implement this
+/// trait, are compared one by one. The key only affects how fast a type set is
+/// computed, never which types it contains.
+///
+/// With the `typeset-without-specialization` feature, types cannot be compared
+/// and every type must implement this trait. A type whose key has already been
+/// visited is then not expanded again, and the type set fails to compile if
+/// this leaves out any type that is linked to.
+#[cfg_attr(
+    feature = "typeset-without-specialization",
+    doc = r##"
+For instance, arrays of different lengths share their key, and so
+`[[u8; 2]; 1]` is not expanded next to `[[u8; 1]; 1]`, which leaves out
+`[u8; 2]`:
+
+```rust,compile_fail
+# #![feature(const_type_name)]
+use const_type_layout::{TypeGraphLayout, TypeLayoutGraph};
+
+const GRAPH: TypeLayoutGraph = <([[u8; 1]; 1], [[u8; 2]; 1])>::TYPE_GRAPH;
+```
+"##
+)]
+pub trait ComputeTypeKey {
+    /// The key of this type, which is produced by the [`tkey`] helper macro.
+    type Key: TypeKey;
+}
+
+/// Helper macro to expand a list of types, e.g. `H, R1, R2`, and a tail,
+/// `.. @ T`, into a set of types.
 ///
 /// This macro is used when implementing the [`ComputeTypeSet::Output`]
-/// associated type to specify the list of types a type links to.
+/// associated type to specify the list of types a type links to. The tail `T`
+/// must be the set that is passed into [`ComputeTypeSet::Output`].
+///
+/// The list of types can also be given without a tail, e.g. `tset![H, R1]` or
+/// `tset![]`, which was the only syntax before the tail was introduced. This
+/// syntax is deprecated since the set must then be inserted into the tail
+/// separately, which makes the type set computation slower.
 pub macro tset {
-    () => { private::Empty },
+    () => { private::Legacy },
     (.. @ $T:tt) => { $T },
-    ($H:ty $(, $R:ty)*) => {
-        Set<$H, tset![$($R),*]>
-    },
     ($H:ty, $($R:ty,)* .. @ $T:ty ) => {
         Set<$H, tset![$($R,)* .. @ $T]>
     },
+    ($H:ty $(, $R:ty)* $(,)?) => {
+        Set<$H, tset![$($R),*]>
+    },
 }
 
-#[doc(hidden)]
-pub trait ExpandTypeSet: ComputeSet {
-    type Output<T: ExpandTypeSet>: ExpandTypeSet;
+/// Helper macro to produce a [`ComputeTypeKey::Key`] from the name of a type,
+/// e.g. `"core::option::Option"`, and its generic type parameters, e.g.
+/// `; T, U`.
+///
+/// The name is hashed with the 32-bit FNV-1a hash during const evaluation and
+/// combined with the keys of the type parameters such that e.g. `Foo<A, B>` and
+/// `Foo<B, A>` get different keys, and such that nesting e.g. `Foo<Foo<A>>`
+/// does not quickly repeat a key. The name must be a const `&str` expression
+/// that does not depend on any generic parameters.
+pub macro tkey {
+    (@mix $K:ty; ) => { $K },
+    (@mix $K:ty; $T:ty $(, $R:ty)*) => {
+        <<<$K as TypeKey>::Xor<
+            <tkey![@mix <$T as ComputeTypeKey>::Key; $($R),*] as TypeKey>::Rotate
+        > as TypeKey>::Substitute as TypeKey>::Add<$K>
+    },
+    ($name:expr $(; $($T:ty),* $(,)?)?) => {
+        tkey![@mix <key::Hex<
+            { key::digit($name, 0) },
+            { key::digit($name, 1) },
+            { key::digit($name, 2) },
+            { key::digit($name, 3) },
+            { key::digit($name, 4) },
+            { key::digit($name, 5) },
+            { key::digit($name, 6) },
+            { key::digit($name, 7) },
+        > as key::Digits>::Key; $($($T),*)?]
+    },
 }
 
-impl ExpandTypeSet for private::Empty {
-    type Output<T: ExpandTypeSet> = T;
+#[doc(hidden)]
+pub trait TypeKey: sealed::TypeKey {
+    type Xor<K: TypeKey>: TypeKey;
+    type Rotate: TypeKey;
+    /// Add the key `K` to `Self`, both of whose first digits are the least
+    /// significant ones.
+    type Add<K: TypeKey>: TypeKey;
+    /// Increment the key, whose first digit is the least significant one.
+    type Inc: TypeKey;
+    /// Replace every digit of the key by its [`key::Digit::Substitute`].
+    type Substitute: TypeKey;
+
+    #[doc(hidden)]
+    type XorCons<D: key::Digit, K: TypeKey>: TypeKey;
+    #[doc(hidden)]
+    type AddCons<D: key::Digit, K: TypeKey>: TypeKey;
+    #[doc(hidden)]
+    type Push<D: key::Digit>: TypeKey;
+
+    /// Check if the branch `B` contains the type `H` with the remaining key
+    /// `Self`.
+    type Contains<B: private::Children, H: ComputeTypeSet>: private::Bool;
+    /// Insert the type `H` with the remaining key `Self` into the branch `B`.
+    type Insert<B: private::Children, H: ComputeTypeSet>: private::Trie;
+    /// Split the leaf with the remaining key `K` and the types `T` to also
+    /// hold the type `H` with the remaining key `Self`.
+    type Split<K: TypeKey, T: private::Tree, H: ComputeTypeSet>: private::Trie;
+    /// Place the leaf with the remaining key `Self` and the types `T` into an
+    /// otherwise empty branch.
+    type Place<T: private::Tree>: private::Trie;
+
+    /// Check if all digits of the key are zero.
+    #[cfg(feature = "typeset-without-specialization")]
+    type IsZero: private::Bool;
 }
 
-impl<H: ComputeTypeSet, T: ExpandTypeSet> ExpandTypeSet for private::Cons<H, T> {
-    type Output<R: ExpandTypeSet> =
-        <T as ExpandTypeSet>::Output<Set<H, <H as ComputeTypeSet>::Output<R>>>;
+/// Keys are type-level lists of hexadecimal digits, which are consumed one by
+/// one while descending into a trie of type sets.
+mod key {
+    use core::marker::PhantomData;
+
+    #[cfg(feature = "typeset-without-specialization")]
+    use super::private::{Bool, True};
+    use super::{
+        private::{Branch, Children, Empty, False, Leaf, Tree, Trie},
+        ComputeTypeSet, TypeKey,
+    };
+
+    pub struct Nil;
+
+    pub struct Cons<D, K> {
+        _digit: PhantomData<D>,
+        _rest: PhantomData<K>,
+    }
+
+    /// The 32-bit FNV-1a hash of the `name`.
+    pub const fn fnv1a32(name: &str) -> u32 {
+        let name = name.as_bytes();
+
+        let mut hash = 0x811c_9dc5_u32;
+        let mut i = 0;
+
+        while i < name.len() {
+            hash = (hash ^ (name[i] as u32)).wrapping_mul(0x0100_0193);
+            i += 1;
+        }
+
+        hash
+    }
+
+    /// The `i`-th hexadecimal digit, starting with the most significant one,
+    /// of the hash of the `name`.
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn digit(name: &str, i: usize) -> u8 {
+        ((fnv1a32(name) >> (28 - i * 4)) & 0xf) as u8
+    }
+
+    /// Eight hexadecimal digits, which are turned into a key by [`Digits`].
+    pub struct Hex<
+        const D0: u8,
+        const D1: u8,
+        const D2: u8,
+        const D3: u8,
+        const D4: u8,
+        const D5: u8,
+        const D6: u8,
+        const D7: u8,
+    >;
+
+    pub trait Digits {
+        type Key: TypeKey;
+    }
+
+    impl<
+            const D0: u8,
+            const D1: u8,
+            const D2: u8,
+            const D3: u8,
+            const D4: u8,
+            const D5: u8,
+            const D6: u8,
+            const D7: u8,
+        > Digits for Hex<D0, D1, D2, D3, D4, D5, D6, D7>
+    where
+        Nibble<D0>: ToDigit,
+        Nibble<D1>: ToDigit,
+        Nibble<D2>: ToDigit,
+        Nibble<D3>: ToDigit,
+        Nibble<D4>: ToDigit,
+        Nibble<D5>: ToDigit,
+        Nibble<D6>: ToDigit,
+        Nibble<D7>: ToDigit,
+    {
+        type Key = Cons<
+            <Nibble<D0> as ToDigit>::Digit,
+            Cons<
+                <Nibble<D1> as ToDigit>::Digit,
+                Cons<
+                    <Nibble<D2> as ToDigit>::Digit,
+                    Cons<
+                        <Nibble<D3> as ToDigit>::Digit,
+                        Cons<
+                            <Nibble<D4> as ToDigit>::Digit,
+                            Cons<
+                                <Nibble<D5> as ToDigit>::Digit,
+                                Cons<
+                                    <Nibble<D6> as ToDigit>::Digit,
+                                    Cons<<Nibble<D7> as ToDigit>::Digit, Nil>,
+                                >,
+                            >,
+                        >,
+                    >,
+                >,
+            >,
+        >;
+    }
+
+    pub struct Nibble<const N: u8>;
+
+    /// Maps the value `N` of a [`Nibble`] to the digit type `XN`.
+    pub trait ToDigit {
+        type Digit: Digit;
+    }
+
+    pub trait Digit {
+        /// Select the type `T0` for `X0`, `T1` for `X1`, and so on.
+        type Select<
+            T0: Digit,
+            T1: Digit,
+            T2: Digit,
+            T3: Digit,
+            T4: Digit,
+            T5: Digit,
+            T6: Digit,
+            T7: Digit,
+            T8: Digit,
+            T9: Digit,
+            TA: Digit,
+            TB: Digit,
+            TC: Digit,
+            TD: Digit,
+            TE: Digit,
+            TF: Digit,
+        >: Digit;
+        type Xor<D: Digit>: Digit;
+        /// The sum of this digit and `D` without the carry.
+        type Add<D: Digit>: Digit;
+        /// The carry of the sum of this digit and `D`, either `X0` or `X1`.
+        type Carry<D: Digit>: Digit;
+        /// Add this digit, which must be a carry, to the key `K`.
+        type CarryInto<K: TypeKey>: TypeKey;
+        /// The digit that this digit is replaced by in the 4-bit S-box of the
+        /// PRESENT block cipher, which is nonlinear.
+        type Substitute: Digit;
+
+        /// The child of the branch `B` for this digit.
+        type Child<B: Children>: Trie;
+        /// The branch `B` whose child for this digit is replaced by `C`.
+        type With<B: Children, C: Trie>: Children;
+
+        /// Check if the digit is zero.
+        #[cfg(feature = "typeset-without-specialization")]
+        type IsZero: Bool;
+    }
+
+    macro_rules! carry_into {
+        (X1, $K:ident) => {
+            <$K as TypeKey>::Inc
+        };
+        ($X:ident, $K:ident) => {
+            $K
+        };
+    }
+
+    #[cfg(feature = "typeset-without-specialization")]
+    macro_rules! is_zero {
+        (X0) => {
+            True
+        };
+        ($X:ident) => {
+            False
+        };
+    }
+
+    macro_rules! child {
+        (_) => {
+            C
+        };
+        ($T:ident) => {
+            B::$T
+        };
+    }
+
+    macro_rules! digits {
+        ($($X:ident = $n:literal => $S:ident: $T:ident [$($xor:ident)*] [$($add:ident)*] [$($carry:ident)*] [$($with:tt)*];)*) => {$(
+            pub struct $X;
+
+            impl ToDigit for Nibble<$n> {
+                type Digit = $X;
+            }
+
+            impl Digit for $X {
+                type Add<D: Digit> = D::Select<$($add),*>;
+                type Carry<D: Digit> = D::Select<$($carry),*>;
+                type CarryInto<K: TypeKey> = carry_into!($X, K);
+                type Child<B: Children> = B::$T;
+                type Substitute = $S;
+                #[cfg(feature = "typeset-without-specialization")]
+                type IsZero = is_zero!($X);
+                type Select<
+                    T0: Digit,
+                    T1: Digit,
+                    T2: Digit,
+                    T3: Digit,
+                    T4: Digit,
+                    T5: Digit,
+                    T6: Digit,
+                    T7: Digit,
+                    T8: Digit,
+                    T9: Digit,
+                    TA: Digit,
+                    TB: Digit,
+                    TC: Digit,
+                    TD: Digit,
+                    TE: Digit,
+                    TF: Digit,
+                > = $T;
+                type With<B: Children, C: Trie> = Branch<$(child!($with)),*>;
+                type Xor<D: Digit> = D::Select<$($xor),*>;
+            }
+        )*};
+    }
+
+    digits! {
+        X0 = 0 => XC: T0 [X0 X1 X2 X3 X4 X5 X6 X7 X8 X9 XA XB XC XD XE XF] [X0 X1 X2 X3 X4 X5 X6 X7 X8 X9 XA XB XC XD XE XF] [X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X0] [_ T1 T2 T3 T4 T5 T6 T7 T8 T9 TA TB TC TD TE TF];
+        X1 = 1 => X5: T1 [X1 X0 X3 X2 X5 X4 X7 X6 X9 X8 XB XA XD XC XF XE] [X1 X2 X3 X4 X5 X6 X7 X8 X9 XA XB XC XD XE XF X0] [X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X1] [T0 _ T2 T3 T4 T5 T6 T7 T8 T9 TA TB TC TD TE TF];
+        X2 = 2 => X6: T2 [X2 X3 X0 X1 X6 X7 X4 X5 XA XB X8 X9 XE XF XC XD] [X2 X3 X4 X5 X6 X7 X8 X9 XA XB XC XD XE XF X0 X1] [X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X1 X1] [T0 T1 _ T3 T4 T5 T6 T7 T8 T9 TA TB TC TD TE TF];
+        X3 = 3 => XB: T3 [X3 X2 X1 X0 X7 X6 X5 X4 XB XA X9 X8 XF XE XD XC] [X3 X4 X5 X6 X7 X8 X9 XA XB XC XD XE XF X0 X1 X2] [X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X1 X1 X1] [T0 T1 T2 _ T4 T5 T6 T7 T8 T9 TA TB TC TD TE TF];
+        X4 = 4 => X9: T4 [X4 X5 X6 X7 X0 X1 X2 X3 XC XD XE XF X8 X9 XA XB] [X4 X5 X6 X7 X8 X9 XA XB XC XD XE XF X0 X1 X2 X3] [X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X1 X1 X1 X1] [T0 T1 T2 T3 _ T5 T6 T7 T8 T9 TA TB TC TD TE TF];
+        X5 = 5 => X0: T5 [X5 X4 X7 X6 X1 X0 X3 X2 XD XC XF XE X9 X8 XB XA] [X5 X6 X7 X8 X9 XA XB XC XD XE XF X0 X1 X2 X3 X4] [X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X1 X1 X1 X1 X1] [T0 T1 T2 T3 T4 _ T6 T7 T8 T9 TA TB TC TD TE TF];
+        X6 = 6 => XA: T6 [X6 X7 X4 X5 X2 X3 X0 X1 XE XF XC XD XA XB X8 X9] [X6 X7 X8 X9 XA XB XC XD XE XF X0 X1 X2 X3 X4 X5] [X0 X0 X0 X0 X0 X0 X0 X0 X0 X0 X1 X1 X1 X1 X1 X1] [T0 T1 T2 T3 T4 T5 _ T7 T8 T9 TA TB TC TD TE TF];
+        X7 = 7 => XD: T7 [X7 X6 X5 X4 X3 X2 X1 X0 XF XE XD XC XB XA X9 X8] [X7 X8 X9 XA XB XC XD XE XF X0 X1 X2 X3 X4 X5 X6] [X0 X0 X0 X0 X0 X0 X0 X0 X0 X1 X1 X1 X1 X1 X1 X1] [T0 T1 T2 T3 T4 T5 T6 _ T8 T9 TA TB TC TD TE TF];
+        X8 = 8 => X3: T8 [X8 X9 XA XB XC XD XE XF X0 X1 X2 X3 X4 X5 X6 X7] [X8 X9 XA XB XC XD XE XF X0 X1 X2 X3 X4 X5 X6 X7] [X0 X0 X0 X0 X0 X0 X0 X0 X1 X1 X1 X1 X1 X1 X1 X1] [T0 T1 T2 T3 T4 T5 T6 T7 _ T9 TA TB TC TD TE TF];
+        X9 = 9 => XE: T9 [X9 X8 XB XA XD XC XF XE X1 X0 X3 X2 X5 X4 X7 X6] [X9 XA XB XC XD XE XF X0 X1 X2 X3 X4 X5 X6 X7 X8] [X0 X0 X0 X0 X0 X0 X0 X1 X1 X1 X1 X1 X1 X1 X1 X1] [T0 T1 T2 T3 T4 T5 T6 T7 T8 _ TA TB TC TD TE TF];
+        XA = 10 => XF: TA [XA XB X8 X9 XE XF XC XD X2 X3 X0 X1 X6 X7 X4 X5] [XA XB XC XD XE XF X0 X1 X2 X3 X4 X5 X6 X7 X8 X9] [X0 X0 X0 X0 X0 X0 X1 X1 X1 X1 X1 X1 X1 X1 X1 X1] [T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 _ TB TC TD TE TF];
+        XB = 11 => X8: TB [XB XA X9 X8 XF XE XD XC X3 X2 X1 X0 X7 X6 X5 X4] [XB XC XD XE XF X0 X1 X2 X3 X4 X5 X6 X7 X8 X9 XA] [X0 X0 X0 X0 X0 X1 X1 X1 X1 X1 X1 X1 X1 X1 X1 X1] [T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 TA _ TC TD TE TF];
+        XC = 12 => X4: TC [XC XD XE XF X8 X9 XA XB X4 X5 X6 X7 X0 X1 X2 X3] [XC XD XE XF X0 X1 X2 X3 X4 X5 X6 X7 X8 X9 XA XB] [X0 X0 X0 X0 X1 X1 X1 X1 X1 X1 X1 X1 X1 X1 X1 X1] [T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 TA TB _ TD TE TF];
+        XD = 13 => X7: TD [XD XC XF XE X9 X8 XB XA X5 X4 X7 X6 X1 X0 X3 X2] [XD XE XF X0 X1 X2 X3 X4 X5 X6 X7 X8 X9 XA XB XC] [X0 X0 X0 X1 X1 X1 X1 X1 X1 X1 X1 X1 X1 X1 X1 X1] [T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 TA TB TC _ TE TF];
+        XE = 14 => X1: TE [XE XF XC XD XA XB X8 X9 X6 X7 X4 X5 X2 X3 X0 X1] [XE XF X0 X1 X2 X3 X4 X5 X6 X7 X8 X9 XA XB XC XD] [X0 X0 X1 X1 X1 X1 X1 X1 X1 X1 X1 X1 X1 X1 X1 X1] [T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 TA TB TC TD _ TF];
+        XF = 15 => X2: TF [XF XE XD XC XB XA X9 X8 X7 X6 X5 X4 X3 X2 X1 X0] [XF X0 X1 X2 X3 X4 X5 X6 X7 X8 X9 XA XB XC XD XE] [X0 X1 X1 X1 X1 X1 X1 X1 X1 X1 X1 X1 X1 X1 X1 X1] [T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 TA TB TC TD TE _];
+    }
+
+    impl TypeKey for Nil {
+        type Add<K: TypeKey> = Self;
+        type AddCons<D: Digit, K: TypeKey> = Self;
+        type Contains<B: Children, H: ComputeTypeSet> = False;
+        type Inc = Self;
+        type Insert<B: Children, H: ComputeTypeSet> = B;
+        #[cfg(feature = "typeset-without-specialization")]
+        type IsZero = True;
+        type Place<T: Tree> = Leaf<Self, T>;
+        type Push<D: Digit> = Cons<D, Self>;
+        type Rotate = Self;
+        // all keys have the same length, so K is also Nil
+        type Split<K: TypeKey, T: Tree, H: ComputeTypeSet> = Leaf<Self, T::Insert<H>>;
+        type Substitute = Self;
+        type Xor<K: TypeKey> = Self;
+        type XorCons<D: Digit, K: TypeKey> = Self;
+    }
+
+    impl<D2: Digit, K2: TypeKey> TypeKey for Cons<D2, K2> {
+        type Add<K: TypeKey> = K::AddCons<D2, K2>;
+        type AddCons<D: Digit, K: TypeKey> =
+            Cons<D::Add<D2>, <D::Carry<D2> as Digit>::CarryInto<K::Add<K2>>>;
+        type Contains<B: Children, H: ComputeTypeSet> = <D2::Child<B> as Trie>::Contains<H, K2>;
+        type Inc = Cons<D2::Add<X1>, <D2::Carry<X1> as Digit>::CarryInto<K2>>;
+        type Insert<B: Children, H: ComputeTypeSet> =
+            D2::With<B, <D2::Child<B> as Trie>::Insert<H, K2>>;
+        #[cfg(feature = "typeset-without-specialization")]
+        type IsZero = <D2::IsZero as Bool>::AndIsZero<K2>;
+        type Place<T: Tree> = D2::With<EmptyBranch, Leaf<K2, T>>;
+        type Push<D: Digit> = Cons<D2, K2::Push<D>>;
+        type Rotate = K2::Push<D2>;
+        type Split<K: TypeKey, T: Tree, H: ComputeTypeSet> = <K::Place<T> as Trie>::Insert<H, Self>;
+        type Substitute = Cons<D2::Substitute, K2::Substitute>;
+        type Xor<K: TypeKey> = K::XorCons<D2, K2>;
+        type XorCons<D: Digit, K: TypeKey> = Cons<D::Xor<D2>, K::Xor<K2>>;
+    }
+
+    type EmptyBranch = Branch<
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+        Empty,
+    >;
 }
 
 #[doc(hidden)]
-pub trait TypeSetFixedPoint: ExpandTypeSet {
+pub trait ExpandTypeSet: ComputeSet {
     type Output: ExpandTypeSet;
 }
 
-impl<T: ExpandTypeSet> TypeSetFixedPoint for T {
-    type Output = <T as private::ComputeTypeSetFixedPoint<
-        <T as ExpandTypeSet>::Output<private::Empty>,
-    >>::Output;
+/// Evaluates to a reference to `$f(Capacity::<N>)` for the smallest power of
+/// two `N` that is at least `$len`, which lets arrays of a length that is only
+/// known during const evaluation be produced without a fixed upper bound.
+macro_rules! right_sized {
+    ($len:expr, $f:expr) => {
+        right_sized!(@arms $len, $f; 0 1 2 4 8 16 32 64 128 256 512 1024 2048 4096 8192 16384
+            32768 65536 131_072 262_144 524_288 1_048_576)
+    };
+    (@arms $len:expr, $f:expr; $($N:literal)*) => {
+        match $len {
+            $(len if len <= $N => &$f($crate::typeset::private::Capacity::<$N>),)*
+            _ => panic!("type graph contains more than 2^20 types"),
+        }
+    };
 }
 
+/// The type set is computed with a worklist: [`private::Expand`] tracks the
+/// set of visited types and the frontier of types that have been inserted but
+/// whose links have not yet been followed. Every round follows the links of the
+/// frontier's types, which are collected into a fresh frontier, until it is
+/// empty. Therefore, every type is only expanded once and the number of rounds
+/// is bounded by the depth of the type graph.
+///
+/// The visited types are stored in a trie that is indexed by the hexadecimal
+/// digits of the types' [`ComputeTypeKey::Key`]s, and which only branches
+/// where the keys of its types differ. Hence, looking up a type only takes a
+/// logarithmic number of steps in the size of the set, and at most eight, and
+/// only the types in the leaf with the same key are compared. Both these types
+/// and the frontier are stored in Braun trees, which are always balanced, such
+/// that the recursion depth of comparing them or following their links only
+/// grows logarithmically with their size.
+///
+/// Without specialisation, two types cannot be compared inside the type
+/// system, and a type is instead considered visited if any visited type has
+/// the same key. Such types are not expanded again but recorded in the set of
+/// witnesses, since they might be different types whose keys collide, e.g.
+/// arrays of different lengths. Once the type set is complete, its entries are
+/// deduplicated by their [`core::any::type_name`] during const evaluation, and
+/// it is checked that every type that an entry links to is itself an entry. A
+/// type that is only reachable through a colliding witness is thus reported as
+/// a compile-time error instead of being left out.
 mod private {
-    use super::{sealed, ComputeSet, ComputeTypeSet, ExpandTypeSet, Set};
+    use core::marker::PhantomData;
+
+    #[cfg(not(feature = "typeset-without-specialization"))]
+    use super::ComputeTypeSet;
+    use super::{sealed::KeyOf, ComputeSet, ExpandTypeSet, TypeKey};
+    #[cfg(feature = "typeset-without-specialization")]
+    use super::{ComputeTypeSet, TypeLayoutInfo};
 
     #[repr(C)]
     #[derive(Copy, Clone)]
     pub struct Empty;
 
+    pub struct Node<H, L, R> {
+        _head: PhantomData<H>,
+        _left: PhantomData<L>,
+        _right: PhantomData<R>,
+    }
+
+    /// Leaf of the trie with the types `T` whose remaining keys all equal `K`.
+    pub struct Leaf<K, T> {
+        _key: PhantomData<K>,
+        _tys: PhantomData<T>,
+    }
+
     #[repr(C)]
     #[derive(Copy, Clone)]
-    pub struct Cons<H, T> {
-        head: H,
-        tail: T,
+    pub struct Pair<L, R> {
+        left: L,
+        right: R,
+    }
+
+    /// Worklist with the visited types `V`, the frontier `F`, and the
+    /// witnesses `W` whose keys had already been visited, which are only
+    /// recorded without specialisation.
+    pub struct Expand<V, F, W> {
+        _visited: PhantomData<V>,
+        _frontier: PhantomData<F>,
+        _witnesses: PhantomData<W>,
+    }
+
+    /// List of types that was produced by the deprecated [`tset`](super::tset)
+    /// syntax without a tail, and which is inserted into the set that was
+    /// passed into [`ComputeTypeSet::Output`] by [`ComputeSet::Rebase`].
+    pub struct Detached<L> {
+        _list: PhantomData<L>,
+    }
+
+    #[deprecated(
+        note = "use `tset![.., .. @ T]` with the set `T` passed into `ComputeTypeSet::Output`"
+    )]
+    pub type Legacy = Detached<Empty>;
+
+    /// The capacity `N` of an array that is produced by [`right_sized`].
+    pub struct Capacity<const N: usize>;
+
+    pub struct True;
+    pub struct False;
+
+    /// Type-level boolean whose operations are lazy, i.e. they only evaluate
+    /// the branch that is taken.
+    pub trait Bool {
+        #[cfg(not(feature = "typeset-without-specialization"))]
+        type OrContains<T: Tree, H: ComputeTypeSet>: Bool;
+        #[cfg(feature = "typeset-without-specialization")]
+        type AndIsZero<K: TypeKey>: Bool;
+        type InsertUnlessContained<V: Trie, F: Tree, W: Tree, H: ComputeTypeSet>: ExpandTypeSet;
+    }
+
+    impl Bool for True {
+        #[cfg(feature = "typeset-without-specialization")]
+        type AndIsZero<K: TypeKey> = K::IsZero;
+        #[cfg(not(feature = "typeset-without-specialization"))]
+        type InsertUnlessContained<V: Trie, F: Tree, W: Tree, H: ComputeTypeSet> = Expand<V, F, W>;
+        #[cfg(feature = "typeset-without-specialization")]
+        type InsertUnlessContained<V: Trie, F: Tree, W: Tree, H: ComputeTypeSet> =
+            Expand<V, F, W::Insert<H>>;
+        #[cfg(not(feature = "typeset-without-specialization"))]
+        type OrContains<T: Tree, H: ComputeTypeSet> = Self;
+    }
+
+    impl Bool for False {
+        #[cfg(feature = "typeset-without-specialization")]
+        type AndIsZero<K: TypeKey> = Self;
+        type InsertUnlessContained<V: Trie, F: Tree, W: Tree, H: ComputeTypeSet> =
+            Expand<V::Insert<H, <H as KeyOf>::Key>, F::Insert<H>, W>;
+        #[cfg(not(feature = "typeset-without-specialization"))]
+        type OrContains<T: Tree, H: ComputeTypeSet> = T::Contains<H>;
     }
 
-    impl ComputeSet for Empty {
-        type Len = Self;
-        type Output<H: ComputeTypeSet> = Cons<H, Self>;
+    pub trait Trie {
+        const LEN: usize;
+
+        type TyHList: 'static + Copy;
+        const TYS: Self::TyHList;
+
+        /// Check if the type `H` with the remaining key `K` is contained.
+        type Contains<H: ComputeTypeSet, K: TypeKey>: Bool;
+        /// Insert the type `H` with the remaining key `K`, which must not be
+        /// contained yet.
+        type Insert<H: ComputeTypeSet, K: TypeKey>: Trie;
+    }
+
+    impl Trie for Empty {
+        type Contains<H: ComputeTypeSet, K: TypeKey> = False;
+        type Insert<H: ComputeTypeSet, K: TypeKey> = Leaf<K, Node<H, Self, Self>>;
         type TyHList = Self;
 
         const LEN: usize = 0;
-        const TYS: &'static Self::TyHList = &Self;
+        const TYS: Self::TyHList = Self;
     }
 
-    impl<H2: ComputeTypeSet, T: ExpandTypeSet> ComputeSet for Cons<H2, T> {
-        type Len = Cons<(), T::Len>;
-        type Output<H1: ComputeTypeSet> = <Self as ComputeCons<H1>>::Output;
-        type TyHList = Cons<&'static crate::TypeLayoutInfo<'static>, T::TyHList>;
+    impl<K2: TypeKey, T: Tree> Trie for Leaf<K2, T> {
+        #[cfg(not(feature = "typeset-without-specialization"))]
+        type Contains<H: ComputeTypeSet, K: TypeKey> = T::Contains<H>;
+        /// Without specialisation, only the keys are compared.
+        #[cfg(feature = "typeset-without-specialization")]
+        type Contains<H: ComputeTypeSet, K: TypeKey> = <K2::Xor<K> as TypeKey>::IsZero;
+        type Insert<H: ComputeTypeSet, K: TypeKey> = K::Split<K2, T, H>;
+        type TyHList = T::TyHList;
+
+        const LEN: usize = T::LEN;
+        const TYS: Self::TyHList = T::TYS;
+    }
+
+    pub trait Children: Trie {
+        type T0: Trie;
+        type T1: Trie;
+        type T2: Trie;
+        type T3: Trie;
+        type T4: Trie;
+        type T5: Trie;
+        type T6: Trie;
+        type T7: Trie;
+        type T8: Trie;
+        type T9: Trie;
+        type TA: Trie;
+        type TB: Trie;
+        type TC: Trie;
+        type TD: Trie;
+        type TE: Trie;
+        type TF: Trie;
+    }
+
+    macro_rules! branch {
+        ($($T:ident)*) => {
+            /// Branch of the trie with one child for every hexadecimal digit.
+            pub struct Branch<$($T),*>($(PhantomData<$T>),*);
+
+            impl<$($T: Trie),*> Children for Branch<$($T),*> {
+                $(type $T = $T;)*
+            }
+
+            impl<$($T: Trie),*> Trie for Branch<$($T),*> {
+                type Contains<H: ComputeTypeSet, K: TypeKey> = K::Contains<Self, H>;
+                type Insert<H: ComputeTypeSet, K: TypeKey> = K::Insert<Self, H>;
+                type TyHList = branch!(@list $($T)*);
 
-        const LEN: usize = T::LEN + 1;
-        const TYS: &'static Self::TyHList = &Cons {
-            head: &H2::TYPE_LAYOUT,
-            tail: *T::TYS,
+                const LEN: usize = 0 $(+ $T::LEN)*;
+                const TYS: Self::TyHList = branch!(@tys $($T)*);
+            }
         };
+        (@list) => { Empty };
+        (@list $T:ident $($R:ident)*) => {
+            Pair<$T::TyHList, branch!(@list $($R)*)>
+        };
+        (@tys) => { Empty };
+        (@tys $T:ident $($R:ident)*) => {
+            Pair {
+                left: $T::TYS,
+                right: branch!(@tys $($R)*),
+            }
+        };
+    }
+
+    branch! { T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 TA TB TC TD TE TF }
+
+    /// Check if the head of the node is the type `H`.
+    #[cfg(not(feature = "typeset-without-specialization"))]
+    pub trait HeadIs<H: ComputeTypeSet>: Tree {
+        type Output: Bool;
     }
 
-    pub trait ComputeCons<H: ComputeTypeSet>: sealed::ComputeSet {
-        type Output: ExpandTypeSet;
+    #[cfg(not(feature = "typeset-without-specialization"))]
+    impl<H: ComputeTypeSet, L: Tree, R: Tree> HeadIs<H> for Node<H, L, R> {
+        type Output = True;
     }
 
-    impl<H: ComputeTypeSet> ComputeCons<H> for Empty {
-        type Output = Cons<H, Self>;
+    #[cfg(not(feature = "typeset-without-specialization"))]
+    impl<H1: ComputeTypeSet, H2: ComputeTypeSet, L: Tree, R: Tree> HeadIs<H1> for Node<H2, L, R> {
+        default type Output = False;
     }
 
-    impl<H: ComputeTypeSet, T: ExpandTypeSet> ComputeCons<H> for Cons<H, T> {
-        type Output = Self;
+    pub trait Tree {
+        const LEN: usize;
+
+        type TyHList: 'static + Copy;
+        const TYS: Self::TyHList;
+
+        /// Check if the type `H` is contained.
+        #[cfg(not(feature = "typeset-without-specialization"))]
+        type Contains<H: ComputeTypeSet>: Bool;
+
+        type Insert<H: ComputeTypeSet>: Tree;
+
+        /// Insert the types that the types in `Self` link to into `S`.
+        type Expand<S: ExpandTypeSet>: ExpandTypeSet;
+
+        /// Compute the fixed point of the visited set `V` with `Self` as the
+        /// frontier and the witnesses `W`.
+        type FixedPoint<V: Trie, W: Tree>: ExpandTypeSet;
     }
 
-    impl<H1: ComputeTypeSet, H2: ComputeTypeSet, T: ExpandTypeSet> ComputeCons<H1> for Cons<H2, T> {
-        default type Output = Cons<H2, Set<H1, T>>;
+    impl Tree for Empty {
+        #[cfg(not(feature = "typeset-without-specialization"))]
+        type Contains<H: ComputeTypeSet> = False;
+        type Expand<S: ExpandTypeSet> = S;
+        type FixedPoint<V: Trie, W: Tree> = Expand<V, Self, W>;
+        type Insert<H: ComputeTypeSet> = Node<H, Self, Self>;
+        type TyHList = Self;
+
+        const LEN: usize = 0;
+        const TYS: Self::TyHList = Self;
+    }
+
+    impl<H2: ComputeTypeSet, L: Tree, R: Tree> Tree for Node<H2, L, R> {
+        #[cfg(not(feature = "typeset-without-specialization"))]
+        type Contains<H1: ComputeTypeSet> =
+            <<<Self as HeadIs<H1>>::Output as Bool>::OrContains<L, H1> as Bool>::OrContains<R, H1>;
+        type Expand<S: ExpandTypeSet> =
+            R::Expand<L::Expand<<<H2 as ComputeTypeSet>::Output<S> as ComputeSet>::Rebase<S>>>;
+        type FixedPoint<V: Trie, W: Tree> =
+            <Self::Expand<Expand<V, Empty, W>> as ExpandTypeSet>::Output;
+        type Insert<H1: ComputeTypeSet> = Node<H1, R::Insert<H2>, L>;
+        type TyHList = Pair<Entry, Pair<L::TyHList, R::TyHList>>;
+
+        const LEN: usize = 1 + L::LEN + R::LEN;
+        const TYS: Self::TyHList = Pair {
+            left: entry::<H2>(),
+            right: Pair {
+                left: L::TYS,
+                right: R::TYS,
+            },
+        };
+    }
+
+    impl<V: Trie, F: Tree, W: Tree> ComputeSet for Expand<V, F, W> {
+        type Output<H: ComputeTypeSet> =
+            <V::Contains<H, <H as KeyOf>::Key> as Bool>::InsertUnlessContained<V, F, W, H>;
+        type Rebase<S: ExpandTypeSet> = Self;
+        type TyHList = Pair<V::TyHList, W::TyHList>;
+
+        const LEN: usize = V::LEN + W::LEN;
+        const TYS: Self::TyHList = Pair {
+            left: V::TYS,
+            right: W::TYS,
+        };
+        #[cfg(feature = "typeset-without-specialization")]
+        const VISITED: usize = V::LEN;
     }
 
-    pub trait ComputeTypeSetFixedPoint<E: ExpandTypeSet>: ExpandTypeSet {
-        type Output: ExpandTypeSet;
+    impl<V: Trie, F: Tree, W: Tree> ExpandTypeSet for Expand<V, F, W> {
+        type Output = F::FixedPoint<V, W>;
     }
 
-    trait Same<T> {}
-    impl<T> Same<T> for T {}
+    impl ComputeSet for Detached<Empty> {
+        type Output<H: ComputeTypeSet> = Detached<Node<H, Empty, Empty>>;
+        type Rebase<S: ExpandTypeSet> = S;
+        type TyHList = Empty;
 
-    impl<T: ExpandTypeSet, E: ExpandTypeSet> ComputeTypeSetFixedPoint<E> for T
+        const LEN: usize = 0;
+        const TYS: Self::TyHList = Empty;
+        #[cfg(feature = "typeset-without-specialization")]
+        const VISITED: usize = 0;
+    }
+
+    impl<H2: ComputeTypeSet, T> ComputeSet for Detached<Node<H2, T, Empty>>
     where
-        T::Len: Same<E::Len>,
+        Detached<T>: ComputeSet,
+    {
+        type Output<H1: ComputeTypeSet> = Detached<Node<H1, Node<H2, T, Empty>, Empty>>;
+        type Rebase<S: ExpandTypeSet> =
+            <<Detached<T> as ComputeSet>::Rebase<S> as ComputeSet>::Output<H2>;
+        /// The names of the listed types, which are the types that a type
+        /// links to if its [`ComputeTypeSet::Output`] is given an empty list.
+        type TyHList = Pair<&'static str, <Detached<T> as ComputeSet>::TyHList>;
+
+        const LEN: usize = 1 + <Detached<T> as ComputeSet>::LEN;
+        const TYS: Self::TyHList = Pair {
+            left: core::any::type_name::<H2>(),
+            right: <Detached<T> as ComputeSet>::TYS,
+        };
+        #[cfg(feature = "typeset-without-specialization")]
+        const VISITED: usize = 0;
+    }
+
+    impl<L> ExpandTypeSet for Detached<L>
+    where
+        Self: ComputeSet,
     {
         type Output = Self;
     }
 
-    impl<T: ExpandTypeSet, E: ExpandTypeSet> ComputeTypeSetFixedPoint<E> for T {
-        default type Output = <E as ComputeTypeSetFixedPoint<<E as ExpandTypeSet>::Output<Empty>>>::Output;
+    /// Entry of a type set, which is the layout of a type.
+    #[cfg(not(feature = "typeset-without-specialization"))]
+    pub type Entry = &'static crate::TypeLayoutInfo<'static>;
+
+    #[cfg(not(feature = "typeset-without-specialization"))]
+    const fn entry<H: ComputeTypeSet>() -> Entry {
+        &H::TYPE_LAYOUT
+    }
+
+    /// Entry of a type set, which is the layout of a type together with the
+    /// names of the types it links to.
+    #[cfg(feature = "typeset-without-specialization")]
+    #[derive(Copy, Clone)]
+    pub struct Entry {
+        pub ty: &'static TypeLayoutInfo<'static>,
+        pub links: &'static [&'static str],
+        /// Whether the type is only a witness, whose links were not followed.
+        pub witness: bool,
+    }
+
+    #[cfg(feature = "typeset-without-specialization")]
+    impl Entry {
+        /// Placeholder entry with which buffers are initialised.
+        pub const EMPTY: Self = entry::<()>();
+    }
+
+    #[cfg(feature = "typeset-without-specialization")]
+    const fn entry<H: ComputeTypeSet>() -> Entry {
+        Entry {
+            ty: &H::TYPE_LAYOUT,
+            links: Links::<H>::NAMES.split_at(Links::<H>::LEN).0,
+            witness: false,
+        }
+    }
+
+    #[cfg(feature = "typeset-without-specialization")]
+    type LinkList<H> = <H as ComputeTypeSet>::Output<Detached<Empty>>;
+
+    /// The names of the types that the type `H` links to.
+    #[cfg(feature = "typeset-without-specialization")]
+    struct Links<H>(PhantomData<H>);
+
+    #[cfg(feature = "typeset-without-specialization")]
+    impl<H: ComputeTypeSet> Links<H> {
+        const LEN: usize = <LinkList<H> as ComputeSet>::LEN;
+        const NAMES: &'static [&'static str] = right_sized!(Self::LEN, Self::names);
+
+        const fn names<const N: usize>(_: Capacity<N>) -> [&'static str; N] {
+            let mut names = <LinkList<H> as ComputeSet>::TYS;
+
+            // SAFETY:
+            // - the list is a tree made of only Pair, &'static str, and Empty, which is
+            //   layout-equivalent to an array of LEN names
+            // - names is a local copy of the list that outlives the slice
+            let names = unsafe {
+                core::slice::from_raw_parts_mut(core::ptr::addr_of_mut!(names).cast(), Self::LEN)
+            };
+
+            let mut array = [""; N];
+            let mut i = 0;
+
+            while i < Self::LEN {
+                array[i] = names[i];
+                i += 1;
+            }
+
+            array
+        }
+    }
+
+    /// Deduplicate the `entries` of a complete type set by their names, using
+    /// the `buffer` that is at least as long, and return the number of
+    /// distinct entries, which are moved to the front and sorted by their
+    /// names.
+    ///
+    /// # Panics
+    ///
+    /// Panics if two entries with the same name have different layouts, or if
+    /// a witness links to a type that is not an entry itself.
+    #[cfg(feature = "typeset-without-specialization")]
+    pub const fn deduplicate(entries: &mut [Entry], buffer: &mut [Entry]) -> usize {
+        sort_by_name(entries, buffer);
+
+        let mut len = 0;
+        let mut i = 0;
+
+        while i < entries.len() {
+            if len > 0 && str_eq(entries[len - 1].ty.name, entries[i].ty.name) {
+                assert!(
+                    layouts_eq(entries[len - 1].ty, entries[i].ty),
+                    "type graph contains different types with the same name"
+                );
+
+                // the links of a type are followed if any of its copies is not
+                // a witness
+                entries[len - 1].witness &= entries[i].witness;
+            } else {
+                entries[len] = entries[i];
+                len += 1;
+            }
+
+            i += 1;
+        }
+
+        let mut i = 0;
+
+        while i < len {
+            let links = entries[i].links;
+            let mut j = 0;
+
+            while entries[i].witness && j < links.len() {
+                assert!(
+                    find(entries, len, links[j]),
+                    "type graph contains a type that shares its key with another type and links \
+                     to types that are only reachable through it, which requires the \
+                     `typeset-without-specialization` feature to be disabled"
+                );
+
+                j += 1;
+            }
+
+            i += 1;
+        }
+
+        len
+    }
+
+    /// Check if the first `len` `entries`, which are sorted by their names,
+    /// contain the type with the `name`.
+    #[cfg(feature = "typeset-without-specialization")]
+    const fn find(entries: &[Entry], len: usize, name: &str) -> bool {
+        let mut lo = 0;
+        let mut hi = len;
+
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+
+            match crate::repr::bytes_cmp(entries[mid].ty.name.as_bytes(), name.as_bytes()) {
+                core::cmp::Ordering::Less => lo = mid + 1,
+                core::cmp::Ordering::Equal => return true,
+                core::cmp::Ordering::Greater => hi = mid,
+            }
+        }
+
+        false
+    }
+
+    /// Sort the `entries` by their names with a bottom-up merge sort into
+    /// the `buffer`, which must be at least as long, since a type set can
+    /// contain many witnesses and merge sort only needs O(n log n) moves.
+    #[cfg(feature = "typeset-without-specialization")]
+    const fn sort_by_name(entries: &mut [Entry], buffer: &mut [Entry]) {
+        let len = entries.len();
+        let mut width = 1;
+
+        while width < len {
+            let mut start = 0;
+
+            while start < len {
+                let mid = if start + width < len {
+                    start + width
+                } else {
+                    len
+                };
+                let end = if mid + width < len { mid + width } else { len };
+
+                let (mut i, mut j, mut k) = (start, mid, start);
+
+                while k < end {
+                    // take from the left run on ties, which keeps the sort stable
+                    if i < mid
+                        && (j >= end
+                            || crate::repr::bytes_cmp(
+                                entries[i].ty.name.as_bytes(),
+                                entries[j].ty.name.as_bytes(),
+                            )
+                            .is_le())
+                    {
+                        buffer[k] = entries[i];
+                        i += 1;
+                    } else {
+                        buffer[k] = entries[j];
+                        j += 1;
+                    }
+
+                    k += 1;
+                }
+
+                start = end;
+            }
+
+            let mut k = 0;
+
+            while k < len {
+                entries[k] = buffer[k];
+                k += 1;
+            }
+
+            width *= 2;
+        }
+    }
+
+    #[cfg(feature = "typeset-without-specialization")]
+    const fn str_eq(a: &str, b: &str) -> bool {
+        crate::repr::bytes_eq(a.as_bytes(), b.as_bytes())
+    }
+
+    /// Check if the layouts `a` and `b` are exactly equal.
+    #[cfg(feature = "typeset-without-specialization")]
+    const fn layouts_eq(a: &TypeLayoutInfo, b: &TypeLayoutInfo) -> bool {
+        use crate::TypeStructure;
+
+        if !str_eq(a.name, b.name) || a.size != b.size || a.alignment != b.alignment {
+            return false;
+        }
+
+        match (&a.structure, &b.structure) {
+            (TypeStructure::Primitive, TypeStructure::Primitive) => true,
+            (
+                TypeStructure::Struct {
+                    repr: repr_a,
+                    fields: fields_a,
+                },
+                TypeStructure::Struct {
+                    repr: repr_b,
+                    fields: fields_b,
+                },
+            )
+            | (
+                TypeStructure::Union {
+                    repr: repr_a,
+                    fields: fields_a,
+                },
+                TypeStructure::Union {
+                    repr: repr_b,
+                    fields: fields_b,
+                },
+            ) => str_eq(repr_a, repr_b) && fields_eq(fields_a, fields_b),
+            (
+                TypeStructure::Enum {
+                    repr: repr_a,
+                    variants: variants_a,
+                },
+                TypeStructure::Enum {
+                    repr: repr_b,
+                    variants: variants_b,
+                },
+            ) => {
+                if !str_eq(repr_a, repr_b) || variants_a.len() != variants_b.len() {
+                    return false;
+                }
+
+                let mut i = 0;
+
+                while i < variants_a.len() {
+                    let (a, b) = (&variants_a[i], &variants_b[i]);
+
+                    let discriminants_eq = match (&a.discriminant, &b.discriminant) {
+                        (
+                            crate::MaybeUninhabited::Inhabited(a),
+                            crate::MaybeUninhabited::Inhabited(b),
+                        ) => crate::repr::bytes_eq(a.value, b.value),
+                        (
+                            crate::MaybeUninhabited::Uninhabited,
+                            crate::MaybeUninhabited::Uninhabited,
+                        ) => true,
+                        _ => false,
+                    };
+
+                    if !str_eq(a.name, b.name)
+                        || !discriminants_eq
+                        || !fields_eq(a.fields, b.fields)
+                    {
+                        return false;
+                    }
+
+                    i += 1;
+                }
+
+                true
+            },
+            _ => false,
+        }
+    }
+
+    #[cfg(feature = "typeset-without-specialization")]
+    const fn fields_eq(a: &[crate::Field], b: &[crate::Field]) -> bool {
+        if a.len() != b.len() {
+            return false;
+        }
+
+        let mut i = 0;
+
+        while i < a.len() {
+            let offsets_eq = match (a[i].offset, b[i].offset) {
+                (crate::MaybeUninhabited::Inhabited(a), crate::MaybeUninhabited::Inhabited(b)) => {
+                    a == b
+                },
+                (crate::MaybeUninhabited::Uninhabited, crate::MaybeUninhabited::Uninhabited) => {
+                    true
+                },
+                _ => false,
+            };
+
+            if !str_eq(a[i].name, b[i].name) || !offsets_eq || !str_eq(a[i].ty, b[i].ty) {
+                return false;
+            }
+
+            i += 1;
+        }
+
+        true
+    }
+}
+
+type TypeSet<T> = <Set<T, private::Expand<private::Empty, private::Empty, private::Empty>> as ExpandTypeSet>::Output;
+
+/// Compute the complete set of types that the type `T` links to, including
+/// `T` itself.
+pub(super) const fn type_set<T: ComputeTypeSet>() -> &'static [&'static TypeLayoutInfo<'static>] {
+    Canonical::<T>::TYS.split_at(Canonical::<T>::len()).0
+}
+
+/// The type set of the type `T` in its canonical order.
+struct Canonical<T>(core::marker::PhantomData<T>);
+
+#[cfg(not(feature = "typeset-without-specialization"))]
+impl<T: ComputeTypeSet> Canonical<T> {
+    const LEN: usize = <TypeSet<T> as ComputeSet>::LEN;
+    const TYS: &'static [&'static TypeLayoutInfo<'static>] =
+        right_sized!(Self::LEN, Self::canonical);
+
+    const fn len() -> usize {
+        Self::LEN
+    }
+
+    const fn canonical<const N: usize>(
+        _: private::Capacity<N>,
+    ) -> [&'static TypeLayoutInfo<'static>; N] {
+        let mut entries = <TypeSet<T> as ComputeSet>::TYS;
+
+        // SAFETY:
+        // - ComputeSet is a sealed trait and its TYS const is always a tree made of
+        //   only Pair, &'static TypeLayoutInfo, and Empty
+        // - Pair is a repr(C) struct with two subtrees, Empty is a zero-sized repr(C)
+        //   struct
+        // - the tree is thus layout-equivalent to an array of LEN entries
+        // - entries is a local copy of the tree that outlives the slice
+        let entries: &[private::Entry] = unsafe {
+            core::slice::from_raw_parts_mut(core::ptr::addr_of_mut!(entries).cast(), Self::LEN)
+        };
+
+        let mut tys = [&T::TYPE_LAYOUT; N];
+        let mut i = 0;
+
+        while i < Self::LEN {
+            tys[i] = entries[i];
+            i += 1;
+        }
+
+        sort_canonically(&mut tys, Self::LEN, T::TYPE_LAYOUT.name);
+
+        tys
+    }
+}
+
+/// The distinct types of a type set, of which only the first `len` are used.
+#[cfg(feature = "typeset-without-specialization")]
+struct Assembled<T: ?Sized> {
+    len: usize,
+    tys: T,
+}
+
+#[cfg(feature = "typeset-without-specialization")]
+impl<T: ComputeTypeSet> Canonical<T> {
+    const ASSEMBLED: &'static Assembled<[&'static TypeLayoutInfo<'static>]> =
+        right_sized!(Self::LEN, Self::assemble);
+    const LEN: usize = <TypeSet<T> as ComputeSet>::LEN;
+    const TYS: &'static [&'static TypeLayoutInfo<'static>] =
+        right_sized!(Self::ASSEMBLED.len, Self::canonical);
+
+    const fn len() -> usize {
+        Self::ASSEMBLED.len
+    }
+
+    const fn assemble<const N: usize>(
+        _: private::Capacity<N>,
+    ) -> Assembled<[&'static TypeLayoutInfo<'static>; N]> {
+        let mut entries = <TypeSet<T> as ComputeSet>::TYS;
+
+        // SAFETY:
+        // - ComputeSet is a sealed trait and its TYS const is always a tree made of
+        //   only Pair, Entry, and Empty
+        // - Pair is a repr(C) struct with two subtrees, Empty is a zero-sized repr(C)
+        //   struct
+        // - the tree is thus layout-equivalent to an array of LEN entries
+        // - entries is a local copy of the tree that we can mutate and that outlives
+        //   the slice
+        let entries: &mut [private::Entry] = unsafe {
+            core::slice::from_raw_parts_mut(core::ptr::addr_of_mut!(entries).cast(), Self::LEN)
+        };
+
+        // the visited types come first, followed by the witnesses
+        let mut i = <TypeSet<T> as ComputeSet>::VISITED;
+
+        while i < Self::LEN {
+            entries[i].witness = true;
+            i += 1;
+        }
+
+        let mut buffer = [private::Entry::EMPTY; N];
+        let len = private::deduplicate(entries, &mut buffer);
+
+        let mut tys = [&T::TYPE_LAYOUT; N];
+        let mut i = 0;
+
+        while i < len {
+            tys[i] = entries[i].ty;
+            i += 1;
+        }
+
+        // the types are already sorted by their names
+        move_to_front(&mut tys, len, T::TYPE_LAYOUT.name);
+
+        Assembled { len, tys }
+    }
+
+    const fn canonical<const N: usize>(
+        _: private::Capacity<N>,
+    ) -> [&'static TypeLayoutInfo<'static>; N] {
+        let mut tys = [&T::TYPE_LAYOUT; N];
+        let mut i = 0;
+
+        while i < Self::ASSEMBLED.len {
+            tys[i] = Self::ASSEMBLED.tys[i];
+            i += 1;
+        }
+
+        tys
+    }
+}
+
+/// Sort the first `len` types of the type set `tys` into its canonical order,
+/// in which the `root` type comes first, followed by all other types in the
+/// order of their names.
+#[cfg(not(feature = "typeset-without-specialization"))]
+const fn sort_canonically(tys: &mut [&'static TypeLayoutInfo<'static>], len: usize, root: &str) {
+    move_to_front(tys, len, root);
+
+    // binary insertion sort, which is stable and only needs O(n log n) name
+    // comparisons
+    let mut i = 2;
+
+    while i < len {
+        let ty = tys[i];
+
+        let mut lo = 1;
+        let mut hi = i;
+
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+
+            if crate::repr::bytes_cmp(tys[mid].name.as_bytes(), ty.name.as_bytes()).is_le() {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+
+        let mut j = i;
+
+        while j > lo {
+            tys[j] = tys[j - 1];
+            j -= 1;
+        }
+
+        tys[lo] = ty;
+
+        i += 1;
+    }
+}
+
+/// Move the first of the first `len` types in `tys` with the `root` name to
+/// the front, keeping the order of all other types.
+const fn move_to_front(tys: &mut [&'static TypeLayoutInfo<'static>], len: usize, root: &str) {
+    let mut i = 0;
+
+    while i < len {
+        if crate::repr::bytes_eq(tys[i].name.as_bytes(), root.as_bytes()) {
+            let ty = tys[i];
+
+            while i > 0 {
+                tys[i] = tys[i - 1];
+                i -= 1;
+            }
+
+            tys[0] = ty;
+
+            break;
+        }
+
+        i += 1;
     }
 }
 
-pub(super) type TypeSet<T> =
-    <Set<T, <T as ComputeTypeSet>::Output<private::Empty>> as TypeSetFixedPoint>::Output;
+#[cfg(test)]
+mod tests {
+    use crate::{TypeGraphLayout, TypeLayoutGraph};
+
+    fn assert_canonical(graph: &TypeLayoutGraph) {
+        assert_eq!(graph.tys[0].name, graph.ty);
+
+        for pair in graph.tys[1..].windows(2) {
+            assert!(pair[0].name <= pair[1].name, "{graph:?}");
+        }
+
+        for ty in graph.tys {
+            assert_eq!(graph.get(ty.name).map(|layout| layout.name), Some(ty.name));
+        }
+
+        assert_eq!(graph.get("not a type"), None);
+    }
+
+    #[test]
+    fn canonical_order() {
+        assert_canonical(&<u8>::TYPE_GRAPH);
+        assert_canonical(&<(u64, u32, u16, u8)>::TYPE_GRAPH);
+        assert_canonical(&<Result<Option<&'static [u8; 4]>, core::num::NonZeroU32>>::TYPE_GRAPH);
+        assert_canonical(&<core::cell::Cell<core::ops::Range<usize>>>::TYPE_GRAPH);
+    }
+
+    #[test]
+    fn field_order_independence() {
+        let a = <(u8, u16, u32)>::TYPE_GRAPH;
+        let b = <(u32, u16, u8)>::TYPE_GRAPH;
+
+        let names = |graph: &TypeLayoutGraph<'static>| {
+            graph.tys[1..]
+                .iter()
+                .map(|ty| ty.name)
+                .collect::<alloc::vec::Vec<_>>()
+        };
+
+        assert_eq!(names(&a), names(&b));
+    }
+
+    #[test]
+    fn key_mixing() {
+        use core::any::TypeId;
+
+        use super::ComputeTypeKey;
+
+        fn key<T: ComputeTypeKey>() -> TypeId
+        where
+            T::Key: 'static,
+        {
+            TypeId::of::<T::Key>()
+        }
+
+        assert_ne!(key::<(u8, u16)>(), key::<(u16, u8)>());
+        assert_ne!(key::<Option<u8>>(), key::<Option<u16>>());
+        assert_ne!(key::<Option<Option<u8>>>(), key::<Option<Option<u16>>>());
+        assert_ne!(key::<&'static u8>(), key::<*const u8>());
+
+        // arrays of different lengths share their key
+        assert_eq!(key::<[u8; 1]>(), key::<[u8; 2]>());
+    }
+
+    #[test]
+    fn computed_keys() {
+        use core::any::TypeId;
+
+        use super::{
+            key::{fnv1a32, Cons, Nil, X1, X2, X3, X4, X5, X7, X8, X9, XA, XB, XD, XF},
+            tkey, ComputeTypeKey,
+        };
+
+        assert_eq!(fnv1a32(""), 0x811c_9dc5);
+        assert_eq!(fnv1a32("i8"), 0x9338_fbb4);
+        assert_eq!(fnv1a32("()"), 0x28d9_f59a);
+
+        assert_eq!(
+            TypeId::of::<tkey!["i8"]>(),
+            TypeId::of::<
+                Cons<X9, Cons<X3, Cons<X3, Cons<X8, Cons<XF, Cons<XB, Cons<XB, Cons<X4, Nil>>>>>>>>,
+            >()
+        );
+        assert_eq!(
+            TypeId::of::<tkey!["()"]>(),
+            TypeId::of::<
+                Cons<X2, Cons<X8, Cons<XD, Cons<X9, Cons<XF, Cons<X5, Cons<X9, Cons<XA, Nil>>>>>>>>,
+            >()
+        );
+        assert_ne!(TypeId::of::<tkey!["i8"]>(), TypeId::of::<tkey!["u8"]>());
+
+        // the key of `core::option::Option` mixed with the key of `u8`
+        assert_eq!(
+            TypeId::of::<<Option<u8> as ComputeTypeKey>::Key>(),
+            TypeId::of::<
+                Cons<X7, Cons<X1, Cons<XF, Cons<XB, Cons<X2, Cons<X5, Cons<X4, Cons<X8, Nil>>>>>>>>,
+            >()
+        );
+    }
+
+    #[test]
+    fn colliding_keys() {
+        let graph = <([u8; 1], [u8; 2], [u8; 3], Leaf, Old)>::TYPE_GRAPH;
+
+        assert_canonical(&graph);
+
+        for pair in graph.tys[1..].windows(2) {
+            assert_ne!(pair[0].name, pair[1].name, "{graph:?}");
+        }
+
+        for name in ["[u8; 1]", "[u8; 2]", "[u8; 3]", "u8", "u16"] {
+            assert!(graph.get(name).is_some(), "{name} is missing in {graph:?}");
+        }
+
+        assert!(graph.get(::core::any::type_name::<Leaf>()).is_some());
+        assert!(graph.get(::core::any::type_name::<Old>()).is_some());
+    }
+
+    #[cfg(not(feature = "typeset-without-specialization"))]
+    #[test]
+    fn nested_colliding_keys() {
+        let graph = <([[u8; 1]; 1], [[u8; 2]; 1])>::TYPE_GRAPH;
+
+        assert_canonical(&graph);
+
+        for name in ["[[u8; 1]; 1]", "[[u8; 2]; 1]", "[u8; 1]", "[u8; 2]", "u8"] {
+            assert!(graph.get(name).is_some(), "{name} is missing in {graph:?}");
+        }
+    }
+
+    mod graphs {
+        use crate::{
+            typeset::{tkey, tset, ComputeTypeKey, ComputeTypeSet, ExpandTypeSet},
+            TypeGraphLayout,
+        };
+
+        #[test]
+        fn deep_graph() {
+            assert_eq!(<Chain<D48>>::TYPE_GRAPH.tys.len(), 49);
+        }
+
+        #[test]
+        fn wide_graph() {
+            assert_eq!(<Bits<D7, ()>>::TYPE_GRAPH.tys.len(), 255);
+        }
+
+        struct Zero;
+        struct Succ<D>(core::marker::PhantomData<D>);
+
+        impl ComputeTypeKey for Zero {
+            type Key = tkey!["Zero"];
+        }
+
+        impl<D: ComputeTypeKey> ComputeTypeKey for Succ<D> {
+            type Key = tkey!["Succ"; D];
+        }
+
+        type D7 = Succ<Succ<Succ<Succ<Succ<Succ<Succ<Zero>>>>>>>;
+        type D48 = Plus8<Plus8<Plus8<Plus8<Plus8<Plus8<Zero>>>>>>;
+        type Plus8<D> = Succ<Succ<Succ<Succ<Succ<Succ<Succ<Succ<D>>>>>>>>;
+
+        /// `Chain<D>` links to `Chain<D - 1>` until `D` is zero, i.e. the graph
+        /// of `Chain<D>` is a path of `D + 1` types.
+        struct Chain<D>(core::marker::PhantomData<D>);
+
+        // SAFETY: the layout is computed from the type itself
+        unsafe impl<D> crate::TypeLayout for Chain<D> {
+            const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![];
+            const TYPE_LAYOUT: crate::TypeLayoutInfo<'static> = crate::TypeLayoutInfo {
+                name: ::core::any::type_name::<Self>(),
+                size: ::core::mem::size_of::<Self>(),
+                alignment: ::core::mem::align_of::<Self>(),
+                structure: crate::TypeStructure::Struct {
+                    repr: "",
+                    fields: &[],
+                },
+            };
+        }
+
+        // SAFETY: Chain<Zero> links to no other types
+        unsafe impl ComputeTypeSet for Chain<Zero> {
+            type Output<T: ExpandTypeSet> = tset![.. @ T];
+        }
+
+        // SAFETY: the set includes the only linked type Chain<D>
+        unsafe impl<D: ComputeTypeKey> ComputeTypeSet for Chain<Succ<D>>
+        where
+            Chain<D>: ComputeTypeSet,
+        {
+            type Output<T: ExpandTypeSet> = tset![Chain<D>, .. @ T];
+        }
+
+        impl<D: ComputeTypeKey> ComputeTypeKey for Chain<D> {
+            type Key = tkey!["Chain"; D];
+        }
+
+        /// `Bits<D, B>` links to `Bits<D - 1, B'>` for the two extensions
+        /// `B' = (B, bool)` and `B' = (B, u8)` until `D` is zero, i.e. the
+        /// graph of `Bits<D, B>` is a complete binary tree of `2^(D + 1) - 1`
+        /// types.
+        struct Bits<D, B>(core::marker::PhantomData<(D, B)>);
+
+        // SAFETY: the layout is computed from the type itself
+        unsafe impl<D, B> crate::TypeLayout for Bits<D, B> {
+            const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![];
+            const TYPE_LAYOUT: crate::TypeLayoutInfo<'static> = crate::TypeLayoutInfo {
+                name: ::core::any::type_name::<Self>(),
+                size: ::core::mem::size_of::<Self>(),
+                alignment: ::core::mem::align_of::<Self>(),
+                structure: crate::TypeStructure::Struct {
+                    repr: "",
+                    fields: &[],
+                },
+            };
+        }
+
+        // SAFETY: Bits<Zero, B> links to no other types
+        unsafe impl<B: ComputeTypeKey> ComputeTypeSet for Bits<Zero, B> {
+            type Output<T: ExpandTypeSet> = tset![.. @ T];
+        }
+
+        // SAFETY: the set includes both linked types
+        unsafe impl<D: ComputeTypeKey, B: ComputeTypeKey> ComputeTypeSet for Bits<Succ<D>, B>
+        where
+            Bits<D, (B, bool)>: ComputeTypeSet,
+            Bits<D, (B, u8)>: ComputeTypeSet,
+        {
+            type Output<T: ExpandTypeSet> = tset![Bits<D, (B, bool)>, Bits<D, (B, u8)>, .. @ T];
+        }
+
+        impl<D: ComputeTypeKey, B: ComputeTypeKey> ComputeTypeKey for Bits<D, B> {
+            type Key = tkey!["Bits"; D, B];
+        }
+    }
+
+    struct Leaf;
+
+    // SAFETY: the layout is computed from the type itself
+    unsafe impl crate::TypeLayout for Leaf {
+        const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![];
+        const TYPE_LAYOUT: crate::TypeLayoutInfo<'static> = crate::TypeLayoutInfo {
+            name: ::core::any::type_name::<Self>(),
+            size: ::core::mem::size_of::<Self>(),
+            alignment: ::core::mem::align_of::<Self>(),
+            structure: crate::TypeStructure::Struct {
+                repr: "",
+                fields: &[],
+            },
+        };
+    }
+
+    #[allow(deprecated)]
+    // SAFETY: Leaf has no fields and thus links to no other types
+    unsafe impl super::ComputeTypeSet for Leaf {
+        type Output<T: super::ExpandTypeSet> = super::tset![];
+    }
+
+    impl super::ComputeTypeKey for Leaf {
+        type Key = super::tkey!["Leaf"];
+    }
+
+    struct Old {
+        a: u8,
+        b: Option<u16>,
+        c: Leaf,
+    }
+
+    // SAFETY: the layout is computed from the type and its fields
+    unsafe impl crate::TypeLayout for Old {
+        const INHABITED: crate::MaybeUninhabited = crate::inhabited::all![u8, Option<u16>, Leaf];
+        const TYPE_LAYOUT: crate::TypeLayoutInfo<'static> = crate::TypeLayoutInfo {
+            name: ::core::any::type_name::<Self>(),
+            size: ::core::mem::size_of::<Self>(),
+            alignment: ::core::mem::align_of::<Self>(),
+            structure: crate::TypeStructure::Struct {
+                repr: "",
+                fields: &[
+                    crate::Field {
+                        name: "a",
+                        offset: crate::MaybeUninhabited::new::<u8>(::core::mem::offset_of!(
+                            Self, a
+                        )),
+                        ty: ::core::any::type_name::<u8>(),
+                    },
+                    crate::Field {
+                        name: "b",
+                        offset: crate::MaybeUninhabited::new::<Option<u16>>(
+                            ::core::mem::offset_of!(Self, b),
+                        ),
+                        ty: ::core::any::type_name::<Option<u16>>(),
+                    },
+                    crate::Field {
+                        name: "c",
+                        offset: crate::MaybeUninhabited::new::<Leaf>(::core::mem::offset_of!(
+                            Self, c
+                        )),
+                        ty: ::core::any::type_name::<Leaf>(),
+                    },
+                ],
+            },
+        };
+    }
+
+    #[allow(deprecated)]
+    // SAFETY: the set includes the types of all fields
+    unsafe impl super::ComputeTypeSet for Old {
+        type Output<T: super::ExpandTypeSet> = super::tset![u8, Option<u16>, Leaf];
+    }
+
+    impl super::ComputeTypeKey for Old {
+        type Key = super::tkey!["Old"];
+    }
+
+    #[test]
+    fn tail_less_tset() {
+        let old = <Old>::TYPE_GRAPH;
+        let new = <(u8, Option<u16>, Leaf)>::TYPE_GRAPH;
+
+        assert_canonical(&old);
+        assert_eq!(old.tys.len(), new.tys.len());
+
+        for (old, new) in old.tys[1..].iter().zip(&new.tys[1..]) {
+            assert_eq!(old.name, new.name);
+        }
+
+        assert_eq!(<Leaf>::TYPE_GRAPH.tys.len(), 1);
+    }
+}
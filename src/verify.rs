@@ -0,0 +1,616 @@
+//! Helper module to check the internal consistency of a [`TypeLayoutGraph`],
+//! e.g. to validate hand-written [`crate::TypeLayout`] and
+//! [`crate::typeset::ComputeTypeSet`] implementations.
+
+use core::fmt;
+
+use crate::{
+    index::IndexedTypeLayoutGraph,
+    repr::{bytes_eq, Repr},
+    Field, MaybeUninhabited, TypeLayoutGraph, TypeLayoutInfo, TypeStructure,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Inconsistency inside a [`TypeLayoutGraph`], as found by
+/// [`TypeLayoutGraph::verify`].
+pub enum Error<'a> {
+    /// The layout of the graph's root type `ty` is not in
+    /// [`TypeLayoutGraph::tys`].
+    MissingType {
+        /// The fully-qualified name of the type.
+        ty: &'a str,
+    },
+    /// The layout of the type `field_ty` of the `field` in the type `ty` is not
+    /// in [`TypeLayoutGraph::tys`], e.g. since it was forgotten in the `tset!`
+    /// of a [`crate::typeset::ComputeTypeSet`] implementation.
+    MissingFieldType {
+        /// The fully-qualified name of the type that contains the field.
+        ty: &'a str,
+        /// The name of the field.
+        field: &'a str,
+        /// The fully-qualified name of the field's type.
+        field_ty: &'a str,
+    },
+    /// The layouts of two different types with the same fully-qualified name
+    /// `ty` are in [`TypeLayoutGraph::tys`], e.g. since they come from
+    /// different versions of the same crate, which makes the name ambiguous.
+    DuplicateType {
+        /// The fully-qualified name of the types.
+        ty: &'a str,
+    },
+    /// The `alignment` of the type `ty` is not a power of two.
+    InvalidAlignment {
+        /// The fully-qualified name of the type.
+        ty: &'a str,
+        /// The type's alignment.
+        alignment: usize,
+    },
+    /// The `size` of the type `ty` is not a multiple of its `alignment`.
+    UnalignedSize {
+        /// The fully-qualified name of the type.
+        ty: &'a str,
+        /// The type's size.
+        size: usize,
+        /// The type's alignment.
+        alignment: usize,
+    },
+    /// The `field` of the type `ty` does not lie inside the type's `size`.
+    FieldOutOfBounds {
+        /// The fully-qualified name of the type that contains the field.
+        ty: &'a str,
+        /// The name of the field.
+        field: &'a str,
+        /// The byte offset of the field.
+        offset: usize,
+        /// The size of the field's type.
+        field_size: usize,
+        /// The size of the type that contains the field.
+        size: usize,
+    },
+    /// The `offset` of the `field` in the type `ty` is not a multiple of the
+    /// field type's `alignment`, which may be reduced by `#[repr(packed)]`.
+    MisalignedField {
+        /// The fully-qualified name of the type that contains the field.
+        ty: &'a str,
+        /// The name of the field.
+        field: &'a str,
+        /// The byte offset of the field.
+        offset: usize,
+        /// The required alignment of the field.
+        alignment: usize,
+    },
+    /// The `field` and `other` field of the struct or enum variant `ty` occupy
+    /// some of the same bytes.
+    OverlappingFields {
+        /// The fully-qualified name of the type that contains the fields.
+        ty: &'a str,
+        /// The name of the first field.
+        field: &'a str,
+        /// The name of the other field.
+        other: &'a str,
+    },
+}
+
+impl fmt::Display for Error<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingType { ty } => {
+                fmt.write_fmt(format_args!("the layout of `{ty}` is not in the graph"))
+            },
+            Self::MissingFieldType {
+                ty,
+                field,
+                field_ty,
+            } => fmt.write_fmt(format_args!(
+                "the layout of `{field_ty}`, the type of `{ty}::{field}`, is not in the graph"
+            )),
+            Self::DuplicateType { ty } => fmt.write_fmt(format_args!(
+                "the graph contains the layouts of several types named `{ty}`"
+            )),
+            Self::InvalidAlignment { ty, alignment } => fmt.write_fmt(format_args!(
+                "the alignment {alignment} of `{ty}` is not a power of two"
+            )),
+            Self::UnalignedSize {
+                ty,
+                size,
+                alignment,
+            } => fmt.write_fmt(format_args!(
+                "the size {size} of `{ty}` is not a multiple of its alignment {alignment}"
+            )),
+            Self::FieldOutOfBounds {
+                ty,
+                field,
+                offset,
+                field_size,
+                size,
+            } => fmt.write_fmt(format_args!(
+                "`{ty}::{field}` at {offset}..{} is outside of the type's {size} bytes",
+                offset + field_size
+            )),
+            Self::MisalignedField {
+                ty,
+                field,
+                offset,
+                alignment,
+            } => fmt.write_fmt(format_args!(
+                "`{ty}::{field}` at offset {offset} is not aligned to {alignment}"
+            )),
+            Self::OverlappingFields { ty, field, other } => {
+                fmt.write_fmt(format_args!("`{ty}::{field}` and `{ty}::{other}` overlap"))
+            },
+        }
+    }
+}
+
+/// Check the consistency of every type layout inside the `graph`.
+pub(crate) const fn graph<'a>(graph: &TypeLayoutGraph<'a>) -> Result<(), Error<'a>> {
+    if graph.find_type(graph.ty).is_none() {
+        return Err(Error::MissingType { ty: graph.ty });
+    }
+
+    let mut i = 0;
+
+    while i < graph.tys.len() {
+        if let Err(err) = unique_type(graph, i) {
+            return Err(err);
+        }
+
+        if let Err(err) = type_layout(graph, graph.tys[i], None) {
+            return Err(err);
+        }
+
+        i += 1;
+    }
+
+    Ok(())
+}
+
+/// Check the consistency of every type layout inside the `indexed` graph,
+/// whose fields find the layouts of their types by their indices.
+pub(crate) const fn indexed<'a>(indexed: &IndexedTypeLayoutGraph<'a>) -> Result<(), Error<'a>> {
+    let mut i = 0;
+
+    while i < indexed.graph.tys.len() {
+        if let Err(err) = type_layout(
+            &indexed.graph,
+            indexed.graph.tys[i],
+            Some(indexed.field_tys_of(i)),
+        ) {
+            return Err(err);
+        }
+
+        i += 1;
+    }
+
+    Ok(())
+}
+
+/// Check that no type before the `i`th type in [`TypeLayoutGraph::tys`] has
+/// the same name.
+const fn unique_type<'a>(graph: &TypeLayoutGraph<'a>, i: usize) -> Result<(), Error<'a>> {
+    let name = graph.tys[i].name;
+
+    let mut j = 0;
+
+    while j < i {
+        if bytes_eq(graph.tys[j].name.as_bytes(), name.as_bytes()) {
+            return Err(Error::DuplicateType { ty: name });
+        }
+
+        j += 1;
+    }
+
+    Ok(())
+}
+
+/// Check the consistency of the type `layout`, whose fields find the layouts
+/// of their types by name or, if given, by their indices in `field_tys`.
+const fn type_layout<'a>(
+    graph: &TypeLayoutGraph<'a>,
+    layout: &TypeLayoutInfo<'a>,
+    field_tys: Option<&[usize]>,
+) -> Result<(), Error<'a>> {
+    if !layout.alignment.is_power_of_two() {
+        return Err(Error::InvalidAlignment {
+            ty: layout.name,
+            alignment: layout.alignment,
+        });
+    }
+
+    if layout.size % layout.alignment != 0 {
+        return Err(Error::UnalignedSize {
+            ty: layout.name,
+            size: layout.size,
+            alignment: layout.alignment,
+        });
+    }
+
+    match layout.structure {
+        TypeStructure::Primitive => Ok(()),
+        TypeStructure::Struct { repr, fields } => {
+            fields_layout(graph, layout, repr, fields, field_tys, true)
+        },
+        // union fields are allowed to overlap
+        TypeStructure::Union { repr, fields } => {
+            fields_layout(graph, layout, repr, fields, field_tys, false)
+        },
+        TypeStructure::Enum { repr, variants } => {
+            // the fields of all variants are indexed one after the other
+            let mut field_tys = field_tys;
+
+            let mut i = 0;
+
+            while i < variants.len() {
+                let fields = variants[i].fields;
+
+                let variant_tys = match field_tys {
+                    Some(tys) => {
+                        let (variant_tys, rest) = tys.split_at(fields.len());
+                        field_tys = Some(rest);
+                        Some(variant_tys)
+                    },
+                    None => None,
+                };
+
+                if let Err(err) = fields_layout(graph, layout, repr, fields, variant_tys, true) {
+                    return Err(err);
+                }
+
+                i += 1;
+            }
+
+            Ok(())
+        },
+    }
+}
+
+const fn fields_layout<'a>(
+    graph: &TypeLayoutGraph<'a>,
+    layout: &TypeLayoutInfo<'a>,
+    repr: &str,
+    fields: &[Field<'a>],
+    field_tys: Option<&[usize]>,
+    disjoint: bool,
+) -> Result<(), Error<'a>> {
+    let packed = Repr::parse(repr).packed;
+
+    let mut i = 0;
+
+    while i < fields.len() {
+        let field = &fields[i];
+
+        let Some(field_layout) = field_layout(graph, fields, field_tys, i) else {
+            return Err(Error::MissingFieldType {
+                ty: layout.name,
+                field: field.name,
+                field_ty: field.ty,
+            });
+        };
+
+        // uninhabited fields have no offset
+        let MaybeUninhabited::Inhabited(offset) = field.offset else {
+            i += 1;
+            continue;
+        };
+
+        if offset > layout.size || field_layout.size > (layout.size - offset) {
+            return Err(Error::FieldOutOfBounds {
+                ty: layout.name,
+                field: field.name,
+                offset,
+                field_size: field_layout.size,
+                size: layout.size,
+            });
+        }
+
+        let alignment = match packed {
+            Some(packed) if packed < field_layout.alignment => packed,
+            _ => field_layout.alignment,
+        };
+
+        if alignment == 0 || offset % alignment != 0 {
+            return Err(Error::MisalignedField {
+                ty: layout.name,
+                field: field.name,
+                offset,
+                alignment,
+            });
+        }
+
+        if disjoint {
+            if let Some(other) =
+                overlapping_field(graph, fields, field_tys, i, offset, field_layout.size)
+            {
+                return Err(Error::OverlappingFields {
+                    ty: layout.name,
+                    field: other,
+                    other: field.name,
+                });
+            }
+        }
+
+        i += 1;
+    }
+
+    Ok(())
+}
+
+/// Find a field before the `i`th field in `fields` that overlaps with the
+/// `size` bytes starting at `offset`.
+const fn overlapping_field<'a>(
+    graph: &TypeLayoutGraph<'a>,
+    fields: &[Field<'a>],
+    field_tys: Option<&[usize]>,
+    i: usize,
+    offset: usize,
+    size: usize,
+) -> Option<&'a str> {
+    // zero-sized fields never overlap with any other field
+    if size == 0 {
+        return None;
+    }
+
+    let mut j = 0;
+
+    while j < i {
+        let other = &fields[j];
+        let other_layout = field_layout(graph, fields, field_tys, j);
+        j += 1;
+
+        let (MaybeUninhabited::Inhabited(other_offset), Some(other_layout)) =
+            (other.offset, other_layout)
+        else {
+            continue;
+        };
+
+        if other_layout.size > 0
+            && offset < (other_offset + other_layout.size)
+            && other_offset < (offset + size)
+        {
+            return Some(other.name);
+        }
+    }
+
+    None
+}
+
+/// The layout of the type of the `i`th field in `fields`, which is found by
+/// name or, if given, by its index in `field_tys`.
+const fn field_layout<'a>(
+    graph: &TypeLayoutGraph<'a>,
+    fields: &[Field<'a>],
+    field_tys: Option<&[usize]>,
+    i: usize,
+) -> Option<&'a TypeLayoutInfo<'a>> {
+    match field_tys {
+        Some(field_tys) => Some(graph.tys[field_tys[i]]),
+        None => graph.find_type(fields[i].ty),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        verify_type_layout, Field, MaybeUninhabited, TypeLayoutGraph, TypeLayoutInfo, TypeStructure,
+    };
+
+    use super::Error;
+
+    macro_rules! assert_verified {
+        ($($ty:ty),* $(,)?) => {
+            for (ty, result) in [$((
+                ::core::any::type_name::<$ty>(),
+                verify_type_layout::<$ty>(),
+            )),*] {
+                assert_eq!(result, Ok(()), "{ty}");
+            }
+        };
+    }
+
+    #[test]
+    fn core_impls() {
+        assert_verified![
+            u8, i16, u32, i64, u128, isize, f32, f64, char, bool, (),
+            [u16; 3], [(); 0],
+            core::cell::UnsafeCell<u32>, core::cell::Cell<u8>, core::cell::OnceCell<u64>,
+            core::cmp::Reverse<i8>, core::cmp::Ordering,
+            core::convert::Infallible,
+            core::ffi::c_void,
+            fn(u8) -> u16, unsafe extern "C" fn(i32, ...) -> bool,
+            core::marker::PhantomData<u128>, core::marker::PhantomPinned,
+            core::mem::ManuallyDrop<u16>, core::mem::MaybeUninit<u64>,
+            core::mem::Discriminant<core::cmp::Ordering>,
+            core::num::NonZeroU8, core::num::NonZeroI128,
+            core::num::Wrapping<u32>, core::num::Saturating<i64>,
+            core::ops::Range<u8>, core::ops::RangeFrom<u16>, core::ops::RangeFull,
+            core::ops::RangeTo<u32>, core::ops::RangeToInclusive<u64>,
+            core::ops::Bound<u16>, core::ops::ControlFlow<u8, u64>,
+            Option<u8>, Option<&'static u32>, Option<core::num::NonZeroU16>,
+            Option<core::convert::Infallible>,
+            core::pin::Pin<&'static u8>,
+            *const u8, *mut u16, core::ptr::NonNull<u32>,
+            &'static u8, &'static mut u64,
+            Result<u8, u64>, Result<(), core::convert::Infallible>,
+            (u8,), (u8, u16, u32), (u8, (), u64, bool, char, f32, i128),
+        ];
+    }
+
+    #[test]
+    #[cfg(feature = "impl-atomics")]
+    fn atomic_impls() {
+        assert_verified![
+            core::sync::atomic::AtomicBool,
+            core::sync::atomic::AtomicU8,
+            core::sync::atomic::AtomicI32,
+            core::sync::atomic::AtomicUsize,
+            core::sync::atomic::AtomicPtr<u8>,
+        ];
+    }
+
+    #[test]
+    #[cfg(feature = "impl-never")]
+    fn never_impls() {
+        assert_verified![!, Option<!>, Result<u8, !>, (u8, !)];
+    }
+
+    #[test]
+    fn missing_field_type() {
+        const GRAPH: TypeLayoutGraph<'static> = TypeLayoutGraph {
+            ty: "Foo",
+            tys: &[&TypeLayoutInfo {
+                name: "Foo",
+                size: 4,
+                alignment: 2,
+                structure: TypeStructure::Struct {
+                    repr: "C",
+                    fields: &[
+                        Field {
+                            name: "a",
+                            offset: MaybeUninhabited::Inhabited(0),
+                            ty: "u16",
+                        },
+                        Field {
+                            name: "b",
+                            offset: MaybeUninhabited::Inhabited(2),
+                            ty: "u16",
+                        },
+                    ],
+                },
+            }],
+        };
+
+        assert_eq!(
+            GRAPH.verify(),
+            Err(Error::MissingFieldType {
+                ty: "Foo",
+                field: "a",
+                field_ty: "u16",
+            })
+        );
+    }
+
+    #[test]
+    fn duplicate_type() {
+        const U8: TypeLayoutInfo<'static> = TypeLayoutInfo {
+            name: "u8",
+            size: 1,
+            alignment: 1,
+            structure: TypeStructure::Primitive,
+        };
+
+        const GRAPH: TypeLayoutGraph<'static> = TypeLayoutGraph {
+            ty: "u8",
+            tys: &[&U8, &U8],
+        };
+
+        assert_eq!(GRAPH.verify(), Err(Error::DuplicateType { ty: "u8" }));
+    }
+
+    #[test]
+    fn inconsistent_fields() {
+        const U16: TypeLayoutInfo<'static> = TypeLayoutInfo {
+            name: "u16",
+            size: 2,
+            alignment: 2,
+            structure: TypeStructure::Primitive,
+        };
+
+        fn assert_verify(
+            size: usize,
+            alignment: usize,
+            repr: &str,
+            offsets: [usize; 2],
+            expected: Result<(), Error>,
+        ) {
+            let fields = [
+                Field {
+                    name: "a",
+                    offset: MaybeUninhabited::Inhabited(offsets[0]),
+                    ty: "u16",
+                },
+                Field {
+                    name: "b",
+                    offset: MaybeUninhabited::Inhabited(offsets[1]),
+                    ty: "u16",
+                },
+            ];
+            let foo = TypeLayoutInfo {
+                name: "Foo",
+                size,
+                alignment,
+                structure: TypeStructure::Struct {
+                    repr,
+                    fields: fields.as_slice(),
+                },
+            };
+            let tys = [&foo, &U16];
+
+            let graph = TypeLayoutGraph {
+                ty: "Foo",
+                tys: tys.as_slice(),
+            };
+
+            assert_eq!(graph.verify(), expected);
+        }
+
+        assert_verify(4, 2, "C", [0, 2], Ok(()));
+        assert_verify(5, 1, "C,packed", [0, 3], Ok(()));
+        assert_verify(
+            4,
+            3,
+            "C",
+            [0, 2],
+            Err(Error::InvalidAlignment {
+                ty: "Foo",
+                alignment: 3,
+            }),
+        );
+        assert_verify(
+            6,
+            4,
+            "C",
+            [0, 2],
+            Err(Error::UnalignedSize {
+                ty: "Foo",
+                size: 6,
+                alignment: 4,
+            }),
+        );
+        assert_verify(
+            4,
+            2,
+            "C",
+            [0, 4],
+            Err(Error::FieldOutOfBounds {
+                ty: "Foo",
+                field: "b",
+                offset: 4,
+                field_size: 2,
+                size: 4,
+            }),
+        );
+        assert_verify(
+            6,
+            2,
+            "C",
+            [0, 3],
+            Err(Error::MisalignedField {
+                ty: "Foo",
+                field: "b",
+                offset: 3,
+                alignment: 2,
+            }),
+        );
+        assert_verify(
+            4,
+            2,
+            "C",
+            [0, 0],
+            Err(Error::OverlappingFields {
+                ty: "Foo",
+                field: "a",
+                other: "b",
+            }),
+        );
+    }
+}
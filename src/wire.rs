@@ -0,0 +1,210 @@
+//! Helper module that describes the versioned binary formats into which
+//! [`TypeLayoutGraph::serialise`] and [`TypeLayoutGraph::serialise_compact`]
+//! serialise a [`TypeLayoutGraph`].
+//!
+//! Every serialised graph starts with a header of
+//! - the [`MAGIC`] bytes
+//! - the format version, encoded as a [LEB128] unsigned integer
+//! - the total length in bytes of the serialised graph, including the header,
+//!   encoded as a [LEB128] unsigned integer
+//!
+//! In the format [`CANONICAL_VERSION`] 1, which [`TypeLayoutGraph::serialise`]
+//! produces, the header is followed by the canonical form of the graph:
+//! - the name of the root type [`TypeLayoutGraph::ty`]
+//! - the number of types in [`TypeLayoutGraph::tys`], followed by each type's
+//!   name, size, alignment, and structure
+//!
+//! In the format [`COMPACT_VERSION`] 2, which
+//! [`TypeLayoutGraph::serialise_compact`] produces, the header is followed by
+//! - the number of types in [`TypeLayoutGraph::tys`], followed by each type's
+//!   name, which together form the type table
+//! - a type reference to the root type [`TypeLayoutGraph::ty`]
+//! - each type's size, alignment, and structure, in the order of the type table
+//!
+//! where
+//! - a type reference is the index of the type in the type table, or the length
+//!   of the type table followed by the type's name if the type is not in the
+//!   graph
+//! - every other string, i.e. every repr, variant name, and field name, is a
+//!   string reference, which is either `0` followed by the string, or `k + 1`
+//!   if it is equal to the `k`-th string reference in the serialised graph
+//!   (writers may always choose to inline a string)
+//!
+//! In both versions, all integers are encoded as [LEB128] unsigned integers
+//! and all inline strings as their length followed by their UTF-8 bytes.
+//! [`TypeLayoutGraph::hash`] is computed over the canonical form, such that
+//! hashes do not change with the format version.
+//!
+//! The format follows these compatibility rules:
+//! - the header never changes, such that every reader can detect the version of
+//!   a serialised graph and skip over graphs that it cannot decode
+//! - the format version is independent of the version of this crate and is only
+//!   increased when the bytes after the header change
+//! - readers should decode all format versions up to the one they support,
+//!   including the unversioned format that was produced before the header was
+//!   introduced (called version 0), which instead started with the total
+//!   length, followed by the version of this crate as a string
+//!
+//! [`TypeLayoutGraph`]: crate::TypeLayoutGraph
+//! [`TypeLayoutGraph::hash`]: crate::TypeLayoutGraph::hash
+//! [`TypeLayoutGraph::serialise`]: crate::TypeLayoutGraph::serialise
+//! [`TypeLayoutGraph::serialise_compact`]: crate::TypeLayoutGraph::serialise_compact
+//! [`TypeLayoutGraph::ty`]: crate::TypeLayoutGraph::ty
+//! [`TypeLayoutGraph::tys`]: crate::TypeLayoutGraph::tys
+//! [LEB128]: https://en.wikipedia.org/wiki/LEB128
+
+/// Magic bytes at the start of every versioned serialised
+/// [`TypeLayoutGraph`](crate::TypeLayoutGraph).
+///
+/// The magic bytes start with `0x80 0x00`, which is a non-minimal [LEB128]
+/// encoding of zero that the unversioned format never started with. Since the
+/// magic bytes do not start with a zero byte, zero padding between serialised
+/// graphs can still be skipped.
+///
+/// [LEB128]: https://en.wikipedia.org/wiki/LEB128
+pub const MAGIC: [u8; 5] = *b"\x80\0CTL";
+
+/// The version of the canonical binary format, which is produced by
+/// [`TypeLayoutGraph::serialise`](crate::TypeLayoutGraph::serialise).
+pub const CANONICAL_VERSION: usize = 1;
+
+/// The version of the compact binary format, which is produced by
+/// [`TypeLayoutGraph::serialise_compact`](crate::TypeLayoutGraph::serialise_compact).
+pub const COMPACT_VERSION: usize = 2;
+
+/// The latest version of the binary format, up to which readers should
+/// decode all versions.
+pub const VERSION: usize = COMPACT_VERSION;
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use crate::{TypeGraphLayout, TypeLayoutGraph};
+
+    use super::{CANONICAL_VERSION, COMPACT_VERSION, MAGIC};
+
+    /// Decodes the header of a serialised graph into its version, its total
+    /// length, and the length of the header.
+    fn header(bytes: &[u8]) -> (usize, usize, usize) {
+        assert_eq!(bytes[..MAGIC.len()], MAGIC);
+
+        let mut cursor = MAGIC.len();
+        let mut leb128 = || {
+            let mut value = 0_usize;
+            let mut shift = 0;
+
+            loop {
+                let byte = bytes[cursor];
+                cursor += 1;
+
+                value |= usize::from(byte & 0x7F) << shift;
+                shift += 7;
+
+                if byte & 0x80 == 0 {
+                    // the encoding is minimal
+                    assert!(byte != 0 || shift == 7);
+                    break value;
+                }
+            }
+        };
+
+        let version = leb128();
+        let len = leb128();
+
+        (version, len, cursor)
+    }
+
+    #[test]
+    fn magic() {
+        assert_eq!(MAGIC, [0x80, 0x00, b'C', b'T', b'L']);
+        assert_ne!(MAGIC[0], 0);
+    }
+
+    #[test]
+    fn headers() {
+        let graph = <(u8, u16)>::TYPE_GRAPH;
+
+        let mut bytes = vec![0_u8; graph.serialised_len()];
+        graph.serialise(&mut bytes);
+        assert_eq!(
+            bytes[..MAGIC.len() + 2],
+            [
+                0x80,
+                0x00,
+                b'C',
+                b'T',
+                b'L',
+                1,
+                bytes.len().try_into().unwrap()
+            ]
+        );
+        assert_eq!(header(&bytes), (CANONICAL_VERSION, bytes.len(), 7));
+
+        let mut bytes = vec![0_u8; graph.serialised_compact_len()];
+        graph.serialise_compact(&mut bytes);
+        assert_eq!(
+            bytes[..MAGIC.len() + 2],
+            [
+                0x80,
+                0x00,
+                b'C',
+                b'T',
+                b'L',
+                2,
+                bytes.len().try_into().unwrap()
+            ]
+        );
+        assert_eq!(header(&bytes), (COMPACT_VERSION, bytes.len(), 7));
+    }
+
+    #[test]
+    fn length_fixed_point() {
+        // the total length includes its own LEB128 encoding, which grows by a
+        // byte at 128 and 16384, so sweep the graph length across both
+        for name_len in (100..140).chain(16_340..16_400) {
+            let name = "T".repeat(name_len);
+            let graph: TypeLayoutGraph = TypeLayoutGraph {
+                ty: &name,
+                tys: &[],
+            };
+
+            for version in [CANONICAL_VERSION, COMPACT_VERSION] {
+                let len = graph.versioned_serialised_len(version);
+                let mut bytes = vec![0_u8; len];
+                graph.versioned_serialise(version, &mut bytes);
+
+                let (header_version, header_len, header_size) = header(&bytes);
+                assert_eq!(header_version, version);
+                assert_eq!(header_len, len, "{name_len}");
+
+                let len_size = match len {
+                    0..=0x7F => 1,
+                    0x80..=0x3FFF => 2,
+                    _ => 3,
+                };
+                assert_eq!(header_size, MAGIC.len() + 1 + len_size, "{name_len}");
+            }
+        }
+
+        let lens = (100..140)
+            .chain(16_340..16_400)
+            .map(|name_len| {
+                let name = "T".repeat(name_len);
+                let graph: TypeLayoutGraph = TypeLayoutGraph {
+                    ty: &name,
+                    tys: &[],
+                };
+                graph.serialised_len()
+            })
+            .collect::<Vec<_>>();
+        // a graph that would be 128 or 16384 bytes long with a one or two byte
+        // length needs one more length byte, so no graph has these lengths
+        for len in [127, 129, 16_383, 16_385, 16_386] {
+            assert!(lens.contains(&len), "{len}");
+        }
+        for len in [128, 16_384] {
+            assert!(!lens.contains(&len), "{len}");
+        }
+    }
+}
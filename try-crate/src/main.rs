@@ -266,6 +266,13 @@ fn main() {
     }
     println!("{ascii_escaped_layout}");
 
+    let mut ascii_escaped_layout = String::new();
+    for &b in SERIALISED_TREE_U8_LAYOUT {
+        let part: Vec<u8> = std::ascii::escape_default(b).collect();
+        ascii_escaped_layout.push_str(std::str::from_utf8(&part).unwrap());
+    }
+    println!("{ascii_escaped_layout}");
+
     let ron_layout = ron::to_string(&<List<u8>>::TYPE_GRAPH).unwrap();
     println!("{ron_layout}");
 }
@@ -277,3 +284,5 @@ fn non_static_ref<'a>(_val: &'a u128) {
 #[cfg(feature = "serialize-to-generic-const-array")]
 const SERIALISED_LIST_U8_LAYOUT: [u8; const_type_layout::serialised_type_graph_len::<List<u8>>()] =
     const_type_layout::serialise_type_graph::<List<u8>>();
+
+static SERIALISED_TREE_U8_LAYOUT: &[u8] = const_type_layout::serialised_type_graph!(Tree<u8>);